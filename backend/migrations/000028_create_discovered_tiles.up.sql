@@ -0,0 +1,8 @@
+-- Tiles each player has discovered (used when map fog is enabled)
+CREATE TABLE discovered_tiles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    x INT NOT NULL,
+    y INT NOT NULL,
+    discovered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, x, y)
+);
@@ -0,0 +1,13 @@
+-- Chained upgrade levels waiting for the current upgrade of a building to finish
+CREATE TABLE building_queue (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    building_id UUID NOT NULL REFERENCES buildings(id) ON DELETE CASCADE,
+    village_id UUID NOT NULL REFERENCES villages(id) ON DELETE CASCADE,
+    target_level INT NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(building_id, target_level)
+);
+
+CREATE INDEX idx_building_queue_building ON building_queue(building_id, target_level);
@@ -0,0 +1 @@
+ALTER TABLE item_definitions DROP COLUMN IF EXISTS allowed_tribe;
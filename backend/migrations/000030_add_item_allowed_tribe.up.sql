@@ -0,0 +1,2 @@
+-- Optional tribe restriction for items (NULL = universal)
+ALTER TABLE item_definitions ADD COLUMN allowed_tribe tribe_type;
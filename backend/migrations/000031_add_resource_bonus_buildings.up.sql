@@ -0,0 +1,6 @@
+-- Processing buildings that boost resource field production
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'sawmill';
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'brickyard';
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'iron_foundry';
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'grain_mill';
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'bakery';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_armies_stationed_expiry;
+ALTER TABLE armies DROP COLUMN IF EXISTS max_station_hours;
@@ -0,0 +1,5 @@
+-- Optional limit on how long support stays stationed before returning home automatically
+ALTER TABLE armies ADD COLUMN max_station_hours INT;
+
+CREATE INDEX idx_armies_stationed_expiry ON armies(arrives_at)
+    WHERE is_stationed = TRUE AND max_station_hours IS NOT NULL;
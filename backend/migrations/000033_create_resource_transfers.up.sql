@@ -0,0 +1,19 @@
+-- Resources carried by merchants between villages
+CREATE TABLE resource_transfers (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    sender_id UUID NOT NULL REFERENCES users(id),
+    from_village_id UUID NOT NULL REFERENCES villages(id) ON DELETE CASCADE,
+    to_village_id UUID NOT NULL REFERENCES villages(id) ON DELETE CASCADE,
+    wood INT NOT NULL DEFAULT 0,
+    clay INT NOT NULL DEFAULT 0,
+    iron INT NOT NULL DEFAULT 0,
+    crop INT NOT NULL DEFAULT 0,
+    tax_percent INT NOT NULL DEFAULT 0, -- Share lost in transit, applied on delivery
+    departed_at TIMESTAMPTZ NOT NULL,
+    arrives_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_resource_transfers_from ON resource_transfers(from_village_id);
+CREATE INDEX idx_resource_transfers_to ON resource_transfers(to_village_id);
+CREATE INDEX idx_resource_transfers_arrives ON resource_transfers(arrives_at);
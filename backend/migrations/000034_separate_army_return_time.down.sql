@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_armies_returns_at;
+
+UPDATE armies SET arrives_at = returns_at WHERE is_returning = TRUE AND returns_at IS NOT NULL;
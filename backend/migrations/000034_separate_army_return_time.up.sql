@@ -0,0 +1,5 @@
+-- Returning armies now keep their outbound arrival time in arrives_at and
+-- track the return leg in returns_at; move in-flight returns over
+UPDATE armies SET returns_at = arrives_at WHERE is_returning = TRUE;
+
+CREATE INDEX idx_armies_returns_at ON armies(returns_at) WHERE is_returning = TRUE;
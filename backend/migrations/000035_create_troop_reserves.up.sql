@@ -0,0 +1,6 @@
+-- Per-village minimum garrison kept home when sending armies
+CREATE TABLE troop_reserves (
+    village_id UUID PRIMARY KEY REFERENCES villages(id) ON DELETE CASCADE,
+    reserves JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1 @@
+ALTER TABLE scout_reports DROP COLUMN IF EXISTS troops_exact;
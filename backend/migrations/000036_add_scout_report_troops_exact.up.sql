@@ -0,0 +1,2 @@
+-- Partially successful scouting reports approximate troop counts
+ALTER TABLE scout_reports ADD COLUMN troops_exact BOOLEAN NOT NULL DEFAULT TRUE;
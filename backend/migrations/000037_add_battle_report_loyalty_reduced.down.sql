@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_battle_reports_loyalty;
+ALTER TABLE battle_reports DROP COLUMN IF EXISTS loyalty_reduced;
@@ -0,0 +1,5 @@
+-- Loyalty removed from the defending village by a conquer battle
+ALTER TABLE battle_reports ADD COLUMN loyalty_reduced INT NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_battle_reports_loyalty ON battle_reports(defender_village_id, occurred_at DESC)
+    WHERE loyalty_reduced > 0;
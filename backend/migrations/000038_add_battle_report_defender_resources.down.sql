@@ -0,0 +1 @@
+ALTER TABLE battle_reports DROP COLUMN IF EXISTS defender_resources;
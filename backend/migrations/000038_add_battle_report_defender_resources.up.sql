@@ -0,0 +1,2 @@
+-- Defender's resources right before a raid or attack, shown only to the defender
+ALTER TABLE battle_reports ADD COLUMN defender_resources JSONB;
@@ -0,0 +1,2 @@
+-- Hero's Mansion houses the hero; its level will gate oasis occupation
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'hero_mansion';
@@ -0,0 +1 @@
+ALTER TABLE troop_definitions DROP COLUMN IF EXISTS is_scout;
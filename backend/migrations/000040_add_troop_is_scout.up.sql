@@ -0,0 +1,6 @@
+-- Only scout-capable units may be sent on (or defend against) scout missions
+ALTER TABLE troop_definitions ADD COLUMN is_scout BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE troop_definitions SET is_scout = TRUE WHERE troop_type IN ('sea_diver', 'swamp_dragon');
+
+COMMENT ON COLUMN troop_definitions.is_scout IS 'Whether this troop can be sent on scout missions.';
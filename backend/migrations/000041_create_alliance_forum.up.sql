@@ -0,0 +1,26 @@
+-- Alliance forum: persistent threads with replies, visible to alliance members only
+
+CREATE TABLE forum_threads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    alliance_id UUID NOT NULL REFERENCES alliances(id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES users(id),
+    title VARCHAR(200) NOT NULL,
+
+    -- Pinned threads are listed first; only officers and the leader can pin
+    is_pinned BOOLEAN NOT NULL DEFAULT FALSE,
+
+    last_post_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_forum_threads_alliance ON forum_threads(alliance_id, is_pinned DESC, last_post_at DESC);
+
+CREATE TABLE forum_posts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    thread_id UUID NOT NULL REFERENCES forum_threads(id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES users(id),
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_forum_posts_thread ON forum_posts(thread_id, created_at ASC);
@@ -0,0 +1,19 @@
+-- Read-only API tokens that players can hand to third-party tools
+
+CREATE TABLE api_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+
+    -- SHA-256 of the token; the plaintext is only shown once on creation
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+
+    -- Resource groups the token may read, e.g. {villages,reports}
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_tokens_user ON api_tokens(user_id, created_at DESC);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_alliances_name_lower;
+DROP INDEX IF EXISTS idx_alliances_tag_lower;
+DROP INDEX IF EXISTS idx_users_display_name_lower;
@@ -0,0 +1,5 @@
+-- Case-insensitive lookups for searching villages by player name or alliance
+
+CREATE INDEX idx_users_display_name_lower ON users (LOWER(display_name));
+CREATE INDEX idx_alliances_tag_lower ON alliances (LOWER(tag));
+CREATE INDEX idx_alliances_name_lower ON alliances (LOWER(name));
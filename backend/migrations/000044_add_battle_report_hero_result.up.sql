@@ -0,0 +1,2 @@
+-- Hero participation and outcome for each side of a battle
+ALTER TABLE battle_reports ADD COLUMN hero_result JSONB;
@@ -0,0 +1,2 @@
+-- Send loot that does not fit in the home village on to the nearest own village with space
+ALTER TABLE users ADD COLUMN forward_loot_overflow BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS village_activity;
+DROP TYPE IF EXISTS village_activity_type;
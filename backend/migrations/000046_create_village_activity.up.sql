@@ -0,0 +1,22 @@
+-- Per-village history shown to the owner
+
+CREATE TYPE village_activity_type AS ENUM (
+    'building_started',
+    'building_completed',
+    'troops_trained',
+    'army_sent',
+    'attack_received'
+);
+
+CREATE TABLE village_activity (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    village_id UUID NOT NULL REFERENCES villages(id) ON DELETE CASCADE,
+    activity_type village_activity_type NOT NULL,
+
+    -- Type-specific fields, e.g. {"building_type": "barracks", "level": 3}
+    details JSONB NOT NULL DEFAULT '{}',
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_village_activity_village ON village_activity(village_id, created_at DESC);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS idx_hero_adventures_one_active;
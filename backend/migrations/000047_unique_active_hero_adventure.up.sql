@@ -0,0 +1,4 @@
+-- A hero can be on at most one adventure at a time; different heroes of the
+-- same player are free to adventure simultaneously
+CREATE UNIQUE INDEX idx_hero_adventures_one_active ON hero_adventures(hero_id)
+    WHERE is_completed = FALSE;
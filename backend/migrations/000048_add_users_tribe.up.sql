@@ -0,0 +1,2 @@
+-- Tribe a player has chosen; drives tribe-wide combat bonuses (NULL = not chosen yet)
+ALTER TABLE users ADD COLUMN tribe tribe_type;
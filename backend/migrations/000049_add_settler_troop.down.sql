@@ -0,0 +1,2 @@
+-- Note: Cannot remove enum values in PostgreSQL without recreating the type
+-- The 'settler' and 'village_founded' values remain; 000050 removes the troop itself
@@ -0,0 +1,6 @@
+-- Settlers found new villages on Settle missions
+
+ALTER TYPE troop_type ADD VALUE IF NOT EXISTS 'settler';
+
+-- Logged on the sending village when its settlers found a village
+ALTER TYPE village_activity_type ADD VALUE IF NOT EXISTS 'village_founded';
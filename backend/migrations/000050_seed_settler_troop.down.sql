@@ -0,0 +1,2 @@
+-- Remove the Settler troop
+DELETE FROM troop_definitions WHERE troop_type = 'settler';
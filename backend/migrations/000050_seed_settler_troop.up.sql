@@ -0,0 +1,15 @@
+-- Insert the Settler troop definition (available to all tribes)
+
+INSERT INTO troop_definitions (
+    troop_type, tribe, name, description,
+    attack, defense_infantry, defense_cavalry, speed, carry_capacity, crop_consumption,
+    training_time_seconds, wood_cost, clay_cost, iron_cost, crop_cost,
+    required_building, required_building_level, loyalty_reduction
+) VALUES
+(
+    'settler', 'special', 'Settler',
+    'Families ready to leave home and clear new land. A group of settlers founds a new village on an empty tile.',
+    0, 80, 80, 5, 3000, 1,
+    26900, 5800, 5300, 7200, 5500,
+    'residence', 10, 0
+);
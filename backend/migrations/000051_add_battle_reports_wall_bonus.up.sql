@@ -0,0 +1,2 @@
+-- Defense bonus (percent) the defender's Wall gave in the battle
+ALTER TABLE battle_reports ADD COLUMN wall_bonus_percent INT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE armies DROP COLUMN IF EXISTS hero_id;
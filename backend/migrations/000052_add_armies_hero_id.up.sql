@@ -0,0 +1,2 @@
+-- Hero riding along with an army; cleared if the hero falls in battle
+ALTER TABLE armies ADD COLUMN hero_id UUID REFERENCES heroes(id) ON DELETE SET NULL;
@@ -0,0 +1,2 @@
+-- PostgreSQL cannot drop enum values; remove the buildings that use them instead
+DELETE FROM buildings WHERE building_type::text = 'cranny';
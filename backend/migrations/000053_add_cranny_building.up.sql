@@ -0,0 +1,2 @@
+-- Cranny hides a fixed amount of each resource from raiders
+ALTER TYPE building_type ADD VALUE IF NOT EXISTS 'cranny';
@@ -0,0 +1,2 @@
+-- PostgreSQL cannot drop enum values; remove the activity entries that use it instead
+DELETE FROM village_activity WHERE activity_type::text = 'troops_starved';
@@ -0,0 +1,2 @@
+-- Logged on a village each time hunger kills troops there
+ALTER TYPE village_activity_type ADD VALUE IF NOT EXISTS 'troops_starved';
@@ -0,0 +1,2 @@
+-- Silver earned by selling hero items
+ALTER TABLE users ADD COLUMN silver_balance INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- PostgreSQL cannot drop enum values; remove the messages that use it instead
+DELETE FROM messages WHERE message_type::text = 'system';
+
+ALTER TABLE messages DROP CONSTRAINT IF EXISTS message_has_sender_or_recipient;
+
+ALTER TABLE messages ALTER COLUMN sender_id SET NOT NULL;
@@ -0,0 +1,7 @@
+-- System messages are sent by the game itself, so they have no sender
+ALTER TYPE message_type ADD VALUE IF NOT EXISTS 'system';
+
+ALTER TABLE messages ALTER COLUMN sender_id DROP NOT NULL;
+
+ALTER TABLE messages ADD CONSTRAINT message_has_sender_or_recipient
+    CHECK (sender_id IS NOT NULL OR recipient_id IS NOT NULL);
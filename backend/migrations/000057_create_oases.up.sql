@@ -0,0 +1,39 @@
+-- Oases: unsettled tiles a village can occupy for a production bonus.
+-- How many a village may hold depends on its Hero's Mansion level.
+CREATE TABLE oases (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    x INT NOT NULL,
+    y INT NOT NULL,
+
+    -- Production bonus (percent) per resource for the occupying village
+    wood_bonus INT NOT NULL DEFAULT 0,
+    clay_bonus INT NOT NULL DEFAULT 0,
+    iron_bonus INT NOT NULL DEFAULT 0,
+    crop_bonus INT NOT NULL DEFAULT 0,
+
+    -- Occupying village (NULL while the oasis is free)
+    village_id UUID REFERENCES villages(id) ON DELETE SET NULL,
+    occupied_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_oasis_coordinates UNIQUE (x, y)
+);
+
+CREATE INDEX idx_oases_village ON oases(village_id) WHERE village_id IS NOT NULL;
+
+-- Scatter oases over the central map, one bonus resource each, skipping
+-- tiles that already hold a village
+INSERT INTO oases (x, y, wood_bonus, clay_bonus, iron_bonus, crop_bonus)
+SELECT
+    gx,
+    gy,
+    CASE WHEN abs(hashtext(gx || ':' || gy)) % 4 = 0 THEN 25 ELSE 0 END,
+    CASE WHEN abs(hashtext(gx || ':' || gy)) % 4 = 1 THEN 25 ELSE 0 END,
+    CASE WHEN abs(hashtext(gx || ':' || gy)) % 4 = 2 THEN 25 ELSE 0 END,
+    CASE WHEN abs(hashtext(gx || ':' || gy)) % 4 = 3 THEN 25 ELSE 0 END
+FROM generate_series(-100, 100) AS gx
+CROSS JOIN generate_series(-100, 100) AS gy
+WHERE abs(hashtext(gx || ':' || gy)) % 12 = 0
+    AND NOT (gx = 0 AND gy = 0)
+    AND NOT EXISTS (SELECT 1 FROM villages v WHERE v.x = gx AND v.y = gy);
@@ -0,0 +1,2 @@
+-- Note: PostgreSQL does not support removing enum values directly
+-- The hero_revive value will remain in the enum but be unused
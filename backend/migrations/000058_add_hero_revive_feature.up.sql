@@ -0,0 +1,2 @@
+-- Instant hero revives are paid for with gold
+ALTER TYPE gold_feature ADD VALUE IF NOT EXISTS 'hero_revive';
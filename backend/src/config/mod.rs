@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use std::env;
 
+use crate::models::troop::TroopType;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub server: ServerConfig,
@@ -8,6 +10,7 @@ pub struct Config {
     pub redis: RedisConfig,
     pub jwt: JwtConfig,
     pub firebase: FirebaseConfig,
+    pub game: GameConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -15,10 +18,148 @@ pub struct FirebaseConfig {
     pub project_id: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// Hide undiscovered distant villages on the map
+    pub map_fog_enabled: bool,
+    /// Radius around own villages that is always visible when fog is enabled
+    pub map_visibility_radius: i32,
+    /// Map range used when a request doesn't specify one
+    pub map_default_range: i32,
+    /// Largest map range a single request may load
+    pub map_max_range: i32,
+    /// Maximum number of hero slots a player can own
+    pub max_hero_slots: i32,
+    /// Allow hostile missions against members of the attacker's own alliance
+    pub allow_internal_alliance_attacks: bool,
+    /// Merchant travel speed in fields per hour
+    pub merchant_speed: f64,
+    /// Percentage of transferred resources lost in transit
+    pub transfer_tax_percent: i32,
+    /// Resources a single merchant can carry
+    pub merchant_capacity: i32,
+    /// Extra merchant and raid carry capacity per Trade Office level (percent)
+    pub trade_office_carry_bonus_percent: i32,
+    /// Minimum hero level to start a Short adventure
+    pub adventure_min_level_short: i32,
+    /// Minimum hero level to start a Long adventure
+    pub adventure_min_level_long: i32,
+    /// Adventure experience, silver and item drop bonus per hero level above 1 (percent)
+    pub adventure_level_bonus_percent: i32,
+    /// Upper bound for the adventure level bonus (percent)
+    pub adventure_level_bonus_max_percent: i32,
+    /// Adventure health loss reduction per hero level above 1 (percent, same upper bound)
+    pub adventure_level_damage_reduction_percent: i32,
+    /// Fixed seed for adventure rolls, for test servers (None = fresh randomness)
+    pub adventure_rng_seed: Option<u64>,
+    /// Use the middle of an adventure's duration range instead of a random pick
+    pub adventure_midpoint_duration: bool,
+    /// How long a player's adventure generation lock lives in Redis (seconds)
+    pub adventure_generation_lock_seconds: u64,
+    /// How long a cached village overview lives in Redis (seconds, 0 = no caching);
+    /// a backstop for mutations that don't invalidate it explicitly
+    pub village_overview_cache_seconds: u64,
+    /// Experience needed for the first hero level-up
+    pub hero_exp_base: f64,
+    /// Multiplier applied to the experience requirement per level
+    pub hero_exp_growth: f64,
+    /// Highest level a hero can reach
+    pub hero_max_level: i32,
+    /// Health (percent) a hero comes back with after an instant gold revive
+    pub hero_revive_health_gold: i32,
+    /// Health (percent) a hero comes back with after waiting out the natural revive
+    pub hero_revive_health_natural: i32,
+    /// Health (percent) a hero comes back with after an instant revive paid in resources
+    pub hero_revive_health_resources: i32,
+    /// Troops granted to every newly settled village (empty by default)
+    pub starting_garrison: Vec<(TroopType, i32)>,
+    /// How far ahead an army departure can be scheduled (hours)
+    pub max_schedule_ahead_hours: i64,
+    /// Maximum armies in motion from a single village (0 = unlimited)
+    pub max_armies_per_village: i64,
+    /// Maximum armies in motion across all of a player's villages (0 = unlimited)
+    pub max_armies_per_player: i64,
+    /// Hard cap on villages a single player can own (0 = unlimited)
+    pub max_villages_per_player: i64,
+    /// Flat hourly production every village gets per resource, fields or not
+    pub village_base_production: i32,
+    /// Hourly production of a level 1 resource field
+    pub field_production_base: f64,
+    /// Per-level multiplier of resource field production
+    pub field_production_growth: f64,
+    /// Extra multiplier applied per squared level, steepening high-level fields
+    pub field_production_curve: f64,
+    /// Loyalty a village starts with right after being conquered
+    pub conquest_loyalty_reset: i32,
+    /// Most loyalty a single conquer attack can remove, however many chiefs (0 = no cap)
+    pub max_loyalty_reduction_per_attack: i32,
+    /// Largest number of units a single army may contain
+    pub max_army_size: i32,
+    /// Settlers a Settle mission needs (and uses up) to found a village
+    pub settlers_per_village: i32,
+    /// Allow conquering a capital when its owner has other villages
+    pub allow_capital_conquest: bool,
+    /// Only accept units that defend better than they attack on support missions
+    pub defensive_support_only: bool,
+    /// Percentage of a building's cumulative cost refunded when it is demolished
+    pub demolish_refund_percent: i32,
+    /// Percentage of a unit's training cost refunded when it is disbanded
+    pub disband_refund_percent: i32,
+    /// Percentage of the paid cost refunded when an upgrade in progress is cancelled
+    pub upgrade_cancel_refund_percent: i32,
+    /// Cap on the production bonus (percent) from heroes stationed in a village
+    pub hero_production_bonus_max_percent: i32,
+    /// Farthest an oasis may lie from the village occupying it (fields per axis)
+    pub oasis_max_distance: i32,
+    /// Defense bonus (percent) each completed Wall level gives the defender
+    pub wall_defense_bonus_percent_per_level: i32,
+    /// Cap on the Wall's defense bonus (percent), so walls never make a village untakeable
+    pub wall_defense_bonus_max_percent: i32,
+    /// Minimum share (percent) of its troops the winner of a defended battle loses (0 = off)
+    pub battle_min_winner_loss_percent: i32,
+    /// Percent added to the attack power of its armies when a player is Phasuttha
+    pub tribe_phasuttha_attack_bonus_percent: i32,
+    /// Percent added to the defense power of its villages when a player is Phasuttha
+    pub tribe_phasuttha_defense_bonus_percent: i32,
+    /// Percent added to the attack power of its armies when a player is Nava
+    pub tribe_nava_attack_bonus_percent: i32,
+    /// Percent added to the defense power of its villages when a player is Nava
+    pub tribe_nava_defense_bonus_percent: i32,
+    /// Percent added to the attack power of its armies when a player is Kiri
+    pub tribe_kiri_attack_bonus_percent: i32,
+    /// Percent added to the defense power of its villages when a player is Kiri
+    pub tribe_kiri_defense_bonus_percent: i32,
+    /// Show hosts the home coordinates of players supporting their villages
+    pub show_support_origin: bool,
+    /// When a starving host has no own troops left, send home just enough stationed
+    /// support to cover the deficit instead of letting it starve
+    pub starvation_returns_support: bool,
+    /// Share of total scout power the attacker needs for any intel
+    pub scout_success_ratio: f64,
+    /// Share of scout power needed for exact troop counts rather than approximations
+    pub scout_exact_ratio: f64,
+    /// Attacker scouts lost on success: (1 - power share) times this
+    pub scout_winner_loss_factor: f64,
+    /// Minimum share of attacker scouts lost when scouting fails
+    pub scout_loser_min_loss: f64,
+    /// Defender scouts lost when scouted: attacker power share times this
+    pub scout_defender_loss_factor: f64,
+    /// Share of defender scouts lost when fending off a scout
+    pub scout_defender_win_loss: f64,
+    /// Send the defender a report of successful scouting even when none of their scouts fought
+    pub scout_always_notify_defender: bool,
+    /// How long a successful scouting keeps a village's loyalty visible (hours)
+    pub scout_intel_hours: i64,
+    /// How long a new player is under beginner protection after joining (hours)
+    pub beginner_protection_hours: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub port: u16,
     pub environment: String,
+    /// Firebase UIDs allowed to use the admin API
+    pub admin_firebase_uids: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +192,13 @@ impl Config {
                     .parse()
                     .context("Invalid SERVER_PORT")?,
                 environment: env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
+                admin_firebase_uids: env::var("ADMIN_FIREBASE_UIDS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|uid| !uid.is_empty())
+                    .map(String::from)
+                    .collect(),
             },
             database: DatabaseConfig {
                 host: env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string()),
@@ -80,6 +228,288 @@ impl Config {
                 project_id: env::var("FIREBASE_PROJECT_ID")
                     .context("FIREBASE_PROJECT_ID is required")?,
             },
+            game: GameConfig::from_env()?,
+        })
+    }
+}
+
+impl Default for GameConfig {
+    /// The rules a server runs with when nothing is configured
+    fn default() -> Self {
+        Self {
+            map_fog_enabled: false,
+            map_visibility_radius: 7,
+            map_default_range: 7,
+            map_max_range: 15,
+            max_hero_slots: 5,
+            allow_internal_alliance_attacks: false,
+            merchant_speed: 16.0,
+            transfer_tax_percent: 0,
+            merchant_capacity: 500,
+            trade_office_carry_bonus_percent: 10,
+            adventure_min_level_short: 1,
+            adventure_min_level_long: 5,
+            adventure_level_bonus_percent: 2,
+            adventure_level_bonus_max_percent: 50,
+            adventure_level_damage_reduction_percent: 1,
+            adventure_rng_seed: None,
+            adventure_midpoint_duration: false,
+            adventure_generation_lock_seconds: 30,
+            village_overview_cache_seconds: 15,
+            hero_exp_base: 100.0,
+            hero_exp_growth: 1.5,
+            hero_max_level: 100,
+            hero_revive_health_gold: 50,
+            hero_revive_health_natural: 25,
+            hero_revive_health_resources: 50,
+            starting_garrison: Vec::new(),
+            max_schedule_ahead_hours: 24,
+            max_armies_per_village: 20,
+            max_armies_per_player: 100,
+            max_villages_per_player: 100,
+            village_base_production: 3,
+            field_production_base: 3.0,
+            field_production_growth: 1.63,
+            field_production_curve: 1.0034,
+            conquest_loyalty_reset: 25,
+            max_loyalty_reduction_per_attack: 0,
+            max_army_size: 1_000_000,
+            settlers_per_village: 3,
+            allow_capital_conquest: false,
+            defensive_support_only: false,
+            demolish_refund_percent: 25,
+            disband_refund_percent: 0,
+            upgrade_cancel_refund_percent: 100,
+            hero_production_bonus_max_percent: 25,
+            oasis_max_distance: 3,
+            wall_defense_bonus_percent_per_level: 3,
+            wall_defense_bonus_max_percent: 50,
+            battle_min_winner_loss_percent: 0,
+            tribe_phasuttha_attack_bonus_percent: 0,
+            tribe_phasuttha_defense_bonus_percent: 0,
+            tribe_nava_attack_bonus_percent: 0,
+            tribe_nava_defense_bonus_percent: 0,
+            tribe_kiri_attack_bonus_percent: 0,
+            tribe_kiri_defense_bonus_percent: 0,
+            show_support_origin: false,
+            starvation_returns_support: true,
+            scout_success_ratio: 0.4,
+            scout_exact_ratio: 0.6,
+            scout_winner_loss_factor: 0.8,
+            scout_loser_min_loss: 0.9,
+            scout_defender_loss_factor: 0.5,
+            scout_defender_win_loss: 0.1,
+            scout_always_notify_defender: true,
+            scout_intel_hours: 24,
+            beginner_protection_hours: 72,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Game rules: the defaults, overridden by whichever settings are set in the environment
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            map_fog_enabled: env_or("MAP_FOG_ENABLED", defaults.map_fog_enabled)?,
+            map_visibility_radius: env_or("MAP_VISIBILITY_RADIUS", defaults.map_visibility_radius)?,
+            map_default_range: env_or("MAP_DEFAULT_RANGE", defaults.map_default_range)?,
+            map_max_range: env_or("MAP_MAX_RANGE", defaults.map_max_range)?,
+            max_hero_slots: env_or("HERO_MAX_SLOTS", defaults.max_hero_slots)?,
+            allow_internal_alliance_attacks: env_or(
+                "ALLOW_INTERNAL_ALLIANCE_ATTACKS",
+                defaults.allow_internal_alliance_attacks,
+            )?,
+            merchant_speed: env_or("MERCHANT_SPEED", defaults.merchant_speed)?,
+            transfer_tax_percent: env_or("TRANSFER_TAX_PERCENT", defaults.transfer_tax_percent)?,
+            merchant_capacity: env_or("MERCHANT_CAPACITY", defaults.merchant_capacity)?,
+            trade_office_carry_bonus_percent: env_or(
+                "TRADE_OFFICE_CARRY_BONUS_PERCENT",
+                defaults.trade_office_carry_bonus_percent,
+            )?,
+            adventure_min_level_short: env_or(
+                "ADVENTURE_MIN_LEVEL_SHORT",
+                defaults.adventure_min_level_short,
+            )?,
+            adventure_min_level_long: env_or(
+                "ADVENTURE_MIN_LEVEL_LONG",
+                defaults.adventure_min_level_long,
+            )?,
+            adventure_level_bonus_percent: env_or(
+                "ADVENTURE_LEVEL_BONUS_PERCENT",
+                defaults.adventure_level_bonus_percent,
+            )?,
+            adventure_level_bonus_max_percent: env_or(
+                "ADVENTURE_LEVEL_BONUS_MAX_PERCENT",
+                defaults.adventure_level_bonus_max_percent,
+            )?,
+            adventure_level_damage_reduction_percent: env_or(
+                "ADVENTURE_LEVEL_DAMAGE_REDUCTION_PERCENT",
+                defaults.adventure_level_damage_reduction_percent,
+            )?,
+            adventure_rng_seed: env::var("ADVENTURE_RNG_SEED")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse())
+                .transpose()
+                .context("Invalid ADVENTURE_RNG_SEED")?,
+            adventure_midpoint_duration: env_or(
+                "ADVENTURE_MIDPOINT_DURATION",
+                defaults.adventure_midpoint_duration,
+            )?,
+            adventure_generation_lock_seconds: env_or(
+                "ADVENTURE_GENERATION_LOCK_SECONDS",
+                defaults.adventure_generation_lock_seconds,
+            )?,
+            village_overview_cache_seconds: env_or(
+                "VILLAGE_OVERVIEW_CACHE_SECONDS",
+                defaults.village_overview_cache_seconds,
+            )?,
+            hero_exp_base: env_or("HERO_EXP_BASE", defaults.hero_exp_base)?,
+            hero_exp_growth: env_or("HERO_EXP_GROWTH", defaults.hero_exp_growth)?,
+            hero_max_level: env_or("HERO_MAX_LEVEL", defaults.hero_max_level)?,
+            hero_revive_health_gold: env_or(
+                "HERO_REVIVE_HEALTH_GOLD",
+                defaults.hero_revive_health_gold,
+            )?,
+            hero_revive_health_natural: env_or(
+                "HERO_REVIVE_HEALTH_NATURAL",
+                defaults.hero_revive_health_natural,
+            )?,
+            hero_revive_health_resources: env_or(
+                "HERO_REVIVE_HEALTH_RESOURCES",
+                defaults.hero_revive_health_resources,
+            )?,
+            starting_garrison: parse_troop_counts(
+                &env::var("STARTING_GARRISON").unwrap_or_default(),
+            )
+            .context("Invalid STARTING_GARRISON")?,
+            max_schedule_ahead_hours: env_or(
+                "MAX_SCHEDULE_AHEAD_HOURS",
+                defaults.max_schedule_ahead_hours,
+            )?,
+            max_armies_per_village: env_or(
+                "MAX_ARMIES_PER_VILLAGE",
+                defaults.max_armies_per_village,
+            )?,
+            max_armies_per_player: env_or("MAX_ARMIES_PER_PLAYER", defaults.max_armies_per_player)?,
+            max_villages_per_player: env_or(
+                "MAX_VILLAGES_PER_PLAYER",
+                defaults.max_villages_per_player,
+            )?,
+            village_base_production: env_or(
+                "VILLAGE_BASE_PRODUCTION",
+                defaults.village_base_production,
+            )?,
+            field_production_base: env_or("FIELD_PRODUCTION_BASE", defaults.field_production_base)?,
+            field_production_growth: env_or(
+                "FIELD_PRODUCTION_GROWTH",
+                defaults.field_production_growth,
+            )?,
+            field_production_curve: env_or(
+                "FIELD_PRODUCTION_CURVE",
+                defaults.field_production_curve,
+            )?,
+            conquest_loyalty_reset: env_or(
+                "CONQUEST_LOYALTY_RESET",
+                defaults.conquest_loyalty_reset,
+            )?,
+            max_loyalty_reduction_per_attack: env_or(
+                "MAX_LOYALTY_REDUCTION_PER_ATTACK",
+                defaults.max_loyalty_reduction_per_attack,
+            )?,
+            max_army_size: env_or("MAX_ARMY_SIZE", defaults.max_army_size)?,
+            settlers_per_village: env_or("SETTLERS_PER_VILLAGE", defaults.settlers_per_village)?,
+            allow_capital_conquest: env_or(
+                "ALLOW_CAPITAL_CONQUEST",
+                defaults.allow_capital_conquest,
+            )?,
+            defensive_support_only: env_or(
+                "DEFENSIVE_SUPPORT_ONLY",
+                defaults.defensive_support_only,
+            )?,
+            disband_refund_percent: env_or(
+                "DISBAND_REFUND_PERCENT",
+                defaults.disband_refund_percent,
+            )?,
+            demolish_refund_percent: env_or(
+                "DEMOLISH_REFUND_PERCENT",
+                defaults.demolish_refund_percent,
+            )?,
+            upgrade_cancel_refund_percent: env_or(
+                "UPGRADE_CANCEL_REFUND_PERCENT",
+                defaults.upgrade_cancel_refund_percent,
+            )?,
+            hero_production_bonus_max_percent: env_or(
+                "HERO_PRODUCTION_BONUS_MAX_PERCENT",
+                defaults.hero_production_bonus_max_percent,
+            )?,
+            oasis_max_distance: env_or("OASIS_MAX_DISTANCE", defaults.oasis_max_distance)?,
+            wall_defense_bonus_percent_per_level: env_or(
+                "WALL_DEFENSE_BONUS_PERCENT_PER_LEVEL",
+                defaults.wall_defense_bonus_percent_per_level,
+            )?,
+            wall_defense_bonus_max_percent: env_or(
+                "WALL_DEFENSE_BONUS_MAX_PERCENT",
+                defaults.wall_defense_bonus_max_percent,
+            )?,
+            battle_min_winner_loss_percent: env_or(
+                "BATTLE_MIN_WINNER_LOSS_PERCENT",
+                defaults.battle_min_winner_loss_percent,
+            )?,
+            tribe_phasuttha_attack_bonus_percent: env_or(
+                "TRIBE_PHASUTTHA_ATTACK_BONUS_PERCENT",
+                defaults.tribe_phasuttha_attack_bonus_percent,
+            )?,
+            tribe_phasuttha_defense_bonus_percent: env_or(
+                "TRIBE_PHASUTTHA_DEFENSE_BONUS_PERCENT",
+                defaults.tribe_phasuttha_defense_bonus_percent,
+            )?,
+            tribe_nava_attack_bonus_percent: env_or(
+                "TRIBE_NAVA_ATTACK_BONUS_PERCENT",
+                defaults.tribe_nava_attack_bonus_percent,
+            )?,
+            tribe_nava_defense_bonus_percent: env_or(
+                "TRIBE_NAVA_DEFENSE_BONUS_PERCENT",
+                defaults.tribe_nava_defense_bonus_percent,
+            )?,
+            tribe_kiri_attack_bonus_percent: env_or(
+                "TRIBE_KIRI_ATTACK_BONUS_PERCENT",
+                defaults.tribe_kiri_attack_bonus_percent,
+            )?,
+            tribe_kiri_defense_bonus_percent: env_or(
+                "TRIBE_KIRI_DEFENSE_BONUS_PERCENT",
+                defaults.tribe_kiri_defense_bonus_percent,
+            )?,
+            show_support_origin: env_or("SHOW_SUPPORT_ORIGIN", defaults.show_support_origin)?,
+            starvation_returns_support: env_or(
+                "STARVATION_RETURNS_SUPPORT",
+                defaults.starvation_returns_support,
+            )?,
+            scout_success_ratio: env_or("SCOUT_SUCCESS_RATIO", defaults.scout_success_ratio)?,
+            scout_exact_ratio: env_or("SCOUT_EXACT_RATIO", defaults.scout_exact_ratio)?,
+            scout_winner_loss_factor: env_or(
+                "SCOUT_WINNER_LOSS_FACTOR",
+                defaults.scout_winner_loss_factor,
+            )?,
+            scout_loser_min_loss: env_or("SCOUT_LOSER_MIN_LOSS", defaults.scout_loser_min_loss)?,
+            scout_defender_loss_factor: env_or(
+                "SCOUT_DEFENDER_LOSS_FACTOR",
+                defaults.scout_defender_loss_factor,
+            )?,
+            scout_defender_win_loss: env_or(
+                "SCOUT_DEFENDER_WIN_LOSS",
+                defaults.scout_defender_win_loss,
+            )?,
+            scout_always_notify_defender: env_or(
+                "SCOUT_ALWAYS_NOTIFY_DEFENDER",
+                defaults.scout_always_notify_defender,
+            )?,
+            scout_intel_hours: env_or("SCOUT_INTEL_HOURS", defaults.scout_intel_hours)?,
+            beginner_protection_hours: env_or(
+                "BEGINNER_PROTECTION_HOURS",
+                defaults.beginner_protection_hours,
+            )?,
         })
     }
 }
@@ -92,3 +522,64 @@ impl DatabaseConfig {
         )
     }
 }
+
+/// Parse a comma-separated list of `troop_type:count` pairs, e.g. `infantry:10,spearman:5`
+fn parse_troop_counts(value: &str) -> Result<Vec<(TroopType, i32)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, count) = entry
+                .split_once(':')
+                .with_context(|| format!("Expected troop_type:count, got '{}'", entry))?;
+            let troop_type: TroopType =
+                serde_json::from_value(serde_json::Value::String(name.trim().to_string()))
+                    .with_context(|| format!("Unknown troop type '{}'", name.trim()))?;
+            let count: i32 = count
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid count for '{}'", name.trim()))?;
+            anyhow::ensure!(count > 0, "Count for '{}' must be positive", name.trim());
+            Ok((troop_type, count))
+        })
+        .collect()
+}
+
+/// Parse the variable `name` from the environment, falling back to `default` when it is not set
+fn env_or<T>(name: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match env::var(name) {
+        Ok(value) => value.parse().with_context(|| format!("Invalid {}", name)),
+        Err(_) => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starting_garrison_is_parsed_from_troop_counts() {
+        assert_eq!(
+            parse_troop_counts("infantry:10, spearman:5").unwrap(),
+            vec![(TroopType::Infantry, 10), (TroopType::Spearman, 5)]
+        );
+    }
+
+    #[test]
+    fn new_villages_start_empty_by_default() {
+        assert!(GameConfig::default().starting_garrison.is_empty());
+        assert!(parse_troop_counts("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_starting_garrison_is_rejected() {
+        assert!(parse_troop_counts("infantry").is_err());
+        assert!(parse_troop_counts("dragon:3").is_err());
+        assert!(parse_troop_counts("infantry:0").is_err());
+    }
+}
@@ -1,9 +1,10 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::config::DatabaseConfig;
+use crate::models::troop::TroopType;
 
 pub async fn create_pool(config: &DatabaseConfig) -> Result<PgPool> {
     let pool = PgPoolOptions::new()
@@ -20,3 +21,54 @@ pub async fn create_pool(config: &DatabaseConfig) -> Result<PgPool> {
 
     Ok(pool)
 }
+
+/// Verify every troop type has a troop_definitions row.
+/// Battles and travel times silently fall back to defaults without one,
+/// so a missing row is treated as a misconfigured database.
+pub async fn verify_troop_definitions(pool: &PgPool) -> Result<()> {
+    let defined: Vec<(TroopType,)> = sqlx::query_as("SELECT troop_type FROM troop_definitions")
+        .fetch_all(pool)
+        .await?;
+
+    let defined: Vec<TroopType> = defined.into_iter().map(|(troop_type,)| troop_type).collect();
+    let missing = missing_troop_definitions(&defined);
+
+    if !missing.is_empty() {
+        for troop_type in &missing {
+            error!("Missing troop definition for {:?}", troop_type);
+        }
+        bail!("{} troop definitions are missing; run the seed migrations", missing.len());
+    }
+
+    info!("All {} troop definitions present", TroopType::ALL.len());
+
+    Ok(())
+}
+
+/// Troop types without a definition among `defined`, in declaration order
+fn missing_troop_definitions(defined: &[TroopType]) -> Vec<TroopType> {
+    TroopType::ALL
+        .into_iter()
+        .filter(|t| !defined.contains(t))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_definitions_pass_verification() {
+        assert!(missing_troop_definitions(&TroopType::ALL).is_empty());
+    }
+
+    #[test]
+    fn missing_definition_is_detected() {
+        let defined: Vec<TroopType> = TroopType::ALL
+            .into_iter()
+            .filter(|t| *t != TroopType::BattleDuck)
+            .collect();
+
+        assert_eq!(missing_troop_definitions(&defined), vec![TroopType::BattleDuck]);
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{
+    extract::rejection::PathRejection,
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -20,6 +21,9 @@ pub enum AppError {
     #[error("{0}")]
     BadRequest(String),
 
+    #[error("Invalid path parameter: {0}")]
+    InvalidPath(String),
+
     #[error("{0}")]
     Conflict(String),
 
@@ -33,6 +37,33 @@ pub enum AppError {
     ValidationError(String),
 }
 
+impl AppError {
+    /// Not-found error naming the resource type and the id that was looked up
+    pub fn not_found(resource: &str, id: impl std::fmt::Display) -> Self {
+        AppError::NotFound(format!("{} {} not found", resource, id))
+    }
+
+    /// Stable machine-readable identifier for the error kind
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::InvalidPath(_) => "invalid_path",
+            AppError::Conflict(_) => "conflict",
+            AppError::ValidationError(_) => "validation_error",
+            AppError::InternalError(_) | AppError::DatabaseError(_) => "internal_error",
+        }
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        AppError::InvalidPath(rejection.body_text())
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, message) = match &self {
@@ -40,6 +71,7 @@ impl IntoResponse for AppError {
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::InvalidPath(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
             AppError::ValidationError(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
             AppError::InternalError(_) | AppError::DatabaseError(_) => {
@@ -51,7 +83,8 @@ impl IntoResponse for AppError {
         let body = Json(json!({
             "error": {
                 "message": message,
-                "code": status.as_u16()
+                "code": status.as_u16(),
+                "error_code": self.error_code()
             }
         }));
 
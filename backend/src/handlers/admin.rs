@@ -0,0 +1,23 @@
+use axum::{extract::State, Extension, Json};
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::AuthenticatedUser;
+use crate::services::job_status_service::JobStatus;
+use crate::AppState;
+
+// GET /api/admin/jobs - Last run, duration, processed count and error of each background job
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> AppResult<Json<Vec<JobStatus>>> {
+    if !state
+        .config
+        .server
+        .admin_firebase_uids
+        .contains(&auth_user.firebase_uid)
+    {
+        return Err(AppError::Forbidden("Admin access required".into()));
+    }
+
+    Ok(Json(state.jobs.snapshot().await))
+}
@@ -0,0 +1,54 @@
+use axum::{extract::State, Extension, Json};
+use uuid::Uuid;
+
+use super::extract::Path;
+use crate::error::{AppError, AppResult};
+use crate::middleware::AuthenticatedUser;
+use crate::models::api_token::{ApiTokenResponse, CreateApiTokenRequest, CreatedApiTokenResponse};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::api_token_service::ApiTokenService;
+use crate::AppState;
+
+// GET /api/auth/api-tokens - List the player's API tokens
+pub async fn list_tokens(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> AppResult<Json<Vec<ApiTokenResponse>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let tokens = ApiTokenService::list_tokens(&state.db, user.id).await?;
+
+    Ok(Json(tokens))
+}
+
+// POST /api/auth/api-tokens - Create a read-only API token
+pub async fn create_token(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(body): Json<CreateApiTokenRequest>,
+) -> AppResult<Json<CreatedApiTokenResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let token = ApiTokenService::create_token(&state.db, user.id, body).await?;
+
+    Ok(Json(token))
+}
+
+// DELETE /api/auth/api-tokens/:token_id - Revoke an API token
+pub async fn revoke_token(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(token_id): Path<Uuid>,
+) -> AppResult<Json<()>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    ApiTokenService::revoke_token(&state.db, user.id, token_id).await?;
+
+    Ok(Json(()))
+}
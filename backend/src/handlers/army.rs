@@ -1,17 +1,24 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Query, State},
     Extension, Json,
 };
 use tracing::info;
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::AuthenticatedUser;
-use crate::models::army::{ArmyResponse, BattleReportResponse, ScoutReportResponse, SendArmyRequest};
+use crate::models::army::{
+    ArmyResponse, BattleReportResponse, ConquerPreviewQuery, ConquerPreviewResponse,
+    DefenseSummaryResponse, PlayerArmiesResponse,
+    ScoutReportResponse, SendArmyRequest, StationedSupportResponse, TravelTimeRequest,
+    TravelTimeResponse,
+};
 use crate::repositories::army_repo::ArmyRepository;
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
 use crate::services::army_service::ArmyService;
+use crate::services::cache_service::CacheService;
 use crate::AppState;
 
 // POST /api/villages/:village_id/armies - Send army
@@ -27,13 +34,26 @@ pub async fn send_army(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
     }
 
-    let response = ArmyService::send_army(&state.db, user.id, village_id, body).await?;
+    let response = ArmyService::send_army(&state.db, &state.config.game, user.id, village_id, body).await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    // Let the defender's incoming badge update live; scheduled armies stay hidden until they depart
+    if response.mission.is_hostile() && !response.is_scheduled {
+        if let Some(target_id) = response.to_village_id {
+            if let Some(target) = VillageRepository::find_by_id(&state.db, target_id).await? {
+                if target.user_id != user.id {
+                    ArmyService::notify_incoming_count(&state.db, &state.ws, target.user_id, target.id).await?;
+                }
+            }
+        }
+    }
 
     info!(
         "Army sent from village {} to ({}, {})",
@@ -43,6 +63,55 @@ pub async fn send_army(
     Ok(Json(response))
 }
 
+// POST /api/villages/:village_id/travel-time - Preview an army's journey without sending it
+pub async fn preview_travel_time(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Json(body): Json<TravelTimeRequest>,
+) -> AppResult<Json<TravelTimeResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let preview = ArmyService::preview_travel(&state.db, &state.config.game, &village, body).await?;
+
+    Ok(Json(preview))
+}
+
+// GET /api/villages/:village_id/conquer-preview - Check a target's conquerability and waves needed
+pub async fn conquer_preview(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Query(query): Query<ConquerPreviewQuery>,
+) -> AppResult<Json<ConquerPreviewResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let preview =
+        ArmyService::conquer_preview(&state.db, &state.config.game, &village, query).await?;
+
+    Ok(Json(preview))
+}
+
 // GET /api/villages/:village_id/armies/outgoing - List outgoing armies
 pub async fn list_outgoing(
     State(state): State<AppState>,
@@ -55,7 +124,7 @@ pub async fn list_outgoing(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -78,7 +147,7 @@ pub async fn list_incoming(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -123,7 +192,7 @@ pub async fn get_report(
 
     let report = ArmyService::get_report(&state.db, report_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Report", report_id))?;
 
     // Check if user is involved in this battle
     let is_attacker = report.attacker_player_id == user.id;
@@ -205,7 +274,7 @@ pub async fn get_scout_report(
 
     let report = ArmyService::get_scout_report(&state.db, report_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Scout report not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Scout report", report_id))?;
 
     // Check if user is involved
     let is_attacker = report.attacker_player_id == user.id;
@@ -242,25 +311,62 @@ pub async fn list_stationed(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(village_id): Path<Uuid>,
-) -> AppResult<Json<Vec<ArmyResponse>>> {
+) -> AppResult<Json<Vec<StationedSupportResponse>>> {
     let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
         .await?
         .ok_or(AppError::Unauthorized)?;
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     // Only village owner can see stationed troops
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
     }
 
-    let armies = ArmyService::get_stationed_at_village(&state.db, village_id).await?;
+    let armies = ArmyService::get_stationed_at_village(&state.db, &state.config.game, village_id).await?;
+
+    Ok(Json(armies))
+}
+
+// GET /api/armies - List all of the player's armies across villages
+pub async fn list_player_armies(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> AppResult<Json<PlayerArmiesResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let armies = ArmyService::get_player_armies(&state.db, user.id).await?;
 
     Ok(Json(armies))
 }
 
+// GET /api/villages/:id/defense-summary - Total defense including stationed support
+pub async fn get_defense_summary(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<DefenseSummaryResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let summary = ArmyService::get_defense_summary(&state.db, &state.config.game, &village).await?;
+
+    Ok(Json(summary))
+}
+
 // GET /api/support-sent - Get support troops sent by player to other villages
 pub async fn list_support_sent(
     State(state): State<AppState>,
@@ -287,7 +393,60 @@ pub async fn recall_support(
 
     let response = ArmyService::recall_support(&state.db, army_id, user.id).await?;
 
+    // Stationed troops eat crop at the host village
+    if let Some(host_village_id) = response.to_village_id {
+        CacheService::invalidate_village(&state.redis, host_village_id).await;
+    }
+
     info!("Support army {} recalled by player {}", army_id, user.id);
 
     Ok(Json(response))
 }
+
+// POST /api/villages/:village_id/support/recall-all - Recall all of the caller's support stationed at a village
+pub async fn recall_all_support(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<Vec<ArmyResponse>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    // The caller owns the support armies, not necessarily the host village
+    VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    let recalled = ArmyService::recall_all_support_at(&state.db, village_id, user.id).await?;
+
+    if !recalled.is_empty() {
+        CacheService::invalidate_village(&state.redis, village_id).await;
+    }
+
+    info!(
+        "{} support armies recalled from village {} by player {}",
+        recalled.len(),
+        village_id,
+        user.id
+    );
+
+    Ok(Json(recalled))
+}
+
+// POST /api/armies/:army_id/cancel - Cancel a scheduled army before departure
+pub async fn cancel_scheduled(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(army_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    ArmyService::cancel_scheduled(&state.db, army_id, user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Scheduled army cancelled"
+    })))
+}
@@ -2,9 +2,10 @@ use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::middleware::AuthenticatedUser;
-use crate::models::user::{CreateUser, UserResponse};
+use crate::models::troop::TribeType;
+use crate::models::user::{CreateUser, PlayerSettings, UpdatePlayerSettings, UserResponse};
 use crate::repositories::user_repo::UserRepository;
 use crate::AppState;
 
@@ -125,6 +126,49 @@ pub async fn update_profile(
     Ok(Json(user.into()))
 }
 
+// GET /api/auth/settings - Get gameplay settings
+pub async fn get_settings(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> AppResult<Json<PlayerSettings>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let settings = UserRepository::get_settings(&state.db, user.id)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    Ok(Json(settings))
+}
+
+// PUT /api/auth/settings - Update gameplay settings
+pub async fn update_settings(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(body): Json<UpdatePlayerSettings>,
+) -> AppResult<Json<PlayerSettings>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    if let Some(tribe) = body.tribe {
+        if tribe == TribeType::Special {
+            return Err(AppError::BadRequest("Special is not a playable tribe".into()));
+        }
+        match UserRepository::get_tribe(&state.db, user.id).await? {
+            Some(current) if current != tribe => {
+                return Err(AppError::BadRequest("Tribe has already been chosen".into()));
+            }
+            _ => {}
+        }
+    }
+
+    let settings = UserRepository::update_settings(&state.db, user.id, &body).await?;
+
+    Ok(Json(settings))
+}
+
 // DELETE /api/auth/account - Soft delete user account
 pub async fn delete_account(
     State(state): State<AppState>,
@@ -1,19 +1,24 @@
-use axum::{
-    extract::{Path, State},
-    Extension, Json,
-};
+use axum::{extract::State, Extension, Json};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::AuthenticatedUser;
-use crate::models::building::{BuildingCost, BuildingResponse, BuildingType, CreateBuilding};
+use crate::models::activity::VillageActivityType;
+use crate::models::building::{
+    BuildingCost, BuildingQueueEntry, BuildingResponse, BuildingSlotResponse, BuildingType,
+    CancelQueueResponse, CreateBuilding,
+};
 use crate::repositories::building_repo::BuildingRepository;
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::activity_service::ActivityService;
 use crate::services::building_service::BuildingService;
+use crate::services::cache_service::CacheService;
+use crate::services::resource_service::ResourceService;
 use crate::AppState;
 
 // GET /api/villages/:village_id/buildings - List buildings in a village
@@ -28,7 +33,7 @@ pub async fn list_buildings(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -39,6 +44,29 @@ pub async fn list_buildings(
     Ok(Json(buildings.into_iter().map(|b| b.into()).collect()))
 }
 
+// GET /api/villages/:village_id/slots - List all building slots, including empty ones
+pub async fn list_slots(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<Vec<BuildingSlotResponse>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let slots = BuildingService::list_slots(&state.db, village_id).await?;
+
+    Ok(Json(slots))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BuildRequest {
     pub building_type: BuildingType,
@@ -63,7 +91,7 @@ pub async fn build(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -115,6 +143,14 @@ pub async fn build(
     let upgrade_ends_at = Utc::now() + chrono::Duration::seconds(cost.time_seconds as i64);
     let building = BuildingRepository::start_upgrade(&state.db, building.id, upgrade_ends_at).await?;
 
+    CacheService::invalidate_village(&state.redis, village_id).await;
+    ActivityService::record(
+        &state.db,
+        village_id,
+        VillageActivityType::BuildingStarted,
+        ActivityService::building_started(&building, 1),
+    );
+
     info!(
         "Building {:?} started at slot {} in village {}",
         body.building_type, slot, village_id
@@ -144,7 +180,7 @@ pub async fn upgrade(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -152,7 +188,7 @@ pub async fn upgrade(
 
     let building = BuildingRepository::find_by_village_and_slot(&state.db, village_id, slot)
         .await?
-        .ok_or_else(|| AppError::NotFound("Building not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Building in slot", slot))?;
 
     if building.is_upgrading {
         return Err(AppError::Conflict("Building is already upgrading".to_string()));
@@ -189,6 +225,14 @@ pub async fn upgrade(
     let upgrade_ends_at = Utc::now() + chrono::Duration::seconds(cost.time_seconds as i64);
     let building = BuildingRepository::start_upgrade(&state.db, building.id, upgrade_ends_at).await?;
 
+    CacheService::invalidate_village(&state.redis, village_id).await;
+    ActivityService::record(
+        &state.db,
+        village_id,
+        VillageActivityType::BuildingStarted,
+        ActivityService::building_started(&building, next_level),
+    );
+
     info!(
         "Upgrading {:?} to level {} in village {}",
         building.building_type, next_level, village_id
@@ -200,6 +244,94 @@ pub async fn upgrade(
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpgradeToRequest {
+    pub target_level: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpgradeToResponse {
+    pub building: BuildingResponse,
+    pub queued: Vec<BuildingQueueEntry>,
+    pub total_cost: BuildingCost,
+}
+
+// POST /api/villages/:village_id/buildings/:slot/upgrade-to - Queue upgrades up to a target level
+pub async fn upgrade_to(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((village_id, slot)): Path<(Uuid, i32)>,
+    Json(body): Json<UpgradeToRequest>,
+) -> AppResult<Json<UpgradeToResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let building = BuildingRepository::find_by_village_and_slot(&state.db, village_id, slot)
+        .await?
+        .ok_or_else(|| AppError::not_found("Building in slot", slot))?;
+
+    let (building, queued, total_cost) =
+        BuildingService::queue_upgrades_to(&state.db, &village, &building, body.target_level)
+            .await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!(
+        "Queued {:?} upgrades to level {} in village {}",
+        building.building_type, body.target_level, village_id
+    );
+
+    Ok(Json(UpgradeToResponse {
+        building: building.into(),
+        queued,
+        total_cost,
+    }))
+}
+
+// DELETE /api/villages/:village_id/buildings/queue - Cancel every upgrade in progress
+pub async fn cancel_queue(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<CancelQueueResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    // Bring stock up to date so the refund lands on current amounts
+    ResourceService::update_village_resources(&state.db, &state.config.game, village_id).await?;
+
+    let result =
+        BuildingService::cancel_village_queue(&state.db, &state.config.game, village_id).await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!(
+        "Cancelled {} upgrades in village {}",
+        result.cancelled.len(),
+        village_id
+    );
+
+    Ok(Json(result))
+}
+
 // DELETE /api/villages/:village_id/buildings/:slot - Demolish building
 pub async fn demolish(
     State(state): State<AppState>,
@@ -212,7 +344,7 @@ pub async fn demolish(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -220,16 +352,12 @@ pub async fn demolish(
 
     let building = BuildingRepository::find_by_village_and_slot(&state.db, village_id, slot)
         .await?
-        .ok_or_else(|| AppError::NotFound("Building not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Building in slot", slot))?;
 
-    // Some buildings cannot be demolished
-    if building.building_type == BuildingType::MainBuilding && building.level > 0 {
-        return Err(AppError::BadRequest(
-            "Cannot demolish Main Building".to_string(),
-        ));
-    }
+    let (released_oases, refund) =
+        BuildingService::demolish(&state.db, &state.config.game, &building).await?;
 
-    BuildingRepository::demolish(&state.db, building.id).await?;
+    CacheService::invalidate_village(&state.redis, village_id).await;
 
     info!(
         "Building {:?} demolished at slot {} in village {}",
@@ -237,7 +365,14 @@ pub async fn demolish(
     );
 
     Ok(Json(serde_json::json!({
-        "message": "Building demolished successfully"
+        "message": "Building demolished successfully",
+        "released_oases": released_oases,
+        "refund": {
+            "wood": refund.wood,
+            "clay": refund.clay,
+            "iron": refund.iron,
+            "crop": refund.crop,
+        }
     })))
 }
 
@@ -253,7 +388,7 @@ pub async fn get_build_queue(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -0,0 +1,54 @@
+use axum::extract::FromRequestParts;
+
+use crate::error::AppError;
+
+/// Path extractor that reports malformed parameters (e.g. a bad UUID) as an
+/// `AppError` so they get the same JSON error body as every other failure
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body, Bytes};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::Service;
+    use uuid::Uuid;
+
+    async fn get_village(uri: &str) -> (StatusCode, Bytes) {
+        let mut app = Router::new().route(
+            "/villages/:village_id",
+            get(|Path(village_id): Path<Uuid>| async move { village_id.to_string() }),
+        );
+        let response = app
+            .call(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        (status, to_bytes(response.into_body(), usize::MAX).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn malformed_uuid_is_a_json_bad_request() {
+        let (status, body) = get_village("/villages/not-a-uuid").await;
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], 400);
+        assert_eq!(body["error"]["error_code"], "invalid_path");
+        assert!(body["error"]["message"].as_str().is_some_and(|m| !m.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn valid_uuid_reaches_the_handler() {
+        let village_id = Uuid::new_v4();
+        let (status, body) = get_village(&format!("/villages/{}", village_id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, village_id.to_string());
+    }
+}
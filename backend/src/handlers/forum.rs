@@ -0,0 +1,116 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::extract::Path;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::forum::{
+    CreatePostRequest, CreateThreadRequest, ForumPostResponse, ForumThreadListItem,
+    ForumThreadResponse, PinThreadRequest,
+};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::forum_service::ForumService;
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct PaginationQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i32,
+    #[serde(default)]
+    pub offset: i32,
+}
+
+fn default_limit() -> i32 {
+    20
+}
+
+/// GET /api/alliances/:id/forum/threads - List forum threads
+pub async fn list_threads(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(alliance_id): Path<Uuid>,
+    Query(query): Query<PaginationQuery>,
+) -> AppResult<Json<Vec<ForumThreadListItem>>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let threads =
+        ForumService::list_threads(&state.db, db_user.id, alliance_id, query.limit, query.offset)
+            .await?;
+    Ok(Json(threads))
+}
+
+/// POST /api/alliances/:id/forum/threads - Start a new thread
+pub async fn create_thread(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(alliance_id): Path<Uuid>,
+    Json(request): Json<CreateThreadRequest>,
+) -> AppResult<Json<ForumThreadListItem>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let thread = ForumService::create_thread(&state.db, db_user.id, alliance_id, request).await?;
+    Ok(Json(thread))
+}
+
+/// GET /api/alliances/:id/forum/threads/:thread_id - Get a thread and its posts
+pub async fn get_thread(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((alliance_id, thread_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<PaginationQuery>,
+) -> AppResult<Json<ForumThreadResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let thread = ForumService::get_thread(
+        &state.db,
+        db_user.id,
+        alliance_id,
+        thread_id,
+        query.limit,
+        query.offset,
+    )
+    .await?;
+    Ok(Json(thread))
+}
+
+/// POST /api/alliances/:id/forum/threads/:thread_id/posts - Reply to a thread
+pub async fn create_post(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((alliance_id, thread_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<CreatePostRequest>,
+) -> AppResult<Json<ForumPostResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let post =
+        ForumService::create_post(&state.db, db_user.id, alliance_id, thread_id, request).await?;
+    Ok(Json(post))
+}
+
+/// PUT /api/alliances/:id/forum/threads/:thread_id/pin - Pin or unpin a thread
+pub async fn pin_thread(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((alliance_id, thread_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<PinThreadRequest>,
+) -> AppResult<Json<()>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    ForumService::set_pinned(&state.db, db_user.id, alliance_id, thread_id, request.pinned)
+        .await?;
+    Ok(Json(()))
+}
@@ -1,16 +1,20 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Query, State},
     Extension, Json,
 };
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::AuthenticatedUser;
 use crate::models::hero::{
-    AssignAttributesRequest, AvailableAdventureResponse, ChangeHomeVillageRequest,
+    AssignAttributesRequest, AvailableAdventureResponse, BulkSellItemsRequest,
+    BulkSellItemsResponse, ChangeHomeVillageRequest,
     CreateHeroRequest, EquipItemRequest, HeroAdventureResponse, HeroItemResponse, HeroListResponse,
     HeroResponse, HeroSlotPurchaseResponse, InventoryResponse, ItemSlot, ReviveHeroRequest,
-    ReviveInfoResponse, StartAdventureRequest, UnequipItemRequest, UseItemRequest,
+    ReviveInfoResponse, SellItemResponse, StartAdventureRequest, TransferHeroRequest,
+    UnequipItemRequest,
+    UseItemRequest,
 };
 use crate::repositories::user_repo::UserRepository;
 use crate::services::hero_service::HeroService;
@@ -27,7 +31,9 @@ pub async fn list_heroes(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let heroes = HeroService::get_user_heroes(&state.db, db_user.id).await?;
+    let heroes =
+        HeroService::get_user_heroes(&state.db, db_user.id, state.config.game.max_hero_slots)
+            .await?;
     Ok(Json(heroes))
 }
 
@@ -55,7 +61,14 @@ pub async fn create_hero(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let hero = HeroService::create_hero(&state.db, db_user.id, request).await?;
+    let hero = HeroService::create_hero(
+        &state.db,
+        &state.redis,
+        &state.config.game,
+        db_user.id,
+        request,
+    )
+    .await?;
     Ok(Json(hero))
 }
 
@@ -75,6 +88,21 @@ pub async fn change_home_village(
     Ok(Json(hero))
 }
 
+/// POST /api/heroes/{id}/transfer - Gift hero to another player
+pub async fn transfer_hero(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(hero_id): Path<Uuid>,
+    Json(request): Json<TransferHeroRequest>,
+) -> AppResult<Json<HeroResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let hero = HeroService::transfer_hero(&state.db, db_user.id, hero_id, request).await?;
+    Ok(Json(hero))
+}
+
 /// PUT /api/heroes/{id}/attributes - Assign attribute points
 pub async fn assign_attributes(
     State(state): State<AppState>,
@@ -90,6 +118,21 @@ pub async fn assign_attributes(
     Ok(Json(hero))
 }
 
+/// GET /api/heroes/{id}/attributes/preview - Preview attribute assignment without saving
+pub async fn preview_attributes(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(hero_id): Path<Uuid>,
+    Query(request): Query<AssignAttributesRequest>,
+) -> AppResult<Json<HeroResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let hero = HeroService::preview_attributes(&state.db, db_user.id, hero_id, request).await?;
+    Ok(Json(hero))
+}
+
 // ==================== Hero Slots ====================
 
 /// POST /api/heroes/slots/buy - Buy additional hero slot
@@ -101,7 +144,9 @@ pub async fn buy_hero_slot(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let result = HeroService::buy_hero_slot(&state.db, db_user.id).await?;
+    let result =
+        HeroService::buy_hero_slot(&state.db, db_user.id, state.config.game.max_hero_slots)
+            .await?;
     Ok(Json(result))
 }
 
@@ -171,16 +216,28 @@ pub async fn sell_item(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
     Path((hero_id, item_id)): Path<(Uuid, Uuid)>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<Json<SellItemResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let result = HeroService::sell_item(&state.db, db_user.id, hero_id, item_id).await?;
+    Ok(Json(result))
+}
+
+/// POST /api/heroes/{id}/inventory/sell - Sell several unequipped items at once
+pub async fn sell_items(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(hero_id): Path<Uuid>,
+    Json(request): Json<BulkSellItemsRequest>,
+) -> AppResult<Json<BulkSellItemsResponse>> {
     let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let sell_value = HeroService::sell_item(&state.db, db_user.id, hero_id, item_id).await?;
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "silver_gained": sell_value
-    })))
+    let result = HeroService::sell_items(&state.db, db_user.id, hero_id, request).await?;
+    Ok(Json(result))
 }
 
 // ==================== Adventures ====================
@@ -194,7 +251,8 @@ pub async fn get_available_adventures(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let adventures = HeroService::get_available_adventures(&state.db, db_user.id).await?;
+    let adventures =
+        HeroService::get_available_adventures(&state.db, &state.config.game, db_user.id).await?;
     Ok(Json(adventures))
 }
 
@@ -210,7 +268,14 @@ pub async fn start_adventure(
         .ok_or(AppError::Unauthorized)?;
 
     let adventure =
-        HeroService::start_adventure(&state.db, db_user.id, hero_id, request.adventure_id).await?;
+        HeroService::start_adventure(
+            &state.db,
+            &state.config.game,
+            db_user.id,
+            hero_id,
+            request.adventure_id,
+        )
+        .await?;
     Ok(Json(adventure))
 }
 
@@ -228,6 +293,41 @@ pub async fn get_active_adventure(
     Ok(Json(adventure))
 }
 
+/// GET /api/heroes/{id}/adventures/history - Get finished adventures with their rewards
+pub async fn get_adventure_history(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(hero_id): Path<Uuid>,
+) -> AppResult<Json<Vec<HeroAdventureResponse>>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let adventures = HeroService::get_adventure_history(&state.db, db_user.id, hero_id).await?;
+    Ok(Json(adventures))
+}
+
+/// POST /api/heroes/{id}/adventures/{adventure_id}/cancel - Cancel active adventure early
+pub async fn cancel_adventure(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((hero_id, adventure_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<HeroAdventureResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let adventure = HeroService::cancel_adventure(
+        &state.db,
+        &state.config.game,
+        db_user.id,
+        hero_id,
+        adventure_id,
+    )
+    .await?;
+    Ok(Json(adventure))
+}
+
 // ==================== Revive ====================
 
 /// GET /api/heroes/{id}/revive-info - Get revive info for dead hero
@@ -255,6 +355,14 @@ pub async fn revive_hero(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let hero = HeroService::revive_hero(&state.db, db_user.id, hero_id, request.use_gold).await?;
+    let hero = HeroService::revive_hero(
+        &state.db,
+        &state.redis,
+        &state.config.game,
+        db_user.id,
+        hero_id,
+        request.method(),
+    )
+    .await?;
     Ok(Json(hero))
 }
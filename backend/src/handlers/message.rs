@@ -1,10 +1,11 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Query, State},
     Extension, Json,
 };
 use serde::Deserialize;
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::AuthenticatedUser;
 use crate::models::message::{
@@ -1,10 +1,18 @@
+mod admin;
 mod alliance;
+mod api_token;
 mod army;
 mod auth;
 mod building;
+mod extract;
+mod forum;
 mod hero;
 mod message;
+mod oasis;
+mod player;
+mod reference;
 mod shop;
+mod transfer;
 mod troop;
 mod village;
 pub mod ws;
@@ -30,6 +38,8 @@ pub fn routes(state: AppState) -> Router<AppState> {
         .nest("/alliance-messages", alliance_message_routes(state.clone()))
         .nest("/shop", shop_routes(state.clone()))
         .nest("/heroes", hero_routes(state.clone()))
+        .nest("/players", player_routes(state.clone()))
+        .nest("/admin", admin_routes(state.clone()))
         // Public routes (no auth required)
         .merge(public_routes())
 }
@@ -37,6 +47,8 @@ pub fn routes(state: AppState) -> Router<AppState> {
 fn public_routes() -> Router<AppState> {
     Router::new()
         .route("/troops/definitions", get(troop::get_definitions))
+        .route("/reference/troops", get(reference::get_troops))
+        .route("/reference/items", get(reference::get_items))
 }
 
 fn auth_routes(state: AppState) -> Router<AppState> {
@@ -44,8 +56,13 @@ fn auth_routes(state: AppState) -> Router<AppState> {
         .route("/me", get(auth::me))
         .route("/sync", post(auth::sync_user))
         .route("/profile", put(auth::update_profile))
+        .route("/settings", get(auth::get_settings))
+        .route("/settings", put(auth::update_settings))
         .route("/account", delete(auth::delete_account))
         .route("/logout", delete(auth::logout))
+        .route("/api-tokens", get(api_token::list_tokens))
+        .route("/api-tokens", post(api_token::create_token))
+        .route("/api-tokens/{token_id}", delete(api_token::revoke_token))
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
@@ -55,28 +72,56 @@ fn village_routes(state: AppState) -> Router<AppState> {
         .route("/", post(village::create_village))
         .route("/{id}", get(village::get_village))
         .route("/{id}", put(village::update_village))
+        .route("/{id}/projection", get(village::get_projection))
+        .route("/{id}/production/breakdown", get(village::get_production_breakdown))
+        .route("/{id}/loyalty", get(village::get_loyalty))
+        .route("/{id}/activity", get(village::get_activity))
+        .route("/{id}/npc-merchant/suggest", get(shop::suggest_npc_split))
         // Building routes nested under village
         .route("/{village_id}/buildings", get(building::list_buildings))
+        .route("/{village_id}/slots", get(building::list_slots))
         .route("/{village_id}/buildings/queue", get(building::get_build_queue))
+        .route("/{village_id}/buildings/queue", delete(building::cancel_queue))
         .route("/{village_id}/buildings/{slot}", post(building::build))
         .route("/{village_id}/buildings/{slot}/upgrade", post(building::upgrade))
+        .route("/{village_id}/buildings/{slot}/upgrade-to", post(building::upgrade_to))
         .route("/{village_id}/buildings/{slot}", delete(building::demolish))
         // Troop routes nested under village
         .route("/{village_id}/troops", get(troop::list_troops))
+        .route("/{village_id}/troops/upkeep", get(troop::get_upkeep))
+        .route("/{village_id}/available-troops", get(troop::get_available_troops))
         .route("/{village_id}/troops/queue", get(troop::get_training_queue))
+        .route("/{village_id}/troops/queue/summary", get(troop::get_queue_summary))
         .route("/{village_id}/troops/train", post(troop::train_troops))
         .route("/{village_id}/troops/queue/{queue_id}", delete(troop::cancel_training))
+        .route("/{village_id}/troops/reserve", get(troop::get_reserve))
+        .route("/{village_id}/troops/reserve", put(troop::set_reserve))
+        .route("/{village_id}/troops/disband", post(troop::disband_troops))
         // Army routes nested under village
         .route("/{village_id}/armies", post(army::send_army))
+        .route("/{village_id}/travel-time", post(army::preview_travel_time))
+        .route("/{village_id}/conquer-preview", get(army::conquer_preview))
         .route("/{village_id}/armies/outgoing", get(army::list_outgoing))
         .route("/{village_id}/armies/incoming", get(army::list_incoming))
         .route("/{village_id}/stationed", get(army::list_stationed))
+        .route("/{village_id}/support/recall-all", post(army::recall_all_support))
+        .route("/{village_id}/defense-summary", get(army::get_defense_summary))
+        // Merchant transfer routes nested under village
+        .route("/{village_id}/transfers", get(transfer::list_transfers))
+        .route("/{village_id}/transfers", post(transfer::send_resources))
+        .route("/{village_id}/transfers/bulk", post(transfer::send_resources_bulk))
+        // Oasis routes nested under village
+        .route("/{village_id}/oases", get(oasis::get_village_oases))
+        .route("/{village_id}/oases/{oasis_id}", post(oasis::occupy_oasis))
+        .route("/{village_id}/oases/{oasis_id}", delete(oasis::abandon_oasis))
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
 fn map_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/", get(village::get_map))
+        .route("/search", get(village::search_map))
+        .route("/oases", get(oasis::list_oases))
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
@@ -105,7 +150,9 @@ fn scout_report_routes(state: AppState) -> Router<AppState> {
 
 fn army_routes(state: AppState) -> Router<AppState> {
     Router::new()
+        .route("/", get(army::list_player_armies))
         .route("/{army_id}/recall", post(army::recall_support))
+        .route("/{army_id}/cancel", post(army::cancel_scheduled))
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
@@ -115,6 +162,18 @@ fn support_routes(state: AppState) -> Router<AppState> {
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
+fn player_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/{id}", get(player::get_player))
+        .route_layer(middleware::from_fn_with_state(state, auth_middleware))
+}
+
+fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/jobs", get(admin::list_jobs))
+        .route_layer(middleware::from_fn_with_state(state, auth_middleware))
+}
+
 fn alliance_routes(state: AppState) -> Router<AppState> {
     Router::new()
         // Alliance CRUD
@@ -136,6 +195,12 @@ fn alliance_routes(state: AppState) -> Router<AppState> {
         // Diplomacy
         .route("/{id}/diplomacy", get(alliance::list_diplomacy))
         .route("/{id}/diplomacy", post(alliance::set_diplomacy))
+        // Forum
+        .route("/{id}/forum/threads", get(forum::list_threads))
+        .route("/{id}/forum/threads", post(forum::create_thread))
+        .route("/{id}/forum/threads/{thread_id}", get(forum::get_thread))
+        .route("/{id}/forum/threads/{thread_id}/posts", post(forum::create_post))
+        .route("/{id}/forum/threads/{thread_id}/pin", put(forum::pin_thread))
         .route_layer(middleware::from_fn_with_state(state, auth_middleware))
 }
 
@@ -179,6 +244,7 @@ fn shop_routes(state: AppState) -> Router<AppState> {
         .route("/checkout", post(shop::create_checkout))
         .route("/subscriptions/buy", post(shop::buy_subscription))
         .route("/transactions", get(shop::get_transactions))
+        .route("/gold-usage", get(shop::get_gold_usage))
         // Gold features
         .route("/features/finish-now", post(shop::use_finish_now))
         .route("/features/npc-merchant", post(shop::use_npc_merchant))
@@ -194,7 +260,9 @@ fn hero_routes(state: AppState) -> Router<AppState> {
         .route("/", post(hero::create_hero))
         .route("/{id}", get(hero::get_hero))
         .route("/{id}/home", put(hero::change_home_village))
+        .route("/{id}/transfer", post(hero::transfer_hero))
         .route("/{id}/attributes", put(hero::assign_attributes))
+        .route("/{id}/attributes/preview", get(hero::preview_attributes))
         // Hero Slots
         .route("/slots/buy", post(hero::buy_hero_slot))
         // Inventory
@@ -203,10 +271,13 @@ fn hero_routes(state: AppState) -> Router<AppState> {
         .route("/{id}/unequip", post(hero::unequip_item))
         .route("/{id}/use-item", post(hero::use_item))
         .route("/{hero_id}/items/{item_id}", delete(hero::sell_item))
+        .route("/{id}/inventory/sell", post(hero::sell_items))
         // Adventures
         .route("/adventures/available", get(hero::get_available_adventures))
         .route("/{id}/adventures", post(hero::start_adventure))
         .route("/{id}/adventures/active", get(hero::get_active_adventure))
+        .route("/{id}/adventures/history", get(hero::get_adventure_history))
+        .route("/{id}/adventures/{adventure_id}/cancel", post(hero::cancel_adventure))
         // Revive
         .route("/{id}/revive-info", get(hero::get_revive_info))
         .route("/{id}/revive", post(hero::revive_hero))
@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use super::extract::Path;
+use crate::error::{AppError, AppResult};
+use crate::middleware::AuthenticatedUser;
+use crate::models::oasis::{Oasis, OasisRangeQuery, VillageOasesResponse};
+use crate::repositories::map_repo::MapRepository;
+use crate::repositories::oasis_repo::OasisRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::repositories::village_repo::VillageRepository;
+use crate::services::cache_service::CacheService;
+use crate::services::oasis_service::OasisService;
+use crate::services::resource_service::ResourceService;
+use crate::services::village_service::VillageService;
+use crate::AppState;
+
+// GET /api/map/oases - Oases around a map position
+pub async fn list_oases(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<OasisRangeQuery>,
+) -> AppResult<Json<Vec<Oasis>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let game = &state.config.game;
+    let range = query
+        .range
+        .unwrap_or(game.map_default_range)
+        .clamp(1, game.map_max_range);
+
+    let mut oases = OasisRepository::find_in_range(&state.db, query.x, query.y, range).await?;
+
+    // With fog enabled, oases follow the same visibility rules as villages on the map
+    if game.map_fog_enabled {
+        let own_villages = VillageRepository::find_by_user_id(&state.db, user.id).await?;
+        let discovered =
+            MapRepository::find_discovered_in_range(&state.db, user.id, query.x, query.y, range)
+                .await?;
+        oases.retain(|o| {
+            // Oases held by the viewer count as their own; free and foreign ones do not
+            let holder = if own_villages.iter().any(|v| o.village_id == Some(v.id)) {
+                user.id
+            } else {
+                Uuid::nil()
+            };
+            VillageService::is_visible_through_fog(
+                user.id,
+                holder,
+                (o.x, o.y),
+                &own_villages,
+                &discovered,
+                game.map_visibility_radius,
+            )
+        });
+    }
+
+    Ok(Json(oases))
+}
+
+// GET /api/villages/:village_id/oases - Oases the village holds and its mansion cap
+pub async fn get_village_oases(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<VillageOasesResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let response = OasisService::get_village_oases(&state.db, village.id).await?;
+    Ok(Json(response))
+}
+
+// POST /api/villages/:village_id/oases/:oasis_id - Occupy a free oasis
+pub async fn occupy_oasis(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((village_id, oasis_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Oasis>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    // Settle production at the old rate before the oasis bonus changes it
+    ResourceService::update_village_resources(&state.db, &state.config.game, village_id).await?;
+
+    let oasis = OasisService::occupy(&state.db, &state.config.game, &village, oasis_id).await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    Ok(Json(oasis))
+}
+
+// DELETE /api/villages/:village_id/oases/:oasis_id - Abandon an occupied oasis
+pub async fn abandon_oasis(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((village_id, oasis_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    // Settle production at the old rate before the oasis bonus is lost
+    ResourceService::update_village_resources(&state.db, &state.config.game, village_id).await?;
+
+    OasisService::abandon(&state.db, village.id, oasis_id).await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!("Village {} abandoned oasis {}", village_id, oasis_id);
+
+    Ok(Json(serde_json::json!({
+        "message": "Oasis abandoned",
+        "oasis_id": oasis_id
+    })))
+}
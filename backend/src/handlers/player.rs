@@ -0,0 +1,19 @@
+use axum::{extract::State, Json};
+use uuid::Uuid;
+
+use super::extract::Path;
+use crate::error::AppResult;
+use crate::models::user::PublicPlayerProfile;
+use crate::services::player_service::PlayerService;
+use crate::AppState;
+
+// GET /api/players/{id} - Public profile of a player
+pub async fn get_player(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+) -> AppResult<Json<PublicPlayerProfile>> {
+    let profile =
+        PlayerService::get_public_profile(&state.db, &state.config.game, player_id).await?;
+
+    Ok(Json(profile))
+}
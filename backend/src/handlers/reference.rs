@@ -0,0 +1,26 @@
+use axum::{extract::State, Json};
+
+use crate::error::AppResult;
+use crate::models::hero::ItemDefinitionResponse;
+use crate::models::troop::TroopDefinitionResponse;
+use crate::services::hero_service::HeroService;
+use crate::services::troop_service::TroopService;
+use crate::AppState;
+
+// GET /api/reference/troops - Every troop definition with stats, costs and requirements (public endpoint)
+pub async fn get_troops(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<TroopDefinitionResponse>>> {
+    let definitions = TroopService::get_definitions(&state.db).await?;
+
+    Ok(Json(definitions))
+}
+
+// GET /api/reference/items - Every hero item with its bonuses and rarity (public endpoint)
+pub async fn get_items(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<ItemDefinitionResponse>>> {
+    let items = HeroService::get_item_definitions(&state.db).await?;
+
+    Ok(Json(items))
+}
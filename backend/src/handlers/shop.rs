@@ -1,29 +1,44 @@
 use axum::{
     body::Bytes,
-    extract::{Path, Query, State},
+    extract::{Query, State},
     http::HeaderMap,
     Extension, Json,
 };
 use serde::Deserialize;
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::auth::AuthenticatedUser;
 use crate::models::shop::{
-    BuySubscriptionRequest, CheckoutResponse, GoldBalanceResponse, GoldPackage,
-    PurchaseGoldRequest, SubscriptionPrice, TransactionResponse, UseBookOfWisdomRequest,
-    UseFeatureResponse, UseFinishNowRequest, UseNpcMerchantRequest, UseProductionBonusRequest,
+    BuySubscriptionRequest, CheckoutResponse, GoldBalanceResponse, GoldFeature, GoldPackage,
+    GoldUsageListResponse, NpcSplitQuery, NpcSplitSuggestion, PurchaseGoldRequest,
+    SubscriptionPrice, TransactionListResponse, TransactionStatus, TransactionType,
+    UseBookOfWisdomRequest, UseFeatureResponse, UseFinishNowRequest, UseNpcMerchantRequest,
+    UseProductionBonusRequest,
 };
 use crate::repositories::user_repo::UserRepository;
+use crate::services::cache_service::CacheService;
 use crate::services::shop_service::ShopService;
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
-pub struct PaginationQuery {
+pub struct TransactionQuery {
     #[serde(default = "default_limit")]
     pub limit: i32,
     #[serde(default)]
     pub offset: i32,
+    pub transaction_type: Option<TransactionType>,
+    pub status: Option<TransactionStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GoldUsageQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i32,
+    #[serde(default)]
+    pub offset: i32,
+    pub feature: Option<GoldFeature>,
 }
 
 fn default_limit() -> i32 {
@@ -168,9 +183,27 @@ pub async fn use_npc_merchant(
         request.crop,
     )
     .await?;
+    CacheService::invalidate_village(&state.redis, request.village_id).await;
+
     Ok(Json(result))
 }
 
+/// GET /api/villages/:id/npc-merchant/suggest - Suggest an NPC merchant split
+pub async fn suggest_npc_split(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Query(query): Query<NpcSplitQuery>,
+) -> AppResult<Json<NpcSplitSuggestion>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let suggestion =
+        ShopService::suggest_npc_split(&state.db, db_user.id, village_id, query).await?;
+    Ok(Json(suggestion))
+}
+
 /// POST /api/shop/features/production-bonus - Activate +25% production
 pub async fn use_production_bonus(
     State(state): State<AppState>,
@@ -188,6 +221,8 @@ pub async fn use_production_bonus(
         &request.resource_type,
     )
     .await?;
+    CacheService::invalidate_village(&state.redis, request.village_id).await;
+
     Ok(Json(result))
 }
 
@@ -202,22 +237,105 @@ pub async fn use_book_of_wisdom(
         .ok_or(AppError::Unauthorized)?;
 
     let result = ShopService::use_book_of_wisdom(&state.db, db_user.id, request.village_id).await?;
+    CacheService::invalidate_village(&state.redis, request.village_id).await;
+
     Ok(Json(result))
 }
 
 // ==================== Transactions ====================
 
-/// GET /api/shop/transactions - Get transaction history
+/// GET /api/shop/transactions - Get transaction history (filter by type/status, paginated)
 pub async fn get_transactions(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
-    Query(query): Query<PaginationQuery>,
-) -> AppResult<Json<Vec<TransactionResponse>>> {
+    Query(query): Query<TransactionQuery>,
+) -> AppResult<Json<TransactionListResponse>> {
     let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    let transactions =
-        ShopService::get_transactions(&state.db, db_user.id, query.limit, query.offset).await?;
+    let transactions = ShopService::get_transactions(
+        &state.db,
+        db_user.id,
+        query.transaction_type,
+        query.status,
+        query.limit,
+        query.offset,
+    )
+    .await?;
     Ok(Json(transactions))
 }
+
+/// GET /api/shop/gold-usage - Get gold spending history (filter by feature, paginated)
+pub async fn get_gold_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<GoldUsageQuery>,
+) -> AppResult<Json<GoldUsageListResponse>> {
+    let db_user = UserRepository::find_by_firebase_uid(&state.db, &user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let usage = ShopService::get_gold_usage(
+        &state.db,
+        db_user.id,
+        query.feature,
+        query.limit,
+        query.offset,
+    )
+    .await?;
+    Ok(Json(usage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Uri;
+
+    #[test]
+    fn transaction_query_parses_filters_and_page() {
+        let uri: Uri = "/api/shop/transactions?transaction_type=gold_spend&status=completed&limit=10&offset=30"
+            .parse()
+            .unwrap();
+        let Query(query) = Query::<TransactionQuery>::try_from_uri(&uri).unwrap();
+
+        assert_eq!(query.transaction_type, Some(TransactionType::GoldSpend));
+        assert_eq!(query.status, Some(TransactionStatus::Completed));
+        assert_eq!((query.limit, query.offset), (10, 30));
+    }
+
+    #[test]
+    fn transaction_query_defaults_to_first_unfiltered_page() {
+        let uri: Uri = "/api/shop/transactions".parse().unwrap();
+        let Query(query) = Query::<TransactionQuery>::try_from_uri(&uri).unwrap();
+
+        assert_eq!(query.transaction_type, None);
+        assert_eq!(query.status, None);
+        assert_eq!((query.limit, query.offset), (20, 0));
+    }
+
+    #[test]
+    fn gold_usage_query_filters_by_feature() {
+        let uri: Uri = "/api/shop/gold-usage?feature=npc_merchant&limit=5".parse().unwrap();
+        let Query(query) = Query::<GoldUsageQuery>::try_from_uri(&uri).unwrap();
+
+        assert_eq!(query.feature, Some(GoldFeature::NpcMerchant));
+        assert_eq!((query.limit, query.offset), (5, 0));
+    }
+
+    #[test]
+    fn gold_usage_query_lists_every_feature_by_default() {
+        let uri: Uri = "/api/shop/gold-usage".parse().unwrap();
+        let Query(query) = Query::<GoldUsageQuery>::try_from_uri(&uri).unwrap();
+
+        assert_eq!(query.feature, None);
+        assert_eq!((query.limit, query.offset), (20, 0));
+    }
+
+    #[test]
+    fn unknown_gold_feature_is_rejected() {
+        let uri: Uri = "/api/shop/gold-usage?feature=free_gold".parse().unwrap();
+
+        assert!(Query::<GoldUsageQuery>::try_from_uri(&uri).is_err());
+    }
+}
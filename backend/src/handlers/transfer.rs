@@ -0,0 +1,94 @@
+use axum::{extract::State, Extension, Json};
+use tracing::info;
+use uuid::Uuid;
+
+use super::extract::Path;
+use crate::error::{AppError, AppResult};
+use crate::middleware::AuthenticatedUser;
+use crate::models::transfer::{
+    BulkSendResourcesRequest, BulkTransferResponse, ResourceTransferResponse,
+    SendResourcesRequest, VillageTransfersResponse,
+};
+use crate::repositories::user_repo::UserRepository;
+use crate::repositories::village_repo::VillageRepository;
+use crate::services::cache_service::CacheService;
+use crate::services::transfer_service::TransferService;
+use crate::AppState;
+
+// POST /api/villages/:village_id/transfers - Send resources by merchant
+pub async fn send_resources(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Json(body): Json<SendResourcesRequest>,
+) -> AppResult<Json<ResourceTransferResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let response =
+        TransferService::send_resources(&state.db, &state.config.game, user.id, village_id, body)
+            .await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!(
+        "Resources sent from village {} to village {}",
+        village_id, response.to_village_id
+    );
+
+    Ok(Json(response))
+}
+
+// POST /api/villages/:village_id/transfers/bulk - Send resources to several own villages
+pub async fn send_resources_bulk(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Json(body): Json<BulkSendResourcesRequest>,
+) -> AppResult<Json<BulkTransferResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let response = TransferService::send_resources_bulk(
+        &state.db,
+        &state.config.game,
+        user.id,
+        village_id,
+        body,
+    )
+    .await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!(
+        "Bulk resources sent from village {} using {} merchants",
+        village_id, response.merchants_used
+    );
+
+    Ok(Json(response))
+}
+
+// GET /api/villages/:village_id/transfers - List merchants leaving and arriving
+pub async fn list_transfers(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<VillageTransfersResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let transfers = TransferService::get_village_transfers(&state.db, village_id).await?;
+
+    Ok(Json(transfers))
+}
@@ -1,19 +1,19 @@
-use axum::{
-    extract::{Path, State},
-    Extension, Json,
-};
+use axum::{extract::State, Extension, Json};
 use tracing::info;
 use uuid::Uuid;
 
+use super::extract::Path;
 use crate::error::{AppError, AppResult};
 use crate::middleware::AuthenticatedUser;
 use crate::models::troop::{
-    TrainTroopsRequest, TrainTroopsResponse, TroopDefinitionResponse, TroopQueueResponse,
-    TroopResponse,
+    DisbandTroopsRequest, DisbandTroopsResponse, SetTroopReserveRequest, TrainTroopsRequest,
+    TrainTroopsResponse, TroopAvailability, TroopDefinitionResponse, TroopQueueResponse,
+    TroopQueueSummaryResponse, TroopReserveResponse, TroopResponse, TroopUpkeepResponse,
 };
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
 use crate::services::troop_service::TroopService;
+use crate::services::cache_service::CacheService;
 use crate::AppState;
 
 // GET /api/troops/definitions - Get all troop definitions (public endpoint)
@@ -22,7 +22,7 @@ pub async fn get_definitions(
 ) -> AppResult<Json<Vec<TroopDefinitionResponse>>> {
     let definitions = TroopService::get_definitions(&state.db).await?;
 
-    Ok(Json(definitions.into_iter().map(|d| d.into()).collect()))
+    Ok(Json(definitions))
 }
 
 // GET /api/villages/:village_id/troops - Get troops in a village
@@ -37,7 +37,7 @@ pub async fn list_troops(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -48,6 +48,52 @@ pub async fn list_troops(
     Ok(Json(troops.into_iter().map(|t| t.into()).collect()))
 }
 
+// GET /api/villages/:village_id/troops/upkeep - Crop upkeep breakdown for troops and buildings
+pub async fn get_upkeep(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<TroopUpkeepResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let upkeep = TroopService::get_upkeep(&state.db, &state.config.game, village_id).await?;
+
+    Ok(Json(upkeep))
+}
+
+// GET /api/villages/:village_id/available-troops - Which troops can be trained here and what is missing
+pub async fn get_available_troops(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<Vec<TroopAvailability>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let availability = TroopService::get_availability(&state.db, village_id).await?;
+
+    Ok(Json(availability))
+}
+
 // GET /api/villages/:village_id/troops/queue - Get training queue
 pub async fn get_training_queue(
     State(state): State<AppState>,
@@ -60,7 +106,7 @@ pub async fn get_training_queue(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -71,6 +117,29 @@ pub async fn get_training_queue(
     Ok(Json(queue.into_iter().map(|q| q.into()).collect()))
 }
 
+// GET /api/villages/:village_id/troops/queue/summary - Pending units and finish time per production building
+pub async fn get_queue_summary(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<TroopQueueSummaryResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let summary = TroopService::get_queue_summary(&state.db, village_id).await?;
+
+    Ok(Json(summary))
+}
+
 // POST /api/villages/:village_id/troops/train - Train troops
 pub async fn train_troops(
     State(state): State<AppState>,
@@ -84,7 +153,7 @@ pub async fn train_troops(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -92,6 +161,8 @@ pub async fn train_troops(
 
     let response = TroopService::train_troops(&state.db, village_id, body.troop_type, body.count).await?;
 
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
     info!(
         "Training {} {:?} in village {}",
         body.count, body.troop_type, village_id
@@ -112,7 +183,7 @@ pub async fn cancel_training(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -120,9 +191,89 @@ pub async fn cancel_training(
 
     TroopService::cancel_training(&state.db, village_id, queue_id).await?;
 
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
     info!("Training cancelled in village {}", village_id);
 
     Ok(Json(serde_json::json!({
         "message": "Training cancelled successfully"
     })))
 }
+
+// GET /api/villages/:village_id/troops/reserve - Get troops kept home when sending armies
+pub async fn get_reserve(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<TroopReserveResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let reserve = TroopService::get_reserve(&state.db, village_id).await?;
+
+    Ok(Json(reserve))
+}
+
+// PUT /api/villages/:village_id/troops/reserve - Set troops kept home when sending armies
+pub async fn set_reserve(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Json(body): Json<SetTroopReserveRequest>,
+) -> AppResult<Json<TroopReserveResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let reserve = TroopService::set_reserve(&state.db, village_id, body.reserves).await?;
+
+    info!("Troop reserve updated for village {}", village_id);
+
+    Ok(Json(reserve))
+}
+
+// POST /api/villages/:village_id/troops/disband - Permanently dismiss troops at home
+pub async fn disband_troops(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Json(body): Json<DisbandTroopsRequest>,
+) -> AppResult<Json<DisbandTroopsResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let response =
+        TroopService::disband_troops(&state.db, &state.config.game, village_id, body.troops).await?;
+
+    CacheService::invalidate_village(&state.redis, village_id).await;
+
+    info!("Disbanded {:?} in village {}", response.disbanded, village_id);
+
+    Ok(Json(response))
+}
@@ -1,16 +1,25 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Query, State},
     Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use uuid::Uuid;
 
+use super::extract::Path;
+use crate::config::GameConfig;
 use crate::error::{AppError, AppResult};
 use crate::middleware::AuthenticatedUser;
-use crate::models::village::{CreateVillage, ProductionRates, UpdateVillage, VillageResponse};
+use crate::models::activity::VillageActivityListResponse;
+use crate::models::village::{
+    CreateVillage, ProductionBreakdownResponse, ProductionRates, ResourceProjectionResponse,
+    UpdateVillage, VillageLoyaltyResponse, VillageMapInfo, VillageResponse,
+};
+use crate::repositories::map_repo::MapRepository;
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::activity_service::ActivityService;
+use crate::services::cache_service::CacheService;
 use crate::services::resource_service::ResourceService;
 use crate::services::village_service::VillageService;
 use crate::AppState;
@@ -42,18 +51,22 @@ pub async fn get_village(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     // Check ownership
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
     }
 
+    if let Some(cached) = CacheService::get_village_overview(&state.redis, village_id).await {
+        return Ok(Json(cached));
+    }
+
     // Update resources based on time elapsed before returning
-    let village = ResourceService::update_village_resources(&state.db, village_id).await?;
+    let village = ResourceService::update_village_resources(&state.db, &state.config.game, village_id).await?;
 
     // Calculate production rates
-    let production = ResourceService::calculate_production(&state.db, village_id).await?;
+    let production = ResourceService::calculate_production(&state.db, &state.config.game, village_id).await?;
     let production_rates = ProductionRates {
         wood_per_hour: production.wood_per_hour,
         clay_per_hour: production.clay_per_hour,
@@ -64,7 +77,114 @@ pub async fn get_village(
     };
 
     let response: VillageResponse = village.into();
-    Ok(Json(response.with_production(production_rates)))
+    let response = response.with_production(production_rates);
+    CacheService::set_village_overview(
+        &state.redis,
+        &response,
+        state.config.game.village_overview_cache_seconds,
+    )
+    .await;
+
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectionQuery {
+    pub target_wood: Option<i32>,
+    pub target_clay: Option<i32>,
+    pub target_iron: Option<i32>,
+    pub target_crop: Option<i32>,
+}
+
+// GET /api/villages/:id/projection - Time until resource targets are reached / stores overflow
+pub async fn get_projection(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Query(query): Query<ProjectionQuery>,
+) -> AppResult<Json<ResourceProjectionResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let projection = ResourceService::project_resources(
+        &state.db,
+        &state.config.game,
+        village_id,
+        [query.target_wood, query.target_clay, query.target_iron, query.target_crop],
+    )
+    .await?;
+
+    Ok(Json(projection))
+}
+
+// GET /api/villages/:id/production/breakdown - Every contribution to the village's production
+pub async fn get_production_breakdown(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+) -> AppResult<Json<ProductionBreakdownResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let breakdown =
+        ResourceService::calculate_breakdown(&state.db, &state.config.game, village_id).await?;
+
+    Ok(Json(breakdown))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    #[serde(default = "default_activity_limit")]
+    pub limit: i32,
+    #[serde(default)]
+    pub offset: i32,
+}
+
+fn default_activity_limit() -> i32 {
+    50
+}
+
+// GET /api/villages/:id/activity - Village history (builds, training, armies, attacks), newest first
+pub async fn get_activity(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(village_id): Path<Uuid>,
+    Query(query): Query<ActivityQuery>,
+) -> AppResult<Json<VillageActivityListResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, village_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let activity =
+        ActivityService::get_activity(&state.db, village_id, query.limit, query.offset).await?;
+
+    Ok(Json(activity))
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +204,13 @@ pub async fn create_village(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
+    if !VillageService::can_own_another_village(&state.db, &state.config.game, user.id).await? {
+        return Err(AppError::BadRequest(format!(
+            "You already own the maximum of {} villages",
+            state.config.game.max_villages_per_player
+        )));
+    }
+
     // Check if coordinates are available
     if !VillageRepository::is_coordinate_available(&state.db, body.x, body.y).await? {
         return Err(AppError::Conflict("Coordinates already occupied".to_string()));
@@ -102,7 +229,15 @@ pub async fn create_village(
     };
 
     // Create village with initial buildings
-    let (village, buildings) = VillageService::create_village_with_buildings(&state.db, create_village).await?;
+    let mut tx = state.db.begin().await?;
+    let (village, buildings) =
+        VillageService::create_village_with_buildings(&mut tx, create_village).await?;
+
+    // The first village is the player's capital; only later settlements get a garrison
+    if !is_capital {
+        VillageService::grant_starting_garrison(&mut tx, &state.config.game, village.id).await?;
+    }
+    tx.commit().await?;
 
     info!(
         "Village created: {} at ({}, {}) for user {} with {} initial buildings",
@@ -130,7 +265,7 @@ pub async fn update_village(
 
     let village = VillageRepository::find_by_id(&state.db, village_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Village not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
     if village.user_id != user.id {
         return Err(AppError::Forbidden("Access denied".into()));
@@ -138,22 +273,50 @@ pub async fn update_village(
 
     let update = UpdateVillage { name: body.name };
     let updated = VillageRepository::update(&state.db, village_id, update).await?;
+    CacheService::invalidate_village(&state.redis, village_id).await;
 
     Ok(Json(updated.into()))
 }
 
 // Map endpoints
 
+// GET /api/villages/:id/loyalty - Get loyalty status and recent conquer hits
+pub async fn get_loyalty(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<VillageLoyaltyResponse>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let village = VillageRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Village", id))?;
+
+    if village.user_id != user.id {
+        return Err(AppError::Forbidden("Access denied".into()));
+    }
+
+    let loyalty = VillageService::get_loyalty(&state.db, &village).await?;
+
+    Ok(Json(loyalty))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MapQuery {
     pub x: i32,
     pub y: i32,
-    #[serde(default = "default_range")]
-    pub range: i32,
+    pub range: Option<i32>,
 }
 
-fn default_range() -> i32 {
-    7
+impl MapQuery {
+    /// Requested range, defaulting and capped per the game config (minimum 1)
+    fn clamped_range(&self, game: &GameConfig) -> i32 {
+        self.range
+            .unwrap_or(game.map_default_range)
+            .clamp(1, game.map_max_range.max(1))
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -168,10 +331,55 @@ pub struct MapVillageInfo {
     pub id: Uuid,
     pub name: String,
     pub player_name: Option<String>,
+    pub alliance_tag: Option<String>,
     pub population: i32,
     pub is_own: bool,
 }
 
+impl MapVillageInfo {
+    /// A map village as seen by `viewer_id`; the tag is null for owners without an alliance
+    fn for_viewer(v: &VillageMapInfo, viewer_id: Uuid) -> Self {
+        Self {
+            id: v.id,
+            name: v.name.clone(),
+            player_name: v.player_name.clone(),
+            alliance_tag: v.alliance_tag.clone(),
+            population: v.population,
+            is_own: v.user_id == viewer_id,
+        }
+    }
+}
+
+/// Most villages returned by a single map search
+const MAP_SEARCH_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct MapSearchQuery {
+    pub player: Option<String>,
+    pub alliance: Option<String>,
+}
+
+impl MapSearchQuery {
+    /// Trimmed player and alliance terms; at least one must be non-empty
+    fn terms(&self) -> AppResult<(Option<&str>, Option<&str>)> {
+        let player = self.player.as_deref().map(str::trim).filter(|p| !p.is_empty());
+        let alliance = self.alliance.as_deref().map(str::trim).filter(|a| !a.is_empty());
+        if player.is_none() && alliance.is_none() {
+            return Err(AppError::BadRequest(
+                "Provide a player or alliance to search for".into(),
+            ));
+        }
+        Ok((player, alliance))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MapSearchResult {
+    pub x: i32,
+    pub y: i32,
+    pub village: MapVillageInfo,
+}
+
 // GET /api/map - Get map tiles around coordinates
 pub async fn get_map(
     State(state): State<AppState>,
@@ -183,10 +391,22 @@ pub async fn get_map(
         .ok_or(AppError::Unauthorized)?;
 
     // Limit range to prevent abuse
-    let range = query.range.min(15).max(1);
+    let range = query.clamped_range(&state.config.game);
 
     let villages = VillageRepository::find_in_range(&state.db, query.x, query.y, range).await?;
 
+    // With fog enabled, only villages near own villages or on discovered tiles are revealed
+    let fog = if state.config.game.map_fog_enabled {
+        let own_villages = VillageRepository::find_by_user_id(&state.db, user.id).await?;
+        let discovered =
+            MapRepository::find_discovered_in_range(&state.db, user.id, query.x, query.y, range)
+                .await?;
+        Some((own_villages, discovered))
+    } else {
+        None
+    };
+    let visibility_radius = state.config.game.map_visibility_radius;
+
     // Generate tiles for the range
     let mut tiles = Vec::new();
     for dy in -range..=range {
@@ -194,21 +414,153 @@ pub async fn get_map(
             let x = query.x + dx;
             let y = query.y + dy;
 
-            let village = villages.iter().find(|v| v.x == x && v.y == y);
+            let village = villages.iter().find(|v| v.x == x && v.y == y).filter(|v| {
+                match &fog {
+                    Some((own_villages, discovered)) => VillageService::is_visible_through_fog(
+                        user.id,
+                        v.user_id,
+                        (v.x, v.y),
+                        own_villages,
+                        discovered,
+                        visibility_radius,
+                    ),
+                    None => true,
+                }
+            });
 
             tiles.push(MapTileResponse {
                 x,
                 y,
-                village: village.map(|v| MapVillageInfo {
-                    id: v.id,
-                    name: v.name.clone(),
-                    player_name: v.player_name.clone(),
-                    population: v.population,
-                    is_own: v.user_id == user.id,
-                }),
+                village: village.map(|v| MapVillageInfo::for_viewer(v, user.id)),
             });
         }
     }
 
     Ok(Json(tiles))
 }
+
+// GET /api/map/search - Find villages by player display name or alliance tag/name
+pub async fn search_map(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<MapSearchQuery>,
+) -> AppResult<Json<Vec<MapSearchResult>>> {
+    let user = UserRepository::find_by_firebase_uid(&state.db, &auth_user.firebase_uid)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let (player, alliance) = query.terms()?;
+
+    let game = &state.config.game;
+    let fog_viewer = game.map_fog_enabled.then_some(user.id);
+
+    let villages = VillageRepository::search_by_owner(
+        &state.db,
+        player,
+        alliance,
+        fog_viewer,
+        game.map_visibility_radius,
+        MAP_SEARCH_LIMIT,
+    )
+    .await?;
+
+    Ok(Json(
+        villages
+            .into_iter()
+            .map(|v| MapSearchResult {
+                x: v.x,
+                y: v.y,
+                village: MapVillageInfo::for_viewer(&v, user.id),
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Uri;
+
+    use crate::test_support::game_config;
+
+    fn map_query(uri: &str) -> MapQuery {
+        let uri: Uri = uri.parse().unwrap();
+        Query::<MapQuery>::try_from_uri(&uri).unwrap().0
+    }
+
+    fn map_village(alliance_tag: Option<&str>) -> VillageMapInfo {
+        VillageMapInfo {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Ayutthaya".to_string(),
+            x: 3,
+            y: 4,
+            population: 120,
+            player_name: Some("Naresuan".to_string()),
+            alliance_tag: alliance_tag.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn map_village_shows_the_owners_alliance_tag() {
+        let allied = map_village(Some("SIAM"));
+        let info = MapVillageInfo::for_viewer(&allied, Uuid::new_v4());
+
+        assert_eq!(info.alliance_tag.as_deref(), Some("SIAM"));
+        assert!(!info.is_own);
+        assert!(MapVillageInfo::for_viewer(&allied, allied.user_id).is_own);
+    }
+
+    #[test]
+    fn unaffiliated_owner_has_no_alliance_tag() {
+        let info = MapVillageInfo::for_viewer(&map_village(None), Uuid::new_v4());
+        assert!(info.alliance_tag.is_none());
+    }
+
+    fn search_query(uri: &str) -> MapSearchQuery {
+        let uri: Uri = uri.parse().unwrap();
+        Query::<MapSearchQuery>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn map_search_by_player_name() {
+        let query = search_query("/api/map/search?player=%20Naresuan%20");
+        assert_eq!(query.terms().unwrap(), (Some("Naresuan"), None));
+    }
+
+    #[test]
+    fn map_search_by_alliance_tag() {
+        let query = search_query("/api/map/search?alliance=SIAM&player=");
+        assert_eq!(query.terms().unwrap(), (None, Some("SIAM")));
+    }
+
+    #[test]
+    fn map_search_needs_a_player_or_alliance() {
+        let blank = search_query("/api/map/search?player=%20&alliance=");
+        assert!(matches!(blank.terms(), Err(AppError::BadRequest(_))));
+        assert!(search_query("/api/map/search").terms().is_err());
+    }
+
+    #[test]
+    fn map_range_is_capped_at_the_configured_max() {
+        let game = GameConfig {
+            map_max_range: 10,
+            ..game_config()
+        };
+
+        assert_eq!(map_query("/api/map?x=0&y=0&range=15").clamped_range(&game), 10);
+        assert_eq!(map_query("/api/map?x=0&y=0&range=4").clamped_range(&game), 4);
+    }
+
+    #[test]
+    fn map_range_defaults_from_config_and_stays_at_least_one() {
+        let game = GameConfig {
+            map_default_range: 5,
+            map_max_range: 10,
+            ..game_config()
+        };
+
+        assert_eq!(map_query("/api/map?x=0&y=0").clamped_range(&game), 5);
+        assert_eq!(map_query("/api/map?x=0&y=0&range=-3").clamped_range(&game), 1);
+    }
+}
@@ -6,6 +6,8 @@ mod middleware;
 mod models;
 mod repositories;
 mod services;
+#[cfg(test)]
+mod test_support;
 
 use axum::{routing::get, Router};
 use std::net::SocketAddr;
@@ -13,6 +15,7 @@ use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
+use services::job_status_service::JobStatusTracker;
 use services::ws_service::WsManager;
 
 #[tokio::main]
@@ -36,22 +39,34 @@ async fn main() -> anyhow::Result<()> {
     // Initialize database connections
     let db_pool = db::postgres::create_pool(&config.database).await?;
     let redis_pool = db::redis::create_pool(&config.redis).await?;
+    db::postgres::verify_troop_definitions(&db_pool).await?;
 
     info!("Database connections established");
 
     // Create WebSocket manager
     let ws_manager = WsManager::new();
 
+    // Background job status, reported by the admin API
+    let job_status = JobStatusTracker::new();
+
     // Create app state
     let state = AppState {
         db: db_pool.clone(),
-        redis: redis_pool,
+        redis: redis_pool.clone(),
         config: config.clone(),
         ws: ws_manager.clone(),
+        jobs: job_status.clone(),
     };
 
     // Start background jobs with WebSocket manager for broadcasting
-    services::background_jobs::start_background_jobs(db_pool, ws_manager).await;
+    services::background_jobs::start_background_jobs(
+        db_pool,
+        redis_pool,
+        ws_manager,
+        config.game.clone(),
+        job_status,
+    )
+    .await;
 
     // Build router
     let app = Router::new()
@@ -82,4 +97,5 @@ pub struct AppState {
     pub redis: redis::aio::ConnectionManager,
     pub config: config::Config,
     pub ws: WsManager,
+    pub jobs: JobStatusTracker,
 }
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Request, State},
+    extract::{OriginalUri, Request, State},
     middleware::Next,
     response::Response,
 };
@@ -12,6 +12,8 @@ use tokio::sync::RwLock;
 use tracing::{debug, error};
 
 use crate::error::AppError;
+use crate::models::api_token::API_TOKEN_PREFIX;
+use crate::services::api_token_service::ApiTokenService;
 use crate::AppState;
 
 // Firebase public keys cache
@@ -180,6 +182,30 @@ pub async fn auth_middleware(
         .strip_prefix("Bearer ")
         .ok_or(AppError::Unauthorized)?;
 
+    // Third-party tools authenticate with scoped, read-only API tokens
+    if token.starts_with(API_TOKEN_PREFIX) {
+        // Nested routers see a stripped path, so check scopes against the full one
+        let path = request
+            .extensions()
+            .get::<OriginalUri>()
+            .map(|uri| uri.path().to_string())
+            .unwrap_or_else(|| request.uri().path().to_string());
+
+        let owner =
+            ApiTokenService::authenticate(&state.db, token, request.method(), &path).await?;
+
+        let user = AuthenticatedUser {
+            firebase_uid: owner.firebase_uid,
+            email: owner.email,
+            name: owner.display_name,
+            picture: owner.photo_url,
+            provider: Some(owner.provider),
+        };
+        request.extensions_mut().insert(user);
+
+        return Ok(next.run(request).await);
+    }
+
     let firebase_auth = FirebaseAuth::new(state.config.firebase.project_id.clone());
     let claims = firebase_auth.verify_token(token).await?;
 
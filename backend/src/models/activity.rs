@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "village_activity_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum VillageActivityType {
+    BuildingStarted,
+    BuildingCompleted,
+    TroopsTrained,
+    ArmySent,
+    AttackReceived,
+    VillageFounded,
+    TroopsStarved,
+}
+
+/// One entry of a village's history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VillageActivity {
+    pub id: Uuid,
+    pub village_id: Uuid,
+    pub activity_type: VillageActivityType,
+    pub details: sqlx::types::Json<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VillageActivityResponse {
+    pub id: Uuid,
+    pub activity_type: VillageActivityType,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<VillageActivity> for VillageActivityResponse {
+    fn from(a: VillageActivity) -> Self {
+        Self {
+            id: a.id,
+            activity_type: a.activity_type,
+            details: a.details.0,
+            created_at: a.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VillageActivityListResponse {
+    pub activities: Vec<VillageActivityResponse>,
+    pub total: i64,
+    pub limit: i32,
+    pub offset: i32,
+}
@@ -14,6 +14,56 @@ pub enum AllianceRole {
     Member,
 }
 
+/// Actions gated by alliance role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlliancePermission {
+    UpdateAlliance,
+    Disband,
+    Invite,
+    KickMember,
+    KickOfficer,
+    ChangeRoles,
+    SetDiplomacy,
+    Broadcast,
+    ForumPost,
+    PinForumThread,
+}
+
+impl AllianceRole {
+    /// Role -> permission matrix
+    pub fn permissions(&self) -> &'static [AlliancePermission] {
+        use AlliancePermission::*;
+        match self {
+            AllianceRole::Leader => &[
+                UpdateAlliance,
+                Disband,
+                Invite,
+                KickMember,
+                KickOfficer,
+                ChangeRoles,
+                SetDiplomacy,
+                Broadcast,
+                ForumPost,
+                PinForumThread,
+            ],
+            AllianceRole::Officer => &[
+                UpdateAlliance,
+                Invite,
+                KickMember,
+                Broadcast,
+                ForumPost,
+                PinForumThread,
+            ],
+            AllianceRole::Member => &[Broadcast, ForumPost],
+        }
+    }
+
+    pub fn can(&self, permission: AlliancePermission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "invitation_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -51,11 +101,8 @@ pub struct Alliance {
 
 #[derive(Debug, Clone, FromRow)]
 pub struct AllianceMember {
-    pub id: Uuid,
     pub alliance_id: Uuid,
-    pub user_id: Uuid,
     pub role: AllianceRole,
-    pub joined_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
@@ -191,3 +238,43 @@ impl From<Alliance> for AllianceResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use AlliancePermission::*;
+
+    #[test]
+    fn leader_holds_every_permission() {
+        for permission in [
+            UpdateAlliance, Disband, Invite, KickMember, KickOfficer, ChangeRoles, SetDiplomacy,
+            Broadcast, ForumPost, PinForumThread,
+        ] {
+            assert!(AllianceRole::Leader.can(permission), "leader lacks {:?}", permission);
+        }
+    }
+
+    #[test]
+    fn officer_manages_members_but_not_the_alliance_itself() {
+        let officer = AllianceRole::Officer;
+        for permission in [UpdateAlliance, Invite, KickMember, Broadcast, ForumPost, PinForumThread] {
+            assert!(officer.can(permission), "officer lacks {:?}", permission);
+        }
+        for permission in [Disband, KickOfficer, ChangeRoles, SetDiplomacy] {
+            assert!(!officer.can(permission), "officer may {:?}", permission);
+        }
+    }
+
+    #[test]
+    fn member_can_only_post_and_broadcast() {
+        let member = AllianceRole::Member;
+        assert!(member.can(Broadcast));
+        assert!(member.can(ForumPost));
+        for permission in [
+            UpdateAlliance, Disband, Invite, KickMember, KickOfficer, ChangeRoles, SetDiplomacy,
+            PinForumThread,
+        ] {
+            assert!(!member.can(permission), "member may {:?}", permission);
+        }
+    }
+}
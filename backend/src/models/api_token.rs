@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Prefix that tells API tokens apart from Firebase ID tokens
+pub const API_TOKEN_PREFIX: &str = "tg_";
+
+/// Resource groups an API token can be granted read access to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    Villages,
+    Armies,
+    Reports,
+    Alliances,
+    Heroes,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScope::Villages => "villages",
+            ApiTokenScope::Armies => "armies",
+            ApiTokenScope::Reports => "reports",
+            ApiTokenScope::Alliances => "alliances",
+            ApiTokenScope::Heroes => "heroes",
+        }
+    }
+
+    /// Scope needed to read a path under /api. Paths without a scope (auth,
+    /// messages, shop, ...) are never reachable with an API token.
+    pub fn for_path(path: &str) -> Option<Self> {
+        let section = path
+            .trim_start_matches("/api")
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default();
+
+        match section {
+            "villages" | "map" => Some(ApiTokenScope::Villages),
+            "armies" | "support-sent" => Some(ApiTokenScope::Armies),
+            "reports" | "scout-reports" => Some(ApiTokenScope::Reports),
+            "alliances" => Some(ApiTokenScope::Alliances),
+            "heroes" => Some(ApiTokenScope::Heroes),
+            _ => None,
+        }
+    }
+}
+
+// ==================== Database Models ====================
+
+/// Token row; the hash only ever appears in lookups, so it is not loaded
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+        self.scopes.iter().any(|s| s == scope.as_str())
+    }
+}
+
+// ==================== Request DTOs ====================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+// ==================== Response DTOs ====================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returned once on creation; the plaintext token cannot be retrieved again
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: ApiTokenResponse,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(t: ApiToken) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            scopes: t.scopes,
+            last_used_at: t.last_used_at,
+            revoked_at: t.revoked_at,
+            created_at: t.created_at,
+        }
+    }
+}
@@ -1,10 +1,11 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use std::collections::HashMap;
 use uuid::Uuid;
 
 use super::troop::TroopType;
+use super::village::Village;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "mission_type", rename_all = "snake_case")]
@@ -23,6 +24,11 @@ impl MissionType {
         matches!(self, MissionType::Raid | MissionType::Attack | MissionType::Conquer | MissionType::Scout)
     }
 
+    /// Missions resolved by a battle, which an army without attack power cannot win
+    pub fn requires_combat(&self) -> bool {
+        matches!(self, MissionType::Raid | MissionType::Attack | MissionType::Conquer)
+    }
+
     pub fn is_support(&self) -> bool {
         matches!(self, MissionType::Support)
     }
@@ -50,6 +56,16 @@ pub struct CarriedResources {
 }
 
 impl CarriedResources {
+    /// Everything a village currently has in stock
+    pub fn held_by(village: &Village) -> Self {
+        Self {
+            wood: village.wood,
+            clay: village.clay,
+            iron: village.iron,
+            crop: village.crop,
+        }
+    }
+
     pub fn total(&self) -> i32 {
         self.wood + self.clay + self.iron + self.crop
     }
@@ -73,9 +89,69 @@ pub struct Army {
     pub is_returning: bool,
     pub is_stationed: bool,
     pub battle_report_id: Option<Uuid>,
+    /// Hero travelling with the army, if any
+    pub hero_id: Option<Uuid>,
+    /// Support only: hours it may stay stationed before it is sent home
+    pub max_station_hours: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
+impl Army {
+    /// Whether the army is still waiting for its scheduled departure
+    pub fn is_scheduled(&self, now: DateTime<Utc>) -> bool {
+        !self.is_returning && self.departed_at > now
+    }
+
+    /// When the army's current leg ends: arrival at the target, or back home once returning
+    pub fn current_leg_ends_at(&self) -> DateTime<Utc> {
+        if self.is_returning {
+            self.returns_at.unwrap_or(self.arrives_at)
+        } else {
+            self.arrives_at
+        }
+    }
+
+    /// Whether the army's current leg is complete and waiting to be resolved.
+    /// `ArmyRepository::find_arrived` selects exactly the armies for which this holds.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        if self.is_stationed {
+            return false;
+        }
+        if self.is_returning {
+            self.returns_at.is_some_and(|returns_at| returns_at <= now)
+        } else {
+            self.arrives_at <= now
+        }
+    }
+
+    /// Whether stationed support has stayed its max station hours and is due home
+    pub fn is_station_expired(&self, now: DateTime<Utc>) -> bool {
+        self.is_stationed
+            && self
+                .max_station_hours
+                .is_some_and(|hours| self.arrives_at + Duration::hours(hours as i64) <= now)
+    }
+}
+
+/// Input for a new army movement
+#[derive(Debug, Clone)]
+pub struct NewArmy {
+    pub player_id: Uuid,
+    pub from_village_id: Uuid,
+    pub to_x: i32,
+    pub to_y: i32,
+    pub to_village_id: Option<Uuid>,
+    pub mission: MissionType,
+    pub troops: ArmyTroops,
+    pub resources: CarriedResources,
+    pub departed_at: DateTime<Utc>,
+    pub arrives_at: DateTime<Utc>,
+    pub returns_at: Option<DateTime<Utc>>,
+    /// How long a support army may stay stationed, if limited
+    pub max_station_hours: Option<i32>,
+    pub hero_id: Option<Uuid>,
+}
+
 /// Battle report record
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct BattleReport {
@@ -90,11 +166,64 @@ pub struct BattleReport {
     pub attacker_losses: sqlx::types::Json<ArmyTroops>,
     pub defender_losses: sqlx::types::Json<ArmyTroops>,
     pub resources_stolen: sqlx::types::Json<CarriedResources>,
+    /// Defender's resources before the battle (raids and attacks only)
+    pub defender_resources: Option<sqlx::types::Json<CarriedResources>>,
     pub winner: String, // "attacker", "defender", "draw"
+    pub loyalty_reduced: i32,
+    /// Heroes that fought on either side; None when no hero took part
+    pub hero_result: Option<sqlx::types::Json<BattleHeroResult>>,
+    /// Defense bonus (percent) the defender's Wall contributed
+    pub wall_bonus_percent: i32,
     pub occurred_at: DateTime<Utc>,
     pub read_by_attacker: bool,
     pub read_by_defender: bool,
     pub created_at: DateTime<Utc>,
+    /// Current alliance tags of both players; only populated by report lookups
+    #[sqlx(default)]
+    pub attacker_alliance_tag: Option<String>,
+    #[sqlx(default)]
+    pub defender_alliance_tag: Option<String>,
+}
+
+/// Input for a new battle report
+#[derive(Debug, Clone)]
+pub struct NewBattleReport<'a> {
+    pub attacker_player_id: Uuid,
+    pub defender_player_id: Option<Uuid>,
+    pub attacker_village_id: Uuid,
+    pub defender_village_id: Option<Uuid>,
+    pub mission: MissionType,
+    pub attacker_troops: &'a ArmyTroops,
+    pub defender_troops: &'a ArmyTroops,
+    pub attacker_losses: &'a ArmyTroops,
+    pub defender_losses: &'a ArmyTroops,
+    pub resources_stolen: &'a CarriedResources,
+    pub defender_resources: Option<&'a CarriedResources>,
+    pub winner: &'a str,
+    pub loyalty_reduced: i32,
+    pub hero_result: Option<&'a BattleHeroResult>,
+    pub wall_bonus_percent: i32,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// How a single hero fared in a battle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeroBattleResult {
+    pub hero_id: Uuid,
+    pub hero_name: String,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub died: bool,
+    pub leveled_up: bool,
+    #[serde(default)]
+    pub experience_gained: i32,
+}
+
+/// Hero outcomes of both sides of a battle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BattleHeroResult {
+    pub attacker: Option<HeroBattleResult>,
+    pub defender: Option<HeroBattleResult>,
 }
 
 /// Scout report record
@@ -112,12 +241,32 @@ pub struct ScoutReport {
     pub success: bool,
     pub scouted_resources: Option<sqlx::types::Json<CarriedResources>>,
     pub scouted_troops: Option<sqlx::types::Json<ArmyTroops>>,
+    /// False when scouted_troops holds approximate counts
+    pub troops_exact: bool,
     pub occurred_at: DateTime<Utc>,
     pub read_by_attacker: bool,
     pub read_by_defender: bool,
     pub created_at: DateTime<Utc>,
 }
 
+/// Input for a new scout report
+#[derive(Debug, Clone)]
+pub struct NewScoutReport<'a> {
+    pub attacker_player_id: Uuid,
+    pub defender_player_id: Option<Uuid>,
+    pub attacker_village_id: Uuid,
+    pub defender_village_id: Option<Uuid>,
+    pub attacker_scouts: i32,
+    pub defender_scouts: i32,
+    pub attacker_scouts_lost: i32,
+    pub defender_scouts_lost: i32,
+    pub success: bool,
+    pub scouted_resources: Option<&'a CarriedResources>,
+    pub scouted_troops: Option<&'a ArmyTroops>,
+    pub troops_exact: bool,
+    pub occurred_at: DateTime<Utc>,
+}
+
 // Request/Response DTOs
 
 #[derive(Debug, Clone, Deserialize)]
@@ -128,6 +277,64 @@ pub struct SendArmyRequest {
     pub troops: HashMap<TroopType, i32>,
     #[serde(default)]
     pub resources: CarriedResources,
+    /// Schedule departure for a future time instead of leaving immediately
+    #[serde(default)]
+    pub depart_at: Option<DateTime<Utc>>,
+    /// Support only: return home automatically after this many hours stationed
+    #[serde(default)]
+    pub max_station_hours: Option<i32>,
+    /// Raid, Attack and Conquer only: an idle hero in the source village to lead the army
+    #[serde(default)]
+    pub hero_id: Option<Uuid>,
+}
+
+/// Army journey to preview without sending any troops
+#[derive(Debug, Clone, Deserialize)]
+pub struct TravelTimeRequest {
+    pub to_x: i32,
+    pub to_y: i32,
+    pub mission: MissionType,
+    pub troops: HashMap<TroopType, i32>,
+    #[serde(default)]
+    pub depart_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TravelTimeResponse {
+    pub distance: f64,
+    pub travel_seconds: i64,
+    pub departs_at: DateTime<Utc>,
+    pub arrives_at: DateTime<Utc>,
+    pub returns_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConquerPreviewQuery {
+    pub to_x: i32,
+    pub to_y: i32,
+}
+
+/// Why a target cannot be conquered right now
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConquerBlocker {
+    NoVillage,
+    OwnVillage,
+    ProtectedCapital,
+    VillageCapReached,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConquerPreviewResponse {
+    pub target_village_id: Option<Uuid>,
+    pub conquerable: bool,
+    pub blocker: Option<ConquerBlocker>,
+    /// Current loyalty; only shown to the owner or after a recent successful scouting
+    pub loyalty: Option<i32>,
+    /// Loyalty one wave of the chiefs now in the sending village would remove
+    pub loyalty_per_wave: i32,
+    /// Waves needed to bring loyalty to 0, assuming full loyalty when it is not visible
+    pub estimated_waves: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -146,10 +353,13 @@ pub struct ArmyResponse {
     pub returns_at: Option<DateTime<Utc>>,
     pub is_returning: bool,
     pub is_stationed: bool,
+    pub is_scheduled: bool,
+    pub hero_id: Option<Uuid>,
 }
 
 impl From<Army> for ArmyResponse {
     fn from(a: Army) -> Self {
+        let is_scheduled = a.is_scheduled(Utc::now());
         Self {
             id: a.id,
             player_id: a.player_id,
@@ -165,15 +375,84 @@ impl From<Army> for ArmyResponse {
             returns_at: a.returns_at,
             is_returning: a.is_returning,
             is_stationed: a.is_stationed,
+            is_scheduled,
+            hero_id: a.hero_id,
         }
     }
 }
 
+/// All of a player's armies across villages, grouped by state
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerArmiesResponse {
+    pub scheduled: Vec<ArmyResponse>,
+    pub outgoing: Vec<ArmyResponse>,
+    pub returning: Vec<ArmyResponse>,
+    pub stationed: Vec<ArmyResponse>,
+}
+
+/// Who sent a support army stationed in a village
+#[derive(Debug, Clone, FromRow)]
+pub struct StationedSender {
+    pub army_id: Uuid,
+    pub sender_name: Option<String>,
+    pub sender_alliance_tag: Option<String>,
+    pub from_village_name: Option<String>,
+    pub from_x: Option<i32>,
+    pub from_y: Option<i32>,
+}
+
+/// Stationed support as seen by the host village's owner
+#[derive(Debug, Clone, Serialize)]
+pub struct StationedSupportResponse {
+    #[serde(flatten)]
+    pub army: ArmyResponse,
+    pub sender_name: Option<String>,
+    pub sender_alliance_tag: Option<String>,
+    pub from_village_name: Option<String>,
+    /// Home coordinates of the sender; hidden unless the server shows support origins
+    pub from_x: Option<i32>,
+    pub from_y: Option<i32>,
+}
+
+/// Defensive strength of a village as battles would see it
+#[derive(Debug, Clone, Serialize)]
+pub struct DefenseSummaryResponse {
+    pub village_id: Uuid,
+    pub village_troops: ArmyTroops,
+    pub support_troops: ArmyTroops,
+    pub support_armies: i32,
+    /// Total defense against a purely infantry attack, with Wall and tribe bonuses
+    pub defense_vs_infantry: f64,
+    /// Total defense against a purely cavalry attack
+    pub defense_vs_cavalry: f64,
+    pub wall_level: i32,
+    /// Defense bonus (percent) from the Wall, included in the totals above
+    pub wall_bonus_percent: i32,
+    /// Defense of idle heroes in the village; not yet applied in battle
+    pub hero_defense: i32,
+}
+
+/// What a conquer attack did to the target's loyalty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConquestOutcome {
+    /// The attack was beaten off
+    Failed,
+    /// Surviving chiefs lowered the village's loyalty
+    LoyaltyReduced,
+    /// The attack won but every chief died, so loyalty is unchanged
+    NoChiefsSurvived,
+    /// The attack won but carried no chiefs, so loyalty is unchanged
+    NoChiefsSent,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct BattleReportResponse {
     pub id: Uuid,
     pub attacker_player_id: Uuid,
     pub defender_player_id: Option<Uuid>,
+    pub attacker_alliance_tag: Option<String>,
+    pub defender_alliance_tag: Option<String>,
     pub attacker_village_id: Uuid,
     pub defender_village_id: Option<Uuid>,
     pub mission: MissionType,
@@ -182,26 +461,87 @@ pub struct BattleReportResponse {
     pub attacker_losses: ArmyTroops,
     pub defender_losses: ArmyTroops,
     pub resources_stolen: CarriedResources,
+    /// Defender's resources before the battle; only shown to the defender
+    pub defender_resources_before: Option<CarriedResources>,
     pub winner: String,
+    /// Loyalty removed from the defending village (conquer battles only)
+    pub loyalty_reduced: i32,
+    /// Why loyalty did or did not change (conquer battles only)
+    pub conquest_outcome: Option<ConquestOutcome>,
+    /// True when the defender's troops and losses were withheld from this viewer
+    pub defender_details_hidden: bool,
+    /// Heroes that took part; the defending hero is hidden along with the defender's details
+    pub hero_result: Option<BattleHeroResult>,
+    /// Defense bonus (percent) from the defender's Wall; hidden with the defender's details
+    pub wall_bonus_percent: Option<i32>,
     pub occurred_at: DateTime<Utc>,
     pub is_read: bool,
 }
 
 impl BattleReport {
+    /// Outcome of a conquer battle for its loyalty; None for other missions
+    pub fn conquest_outcome(&self) -> Option<ConquestOutcome> {
+        if self.mission != MissionType::Conquer {
+            return None;
+        }
+        if self.winner != "attacker" {
+            return Some(ConquestOutcome::Failed);
+        }
+        if self.loyalty_reduced > 0 {
+            return Some(ConquestOutcome::LoyaltyReduced);
+        }
+
+        let chiefs_sent = self
+            .attacker_troops
+            .0
+            .iter()
+            .any(|(troop_type, count)| troop_type.is_chief() && *count > 0);
+        Some(if chiefs_sent {
+            ConquestOutcome::NoChiefsSurvived
+        } else {
+            ConquestOutcome::NoChiefsSent
+        })
+    }
+
+    /// Build the report as seen by one side.
+    /// The defender always sees everything; an attacker only learns the defender's
+    /// composition and losses when the attack won, otherwise just their own side.
     pub fn to_response(&self, is_attacker: bool) -> BattleReportResponse {
+        let hide_defender = is_attacker && self.winner != "attacker";
+        let (defender_troops, defender_losses) = if hide_defender {
+            (ArmyTroops::new(), ArmyTroops::new())
+        } else {
+            (self.defender_troops.0.clone(), self.defender_losses.0.clone())
+        };
+
         BattleReportResponse {
             id: self.id,
             attacker_player_id: self.attacker_player_id,
             defender_player_id: self.defender_player_id,
+            attacker_alliance_tag: self.attacker_alliance_tag.clone(),
+            defender_alliance_tag: self.defender_alliance_tag.clone(),
             attacker_village_id: self.attacker_village_id,
             defender_village_id: self.defender_village_id,
             mission: self.mission,
             attacker_troops: self.attacker_troops.0.clone(),
-            defender_troops: self.defender_troops.0.clone(),
+            defender_troops,
             attacker_losses: self.attacker_losses.0.clone(),
-            defender_losses: self.defender_losses.0.clone(),
+            defender_losses,
             resources_stolen: self.resources_stolen.0.clone(),
+            defender_resources_before: if is_attacker {
+                None
+            } else {
+                self.defender_resources.as_ref().map(|r| r.0.clone())
+            },
             winner: self.winner.clone(),
+            loyalty_reduced: self.loyalty_reduced,
+            conquest_outcome: self.conquest_outcome(),
+            defender_details_hidden: hide_defender,
+            hero_result: self.hero_result.as_ref().map(|h| BattleHeroResult {
+                attacker: h.0.attacker.clone(),
+                defender: if hide_defender { None } else { h.0.defender.clone() },
+            }),
+            wall_bonus_percent: (!hide_defender).then_some(self.wall_bonus_percent),
             occurred_at: self.occurred_at,
             is_read: if is_attacker { self.read_by_attacker } else { self.read_by_defender },
         }
@@ -222,6 +562,8 @@ pub struct ScoutReportResponse {
     pub success: bool,
     pub scouted_resources: Option<CarriedResources>,
     pub scouted_troops: Option<ArmyTroops>,
+    /// False when scouted_troops are approximate (partial scouting success)
+    pub troops_exact: bool,
     pub occurred_at: DateTime<Utc>,
     pub is_read: bool,
 }
@@ -241,8 +583,183 @@ impl ScoutReport {
             success: self.success,
             scouted_resources: self.scouted_resources.as_ref().map(|r| r.0.clone()),
             scouted_troops: self.scouted_troops.as_ref().map(|t| t.0.clone()),
+            troops_exact: self.troops_exact,
             occurred_at: self.occurred_at,
             is_read: if is_attacker { self.read_by_attacker } else { self.read_by_defender },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::army;
+    use sqlx::types::Json;
+
+    fn hero(name: &str) -> HeroBattleResult {
+        HeroBattleResult {
+            hero_id: Uuid::new_v4(),
+            hero_name: name.to_string(),
+            damage_dealt: 100,
+            damage_taken: 50,
+            died: false,
+            leveled_up: false,
+            experience_gained: 10,
+        }
+    }
+
+    fn report(winner: &str) -> BattleReport {
+        let now = Utc::now();
+        BattleReport {
+            id: Uuid::new_v4(),
+            attacker_player_id: Uuid::new_v4(),
+            defender_player_id: Some(Uuid::new_v4()),
+            attacker_village_id: Uuid::new_v4(),
+            defender_village_id: Some(Uuid::new_v4()),
+            mission: MissionType::Raid,
+            attacker_troops: Json(HashMap::from([(TroopType::Infantry, 50)])),
+            defender_troops: Json(HashMap::from([(TroopType::Spearman, 80)])),
+            attacker_losses: Json(HashMap::from([(TroopType::Infantry, 20)])),
+            defender_losses: Json(HashMap::from([(TroopType::Spearman, 30)])),
+            resources_stolen: Json(CarriedResources::default()),
+            defender_resources: Some(Json(CarriedResources { wood: 400, ..Default::default() })),
+            winner: winner.to_string(),
+            loyalty_reduced: 0,
+            hero_result: Some(Json(BattleHeroResult {
+                attacker: Some(hero("Raider")),
+                defender: Some(hero("Warden")),
+            })),
+            wall_bonus_percent: 15,
+            occurred_at: now,
+            read_by_attacker: true,
+            read_by_defender: false,
+            created_at: now,
+            attacker_alliance_tag: None,
+            defender_alliance_tag: None,
+        }
+    }
+
+    #[test]
+    fn report_shows_alliance_tags_and_null_for_unaffiliated_players() {
+        let mut report = report("attacker");
+        report.attacker_alliance_tag = Some("SIAM".to_string());
+
+        for view in [report.to_response(true), report.to_response(false)] {
+            assert_eq!(view.attacker_alliance_tag.as_deref(), Some("SIAM"));
+            assert!(view.defender_alliance_tag.is_none());
+        }
+    }
+
+    fn conquest(winner: &str, loyalty_reduced: i32) -> BattleReport {
+        BattleReport {
+            mission: MissionType::Conquer,
+            attacker_troops: Json(HashMap::from([
+                (TroopType::Infantry, 50),
+                (TroopType::RoyalAdvisor, 1),
+            ])),
+            loyalty_reduced,
+            ..report(winner)
+        }
+    }
+
+    #[test]
+    fn conquest_report_explains_the_loyalty_change() {
+        let reduced = conquest("attacker", 25).to_response(true);
+        assert_eq!(reduced.conquest_outcome, Some(ConquestOutcome::LoyaltyReduced));
+        assert_eq!(reduced.loyalty_reduced, 25);
+
+        let chiefs_died = conquest("attacker", 0).to_response(true);
+        assert_eq!(chiefs_died.conquest_outcome, Some(ConquestOutcome::NoChiefsSurvived));
+        assert_eq!(chiefs_died.loyalty_reduced, 0);
+
+        let no_chiefs = BattleReport {
+            attacker_troops: Json(HashMap::from([(TroopType::Infantry, 50)])),
+            ..conquest("attacker", 0)
+        };
+        assert_eq!(no_chiefs.conquest_outcome(), Some(ConquestOutcome::NoChiefsSent));
+        assert_eq!(conquest("defender", 0).conquest_outcome(), Some(ConquestOutcome::Failed));
+        assert_eq!(report("attacker").conquest_outcome(), None);
+    }
+
+    #[test]
+    fn losing_attacker_sees_only_their_own_side() {
+        let report = report("defender");
+        let attacker_view = report.to_response(true);
+
+        assert!(attacker_view.defender_details_hidden);
+        assert_eq!(attacker_view.attacker_losses[&TroopType::Infantry], 20);
+        assert!(attacker_view.defender_troops.is_empty());
+        assert!(attacker_view.defender_losses.is_empty());
+        assert!(attacker_view.hero_result.as_ref().unwrap().defender.is_none());
+        assert!(attacker_view.wall_bonus_percent.is_none());
+        assert!(attacker_view.defender_resources_before.is_none());
+        assert!(attacker_view.is_read);
+    }
+
+    #[test]
+    fn defender_sees_full_detail_of_the_same_report() {
+        let report = report("defender");
+        let defender_view = report.to_response(false);
+
+        assert!(!defender_view.defender_details_hidden);
+        assert_eq!(defender_view.defender_troops[&TroopType::Spearman], 80);
+        assert_eq!(defender_view.defender_losses[&TroopType::Spearman], 30);
+        assert_eq!(defender_view.attacker_losses[&TroopType::Infantry], 20);
+        assert!(defender_view.hero_result.as_ref().unwrap().defender.is_some());
+        assert_eq!(defender_view.wall_bonus_percent, Some(15));
+        assert_eq!(defender_view.defender_resources_before.unwrap().wood, 400);
+        assert!(!defender_view.is_read);
+    }
+
+    #[test]
+    fn winning_attacker_sees_defender_troops_but_not_their_stockpile() {
+        let attacker_view = report("attacker").to_response(true);
+
+        assert!(!attacker_view.defender_details_hidden);
+        assert_eq!(attacker_view.defender_troops[&TroopType::Spearman], 80);
+        assert_eq!(attacker_view.defender_losses[&TroopType::Spearman], 30);
+        assert_eq!(attacker_view.wall_bonus_percent, Some(15));
+        assert!(attacker_view.defender_resources_before.is_none());
+    }
+
+    #[test]
+    fn outbound_arrival_then_return_completion_use_separate_columns() {
+        let mut raid = army(MissionType::Raid, ArmyTroops::from([(TroopType::Infantry, 10)]));
+        let arrives_at = raid.arrives_at;
+        let returns_at = raid.returns_at.unwrap();
+        assert!(!raid.is_due(arrives_at - Duration::seconds(1)));
+        assert!(raid.is_due(arrives_at));
+
+        // Resolving the arrival turns it around without touching arrives_at
+        raid.is_returning = true;
+        assert_eq!(raid.arrives_at, arrives_at);
+        let after_arrival = arrives_at + Duration::seconds(1);
+        assert!(!raid.is_due(after_arrival), "arrival must not be processed twice");
+        assert!(raid.is_due(returns_at));
+    }
+
+    #[test]
+    fn stationed_support_is_never_due() {
+        let mut support = army(MissionType::Support, ArmyTroops::from([(TroopType::Spearman, 5)]));
+        let later = support.arrives_at + Duration::hours(10);
+        assert!(support.is_due(later));
+
+        support.is_stationed = true;
+        assert!(!support.is_due(later));
+    }
+
+    #[test]
+    fn support_past_its_max_station_hours_is_sent_home() {
+        let mut support = army(MissionType::Support, ArmyTroops::from([(TroopType::Spearman, 5)]));
+        support.is_stationed = true;
+        support.max_station_hours = Some(6);
+        let expires_at = support.arrives_at + Duration::hours(6);
+
+        assert!(!support.is_station_expired(expires_at - Duration::seconds(1)));
+        assert!(support.is_station_expired(expires_at));
+
+        // Without a limit support stays until recalled
+        support.max_station_hours = None;
+        assert!(!support.is_station_expired(expires_at + Duration::days(30)));
+    }
+}
@@ -25,6 +25,14 @@ pub enum BuildingType {
     Treasury,
     TradeOffice,
     Wall,
+    HeroMansion,
+    Cranny,
+    // Resource bonus buildings
+    Sawmill,
+    Brickyard,
+    IronFoundry,
+    GrainMill,
+    Bakery,
     // Resource fields
     Woodcutter,
     ClayPit,
@@ -50,9 +58,16 @@ impl BuildingType {
         )
     }
 
+    /// Buildings that can never be fully removed once built (resource fields included)
+    pub fn is_demolition_protected(&self) -> bool {
+        matches!(self, BuildingType::MainBuilding | BuildingType::RallyPoint) || self.is_resource_field()
+    }
+
     pub fn max_level(&self) -> i32 {
         match self {
             BuildingType::Wall => 20,
+            BuildingType::Cranny => 10,
+            _ if self.is_resource_bonus() => 5,
             BuildingType::Palace | BuildingType::Residence => 20,
             _ if self.is_resource_field() => 20,
             _ => 20,
@@ -73,6 +88,9 @@ impl BuildingType {
                 BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 1 },
             ],
             BuildingType::Wall => vec![],
+            BuildingType::Cranny => vec![
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 1 },
+            ],
 
             // Military buildings
             BuildingType::Barracks => vec![
@@ -125,6 +143,32 @@ impl BuildingType {
             BuildingType::Treasury => vec![
                 BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 10 },
             ],
+            BuildingType::HeroMansion => vec![
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 3 },
+                BuildingPrerequisite { building_type: BuildingType::RallyPoint, min_level: 1 },
+            ],
+
+            // Resource bonus buildings
+            BuildingType::Sawmill => vec![
+                BuildingPrerequisite { building_type: BuildingType::Woodcutter, min_level: 10 },
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 5 },
+            ],
+            BuildingType::Brickyard => vec![
+                BuildingPrerequisite { building_type: BuildingType::ClayPit, min_level: 10 },
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 5 },
+            ],
+            BuildingType::IronFoundry => vec![
+                BuildingPrerequisite { building_type: BuildingType::IronMine, min_level: 10 },
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 5 },
+            ],
+            BuildingType::GrainMill => vec![
+                BuildingPrerequisite { building_type: BuildingType::CropField, min_level: 5 },
+            ],
+            BuildingType::Bakery => vec![
+                BuildingPrerequisite { building_type: BuildingType::CropField, min_level: 10 },
+                BuildingPrerequisite { building_type: BuildingType::GrainMill, min_level: 5 },
+                BuildingPrerequisite { building_type: BuildingType::MainBuilding, min_level: 5 },
+            ],
 
             // Resource fields - no prerequisites
             BuildingType::Woodcutter => vec![],
@@ -153,6 +197,7 @@ impl BuildingType {
             BuildingType::Granary => 1,
             BuildingType::RallyPoint => 1,
             BuildingType::Wall => 0,
+            BuildingType::Cranny => 0,
 
             // Military buildings - higher population
             BuildingType::Barracks => 4,
@@ -171,6 +216,14 @@ impl BuildingType {
             BuildingType::Residence => 1,
             BuildingType::Palace => 1,
             BuildingType::Treasury => 4,
+            BuildingType::HeroMansion => 2,
+
+            // Resource bonus buildings
+            BuildingType::Sawmill => 4,
+            BuildingType::Brickyard => 3,
+            BuildingType::IronFoundry => 6,
+            BuildingType::GrainMill => 3,
+            BuildingType::Bakery => 4,
         };
 
         // Population increases slightly with level
@@ -191,6 +244,18 @@ pub struct Building {
     pub updated_at: DateTime<Utc>,
 }
 
+// Queued level of a chained upgrade (starts when the previous level finishes)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BuildingQueueEntry {
+    pub id: Uuid,
+    pub building_id: Uuid,
+    pub village_id: Uuid,
+    pub target_level: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateBuilding {
     pub village_id: Uuid,
@@ -221,6 +286,42 @@ impl From<Building> for BuildingResponse {
     }
 }
 
+// Village center slots hold infrastructure, resource field slots hold the 18 fields
+pub const VILLAGE_SLOTS: std::ops::RangeInclusive<i32> = 1..=22;
+pub const RESOURCE_SLOTS: std::ops::RangeInclusive<i32> = 101..=118;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotKind {
+    Village,
+    Resource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildingSlotResponse {
+    pub slot: i32,
+    pub kind: SlotKind,
+    pub building: Option<BuildingResponse>,
+}
+
+/// An upgrade that was cancelled, with every level it had paid for
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelledUpgrade {
+    pub building_id: Uuid,
+    pub building_type: BuildingType,
+    pub slot: i32,
+    /// Level the building stays at (0 = the construction was removed)
+    pub level: i32,
+    pub cancelled_levels: Vec<i32>,
+    pub refund: BuildingCost,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelQueueResponse {
+    pub cancelled: Vec<CancelledUpgrade>,
+    pub refund: BuildingCost,
+}
+
 // Building costs and production rates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildingCost {
@@ -305,6 +406,56 @@ impl BuildingType {
                 crop: 20,
                 time_seconds: 150,
             },
+            // Resource bonus buildings
+            BuildingType::Sawmill => BuildingCost {
+                wood: 520,
+                clay: 380,
+                iron: 290,
+                crop: 90,
+                time_seconds: 1800,
+            },
+            BuildingType::Brickyard => BuildingCost {
+                wood: 440,
+                clay: 480,
+                iron: 320,
+                crop: 50,
+                time_seconds: 1680,
+            },
+            BuildingType::IronFoundry => BuildingCost {
+                wood: 200,
+                clay: 450,
+                iron: 510,
+                crop: 120,
+                time_seconds: 2000,
+            },
+            BuildingType::GrainMill => BuildingCost {
+                wood: 500,
+                clay: 440,
+                iron: 380,
+                crop: 1240,
+                time_seconds: 1840,
+            },
+            BuildingType::Bakery => BuildingCost {
+                wood: 1200,
+                clay: 1480,
+                iron: 870,
+                crop: 1600,
+                time_seconds: 3000,
+            },
+            BuildingType::HeroMansion => BuildingCost {
+                wood: 700,
+                clay: 670,
+                iron: 700,
+                crop: 240,
+                time_seconds: 3000,
+            },
+            BuildingType::Cranny => BuildingCost {
+                wood: 40,
+                clay: 50,
+                iron: 30,
+                crop: 10,
+                time_seconds: 220,
+            },
             // Default for others
             _ => BuildingCost {
                 wood: 100,
@@ -328,13 +479,46 @@ impl BuildingType {
         }
     }
 
-    pub fn production_per_hour(&self, level: i32) -> i32 {
+    /// Total cost of building every level from 1 up to `level`
+    pub fn cumulative_cost(&self, level: i32) -> BuildingCost {
+        (1..=level).map(|l| self.cost_at_level(l)).fold(
+            BuildingCost { wood: 0, clay: 0, iron: 0, crop: 0, time_seconds: 0 },
+            |acc, c| BuildingCost {
+                wood: acc.wood + c.wood,
+                clay: acc.clay + c.clay,
+                iron: acc.iron + c.iron,
+                crop: acc.crop + c.crop,
+                time_seconds: acc.time_seconds + c.time_seconds,
+            },
+        )
+    }
+
+    /// Hourly field production: base * growth^(level-1) * curve^((level-1)^2),
+    /// similar to Travian with the default parameters (3, 1.63, 1.0034)
+    pub fn production_per_hour(&self, level: i32, base: f64, growth: f64, curve: f64) -> i32 {
         if !self.is_resource_field() {
             return 0;
         }
-        // Base production formula similar to Travian
-        let base = 3;
-        (base as f64 * (1.63_f64).powi(level - 1) * 1.0034_f64.powi((level - 1) * (level - 1))) as i32
+        (base * growth.powi(level - 1) * curve.powi((level - 1) * (level - 1))) as i32
+    }
+
+    pub fn is_resource_bonus(&self) -> bool {
+        matches!(
+            self,
+            BuildingType::Sawmill
+                | BuildingType::Brickyard
+                | BuildingType::IronFoundry
+                | BuildingType::GrainMill
+                | BuildingType::Bakery
+        )
+    }
+
+    /// Production bonus percentage granted by a resource bonus building (5% per level)
+    pub fn production_bonus_percent(&self, level: i32) -> i32 {
+        if !self.is_resource_bonus() {
+            return 0;
+        }
+        level.clamp(0, self.max_level()) * 5
     }
 
     /// Storage capacity for Warehouse/Granary at given level
@@ -350,4 +534,12 @@ impl BuildingType {
         };
         (base as f64 * (1.2_f64).powi(level)) as i32
     }
+
+    /// Amount of each resource a Cranny at given level hides from raiders: 200 * 1.2^level
+    pub fn hidden_capacity(&self, level: i32) -> i32 {
+        if !matches!(self, BuildingType::Cranny) || level <= 0 {
+            return 0;
+        }
+        (200.0 * (1.2_f64).powi(level.min(self.max_level()))) as i32
+    }
 }
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// ==================== Database Models ====================
+
+/// Thread row as used for lookups and access checks; listings use `ForumThreadListItem`
+#[derive(Debug, Clone, FromRow)]
+pub struct ForumThread {
+    pub id: Uuid,
+    pub alliance_id: Uuid,
+}
+
+// ==================== Request DTOs ====================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateThreadRequest {
+    pub title: String,
+    /// Opening post of the thread
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePostRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PinThreadRequest {
+    pub pinned: bool,
+}
+
+// ==================== Response DTOs ====================
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ForumThreadListItem {
+    pub id: Uuid,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub title: String,
+    pub is_pinned: bool,
+    pub post_count: i64,
+    pub last_post_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ForumPostResponse {
+    pub id: Uuid,
+    pub thread_id: Uuid,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForumThreadResponse {
+    pub thread: ForumThreadListItem,
+    pub posts: Vec<ForumPostResponse>,
+}
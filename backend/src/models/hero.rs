@@ -20,7 +20,7 @@ pub enum HeroStatus {
     Reviving,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, PartialOrd)]
 #[sqlx(type_name = "item_rarity", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum ItemRarity {
@@ -136,9 +136,74 @@ impl Hero {
     }
 
     /// Calculate experience needed for level
-    pub fn exp_for_level(level: i32) -> i32 {
-        // Exponential growth: 100 * 1.5^(level-1)
-        (100.0 * 1.5_f64.powi(level - 1)) as i32
+    pub fn exp_for_level(level: i32, base: f64, growth: f64) -> i32 {
+        // Exponential growth: base * growth^(level-1), computed in f64 and
+        // clamped so high levels saturate at i32::MAX instead of overflowing
+        let exp = base * growth.powi(level - 1);
+        if exp.is_finite() {
+            exp.clamp(1.0, i32::MAX as f64) as i32
+        } else {
+            i32::MAX
+        }
+    }
+
+    /// Base health regeneration (HP per hour) before item bonuses
+    pub fn base_health_regen(level: i32) -> Decimal {
+        // 10 HP/hour at level 1, +0.5 per level after
+        Decimal::from(10) + Decimal::new(5, 1) * Decimal::from((level - 1).max(0))
+    }
+
+    /// Effective health regeneration (HP per hour): the level base plus the
+    /// regen bonus of every equipped item
+    pub fn health_regen_rate(level: i32, equipped: &[ItemDefinition]) -> Decimal {
+        Self::base_health_regen(level)
+            + equipped.iter().map(|item| item.health_regen_bonus).sum::<Decimal>()
+    }
+}
+
+/// Where a hero stands on the experience curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeroProgress {
+    pub level: i32,
+    pub experience: i32,
+    pub experience_to_next: i32,
+    pub unassigned_points: i32,
+}
+
+impl HeroProgress {
+    pub fn of(hero: &Hero) -> Self {
+        Self {
+            level: hero.level,
+            experience: hero.experience,
+            experience_to_next: hero.experience_to_next,
+            unassigned_points: hero.unassigned_points,
+        }
+    }
+
+    /// Add experience, levelling up (4 attribute points per level) until the bar
+    /// no longer fills or `max_level` is reached
+    pub fn gain(self, exp: i32, max_level: i32, exp_base: f64, exp_growth: f64) -> Self {
+        let mut progress = Self {
+            experience: self.experience.saturating_add(exp),
+            experience_to_next: self.experience_to_next.max(1),
+            ..self
+        };
+
+        // Terminates because experience_to_next is always >= 1 and
+        // leveling stops at the configured max level
+        while progress.experience >= progress.experience_to_next && progress.level < max_level {
+            progress.experience -= progress.experience_to_next;
+            progress.level += 1;
+            progress.unassigned_points += 4;
+            progress.experience_to_next = Hero::exp_for_level(progress.level + 1, exp_base, exp_growth);
+        }
+
+        // Max level heroes keep a full bar instead of accumulating experience
+        if progress.level >= max_level {
+            progress.experience = progress.experience.min(progress.experience_to_next);
+        }
+
+        progress
     }
 }
 
@@ -176,9 +241,19 @@ pub struct ItemDefinition {
     pub can_drop_adventure: bool,
     pub can_buy_auction: bool,
 
+    // Tribe restriction (None = usable by all tribes)
+    pub allowed_tribe: Option<TribeType>,
+
     pub created_at: DateTime<Utc>,
 }
 
+impl ItemDefinition {
+    /// Check if a hero of the given tribe can use this item
+    pub fn is_usable_by(&self, tribe: TribeType) -> bool {
+        self.allowed_tribe.is_none_or(|allowed| allowed == tribe)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct HeroItem {
     pub id: Uuid,
@@ -228,6 +303,7 @@ pub struct HeroItemWithDefinition {
     pub item_is_consumable: bool,
     pub item_extra_inventory_slots: i32,
     pub item_sell_value: i32,
+    pub item_allowed_tribe: Option<TribeType>,
 }
 
 impl HeroItemWithDefinition {
@@ -263,6 +339,7 @@ impl HeroItemWithDefinition {
             sell_value: self.item_sell_value,
             can_drop_adventure: true,
             can_buy_auction: true,
+            allowed_tribe: self.item_allowed_tribe,
             created_at: Utc::now(),
         };
 
@@ -299,6 +376,25 @@ pub struct HeroAdventure {
     pub created_at: DateTime<Utc>,
 }
 
+impl HeroAdventure {
+    /// Still under way, so it can be cancelled
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.is_completed && self.ends_at > now
+    }
+
+    /// Finished but not yet rewarded, so it can be completed (once)
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        !self.is_completed && self.ends_at <= now
+    }
+
+    /// Share of a full reward earned by cancelling at `now`, pro-rated by elapsed time
+    pub fn prorated_reward(&self, full_reward: i32, now: DateTime<Utc>) -> i32 {
+        let elapsed = (now - self.started_at).num_seconds().max(0) as f64;
+        let fraction = (elapsed / self.duration_seconds.max(1) as f64).min(1.0);
+        (full_reward as f64 * fraction) as i32
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct AvailableAdventure {
     pub id: Uuid,
@@ -349,6 +445,13 @@ pub struct ChangeHomeVillageRequest {
     pub village_id: Uuid,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferHeroRequest {
+    pub recipient_id: Uuid,
+    /// Recipient village to become the hero's home; defaults to their capital
+    pub village_id: Option<Uuid>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct EquipItemRequest {
     pub item_id: Uuid,
@@ -371,7 +474,40 @@ pub struct StartAdventureRequest {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ReviveHeroRequest {
+    /// Older clients send only this; `true` means `revive_method: gold`
+    #[serde(default)]
     pub use_gold: bool,
+    #[serde(default)]
+    pub revive_method: Option<ReviveMethod>,
+}
+
+impl ReviveHeroRequest {
+    pub fn method(&self) -> ReviveMethod {
+        self.revive_method.unwrap_or(if self.use_gold {
+            ReviveMethod::Gold
+        } else {
+            ReviveMethod::Natural
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviveMethod {
+    /// Instantly, for gold
+    Gold,
+    /// Instantly, for resources from the hero's home village
+    Resources,
+    /// Free, once the revive timer has run out
+    Natural,
+}
+
+/// Items to sell at once; both filters apply when given, equipped items are never sold
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkSellItemsRequest {
+    pub item_ids: Option<Vec<Uuid>>,
+    /// Sell only items of this rarity or lower
+    pub max_rarity: Option<ItemRarity>,
 }
 
 // ==================== Response DTOs ====================
@@ -457,6 +593,7 @@ pub struct HeroListResponse {
     pub total_slots: i32,
     pub used_slots: i32,
     pub next_slot_cost: Option<i32>,
+    pub silver_balance: i32,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -485,6 +622,9 @@ pub struct ItemDefinitionResponse {
     pub extra_inventory_slots: i32,
 
     pub sell_value: i32,
+    pub can_drop_adventure: bool,
+    pub can_buy_auction: bool,
+    pub allowed_tribe: Option<TribeType>,
 }
 
 impl From<ItemDefinition> for ItemDefinitionResponse {
@@ -507,6 +647,9 @@ impl From<ItemDefinition> for ItemDefinitionResponse {
             is_consumable: d.is_consumable,
             extra_inventory_slots: d.extra_inventory_slots,
             sell_value: d.sell_value,
+            can_drop_adventure: d.can_drop_adventure,
+            can_buy_auction: d.can_buy_auction,
+            allowed_tribe: d.allowed_tribe,
         }
     }
 }
@@ -549,10 +692,13 @@ pub struct AvailableAdventureResponse {
     pub potential_reward: Option<String>,
     pub potential_rarity: Option<ItemRarity>,
     pub expires_at: DateTime<Utc>,
+    /// Minimum hero level needed to start this adventure
+    pub required_level: i32,
 }
 
-impl From<AvailableAdventure> for AvailableAdventureResponse {
-    fn from(a: AvailableAdventure) -> Self {
+impl AvailableAdventureResponse {
+    /// Build the response; the level requirement depends on server config, so callers pass it in
+    pub fn new(a: AvailableAdventure, required_level: i32) -> Self {
         let min_mins = a.min_duration_seconds / 60;
         let max_mins = a.max_duration_seconds / 60;
         let duration_range = if max_mins < 60 {
@@ -568,6 +714,7 @@ impl From<AvailableAdventure> for AvailableAdventureResponse {
             potential_reward: a.potential_reward_type,
             potential_rarity: a.potential_item_rarity,
             expires_at: a.expires_at,
+            required_level,
         }
     }
 }
@@ -606,6 +753,20 @@ pub struct ReviveResourceCost {
     pub crop: i32,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkSellItemsResponse {
+    pub items_sold: i32,
+    pub silver_gained: i32,
+    pub silver_balance: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SellItemResponse {
+    pub success: bool,
+    pub silver_gained: i32,
+    pub silver_balance: i32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct HeroSlotPurchaseResponse {
     pub success: bool,
@@ -614,3 +775,60 @@ pub struct HeroSlotPurchaseResponse {
     pub new_balance: i32,
     pub total_slots: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXP_BASE: f64 = 100.0;
+    const EXP_GROWTH: f64 = 1.5;
+
+    #[test]
+    fn experience_thresholds_increase_without_overflowing() {
+        let thresholds: Vec<i32> =
+            (1..=200).map(|level| Hero::exp_for_level(level, EXP_BASE, EXP_GROWTH)).collect();
+
+        assert_eq!(thresholds[0], 100);
+        assert_eq!(thresholds[1], 150);
+        assert!(thresholds.iter().all(|exp| *exp > 0));
+        assert!(thresholds.windows(2).all(|pair| pair[1] >= pair[0]));
+        // Far past the point where 1.5^(level-1) leaves i32 the curve saturates
+        assert_eq!(thresholds[199], i32::MAX);
+    }
+
+    #[test]
+    fn hero_levels_to_a_high_level_without_overflow() {
+        let max_level = 100;
+        let mut progress = HeroProgress {
+            level: 1,
+            experience: 0,
+            experience_to_next: Hero::exp_for_level(2, EXP_BASE, EXP_GROWTH),
+            unassigned_points: 0,
+        };
+
+        for _ in 0..1000 {
+            progress = progress.gain(i32::MAX, max_level, EXP_BASE, EXP_GROWTH);
+            assert!(progress.experience >= 0);
+            assert!(progress.experience <= progress.experience_to_next);
+        }
+
+        assert_eq!(progress.level, max_level);
+        assert_eq!(progress.unassigned_points, (max_level - 1) * 4);
+    }
+
+    #[test]
+    fn one_big_gain_crosses_several_levels() {
+        let start = HeroProgress {
+            level: 1,
+            experience: 0,
+            experience_to_next: 100,
+            unassigned_points: 0,
+        };
+
+        // 100 (to level 2) + 225 (to level 3) + 50 left over
+        let progress = start.gain(375, 100, EXP_BASE, EXP_GROWTH);
+        assert_eq!(progress.level, 3);
+        assert_eq!(progress.experience, 50);
+        assert_eq!(progress.unassigned_points, 8);
+    }
+}
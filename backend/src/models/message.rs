@@ -11,6 +11,8 @@ use uuid::Uuid;
 pub enum MessageType {
     Private,
     Alliance,
+    /// Sent by the game itself, without a sender
+    System,
 }
 
 // ==================== Database Models ====================
@@ -19,7 +21,7 @@ pub enum MessageType {
 pub struct Message {
     pub id: Uuid,
     pub message_type: MessageType,
-    pub sender_id: Uuid,
+    pub sender_id: Option<Uuid>,
     pub recipient_id: Option<Uuid>,
     pub alliance_id: Option<Uuid>,
     pub conversation_id: Option<Uuid>,
@@ -77,8 +79,8 @@ pub struct ReplyMessageRequest {
 pub struct MessageResponse {
     pub id: Uuid,
     pub message_type: MessageType,
-    pub sender_id: Uuid,
-    pub sender_name: String,
+    pub sender_id: Option<Uuid>,
+    pub sender_name: Option<String>,
     pub recipient_id: Option<Uuid>,
     pub recipient_name: Option<String>,
     pub alliance_id: Option<Uuid>,
@@ -103,8 +105,8 @@ pub struct ConversationResponse {
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct MessageListItem {
     pub id: Uuid,
-    pub sender_id: Uuid,
-    pub sender_name: String,
+    pub sender_id: Option<Uuid>,
+    pub sender_name: Option<String>,
     pub subject: String,
     pub is_read: bool,
     pub created_at: DateTime<Utc>,
@@ -126,7 +128,7 @@ impl From<Message> for MessageResponse {
             id: m.id,
             message_type: m.message_type,
             sender_id: m.sender_id,
-            sender_name: String::new(), // Will be populated by service
+            sender_name: None, // Will be populated by service
             recipient_id: m.recipient_id,
             recipient_name: None,
             alliance_id: m.alliance_id,
@@ -1,9 +1,14 @@
+pub mod activity;
 pub mod alliance;
+pub mod api_token;
 pub mod army;
 pub mod building;
+pub mod forum;
 pub mod hero;
 pub mod message;
+pub mod oasis;
 pub mod shop;
+pub mod transfer;
 pub mod troop;
 pub mod user;
 pub mod village;
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Hero's Mansion levels that each unlock one more oasis slot
+pub const OASIS_MANSION_LEVELS: [i32; 3] = [10, 15, 20];
+
+/// Unsettled tile a village can occupy for a production bonus
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Oasis {
+    pub id: Uuid,
+    pub x: i32,
+    pub y: i32,
+    pub wood_bonus: i32,
+    pub clay_bonus: i32,
+    pub iron_bonus: i32,
+    pub crop_bonus: i32,
+    pub village_id: Option<Uuid>,
+    pub occupied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Oasis {
+    /// Sum of all production bonuses, used to rank oases by strength
+    pub fn total_bonus(&self) -> i32 {
+        self.wood_bonus + self.clay_bonus + self.iron_bonus + self.crop_bonus
+    }
+}
+
+/// Oases a village may hold for a given Hero's Mansion level
+pub fn max_oases(mansion_level: i32) -> i32 {
+    OASIS_MANSION_LEVELS
+        .iter()
+        .filter(|required| mansion_level >= **required)
+        .count() as i32
+}
+
+// Request/Response DTOs
+
+#[derive(Debug, Deserialize)]
+pub struct OasisRangeQuery {
+    pub x: i32,
+    pub y: i32,
+    pub range: Option<i32>,
+}
+
+/// Oases held by a village next to the slots its Hero's Mansion allows
+#[derive(Debug, Serialize)]
+pub struct VillageOasesResponse {
+    pub village_id: Uuid,
+    pub mansion_level: i32,
+    pub max_oases: i32,
+    /// Mansion level needed for the next slot, None once all slots are unlocked
+    pub next_slot_level: Option<i32>,
+    pub oases: Vec<Oasis>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mansion_levels_unlock_oasis_slots() {
+        assert_eq!(max_oases(0), 0);
+        assert_eq!(max_oases(9), 0);
+        assert_eq!(max_oases(10), 1);
+        assert_eq!(max_oases(14), 1);
+        assert_eq!(max_oases(15), 2);
+        assert_eq!(max_oases(20), 3);
+    }
+}
@@ -45,6 +45,7 @@ pub enum GoldFeature {
     Ointment,
     PlusSubscription,
     HeroSlot,
+    HeroRevive,
 }
 
 // ==================== Database Models ====================
@@ -156,6 +157,28 @@ pub struct UseNpcMerchantRequest {
     pub crop: i32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NpcSplitMode {
+    /// Spread the total evenly across all four resources
+    Even,
+    /// Split the total by the given wood/clay/iron/crop weights
+    Ratio,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NpcSplitQuery {
+    pub mode: NpcSplitMode,
+    #[serde(default)]
+    pub wood: u32,
+    #[serde(default)]
+    pub clay: u32,
+    #[serde(default)]
+    pub iron: u32,
+    #[serde(default)]
+    pub crop: u32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UseProductionBonusRequest {
     pub village_id: Uuid,
@@ -192,6 +215,33 @@ pub struct TransactionResponse {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionListResponse {
+    pub transactions: Vec<TransactionResponse>,
+    pub total: i64,
+    pub limit: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoldUsageResponse {
+    pub id: Uuid,
+    pub feature: GoldFeature,
+    pub gold_spent: i32,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoldUsageListResponse {
+    pub usages: Vec<GoldUsageResponse>,
+    pub total: i64,
+    pub limit: i32,
+    pub offset: i32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct FeatureCostResponse {
     pub feature: GoldFeature,
@@ -199,6 +249,17 @@ pub struct FeatureCostResponse {
     pub description: Option<String>,
 }
 
+/// Resource split that can be submitted to the NPC merchant as-is
+#[derive(Debug, Clone, Serialize)]
+pub struct NpcSplitSuggestion {
+    pub village_id: Uuid,
+    pub total: i32,
+    pub wood: i32,
+    pub clay: i32,
+    pub iron: i32,
+    pub crop: i32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct UseFeatureResponse {
     pub success: bool,
@@ -219,3 +280,17 @@ impl From<Transaction> for TransactionResponse {
         }
     }
 }
+
+impl From<GoldUsage> for GoldUsageResponse {
+    fn from(u: GoldUsage) -> Self {
+        Self {
+            id: u.id,
+            feature: u.feature,
+            gold_spent: u.gold_spent,
+            target_type: u.target_type,
+            target_id: u.target_id,
+            expires_at: u.expires_at,
+            created_at: u.created_at,
+        }
+    }
+}
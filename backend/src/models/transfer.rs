@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Resources in transit between villages
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResourceTransfer {
+    pub id: Uuid,
+    pub sender_id: Uuid,
+    pub from_village_id: Uuid,
+    pub to_village_id: Uuid,
+    pub wood: i32,
+    pub clay: i32,
+    pub iron: i32,
+    pub crop: i32,
+    pub tax_percent: i32,
+    pub departed_at: DateTime<Utc>,
+    pub arrives_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ResourceTransfer {
+    /// Amount that reaches the destination after the transit tax
+    pub fn delivered(&self, amount: i32) -> i32 {
+        amount * (100 - self.tax_percent) / 100
+    }
+
+    pub fn total(&self) -> i64 {
+        self.wood as i64 + self.clay as i64 + self.iron as i64 + self.crop as i64
+    }
+}
+
+/// Input for a new resource transfer
+#[derive(Debug, Clone)]
+pub struct NewTransfer {
+    pub sender_id: Uuid,
+    pub from_village_id: Uuid,
+    pub to_village_id: Uuid,
+    pub wood: i32,
+    pub clay: i32,
+    pub iron: i32,
+    pub crop: i32,
+    pub tax_percent: i32,
+    pub departed_at: DateTime<Utc>,
+    pub arrives_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SendResourcesRequest {
+    pub to_x: i32,
+    pub to_y: i32,
+    #[serde(default)]
+    pub wood: i32,
+    #[serde(default)]
+    pub clay: i32,
+    #[serde(default)]
+    pub iron: i32,
+    #[serde(default)]
+    pub crop: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkTransferTarget {
+    pub village_id: Uuid,
+    #[serde(default)]
+    pub wood: i32,
+    #[serde(default)]
+    pub clay: i32,
+    #[serde(default)]
+    pub iron: i32,
+    #[serde(default)]
+    pub crop: i32,
+}
+
+impl BulkTransferTarget {
+    pub fn total(&self) -> i64 {
+        self.wood as i64 + self.clay as i64 + self.iron as i64 + self.crop as i64
+    }
+}
+
+/// Send resources from one village to several of the player's own villages
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkSendResourcesRequest {
+    pub targets: Vec<BulkTransferTarget>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkTransferStatus {
+    /// Everything requested was dispatched
+    Sent,
+    /// Merchants ran out, so only part of the request was dispatched
+    Partial,
+    /// Nothing was dispatched for this target
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkTransferResult {
+    pub to_village_id: Uuid,
+    pub status: BulkTransferStatus,
+    pub transfer: Option<ResourceTransferResponse>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkTransferResponse {
+    pub from_village_id: Uuid,
+    pub merchants_total: i32,
+    pub merchants_used: i32,
+    pub results: Vec<BulkTransferResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceTransferResponse {
+    pub id: Uuid,
+    pub from_village_id: Uuid,
+    pub to_village_id: Uuid,
+    pub wood: i32,
+    pub clay: i32,
+    pub iron: i32,
+    pub crop: i32,
+    pub tax_percent: i32,
+    pub delivered_wood: i32,
+    pub delivered_clay: i32,
+    pub delivered_iron: i32,
+    pub delivered_crop: i32,
+    pub departed_at: DateTime<Utc>,
+    pub arrives_at: DateTime<Utc>,
+}
+
+impl From<ResourceTransfer> for ResourceTransferResponse {
+    fn from(t: ResourceTransfer) -> Self {
+        Self {
+            id: t.id,
+            from_village_id: t.from_village_id,
+            to_village_id: t.to_village_id,
+            wood: t.wood,
+            clay: t.clay,
+            iron: t.iron,
+            crop: t.crop,
+            tax_percent: t.tax_percent,
+            delivered_wood: t.delivered(t.wood),
+            delivered_clay: t.delivered(t.clay),
+            delivered_iron: t.delivered(t.iron),
+            delivered_crop: t.delivered(t.crop),
+            departed_at: t.departed_at,
+            arrives_at: t.arrives_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VillageTransfersResponse {
+    pub outgoing: Vec<ResourceTransferResponse>,
+    pub incoming: Vec<ResourceTransferResponse>,
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use super::building::BuildingType;
@@ -33,6 +34,8 @@ pub enum TroopType {
     RoyalAdvisor,
     HarborMaster,
     ElderChief,
+    // Settlers (found new villages)
+    Settler,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -46,6 +49,30 @@ pub enum TribeType {
 }
 
 impl TroopType {
+    /// Every troop type, in declaration order
+    pub const ALL: [TroopType; 20] = [
+        TroopType::Infantry,
+        TroopType::Spearman,
+        TroopType::WarElephant,
+        TroopType::BuffaloWagon,
+        TroopType::KrisWarrior,
+        TroopType::SeaDiver,
+        TroopType::WarPrahu,
+        TroopType::MerchantShip,
+        TroopType::Crossbowman,
+        TroopType::MountainWarrior,
+        TroopType::HighlandPony,
+        TroopType::TrapMaker,
+        TroopType::SwampDragon,
+        TroopType::LocustSwarm,
+        TroopType::BattleDuck,
+        TroopType::PortugueseMusketeer,
+        TroopType::RoyalAdvisor,
+        TroopType::HarborMaster,
+        TroopType::ElderChief,
+        TroopType::Settler,
+    ];
+
     pub fn tribe(&self) -> TribeType {
         match self {
             TroopType::Infantry | TroopType::Spearman | TroopType::WarElephant | TroopType::BuffaloWagon | TroopType::RoyalAdvisor => TribeType::Phasuttha,
@@ -77,6 +104,11 @@ impl TroopType {
             TroopType::RoyalAdvisor | TroopType::HarborMaster | TroopType::ElderChief
         )
     }
+
+    /// Check if this troop type is a Settler (can found a village)
+    pub fn is_settler(&self) -> bool {
+        matches!(self, TroopType::Settler)
+    }
 }
 
 /// Troop definition from database (base stats)
@@ -105,9 +137,18 @@ pub struct TroopDefinition {
     pub required_building_level: i32,
     // Conquer ability
     pub loyalty_reduction: i32,
+    // Can be sent on scout missions
+    pub is_scout: bool,
     pub created_at: DateTime<Utc>,
 }
 
+impl TroopDefinition {
+    /// Whether the unit defends better (against either infantry or cavalry) than it attacks
+    pub fn is_defensive(&self) -> bool {
+        self.defense_infantry.max(self.defense_cavalry) > self.attack
+    }
+}
+
 /// Troops owned by a village
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Troop {
@@ -133,6 +174,30 @@ pub struct TroopQueue {
     pub created_at: DateTime<Utc>,
 }
 
+impl TroopQueue {
+    /// Number of units in this batch that have finished training by `now`
+    pub fn units_completed_at(&self, now: DateTime<Utc>) -> i32 {
+        if self.each_duration_seconds <= 0 {
+            return if now >= self.ends_at { self.count } else { 0 };
+        }
+
+        let elapsed = (now - self.started_at).num_seconds();
+        if elapsed <= 0 {
+            return 0;
+        }
+
+        (elapsed / self.each_duration_seconds as i64).min(self.count as i64) as i32
+    }
+}
+
+/// Troops a village keeps home; sends may not dip below these counts
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TroopReserve {
+    pub village_id: Uuid,
+    pub reserves: sqlx::types::Json<HashMap<TroopType, i32>>,
+    pub updated_at: DateTime<Utc>,
+}
+
 // Request/Response DTOs
 
 #[derive(Debug, Clone, Deserialize)]
@@ -141,6 +206,79 @@ pub struct TrainTroopsRequest {
     pub count: i32,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetTroopReserveRequest {
+    pub reserves: HashMap<TroopType, i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DisbandTroopsRequest {
+    pub troops: HashMap<TroopType, i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisbandTroopsResponse {
+    pub village_id: Uuid,
+    pub disbanded: HashMap<TroopType, i32>,
+    pub wood_refunded: i32,
+    pub clay_refunded: i32,
+    pub iron_refunded: i32,
+    pub crop_refunded: i32,
+    /// Crop the village's troops eat per hour after disbanding
+    pub crop_consumption: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopReserveResponse {
+    pub village_id: Uuid,
+    pub reserves: HashMap<TroopType, i32>,
+}
+
+/// Whether a troop type can be trained in a village, and what is missing if not
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopAvailability {
+    pub troop_type: TroopType,
+    pub available: bool,
+    pub required_building: BuildingType,
+    pub required_level: i32,
+    pub current_level: Option<i32>,
+    pub missing_requirement: Option<String>,
+}
+
+/// Pending training for one production building
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopQueueLine {
+    pub building_type: BuildingType,
+    pub units_pending: i32,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopQueueSummaryResponse {
+    pub village_id: Uuid,
+    pub all_done_at: Option<DateTime<Utc>>,
+    pub lines: Vec<TroopQueueLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopUpkeepEntry {
+    pub troop_type: TroopType,
+    pub count: i32,
+    pub crop_per_unit: i32,
+    pub crop_per_hour: i32,
+}
+
+/// Hourly crop balance of a village split into building and troop upkeep
+#[derive(Debug, Clone, Serialize)]
+pub struct TroopUpkeepResponse {
+    pub village_id: Uuid,
+    pub troops: Vec<TroopUpkeepEntry>,
+    pub troop_upkeep: i32,
+    pub building_upkeep: i32,
+    pub gross_crop_per_hour: i32,
+    pub net_crop_per_hour: i32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TrainTroopsResponse {
     pub queue_entry: TroopQueueResponse,
@@ -218,6 +356,7 @@ pub struct TroopDefinitionResponse {
     pub required_building: BuildingType,
     pub required_building_level: i32,
     pub loyalty_reduction: i32,
+    pub is_scout: bool,
 }
 
 impl From<TroopDefinition> for TroopDefinitionResponse {
@@ -241,6 +380,45 @@ impl From<TroopDefinition> for TroopDefinitionResponse {
             required_building: d.required_building,
             required_building_level: d.required_building_level,
             loyalty_reduction: d.loyalty_reduction,
+            is_scout: d.is_scout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn batch(count: i32, each_duration_seconds: i32, started_at: DateTime<Utc>) -> TroopQueue {
+        TroopQueue {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            troop_type: TroopType::Infantry,
+            count,
+            each_duration_seconds,
+            started_at,
+            ends_at: started_at + Duration::seconds((count * each_duration_seconds) as i64),
+            created_at: started_at,
         }
     }
+
+    #[test]
+    fn partially_trained_batch_delivers_the_finished_units() {
+        let started_at = Utc::now();
+        let queue = batch(10, 60, started_at);
+
+        assert_eq!(queue.units_completed_at(started_at + Duration::seconds(59)), 0);
+        assert_eq!(queue.units_completed_at(started_at + Duration::seconds(150)), 2);
+        assert_eq!(queue.units_completed_at(started_at + Duration::seconds(420)), 7);
+    }
+
+    #[test]
+    fn finished_batch_never_delivers_more_than_it_trains() {
+        let started_at = Utc::now();
+        let queue = batch(10, 60, started_at);
+
+        assert_eq!(queue.units_completed_at(started_at + Duration::hours(5)), 10);
+        assert_eq!(queue.units_completed_at(started_at - Duration::seconds(30)), 0);
+    }
 }
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::troop::TribeType;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: Uuid,
@@ -33,6 +35,40 @@ pub struct UpdateUser {
     pub photo_url: Option<String>,
 }
 
+/// Per-player gameplay preferences
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PlayerSettings {
+    /// Forward returning loot that overflows the home village to another own village
+    pub forward_loot_overflow: bool,
+    /// Tribe the player plays as; picked once, it decides tribe combat bonuses
+    pub tribe: Option<TribeType>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdatePlayerSettings {
+    pub forward_loot_overflow: Option<bool>,
+    pub tribe: Option<TribeType>,
+}
+
+/// What anyone may see about a player: no resources, troops or coordinates
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PublicPlayerProfile {
+    pub id: Uuid,
+    pub display_name: Option<String>,
+    pub tribe: Option<TribeType>,
+    pub alliance_id: Option<Uuid>,
+    pub alliance_name: Option<String>,
+    pub alliance_tag: Option<String>,
+    pub population: i64,
+    pub village_count: i64,
+    /// Position by total population among all players with villages
+    pub rank: i64,
+    pub joined_at: DateTime<Utc>,
+    /// Whether the player is still in their beginner protection window; set by the service
+    #[sqlx(default)]
+    pub under_beginner_protection: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: Uuid,
@@ -104,6 +104,25 @@ impl VillageResponse {
     }
 }
 
+/// A conquer battle that reduced a village's loyalty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoyaltyEvent {
+    pub report_id: Uuid,
+    pub attacker_player_id: Uuid,
+    pub attacker_village_id: Uuid,
+    pub loyalty_reduced: i32,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VillageLoyaltyResponse {
+    pub village_id: Uuid,
+    pub loyalty: i32,
+    pub max_loyalty: i32,
+    pub regen_per_hour: i32,
+    pub recent_events: Vec<LoyaltyEvent>,
+}
+
 // For map display - lightweight version
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct VillageMapInfo {
@@ -114,4 +133,60 @@ pub struct VillageMapInfo {
     pub y: i32,
     pub population: i32,
     pub player_name: Option<String>,
+    pub alliance_tag: Option<String>,
+}
+
+// Resource projection - when targets are reached and stores overflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceProjection {
+    pub current: i32,
+    pub per_hour: i32,
+    pub capacity: i32,
+    pub target: Option<i32>,
+    // None = never reached at current production
+    pub seconds_to_target: Option<i64>,
+    pub seconds_to_full: Option<i64>,
+    pub target_reached_at: Option<DateTime<Utc>>,
+    pub full_at: Option<DateTime<Utc>>,
+}
+
+/// Contributions to one resource's hourly production; base + fields + bonus = total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceProductionBreakdown {
+    /// Flat production every village gets
+    pub base: i32,
+    /// Raw output of the resource fields
+    pub fields: i32,
+    /// Boost from bonus buildings (Sawmill, Grain Mill, ...)
+    pub building_bonus_percent: i32,
+    /// Boost from idle heroes in the village
+    pub hero_bonus_percent: i32,
+    /// Boost from oases the village occupies
+    pub oasis_bonus_percent: i32,
+    /// Extra output from all percentage boosts
+    pub bonus: i32,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionBreakdownResponse {
+    pub village_id: Uuid,
+    pub wood: ResourceProductionBreakdown,
+    pub clay: ResourceProductionBreakdown,
+    pub iron: ResourceProductionBreakdown,
+    pub crop: ResourceProductionBreakdown,
+    /// Crop eaten by the population and troop upkeep each hour
+    pub crop_consumption: i32,
+    /// Negative when the village eats more crop than it grows
+    pub net_crop_per_hour: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceProjectionResponse {
+    pub village_id: Uuid,
+    pub wood: ResourceProjection,
+    pub clay: ResourceProjection,
+    pub iron: ResourceProjection,
+    pub crop: ResourceProjection,
+    pub calculated_at: DateTime<Utc>,
 }
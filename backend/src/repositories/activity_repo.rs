@@ -0,0 +1,68 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::activity::{VillageActivity, VillageActivityType};
+
+pub struct ActivityRepository;
+
+impl ActivityRepository {
+    pub async fn create(
+        pool: &PgPool,
+        village_id: Uuid,
+        activity_type: VillageActivityType,
+        details: &serde_json::Value,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO village_activity (village_id, activity_type, details)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(village_id)
+        .bind(activity_type)
+        .bind(sqlx::types::Json(details))
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Newest first
+    pub async fn find_by_village(
+        pool: &PgPool,
+        village_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<Vec<VillageActivity>> {
+        let activities = sqlx::query_as::<_, VillageActivity>(
+            r#"
+            SELECT id, village_id, activity_type, details, created_at
+            FROM village_activity
+            WHERE village_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(village_id)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(activities)
+    }
+
+    pub async fn count_by_village(pool: &PgPool, village_id: Uuid) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM village_activity WHERE village_id = $1
+            "#,
+        )
+        .bind(village_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.0)
+    }
+}
@@ -161,7 +161,7 @@ impl AllianceRepository {
             r#"
             INSERT INTO alliance_members (alliance_id, user_id, role)
             VALUES ($1, $2, $3)
-            RETURNING id, alliance_id, user_id, role, joined_at
+            RETURNING alliance_id, role
             "#,
         )
         .bind(alliance_id)
@@ -188,7 +188,7 @@ impl AllianceRepository {
     pub async fn get_member(pool: &PgPool, alliance_id: Uuid, user_id: Uuid) -> AppResult<Option<AllianceMember>> {
         let member = sqlx::query_as::<_, AllianceMember>(
             r#"
-            SELECT id, alliance_id, user_id, role, joined_at
+            SELECT alliance_id, role
             FROM alliance_members
             WHERE alliance_id = $1 AND user_id = $2
             "#,
@@ -204,7 +204,7 @@ impl AllianceRepository {
     pub async fn get_user_alliance(pool: &PgPool, user_id: Uuid) -> AppResult<Option<AllianceMember>> {
         let member = sqlx::query_as::<_, AllianceMember>(
             r#"
-            SELECT id, alliance_id, user_id, role, joined_at
+            SELECT alliance_id, role
             FROM alliance_members
             WHERE user_id = $1
             "#,
@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::api_token::ApiToken;
+
+pub struct ApiTokenRepository;
+
+impl ApiTokenRepository {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+    ) -> AppResult<ApiToken> {
+        let token = sqlx::query_as::<_, ApiToken>(
+            r#"
+            INSERT INTO api_tokens (user_id, name, token_hash, scopes)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, name, scopes, last_used_at, revoked_at, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(scopes)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn find_by_user(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<ApiToken>> {
+        let tokens = sqlx::query_as::<_, ApiToken>(
+            r#"
+            SELECT id, user_id, name, scopes, last_used_at, revoked_at, created_at
+            FROM api_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Find a token that has not been revoked
+    pub async fn find_active_by_hash(pool: &PgPool, token_hash: &str) -> AppResult<Option<ApiToken>> {
+        let token = sqlx::query_as::<_, ApiToken>(
+            r#"
+            SELECT id, user_id, name, scopes, last_used_at, revoked_at, created_at
+            FROM api_tokens
+            WHERE token_hash = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn count_active(pool: &PgPool, user_id: Uuid) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM api_tokens WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Revoke a token owned by the user; returns false if there was nothing to revoke
+    pub async fn revoke(pool: &PgPool, user_id: Uuid, token_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_tokens
+            SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn touch(pool: &PgPool, token_id: Uuid) -> AppResult<()> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = NOW() WHERE id = $1")
+            .bind(token_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
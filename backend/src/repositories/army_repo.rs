@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppResult;
-use crate::models::army::{Army, ArmyTroops, BattleReport, CarriedResources, MissionType, ScoutReport};
+use crate::models::army::{
+    Army, ArmyTroops, BattleReport, CarriedResources, NewArmy, NewBattleReport, NewScoutReport, ScoutReport,
+    StationedSender,
+};
+use crate::models::village::LoyaltyEvent;
 
 pub struct ArmyRepository;
 
@@ -15,7 +19,8 @@ impl ArmyRepository {
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE id = $1
             "#,
@@ -32,7 +37,8 @@ impl ArmyRepository {
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE player_id = $1
             ORDER BY arrives_at ASC
@@ -50,7 +56,8 @@ impl ArmyRepository {
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE from_village_id = $1 AND is_stationed = FALSE
             ORDER BY arrives_at ASC
@@ -68,9 +75,11 @@ impl ArmyRepository {
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE to_village_id = $1 AND is_returning = FALSE AND is_stationed = FALSE
+              AND departed_at <= NOW()
             ORDER BY arrives_at ASC
             "#,
         )
@@ -81,49 +90,98 @@ impl ArmyRepository {
         Ok(armies)
     }
 
-    pub async fn create(
+    /// Count hostile armies currently visible as incoming to any of a player's villages.
+    /// Scheduled armies that have not departed yet stay hidden from the defender.
+    pub async fn count_incoming_hostile_for_player(pool: &PgPool, user_id: Uuid) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM armies a
+            JOIN villages v ON v.id = a.to_village_id
+            WHERE v.user_id = $1 AND a.player_id <> $1
+              AND a.mission IN ('raid', 'attack', 'conquer', 'scout')
+              AND a.is_returning = FALSE AND a.is_stationed = FALSE
+              AND a.departed_at <= NOW()
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Defender and target village of every scheduled hostile army whose departure
+    /// time fell in `(since, until]`, i.e. that just became visible as incoming
+    pub async fn find_released_hostile_targets(
         pool: &PgPool,
-        player_id: Uuid,
-        from_village_id: Uuid,
-        to_x: i32,
-        to_y: i32,
-        to_village_id: Option<Uuid>,
-        mission: MissionType,
-        troops: &ArmyTroops,
-        resources: &CarriedResources,
-        departed_at: DateTime<Utc>,
-        arrives_at: DateTime<Utc>,
-        returns_at: Option<DateTime<Utc>>,
-    ) -> AppResult<Army> {
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> AppResult<Vec<(Uuid, Uuid)>> {
+        let targets = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"
+            SELECT DISTINCT v.user_id, v.id
+            FROM armies a
+            JOIN villages v ON v.id = a.to_village_id
+            WHERE a.player_id <> v.user_id
+              AND a.mission IN ('raid', 'attack', 'conquer', 'scout')
+              AND a.is_returning = FALSE AND a.is_stationed = FALSE
+              AND a.departed_at > a.created_at
+              AND a.departed_at > $1 AND a.departed_at <= $2
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(targets)
+    }
+
+    pub async fn create<'e>(executor: impl PgExecutor<'e>, input: NewArmy) -> AppResult<Army> {
         let army = sqlx::query_as::<_, Army>(
             r#"
             INSERT INTO armies (player_id, from_village_id, to_x, to_y, to_village_id,
-                               mission, troops, resources, departed_at, arrives_at, returns_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                               mission, troops, resources, departed_at, arrives_at, returns_at,
+                               max_station_hours, hero_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING id, player_id, from_village_id, to_x, to_y, to_village_id,
                       mission, troops, resources, departed_at, arrives_at,
-                      returns_at, is_returning, is_stationed, battle_report_id, created_at
+                      returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                      max_station_hours, created_at
             "#,
         )
-        .bind(player_id)
-        .bind(from_village_id)
-        .bind(to_x)
-        .bind(to_y)
-        .bind(to_village_id)
-        .bind(&mission)
-        .bind(sqlx::types::Json(troops))
-        .bind(sqlx::types::Json(resources))
-        .bind(departed_at)
-        .bind(arrives_at)
-        .bind(returns_at)
-        .fetch_one(pool)
+        .bind(input.player_id)
+        .bind(input.from_village_id)
+        .bind(input.to_x)
+        .bind(input.to_y)
+        .bind(input.to_village_id)
+        .bind(input.mission)
+        .bind(sqlx::types::Json(input.troops))
+        .bind(sqlx::types::Json(input.resources))
+        .bind(input.departed_at)
+        .bind(input.arrives_at)
+        .bind(input.returns_at)
+        .bind(input.max_station_hours)
+        .bind(input.hero_id)
+        .fetch_one(executor)
         .await?;
 
         Ok(army)
     }
 
-    pub async fn set_returning(
-        pool: &PgPool,
+    /// Detach the hero from an army, e.g. after it fell in battle
+    pub async fn clear_hero<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<()> {
+        sqlx::query("UPDATE armies SET hero_id = NULL WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_returning<'e>(
+        executor: impl PgExecutor<'e>,
         id: Uuid,
         returns_at: DateTime<Utc>,
         resources: &CarriedResources,
@@ -134,14 +192,15 @@ impl ArmyRepository {
             r#"
             UPDATE armies
             SET is_returning = TRUE,
-                arrives_at = $2,
+                returns_at = $2,
                 resources = $3,
                 troops = $4,
                 battle_report_id = $5
             WHERE id = $1
             RETURNING id, player_id, from_village_id, to_x, to_y, to_village_id,
                       mission, troops, resources, departed_at, arrives_at,
-                      returns_at, is_returning, is_stationed, battle_report_id, created_at
+                      returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                      max_station_hours, created_at
             "#,
         )
         .bind(id)
@@ -149,29 +208,93 @@ impl ArmyRepository {
         .bind(sqlx::types::Json(resources))
         .bind(sqlx::types::Json(surviving_troops))
         .bind(battle_report_id)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(army)
     }
 
-    pub async fn delete(pool: &PgPool, id: Uuid) -> AppResult<()> {
+    /// Count armies from a village that are scheduled, travelling or returning
+    pub async fn count_active_from_village(pool: &PgPool, village_id: Uuid) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM armies
+            WHERE from_village_id = $1 AND is_stationed = FALSE
+            "#,
+        )
+        .bind(village_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Count a player's armies that are scheduled, travelling or returning
+    pub async fn count_active_by_player(pool: &PgPool, player_id: Uuid) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM armies
+            WHERE player_id = $1 AND is_stationed = FALSE
+            "#,
+        )
+        .bind(player_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<()> {
         sqlx::query("DELETE FROM armies WHERE id = $1")
             .bind(id)
-            .execute(pool)
+            .execute(executor)
             .await?;
 
         Ok(())
     }
 
+    /// Delete an army that has not departed yet, returning it; None once it
+    /// has left (or was already cancelled), so only one caller gets to refund it
+    pub async fn delete_scheduled<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> AppResult<Option<Army>> {
+        let army = sqlx::query_as::<_, Army>(
+            r#"
+            DELETE FROM armies
+            WHERE id = $1 AND is_returning = FALSE AND departed_at > NOW()
+            RETURNING id, player_id, from_village_id, to_x, to_y, to_village_id,
+                      mission, troops, resources, departed_at, arrives_at,
+                      returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                      max_station_hours, created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(army)
+    }
+
+    /// Find armies whose current leg is complete: outbound armies that reached
+    /// their target (arrives_at) and returning armies that reached home (returns_at)
+    ///
+    /// Ordered by the time each leg completed so that waves landing on the same
+    /// village seconds apart are resolved in arrival order; ties fall back to
+    /// send order. Matches `Army::is_due`.
     pub async fn find_arrived(pool: &PgPool) -> AppResult<Vec<Army>> {
         let armies = sqlx::query_as::<_, Army>(
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
-            WHERE arrives_at <= NOW() AND is_stationed = FALSE
+            WHERE is_stationed = FALSE
+              AND ((is_returning = FALSE AND arrives_at <= NOW())
+                OR (is_returning = TRUE AND returns_at <= NOW()))
+            ORDER BY CASE WHEN is_returning THEN returns_at ELSE arrives_at END ASC,
+                     departed_at ASC, created_at ASC, id ASC
             "#,
         )
         .fetch_all(pool)
@@ -182,19 +305,27 @@ impl ArmyRepository {
 
     // ==================== Stationed Troops (Support) ====================
 
-    /// Mark army as stationed at target village
-    pub async fn set_stationed(pool: &PgPool, id: Uuid) -> AppResult<Army> {
+    /// Station an arrived support army; `resources` is what it keeps carrying
+    /// (whatever did not fit in the host's storage)
+    pub async fn set_stationed(
+        pool: &PgPool,
+        id: Uuid,
+        resources: &CarriedResources,
+    ) -> AppResult<Army> {
         let army = sqlx::query_as::<_, Army>(
             r#"
             UPDATE armies
-            SET is_stationed = TRUE
+            SET is_stationed = TRUE,
+                resources = $2
             WHERE id = $1
             RETURNING id, player_id, from_village_id, to_x, to_y, to_village_id,
                       mission, troops, resources, departed_at, arrives_at,
-                      returns_at, is_returning, is_stationed, battle_report_id, created_at
+                      returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                      max_station_hours, created_at
             "#,
         )
         .bind(id)
+        .bind(sqlx::types::Json(resources))
         .fetch_one(pool)
         .await?;
 
@@ -207,7 +338,8 @@ impl ArmyRepository {
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE to_village_id = $1 AND is_stationed = TRUE
             ORDER BY arrives_at ASC
@@ -220,13 +352,38 @@ impl ArmyRepository {
         Ok(armies)
     }
 
+    /// Sender details (player, alliance, home village) of the support stationed at a village
+    pub async fn find_stationed_senders(
+        pool: &PgPool,
+        village_id: Uuid,
+    ) -> AppResult<Vec<StationedSender>> {
+        let senders = sqlx::query_as::<_, StationedSender>(
+            r#"
+            SELECT a.id as army_id, u.display_name as sender_name, al.tag as sender_alliance_tag,
+                   v.name as from_village_name, v.x as from_x, v.y as from_y
+            FROM armies a
+            LEFT JOIN users u ON u.id = a.player_id
+            LEFT JOIN alliance_members am ON am.user_id = a.player_id
+            LEFT JOIN alliances al ON al.id = am.alliance_id
+            LEFT JOIN villages v ON v.id = a.from_village_id
+            WHERE a.to_village_id = $1 AND a.is_stationed = TRUE
+            "#,
+        )
+        .bind(village_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(senders)
+    }
+
     /// Find support sent by a player to other villages
     pub async fn find_support_sent_by_player(pool: &PgPool, player_id: Uuid) -> AppResult<Vec<Army>> {
         let armies = sqlx::query_as::<_, Army>(
             r#"
             SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
                    mission, troops, resources, departed_at, arrives_at,
-                   returns_at, is_returning, is_stationed, battle_report_id, created_at
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
             FROM armies
             WHERE player_id = $1 AND is_stationed = TRUE
             ORDER BY arrives_at ASC
@@ -239,6 +396,27 @@ impl ArmyRepository {
         Ok(armies)
     }
 
+    /// Find stationed support that has outlived its max station duration
+    /// (`Army::is_station_expired`)
+    pub async fn find_expired_stationed(pool: &PgPool) -> AppResult<Vec<Army>> {
+        let armies = sqlx::query_as::<_, Army>(
+            r#"
+            SELECT id, player_id, from_village_id, to_x, to_y, to_village_id,
+                   mission, troops, resources, departed_at, arrives_at,
+                   returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                   max_station_hours, created_at
+            FROM armies
+            WHERE is_stationed = TRUE
+              AND max_station_hours IS NOT NULL
+              AND arrives_at + make_interval(hours => max_station_hours) <= NOW()
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(armies)
+    }
+
     /// Start recall: set army as returning from stationed position
     pub async fn start_recall(
         pool: &PgPool,
@@ -250,11 +428,12 @@ impl ArmyRepository {
             UPDATE armies
             SET is_stationed = FALSE,
                 is_returning = TRUE,
-                arrives_at = $2
+                returns_at = $2
             WHERE id = $1
             RETURNING id, player_id, from_village_id, to_x, to_y, to_village_id,
                       mission, troops, resources, departed_at, arrives_at,
-                      returns_at, is_returning, is_stationed, battle_report_id, created_at
+                      returns_at, is_returning, is_stationed, battle_report_id, hero_id,
+                      max_station_hours, created_at
             "#,
         )
         .bind(id)
@@ -295,46 +474,39 @@ impl ArmyRepository {
 
     // ==================== Battle Reports ====================
 
-    pub async fn create_battle_report(
-        pool: &PgPool,
-        attacker_player_id: Uuid,
-        defender_player_id: Option<Uuid>,
-        attacker_village_id: Uuid,
-        defender_village_id: Option<Uuid>,
-        mission: MissionType,
-        attacker_troops: &ArmyTroops,
-        defender_troops: &ArmyTroops,
-        attacker_losses: &ArmyTroops,
-        defender_losses: &ArmyTroops,
-        resources_stolen: &CarriedResources,
-        winner: &str,
-        occurred_at: DateTime<Utc>,
-    ) -> AppResult<BattleReport> {
+    pub async fn create_battle_report(pool: &PgPool, input: NewBattleReport<'_>) -> AppResult<BattleReport> {
         let report = sqlx::query_as::<_, BattleReport>(
             r#"
             INSERT INTO battle_reports (
                 attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                 mission, attacker_troops, defender_troops, attacker_losses, defender_losses,
-                resources_stolen, winner, occurred_at
+                resources_stolen, defender_resources, winner, loyalty_reduced, hero_result,
+                wall_bonus_percent, occurred_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             RETURNING id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                       mission, attacker_troops, defender_troops, attacker_losses, defender_losses,
-                      resources_stolen, winner, occurred_at, read_by_attacker, read_by_defender, created_at
+                      resources_stolen, defender_resources, winner, loyalty_reduced, hero_result,
+                      wall_bonus_percent, occurred_at,
+                      read_by_attacker, read_by_defender, created_at
             "#,
         )
-        .bind(attacker_player_id)
-        .bind(defender_player_id)
-        .bind(attacker_village_id)
-        .bind(defender_village_id)
-        .bind(&mission)
-        .bind(sqlx::types::Json(attacker_troops))
-        .bind(sqlx::types::Json(defender_troops))
-        .bind(sqlx::types::Json(attacker_losses))
-        .bind(sqlx::types::Json(defender_losses))
-        .bind(sqlx::types::Json(resources_stolen))
-        .bind(winner)
-        .bind(occurred_at)
+        .bind(input.attacker_player_id)
+        .bind(input.defender_player_id)
+        .bind(input.attacker_village_id)
+        .bind(input.defender_village_id)
+        .bind(input.mission)
+        .bind(sqlx::types::Json(input.attacker_troops))
+        .bind(sqlx::types::Json(input.defender_troops))
+        .bind(sqlx::types::Json(input.attacker_losses))
+        .bind(sqlx::types::Json(input.defender_losses))
+        .bind(sqlx::types::Json(input.resources_stolen))
+        .bind(input.defender_resources.map(sqlx::types::Json))
+        .bind(input.winner)
+        .bind(input.loyalty_reduced)
+        .bind(input.hero_result.map(sqlx::types::Json))
+        .bind(input.wall_bonus_percent)
+        .bind(input.occurred_at)
         .fetch_one(pool)
         .await?;
 
@@ -344,12 +516,19 @@ impl ArmyRepository {
     pub async fn find_reports_by_player(pool: &PgPool, player_id: Uuid) -> AppResult<Vec<BattleReport>> {
         let reports = sqlx::query_as::<_, BattleReport>(
             r#"
-            SELECT id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
-                   mission, attacker_troops, defender_troops, attacker_losses, defender_losses,
-                   resources_stolen, winner, occurred_at, read_by_attacker, read_by_defender, created_at
-            FROM battle_reports
-            WHERE attacker_player_id = $1 OR defender_player_id = $1
-            ORDER BY occurred_at DESC
+            SELECT r.id, r.attacker_player_id, r.defender_player_id, r.attacker_village_id,
+                   r.defender_village_id, r.mission, r.attacker_troops, r.defender_troops,
+                   r.attacker_losses, r.defender_losses, r.resources_stolen, r.defender_resources, r.winner,
+                   r.loyalty_reduced, r.hero_result, r.wall_bonus_percent, r.occurred_at,
+                   r.read_by_attacker, r.read_by_defender, r.created_at,
+                   aa.tag as attacker_alliance_tag, da.tag as defender_alliance_tag
+            FROM battle_reports r
+            LEFT JOIN alliance_members aam ON aam.user_id = r.attacker_player_id
+            LEFT JOIN alliances aa ON aa.id = aam.alliance_id
+            LEFT JOIN alliance_members dam ON dam.user_id = r.defender_player_id
+            LEFT JOIN alliances da ON da.id = dam.alliance_id
+            WHERE r.attacker_player_id = $1 OR r.defender_player_id = $1
+            ORDER BY r.occurred_at DESC
             LIMIT 100
             "#,
         )
@@ -363,11 +542,18 @@ impl ArmyRepository {
     pub async fn find_report_by_id(pool: &PgPool, id: Uuid) -> AppResult<Option<BattleReport>> {
         let report = sqlx::query_as::<_, BattleReport>(
             r#"
-            SELECT id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
-                   mission, attacker_troops, defender_troops, attacker_losses, defender_losses,
-                   resources_stolen, winner, occurred_at, read_by_attacker, read_by_defender, created_at
-            FROM battle_reports
-            WHERE id = $1
+            SELECT r.id, r.attacker_player_id, r.defender_player_id, r.attacker_village_id,
+                   r.defender_village_id, r.mission, r.attacker_troops, r.defender_troops,
+                   r.attacker_losses, r.defender_losses, r.resources_stolen, r.defender_resources, r.winner,
+                   r.loyalty_reduced, r.hero_result, r.wall_bonus_percent, r.occurred_at,
+                   r.read_by_attacker, r.read_by_defender, r.created_at,
+                   aa.tag as attacker_alliance_tag, da.tag as defender_alliance_tag
+            FROM battle_reports r
+            LEFT JOIN alliance_members aam ON aam.user_id = r.attacker_player_id
+            LEFT JOIN alliances aa ON aa.id = aam.alliance_id
+            LEFT JOIN alliance_members dam ON dam.user_id = r.defender_player_id
+            LEFT JOIN alliances da ON da.id = dam.alliance_id
+            WHERE r.id = $1
             "#,
         )
         .bind(id)
@@ -377,6 +563,29 @@ impl ArmyRepository {
         Ok(report)
     }
 
+    /// Recent conquer battles that reduced a village's loyalty, newest first
+    pub async fn find_loyalty_events(
+        pool: &PgPool,
+        village_id: Uuid,
+        limit: i64,
+    ) -> AppResult<Vec<LoyaltyEvent>> {
+        let events = sqlx::query_as::<_, LoyaltyEvent>(
+            r#"
+            SELECT id as report_id, attacker_player_id, attacker_village_id, loyalty_reduced, occurred_at
+            FROM battle_reports
+            WHERE defender_village_id = $1 AND loyalty_reduced > 0
+            ORDER BY occurred_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(village_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
     pub async fn mark_report_read(pool: &PgPool, id: Uuid, is_attacker: bool) -> AppResult<()> {
         let query = if is_attacker {
             "UPDATE battle_reports SET read_by_attacker = TRUE WHERE id = $1"
@@ -407,47 +616,34 @@ impl ArmyRepository {
 
     // ==================== Scout Reports ====================
 
-    pub async fn create_scout_report(
-        pool: &PgPool,
-        attacker_player_id: Uuid,
-        defender_player_id: Option<Uuid>,
-        attacker_village_id: Uuid,
-        defender_village_id: Option<Uuid>,
-        attacker_scouts: i32,
-        defender_scouts: i32,
-        attacker_scouts_lost: i32,
-        defender_scouts_lost: i32,
-        success: bool,
-        scouted_resources: Option<&CarriedResources>,
-        scouted_troops: Option<&ArmyTroops>,
-        occurred_at: DateTime<Utc>,
-    ) -> AppResult<ScoutReport> {
+    pub async fn create_scout_report(pool: &PgPool, input: NewScoutReport<'_>) -> AppResult<ScoutReport> {
         let report = sqlx::query_as::<_, ScoutReport>(
             r#"
             INSERT INTO scout_reports (
                 attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                 attacker_scouts, defender_scouts, attacker_scouts_lost, defender_scouts_lost,
-                success, scouted_resources, scouted_troops, occurred_at
+                success, scouted_resources, scouted_troops, troops_exact, occurred_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                       attacker_scouts, defender_scouts, attacker_scouts_lost, defender_scouts_lost,
-                      success, scouted_resources, scouted_troops, occurred_at,
+                      success, scouted_resources, scouted_troops, troops_exact, occurred_at,
                       read_by_attacker, read_by_defender, created_at
             "#,
         )
-        .bind(attacker_player_id)
-        .bind(defender_player_id)
-        .bind(attacker_village_id)
-        .bind(defender_village_id)
-        .bind(attacker_scouts)
-        .bind(defender_scouts)
-        .bind(attacker_scouts_lost)
-        .bind(defender_scouts_lost)
-        .bind(success)
-        .bind(scouted_resources.map(|r| sqlx::types::Json(r)))
-        .bind(scouted_troops.map(|t| sqlx::types::Json(t)))
-        .bind(occurred_at)
+        .bind(input.attacker_player_id)
+        .bind(input.defender_player_id)
+        .bind(input.attacker_village_id)
+        .bind(input.defender_village_id)
+        .bind(input.attacker_scouts)
+        .bind(input.defender_scouts)
+        .bind(input.attacker_scouts_lost)
+        .bind(input.defender_scouts_lost)
+        .bind(input.success)
+        .bind(input.scouted_resources.map(|r| sqlx::types::Json(r)))
+        .bind(input.scouted_troops.map(|t| sqlx::types::Json(t)))
+        .bind(input.troops_exact)
+        .bind(input.occurred_at)
         .fetch_one(pool)
         .await?;
 
@@ -459,7 +655,7 @@ impl ArmyRepository {
             r#"
             SELECT id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                    attacker_scouts, defender_scouts, attacker_scouts_lost, defender_scouts_lost,
-                   success, scouted_resources, scouted_troops, occurred_at,
+                   success, scouted_resources, scouted_troops, troops_exact, occurred_at,
                    read_by_attacker, read_by_defender, created_at
             FROM scout_reports
             WHERE attacker_player_id = $1 OR defender_player_id = $1
@@ -479,7 +675,7 @@ impl ArmyRepository {
             r#"
             SELECT id, attacker_player_id, defender_player_id, attacker_village_id, defender_village_id,
                    attacker_scouts, defender_scouts, attacker_scouts_lost, defender_scouts_lost,
-                   success, scouted_resources, scouted_troops, occurred_at,
+                   success, scouted_resources, scouted_troops, troops_exact, occurred_at,
                    read_by_attacker, read_by_defender, created_at
             FROM scout_reports
             WHERE id = $1
@@ -492,6 +688,31 @@ impl ArmyRepository {
         Ok(report)
     }
 
+    /// Whether the player scouted the village successfully since `since`
+    pub async fn has_successful_scout_since(
+        pool: &PgPool,
+        player_id: Uuid,
+        village_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> AppResult<bool> {
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM scout_reports
+                WHERE attacker_player_id = $1 AND defender_village_id = $2
+                  AND success = TRUE AND occurred_at >= $3
+            )
+            "#,
+        )
+        .bind(player_id)
+        .bind(village_id)
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
     pub async fn mark_scout_report_read(pool: &PgPool, id: Uuid, is_attacker: bool) -> AppResult<()> {
         let query = if is_attacker {
             "UPDATE scout_reports SET read_by_attacker = TRUE WHERE id = $1"
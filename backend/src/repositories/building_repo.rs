@@ -1,9 +1,9 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppResult;
-use crate::models::building::{Building, BuildingType, CreateBuilding};
+use crate::models::building::{Building, BuildingQueueEntry, BuildingType, CreateBuilding};
 
 pub struct BuildingRepository;
 
@@ -24,7 +24,31 @@ impl BuildingRepository {
         Ok(building)
     }
 
-    pub async fn find_by_village_id(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<Building>> {
+    /// Read a building and lock its row until the transaction ends
+    pub async fn find_by_id_for_update<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> AppResult<Option<Building>> {
+        let building = sqlx::query_as::<_, Building>(
+            r#"
+            SELECT id, village_id, building_type, slot, level,
+                   is_upgrading, upgrade_ends_at, created_at, updated_at
+            FROM buildings
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(building)
+    }
+
+    pub async fn find_by_village_id<'e>(
+        executor: impl PgExecutor<'e>,
+        village_id: Uuid,
+    ) -> AppResult<Vec<Building>> {
         let buildings = sqlx::query_as::<_, Building>(
             r#"
             SELECT id, village_id, building_type, slot, level,
@@ -35,7 +59,7 @@ impl BuildingRepository {
             "#,
         )
         .bind(village_id)
-        .fetch_all(pool)
+        .fetch_all(executor)
         .await?;
 
         Ok(buildings)
@@ -62,8 +86,8 @@ impl BuildingRepository {
         Ok(building)
     }
 
-    pub async fn find_upgrading_by_village(
-        pool: &PgPool,
+    pub async fn find_upgrading_by_village<'e>(
+        executor: impl PgExecutor<'e>,
         village_id: Uuid,
     ) -> AppResult<Vec<Building>> {
         let buildings = sqlx::query_as::<_, Building>(
@@ -76,13 +100,16 @@ impl BuildingRepository {
             "#,
         )
         .bind(village_id)
-        .fetch_all(pool)
+        .fetch_all(executor)
         .await?;
 
         Ok(buildings)
     }
 
-    pub async fn create(pool: &PgPool, input: CreateBuilding) -> AppResult<Building> {
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        input: CreateBuilding,
+    ) -> AppResult<Building> {
         let building = sqlx::query_as::<_, Building>(
             r#"
             INSERT INTO buildings (village_id, building_type, slot, level)
@@ -94,14 +121,14 @@ impl BuildingRepository {
         .bind(&input.village_id)
         .bind(&input.building_type)
         .bind(input.slot)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(building)
     }
 
-    pub async fn start_upgrade(
-        pool: &PgPool,
+    pub async fn start_upgrade<'e>(
+        executor: impl PgExecutor<'e>,
         id: Uuid,
         upgrade_ends_at: DateTime<Utc>,
     ) -> AppResult<Building> {
@@ -118,7 +145,7 @@ impl BuildingRepository {
         )
         .bind(id)
         .bind(upgrade_ends_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(building)
@@ -144,36 +171,41 @@ impl BuildingRepository {
         Ok(building)
     }
 
-    pub async fn cancel_upgrade(pool: &PgPool, id: Uuid) -> AppResult<Building> {
+    /// Stop an upgrade in progress; None if it already finished or was cancelled
+    pub async fn cancel_upgrade<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<Option<Building>> {
         let building = sqlx::query_as::<_, Building>(
             r#"
             UPDATE buildings
             SET is_upgrading = FALSE,
                 upgrade_ends_at = NULL,
                 updated_at = NOW()
-            WHERE id = $1
+            WHERE id = $1 AND is_upgrading = TRUE
             RETURNING id, village_id, building_type, slot, level,
                       is_upgrading, upgrade_ends_at, created_at, updated_at
             "#,
         )
         .bind(id)
-        .fetch_one(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(building)
     }
 
-    pub async fn demolish(pool: &PgPool, id: Uuid) -> AppResult<()> {
-        sqlx::query(
+    /// Delete a building that is not upgrading; None if it is already gone or an upgrade started
+    pub async fn demolish<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<Option<Building>> {
+        let building = sqlx::query_as::<_, Building>(
             r#"
-            DELETE FROM buildings WHERE id = $1
+            DELETE FROM buildings
+            WHERE id = $1 AND is_upgrading = FALSE
+            RETURNING id, village_id, building_type, slot, level,
+                      is_upgrading, upgrade_ends_at, created_at, updated_at
             "#,
         )
         .bind(id)
-        .execute(pool)
+        .fetch_optional(executor)
         .await?;
 
-        Ok(())
+        Ok(building)
     }
 
     pub async fn find_completed_upgrades(pool: &PgPool) -> AppResult<Vec<Building>> {
@@ -191,20 +223,6 @@ impl BuildingRepository {
         Ok(buildings)
     }
 
-    pub async fn count_upgrading_by_village(pool: &PgPool, village_id: Uuid) -> AppResult<i64> {
-        let count: (i64,) = sqlx::query_as(
-            r#"
-            SELECT COUNT(*) FROM buildings
-            WHERE village_id = $1 AND is_upgrading = TRUE
-            "#,
-        )
-        .bind(village_id)
-        .fetch_one(pool)
-        .await?;
-
-        Ok(count.0)
-    }
-
     pub async fn find_by_type(
         pool: &PgPool,
         village_id: Uuid,
@@ -226,4 +244,60 @@ impl BuildingRepository {
 
         Ok(buildings)
     }
+
+    // ==================== Chained Upgrade Queue ====================
+
+    pub async fn enqueue_upgrade<'e>(
+        executor: impl PgExecutor<'e>,
+        building_id: Uuid,
+        village_id: Uuid,
+        target_level: i32,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> AppResult<BuildingQueueEntry> {
+        let entry = sqlx::query_as::<_, BuildingQueueEntry>(
+            r#"
+            INSERT INTO building_queue (building_id, village_id, target_level, starts_at, ends_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, building_id, village_id, target_level, starts_at, ends_at, created_at
+            "#,
+        )
+        .bind(building_id)
+        .bind(village_id)
+        .bind(target_level)
+        .bind(starts_at)
+        .bind(ends_at)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(entry)
+    }
+
+    pub async fn find_queue_by_building<'e>(
+        executor: impl PgExecutor<'e>,
+        building_id: Uuid,
+    ) -> AppResult<Vec<BuildingQueueEntry>> {
+        let entries = sqlx::query_as::<_, BuildingQueueEntry>(
+            r#"
+            SELECT id, building_id, village_id, target_level, starts_at, ends_at, created_at
+            FROM building_queue
+            WHERE building_id = $1
+            ORDER BY target_level ASC
+            "#,
+        )
+        .bind(building_id)
+        .fetch_all(executor)
+        .await?;
+
+        Ok(entries)
+    }
+
+    pub async fn delete_queued<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<()> {
+        sqlx::query("DELETE FROM building_queue WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
 }
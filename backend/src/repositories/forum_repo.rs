@@ -0,0 +1,182 @@
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::forum::{ForumPostResponse, ForumThread, ForumThreadListItem};
+
+pub struct ForumRepository;
+
+impl ForumRepository {
+    // ==================== Threads ====================
+
+    pub async fn create_thread<'e>(
+        executor: impl PgExecutor<'e>,
+        alliance_id: Uuid,
+        author_id: Uuid,
+        title: &str,
+    ) -> AppResult<ForumThread> {
+        let thread = sqlx::query_as::<_, ForumThread>(
+            r#"
+            INSERT INTO forum_threads (alliance_id, author_id, title)
+            VALUES ($1, $2, $3)
+            RETURNING id, alliance_id
+            "#,
+        )
+        .bind(alliance_id)
+        .bind(author_id)
+        .bind(title)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(thread)
+    }
+
+    pub async fn find_thread(pool: &PgPool, thread_id: Uuid) -> AppResult<Option<ForumThread>> {
+        let thread = sqlx::query_as::<_, ForumThread>(
+            r#"
+            SELECT id, alliance_id
+            FROM forum_threads
+            WHERE id = $1
+            "#,
+        )
+        .bind(thread_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(thread)
+    }
+
+    /// Thread with author name and reply count
+    pub async fn get_thread_item(
+        pool: &PgPool,
+        thread_id: Uuid,
+    ) -> AppResult<Option<ForumThreadListItem>> {
+        let thread = sqlx::query_as::<_, ForumThreadListItem>(
+            r#"
+            SELECT t.id, t.author_id, u.display_name as author_name, t.title, t.is_pinned,
+                   (SELECT COUNT(*) FROM forum_posts p WHERE p.thread_id = t.id) as post_count,
+                   t.last_post_at, t.created_at
+            FROM forum_threads t
+            JOIN users u ON u.id = t.author_id
+            WHERE t.id = $1
+            "#,
+        )
+        .bind(thread_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(thread)
+    }
+
+    /// List an alliance's threads, pinned first then most recently active
+    pub async fn list_threads(
+        pool: &PgPool,
+        alliance_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<Vec<ForumThreadListItem>> {
+        let threads = sqlx::query_as::<_, ForumThreadListItem>(
+            r#"
+            SELECT t.id, t.author_id, u.display_name as author_name, t.title, t.is_pinned,
+                   (SELECT COUNT(*) FROM forum_posts p WHERE p.thread_id = t.id) as post_count,
+                   t.last_post_at, t.created_at
+            FROM forum_threads t
+            JOIN users u ON u.id = t.author_id
+            WHERE t.alliance_id = $1
+            ORDER BY t.is_pinned DESC, t.last_post_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(alliance_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(threads)
+    }
+
+    pub async fn set_pinned(pool: &PgPool, thread_id: Uuid, pinned: bool) -> AppResult<()> {
+        sqlx::query("UPDATE forum_threads SET is_pinned = $2 WHERE id = $1")
+            .bind(thread_id)
+            .bind(pinned)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ==================== Posts ====================
+
+    /// Add a post and bump the thread's activity time
+    pub async fn create_post(
+        conn: &mut PgConnection,
+        thread_id: Uuid,
+        author_id: Uuid,
+        body: &str,
+    ) -> AppResult<Uuid> {
+        let (post_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO forum_posts (thread_id, author_id, body)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+        )
+        .bind(thread_id)
+        .bind(author_id)
+        .bind(body)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        sqlx::query("UPDATE forum_threads SET last_post_at = NOW() WHERE id = $1")
+            .bind(thread_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(post_id)
+    }
+
+    pub async fn get_post(pool: &PgPool, post_id: Uuid) -> AppResult<Option<ForumPostResponse>> {
+        let post = sqlx::query_as::<_, ForumPostResponse>(
+            r#"
+            SELECT p.id, p.thread_id, p.author_id, u.display_name as author_name,
+                   p.body, p.created_at
+            FROM forum_posts p
+            JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1
+            "#,
+        )
+        .bind(post_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(post)
+    }
+
+    /// List a thread's posts, oldest first
+    pub async fn list_posts(
+        pool: &PgPool,
+        thread_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<Vec<ForumPostResponse>> {
+        let posts = sqlx::query_as::<_, ForumPostResponse>(
+            r#"
+            SELECT p.id, p.thread_id, p.author_id, u.display_name as author_name,
+                   p.body, p.created_at
+            FROM forum_posts p
+            JOIN users u ON u.id = p.author_id
+            WHERE p.thread_id = $1
+            ORDER BY p.created_at ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(thread_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(posts)
+    }
+}
@@ -1,11 +1,12 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::AppResult;
 use crate::models::hero::{
     AvailableAdventure, Hero, HeroAdventure, HeroItem, HeroItemWithDefinition, HeroSlotPrice,
-    HeroStatus, ItemDefinition, ItemRarity, ItemSlot, AdventureDifficulty,
+    HeroProgress, HeroStatus, ItemDefinition, ItemRarity, ItemSlot, AdventureDifficulty,
 };
 use crate::models::troop::TribeType;
 
@@ -36,7 +37,10 @@ impl HeroRepository {
     }
 
     /// Get hero by ID
-    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> AppResult<Option<Hero>> {
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> AppResult<Option<Hero>> {
         let hero = sqlx::query_as::<_, Hero>(
             r#"
             SELECT id, user_id, slot_number, name, tribe, home_village_id, current_village_id,
@@ -49,15 +53,15 @@ impl HeroRepository {
             "#,
         )
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(hero)
     }
 
-    /// Get hero by user and slot
-    pub async fn find_by_slot(pool: &PgPool, user_id: Uuid, slot: i32) -> AppResult<Option<Hero>> {
-        let hero = sqlx::query_as::<_, Hero>(
+    /// Get idle heroes currently in a village
+    pub async fn find_idle_at_village(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<Hero>> {
+        let heroes = sqlx::query_as::<_, Hero>(
             r#"
             SELECT id, user_id, slot_number, name, tribe, home_village_id, current_village_id,
                    status, level, experience, experience_to_next, health, health_regen_rate,
@@ -65,15 +69,14 @@ impl HeroRepository {
                    base_attack, base_defense, base_speed, last_health_update, died_at, revive_at,
                    created_at, updated_at
             FROM heroes
-            WHERE user_id = $1 AND slot_number = $2
+            WHERE current_village_id = $1 AND status = 'idle'
             "#,
         )
-        .bind(user_id)
-        .bind(slot)
-        .fetch_optional(pool)
+        .bind(village_id)
+        .fetch_all(pool)
         .await?;
 
-        Ok(hero)
+        Ok(heroes)
     }
 
     /// Count user's heroes
@@ -171,10 +174,89 @@ impl HeroRepository {
         Ok(hero)
     }
 
-    /// Update hero status
-    pub async fn update_status(
+    /// Bring a moving hero back as idle at a new home, e.g. when the village it was
+    /// returning to changed hands. Returns false if the hero was no longer moving.
+    pub async fn rehome_moving_hero<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        village_id: Uuid,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE heroes
+            SET status = 'idle', home_village_id = $2, current_village_id = $2,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'moving'
+            "#,
+        )
+        .bind(hero_id)
+        .bind(village_id)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hand a hero to another player, placing it in the given slot and village.
+    /// Only applies while the hero is still owned by `from_user_id` and idle;
+    /// returns None otherwise. Items follow the hero since they are keyed by hero_id.
+    pub async fn transfer_owner(
         pool: &PgPool,
         hero_id: Uuid,
+        from_user_id: Uuid,
+        to_user_id: Uuid,
+        slot_number: i32,
+        village_id: Uuid,
+    ) -> AppResult<Option<Hero>> {
+        let hero = sqlx::query_as::<_, Hero>(
+            r#"
+            UPDATE heroes
+            SET user_id = $3, slot_number = $4,
+                home_village_id = $5, current_village_id = $5,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'idle'
+            RETURNING id, user_id, slot_number, name, tribe, home_village_id, current_village_id,
+                      status, level, experience, experience_to_next, health, health_regen_rate,
+                      unassigned_points, fighting_strength, off_bonus, def_bonus, resources_bonus,
+                      base_attack, base_defense, base_speed, last_health_update, died_at, revive_at,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(hero_id)
+        .bind(from_user_id)
+        .bind(to_user_id)
+        .bind(slot_number)
+        .bind(village_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(hero)
+    }
+
+    /// Move hero from one status to another, only if it is still in the expected status.
+    /// Returns false if the hero's status changed concurrently.
+    pub async fn transition_status<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        from: HeroStatus,
+        to: HeroStatus,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            "UPDATE heroes SET status = $3, updated_at = NOW() WHERE id = $1 AND status = $2",
+        )
+        .bind(hero_id)
+        .bind(from)
+        .bind(to)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Update hero status
+    pub async fn update_status<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
         status: HeroStatus,
     ) -> AppResult<Hero> {
         let hero = sqlx::query_as::<_, Hero>(
@@ -191,7 +273,7 @@ impl HeroRepository {
         )
         .bind(hero_id)
         .bind(&status)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(hero)
@@ -237,24 +319,24 @@ impl HeroRepository {
     }
 
     /// Add experience to hero
-    pub async fn add_experience(pool: &PgPool, hero_id: Uuid, exp: i32) -> AppResult<Hero> {
+    pub async fn add_experience(
+        conn: &mut PgConnection,
+        game: &GameConfig,
+        hero_id: Uuid,
+        exp: i32,
+    ) -> AppResult<Hero> {
         // Get current hero
-        let hero = Self::find_by_id(pool, hero_id)
+        let hero = Self::find_by_id(&mut *conn, hero_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound("Hero not found".into()))?;
-
-        let mut new_exp = hero.experience + exp;
-        let mut new_level = hero.level;
-        let mut new_points = hero.unassigned_points;
-        let mut exp_to_next = hero.experience_to_next;
-
-        // Level up loop
-        while new_exp >= exp_to_next {
-            new_exp -= exp_to_next;
-            new_level += 1;
-            new_points += 4; // 4 attribute points per level
-            exp_to_next = Hero::exp_for_level(new_level + 1);
-        }
+            .ok_or_else(|| crate::error::AppError::not_found("Hero", hero_id))?;
+
+        let previous_level = hero.level;
+        let progress = HeroProgress::of(&hero).gain(
+            exp,
+            game.hero_max_level,
+            game.hero_exp_base,
+            game.hero_exp_growth,
+        );
 
         let hero = sqlx::query_as::<_, Hero>(
             r#"
@@ -273,18 +355,62 @@ impl HeroRepository {
             "#,
         )
         .bind(hero_id)
-        .bind(new_exp)
-        .bind(new_level)
-        .bind(new_points)
-        .bind(exp_to_next)
-        .fetch_one(pool)
+        .bind(progress.experience)
+        .bind(progress.level)
+        .bind(progress.unassigned_points)
+        .bind(progress.experience_to_next)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        // Regen rate scales with level
+        if progress.level != previous_level {
+            return Self::recalculate_health_regen(conn, hero_id).await;
+        }
+
+        Ok(hero)
+    }
+
+    /// Recompute health regen rate from level and equipped items
+    pub async fn recalculate_health_regen(
+        conn: &mut PgConnection,
+        hero_id: Uuid,
+    ) -> AppResult<Hero> {
+        let hero = Self::find_by_id(&mut *conn, hero_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::not_found("Hero", hero_id))?;
+        let equipped: Vec<ItemDefinition> = Self::get_equipped_items(&mut *conn, hero_id)
+            .await?
+            .into_iter()
+            .map(|(_, def)| def)
+            .collect();
+
+        let hero = sqlx::query_as::<_, Hero>(
+            r#"
+            UPDATE heroes
+            SET health_regen_rate = $2,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, user_id, slot_number, name, tribe, home_village_id, current_village_id,
+                      status, level, experience, experience_to_next, health, health_regen_rate,
+                      unassigned_points, fighting_strength, off_bonus, def_bonus, resources_bonus,
+                      base_attack, base_defense, base_speed, last_health_update, died_at, revive_at,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(hero_id)
+        .bind(Hero::health_regen_rate(hero.level, &equipped))
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(hero)
     }
 
     /// Update hero health
-    pub async fn update_health(pool: &PgPool, hero_id: Uuid, health: i32) -> AppResult<Hero> {
+    pub async fn update_health<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        health: i32,
+    ) -> AppResult<Hero> {
         let health = health.clamp(0, 100);
         let (status, died_at): (HeroStatus, Option<DateTime<Utc>>) = if health <= 0 {
             (HeroStatus::Dead, Some(Utc::now()))
@@ -312,33 +438,43 @@ impl HeroRepository {
         .bind(health)
         .bind(&status)
         .bind(died_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(hero)
     }
 
     /// Damage hero (reduce health)
-    pub async fn damage_hero(pool: &PgPool, hero_id: Uuid, damage: i32) -> AppResult<Hero> {
-        let hero = Self::find_by_id(pool, hero_id)
+    pub async fn damage_hero(
+        conn: &mut PgConnection,
+        hero_id: Uuid,
+        damage: i32,
+    ) -> AppResult<Hero> {
+        let hero = Self::find_by_id(&mut *conn, hero_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| crate::error::AppError::not_found("Hero", hero_id))?;
 
         let new_health = (hero.health - damage).max(0);
-        Self::update_health(pool, hero_id, new_health).await
+        Self::update_health(conn, hero_id, new_health).await
     }
 
-    /// Kill hero
-    pub async fn kill_hero(pool: &PgPool, hero_id: Uuid) -> AppResult<Hero> {
+    /// Take `damage` off a hero without touching its status, unless the blow is
+    /// fatal: then the hero dies and its revive timer starts
+    pub async fn wound_hero<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        damage: i32,
+    ) -> AppResult<Hero> {
         let revive_at = Utc::now() + chrono::Duration::hours(24); // 24 hour revive time
 
         let hero = sqlx::query_as::<_, Hero>(
             r#"
             UPDATE heroes
-            SET health = 0,
-                status = 'dead',
-                died_at = NOW(),
-                revive_at = $2,
+            SET health = GREATEST(health - $2, 0),
+                status = CASE WHEN health - $2 <= 0 THEN 'dead' ELSE status END,
+                died_at = CASE WHEN health - $2 <= 0 THEN NOW() ELSE died_at END,
+                revive_at = CASE WHEN health - $2 <= 0 THEN $3 ELSE revive_at END,
+                last_health_update = NOW(),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING id, user_id, slot_number, name, tribe, home_village_id, current_village_id,
@@ -349,15 +485,20 @@ impl HeroRepository {
             "#,
         )
         .bind(hero_id)
+        .bind(damage)
         .bind(revive_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(hero)
     }
 
     /// Revive hero
-    pub async fn revive_hero(pool: &PgPool, hero_id: Uuid, health: i32) -> AppResult<Hero> {
+    pub async fn revive_hero<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        health: i32,
+    ) -> AppResult<Hero> {
         let hero = sqlx::query_as::<_, Hero>(
             r#"
             UPDATE heroes
@@ -377,7 +518,7 @@ impl HeroRepository {
         )
         .bind(hero_id)
         .bind(health)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(hero)
@@ -385,17 +526,6 @@ impl HeroRepository {
 
     // ==================== Hero Slots ====================
 
-    /// Get hero slot prices
-    pub async fn get_slot_prices(pool: &PgPool) -> AppResult<Vec<HeroSlotPrice>> {
-        let prices = sqlx::query_as::<_, HeroSlotPrice>(
-            "SELECT slot_number, gold_cost FROM hero_slot_prices ORDER BY slot_number"
-        )
-        .fetch_all(pool)
-        .await?;
-
-        Ok(prices)
-    }
-
     /// Get price for specific slot
     pub async fn get_slot_price(pool: &PgPool, slot: i32) -> AppResult<Option<HeroSlotPrice>> {
         let price = sqlx::query_as::<_, HeroSlotPrice>(
@@ -435,7 +565,7 @@ impl HeroRepository {
                    attack_bonus, defense_bonus, speed_bonus, health_regen_bonus,
                    experience_bonus, resource_bonus, carry_bonus,
                    health_restore, is_consumable, extra_inventory_slots,
-                   sell_value, can_drop_adventure, can_buy_auction, created_at
+                   sell_value, can_drop_adventure, can_buy_auction, allowed_tribe, created_at
             FROM item_definitions
             ORDER BY slot, rarity, name
             "#,
@@ -454,7 +584,7 @@ impl HeroRepository {
                    attack_bonus, defense_bonus, speed_bonus, health_regen_bonus,
                    experience_bonus, resource_bonus, carry_bonus,
                    health_restore, is_consumable, extra_inventory_slots,
-                   sell_value, can_drop_adventure, can_buy_auction, created_at
+                   sell_value, can_drop_adventure, can_buy_auction, allowed_tribe, created_at
             FROM item_definitions
             WHERE id = $1
             "#,
@@ -477,7 +607,7 @@ impl HeroRepository {
                    attack_bonus, defense_bonus, speed_bonus, health_regen_bonus,
                    experience_bonus, resource_bonus, carry_bonus,
                    health_restore, is_consumable, extra_inventory_slots,
-                   sell_value, can_drop_adventure, can_buy_auction, created_at
+                   sell_value, can_drop_adventure, can_buy_auction, allowed_tribe, created_at
             FROM item_definitions
             WHERE rarity = $1 AND can_drop_adventure = TRUE
             "#,
@@ -504,7 +634,7 @@ impl HeroRepository {
                    id.experience_bonus as item_experience_bonus, id.resource_bonus as item_resource_bonus,
                    id.carry_bonus as item_carry_bonus, id.health_restore as item_health_restore,
                    id.is_consumable as item_is_consumable, id.extra_inventory_slots as item_extra_inventory_slots,
-                   id.sell_value as item_sell_value
+                   id.sell_value as item_sell_value, id.allowed_tribe as item_allowed_tribe
             FROM hero_items hi
             JOIN item_definitions id ON hi.item_definition_id = id.id
             WHERE hi.hero_id = $1
@@ -519,7 +649,10 @@ impl HeroRepository {
     }
 
     /// Get hero's equipped items
-    pub async fn get_equipped_items(pool: &PgPool, hero_id: Uuid) -> AppResult<Vec<(HeroItem, ItemDefinition)>> {
+    pub async fn get_equipped_items<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+    ) -> AppResult<Vec<(HeroItem, ItemDefinition)>> {
         let items = sqlx::query_as::<_, HeroItemWithDefinition>(
             r#"
             SELECT hi.id, hi.hero_id, hi.item_definition_id, hi.is_equipped, hi.equipped_slot,
@@ -531,14 +664,14 @@ impl HeroRepository {
                    id.experience_bonus as item_experience_bonus, id.resource_bonus as item_resource_bonus,
                    id.carry_bonus as item_carry_bonus, id.health_restore as item_health_restore,
                    id.is_consumable as item_is_consumable, id.extra_inventory_slots as item_extra_inventory_slots,
-                   id.sell_value as item_sell_value
+                   id.sell_value as item_sell_value, id.allowed_tribe as item_allowed_tribe
             FROM hero_items hi
             JOIN item_definitions id ON hi.item_definition_id = id.id
             WHERE hi.hero_id = $1 AND hi.is_equipped = TRUE
             "#,
         )
         .bind(hero_id)
-        .fetch_all(pool)
+        .fetch_all(executor)
         .await?;
 
         Ok(items.into_iter().map(|i| i.into_parts()).collect())
@@ -557,7 +690,7 @@ impl HeroRepository {
                    id.experience_bonus as item_experience_bonus, id.resource_bonus as item_resource_bonus,
                    id.carry_bonus as item_carry_bonus, id.health_restore as item_health_restore,
                    id.is_consumable as item_is_consumable, id.extra_inventory_slots as item_extra_inventory_slots,
-                   id.sell_value as item_sell_value
+                   id.sell_value as item_sell_value, id.allowed_tribe as item_allowed_tribe
             FROM hero_items hi
             JOIN item_definitions id ON hi.item_definition_id = id.id
             WHERE hi.id = $1
@@ -571,8 +704,8 @@ impl HeroRepository {
     }
 
     /// Add item to hero's inventory
-    pub async fn add_item(
-        pool: &PgPool,
+    pub async fn add_item<'e>(
+        executor: impl PgExecutor<'e>,
         hero_id: Uuid,
         item_def_id: Uuid,
         quantity: i32,
@@ -588,7 +721,7 @@ impl HeroRepository {
         .bind(hero_id)
         .bind(item_def_id)
         .bind(quantity)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(item)
@@ -613,24 +746,6 @@ impl HeroRepository {
         Ok(item)
     }
 
-    /// Unequip item
-    pub async fn unequip_item(pool: &PgPool, item_id: Uuid) -> AppResult<HeroItem> {
-        let item = sqlx::query_as::<_, HeroItem>(
-            r#"
-            UPDATE hero_items
-            SET is_equipped = FALSE, equipped_slot = NULL, equipped_at = NULL
-            WHERE id = $1
-            RETURNING id, hero_id, item_definition_id, is_equipped, equipped_slot,
-                      quantity, obtained_at, equipped_at
-            "#,
-        )
-        .bind(item_id)
-        .fetch_one(pool)
-        .await?;
-
-        Ok(item)
-    }
-
     /// Unequip all items in a slot
     pub async fn unequip_slot(pool: &PgPool, hero_id: Uuid, slot: ItemSlot) -> AppResult<()> {
         sqlx::query(
@@ -670,14 +785,25 @@ impl HeroRepository {
         Ok(())
     }
 
-    /// Delete item
-    pub async fn delete_item(pool: &PgPool, item_id: Uuid) -> AppResult<()> {
-        sqlx::query("DELETE FROM hero_items WHERE id = $1")
-            .bind(item_id)
-            .execute(pool)
-            .await?;
+    /// Delete several unequipped items of a hero in one statement, returning the ids removed
+    pub async fn delete_unequipped_items<'e>(
+        executor: impl PgExecutor<'e>,
+        hero_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> AppResult<Vec<Uuid>> {
+        let deleted: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            DELETE FROM hero_items
+            WHERE hero_id = $1 AND id = ANY($2) AND is_equipped = FALSE
+            RETURNING id
+            "#,
+        )
+        .bind(hero_id)
+        .bind(item_ids)
+        .fetch_all(executor)
+        .await?;
 
-        Ok(())
+        Ok(deleted.into_iter().map(|(id,)| id).collect())
     }
 
     // ==================== Adventures ====================
@@ -759,25 +885,29 @@ impl HeroRepository {
     }
 
     /// Mark adventure as taken
-    pub async fn mark_adventure_taken(pool: &PgPool, id: Uuid) -> AppResult<()> {
-        sqlx::query("UPDATE available_adventures SET is_taken = TRUE WHERE id = $1")
-            .bind(id)
-            .execute(pool)
-            .await?;
-
-        Ok(())
+    pub async fn mark_adventure_taken<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> AppResult<bool> {
+        let result =
+            sqlx::query("UPDATE available_adventures SET is_taken = TRUE WHERE id = $1 AND is_taken = FALSE")
+                .bind(id)
+                .execute(executor)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Start hero adventure
-    pub async fn start_adventure(
-        pool: &PgPool,
+    /// Start hero adventure; None if the hero already has one that is not completed
+    pub async fn start_adventure<'e>(
+        executor: impl PgExecutor<'e>,
         hero_id: Uuid,
         difficulty: AdventureDifficulty,
         duration_seconds: i32,
-    ) -> AppResult<HeroAdventure> {
+    ) -> AppResult<Option<HeroAdventure>> {
         let ends_at = Utc::now() + chrono::Duration::seconds(duration_seconds as i64);
 
-        let adventure = sqlx::query_as::<_, HeroAdventure>(
+        let result = sqlx::query_as::<_, HeroAdventure>(
             r#"
             INSERT INTO hero_adventures (hero_id, difficulty, duration_seconds, ends_at)
             VALUES ($1, $2, $3, $4)
@@ -790,10 +920,37 @@ impl HeroRepository {
         .bind(&difficulty)
         .bind(duration_seconds)
         .bind(ends_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
+        .await;
+
+        // idx_hero_adventures_one_active allows one open adventure per hero
+        match result {
+            Ok(adventure) => Ok(Some(adventure)),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Adventures not yet completed for any of a user's heroes
+    pub async fn get_active_adventures_by_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> AppResult<Vec<HeroAdventure>> {
+        let adventures = sqlx::query_as::<_, HeroAdventure>(
+            r#"
+            SELECT ha.id, ha.hero_id, ha.difficulty, ha.started_at, ha.duration_seconds, ha.ends_at,
+                   ha.is_completed, ha.completed_at, ha.reward_experience, ha.reward_silver,
+                   ha.reward_resources, ha.reward_item_id, ha.health_lost, ha.created_at
+            FROM hero_adventures ha
+            JOIN heroes h ON h.id = ha.hero_id
+            WHERE h.user_id = $1 AND ha.is_completed = FALSE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
         .await?;
 
-        Ok(adventure)
+        Ok(adventures)
     }
 
     /// Get active adventure for hero
@@ -816,16 +973,41 @@ impl HeroRepository {
         Ok(adventure)
     }
 
-    /// Complete adventure with rewards
-    pub async fn complete_adventure(
-        pool: &PgPool,
+    /// Get hero adventure by ID
+    pub async fn find_adventure_by_id(pool: &PgPool, id: Uuid) -> AppResult<Option<HeroAdventure>> {
+        let adventure = sqlx::query_as::<_, HeroAdventure>(
+            r#"
+            SELECT id, hero_id, difficulty, started_at, duration_seconds, ends_at,
+                   is_completed, completed_at, reward_experience, reward_silver,
+                   reward_resources, reward_item_id, health_lost, created_at
+            FROM hero_adventures
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(adventure)
+    }
+
+    /// Complete adventure with rewards.
+    /// Returns None if the adventure was already completed.
+    ///
+    /// This is the completion claim, and it needs no explicit lock: two workers running
+    /// this UPDATE for the same adventure queue on the row lock, and under READ COMMITTED
+    /// the second re-checks `is_completed = FALSE` against the committed row once the first
+    /// finishes. It then matches nothing and gets None, so only one caller ever goes on to
+    /// hand out the rewards.
+    pub async fn complete_adventure<'e>(
+        executor: impl PgExecutor<'e>,
         adventure_id: Uuid,
         exp: i32,
         silver: i32,
         resources: Option<serde_json::Value>,
         item_id: Option<Uuid>,
         health_lost: i32,
-    ) -> AppResult<HeroAdventure> {
+    ) -> AppResult<Option<HeroAdventure>> {
         let adventure = sqlx::query_as::<_, HeroAdventure>(
             r#"
             UPDATE hero_adventures
@@ -836,7 +1018,7 @@ impl HeroRepository {
                 reward_resources = $4,
                 reward_item_id = $5,
                 health_lost = $6
-            WHERE id = $1
+            WHERE id = $1 AND is_completed = FALSE
             RETURNING id, hero_id, difficulty, started_at, duration_seconds, ends_at,
                       is_completed, completed_at, reward_experience, reward_silver,
                       reward_resources, reward_item_id, health_lost, created_at
@@ -848,7 +1030,7 @@ impl HeroRepository {
         .bind(resources)
         .bind(item_id)
         .bind(health_lost)
-        .fetch_one(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(adventure)
@@ -0,0 +1,85 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+
+pub struct MapRepository;
+
+impl MapRepository {
+    /// Mark all tiles within radius of (x, y) as discovered by the user
+    pub async fn mark_discovered(
+        pool: &PgPool,
+        user_id: Uuid,
+        x: i32,
+        y: i32,
+        radius: i32,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO discovered_tiles (user_id, x, y)
+            SELECT $1, gx, gy
+            FROM generate_series($2 - $4, $2 + $4) AS gx,
+                 generate_series($3 - $4, $3 + $4) AS gy
+            ON CONFLICT (user_id, x, y) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(x)
+        .bind(y)
+        .bind(radius)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark the given tiles as discovered by the user
+    pub async fn mark_tiles_discovered(
+        pool: &PgPool,
+        user_id: Uuid,
+        tiles: &[(i32, i32)],
+    ) -> AppResult<()> {
+        let (xs, ys): (Vec<i32>, Vec<i32>) = tiles.iter().copied().unzip();
+        sqlx::query(
+            r#"
+            INSERT INTO discovered_tiles (user_id, x, y)
+            SELECT $1, t.x, t.y
+            FROM UNNEST($2::INT[], $3::INT[]) AS t(x, y)
+            ON CONFLICT (user_id, x, y) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(xs)
+        .bind(ys)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get discovered tiles for a user within a square range
+    pub async fn find_discovered_in_range(
+        pool: &PgPool,
+        user_id: Uuid,
+        center_x: i32,
+        center_y: i32,
+        range: i32,
+    ) -> AppResult<Vec<(i32, i32)>> {
+        let tiles: Vec<(i32, i32)> = sqlx::query_as(
+            r#"
+            SELECT x, y FROM discovered_tiles
+            WHERE user_id = $1
+              AND x BETWEEN $2 - $4 AND $2 + $4
+              AND y BETWEEN $3 - $4 AND $3 + $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(center_x)
+        .bind(center_y)
+        .bind(range)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tiles)
+    }
+}
@@ -64,6 +64,29 @@ impl MessageRepository {
         Ok(message)
     }
 
+    /// Create a system message from the game to a player
+    pub async fn create_system_message(
+        pool: &PgPool,
+        recipient_id: Uuid,
+        subject: &str,
+        body: &str,
+    ) -> AppResult<Message> {
+        let message = sqlx::query_as::<_, Message>(
+            r#"
+            INSERT INTO messages (message_type, recipient_id, subject, body)
+            VALUES ('system', $1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(recipient_id)
+        .bind(subject)
+        .bind(body)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(message)
+    }
+
     /// Get message by ID with full details
     pub async fn get_message(pool: &PgPool, message_id: Uuid) -> AppResult<Option<MessageResponse>> {
         let message = sqlx::query_as::<_, MessageResponse>(
@@ -82,7 +105,7 @@ impl MessageRepository {
                 m.is_read,
                 m.created_at
             FROM messages m
-            JOIN users sender ON sender.id = m.sender_id
+            LEFT JOIN users sender ON sender.id = m.sender_id
             LEFT JOIN users recipient ON recipient.id = m.recipient_id
             LEFT JOIN alliances a ON a.id = m.alliance_id
             WHERE m.id = $1
@@ -95,7 +118,7 @@ impl MessageRepository {
         Ok(message)
     }
 
-    /// Get inbox (received private messages)
+    /// Get inbox (received private and system messages)
     pub async fn get_inbox(
         pool: &PgPool,
         user_id: Uuid,
@@ -112,8 +135,8 @@ impl MessageRepository {
                 m.is_read,
                 m.created_at
             FROM messages m
-            JOIN users sender ON sender.id = m.sender_id
-            WHERE m.message_type = 'private'
+            LEFT JOIN users sender ON sender.id = m.sender_id
+            WHERE m.message_type IN ('private', 'system')
                 AND m.recipient_id = $1
                 AND m.recipient_deleted = FALSE
             ORDER BY m.created_at DESC
@@ -261,13 +284,13 @@ impl MessageRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Get unread private message count
+    /// Get unread private and system message count
     pub async fn get_unread_count(pool: &PgPool, user_id: Uuid) -> AppResult<i64> {
         let count: (i64,) = sqlx::query_as(
             r#"
             SELECT COUNT(*)
             FROM messages
-            WHERE message_type = 'private'
+            WHERE message_type IN ('private', 'system')
                 AND recipient_id = $1
                 AND recipient_deleted = FALSE
                 AND is_read = FALSE
@@ -486,6 +509,7 @@ impl MessageRepository {
                 MessageType::Private => {
                     Ok(sender_id == Some(user_id) || recipient_id == Some(user_id))
                 }
+                MessageType::System => Ok(recipient_id == Some(user_id)),
                 MessageType::Alliance => {
                     // For alliance messages, check membership via service
                     Ok(true) // Will be validated in service
@@ -1,9 +1,15 @@
+pub mod activity_repo;
 pub mod alliance_repo;
+pub mod api_token_repo;
 pub mod army_repo;
 pub mod building_repo;
+pub mod forum_repo;
 pub mod hero_repo;
+pub mod map_repo;
 pub mod message_repo;
+pub mod oasis_repo;
 pub mod shop_repo;
+pub mod transfer_repo;
 pub mod troop_repo;
 pub mod user_repo;
 pub mod village_repo;
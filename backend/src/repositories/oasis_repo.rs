@@ -0,0 +1,107 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::oasis::Oasis;
+
+pub struct OasisRepository;
+
+impl OasisRepository {
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> AppResult<Option<Oasis>> {
+        let oasis = sqlx::query_as::<_, Oasis>(
+            r#"
+            SELECT * FROM oases WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(oasis)
+    }
+
+    pub async fn find_in_range(pool: &PgPool, x: i32, y: i32, range: i32) -> AppResult<Vec<Oasis>> {
+        let oases = sqlx::query_as::<_, Oasis>(
+            r#"
+            SELECT * FROM oases
+            WHERE x BETWEEN $1 - $3 AND $1 + $3
+              AND y BETWEEN $2 - $3 AND $2 + $3
+            ORDER BY y, x
+            "#,
+        )
+        .bind(x)
+        .bind(y)
+        .bind(range)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(oases)
+    }
+
+    /// Oases a village holds, oldest occupation first
+    pub async fn find_by_village<'e>(
+        executor: impl PgExecutor<'e>,
+        village_id: Uuid,
+    ) -> AppResult<Vec<Oasis>> {
+        let oases = sqlx::query_as::<_, Oasis>(
+            r#"
+            SELECT * FROM oases
+            WHERE village_id = $1
+            ORDER BY occupied_at
+            "#,
+        )
+        .bind(village_id)
+        .fetch_all(executor)
+        .await?;
+
+        Ok(oases)
+    }
+
+    /// Claim a free oasis for a village, unless the village already holds
+    /// `max_oases`. Returns None if the oasis was taken or the cap was reached.
+    pub async fn occupy(
+        pool: &PgPool,
+        id: Uuid,
+        village_id: Uuid,
+        max_oases: i32,
+    ) -> AppResult<Option<Oasis>> {
+        let oasis = sqlx::query_as::<_, Oasis>(
+            r#"
+            UPDATE oases
+            SET village_id = $2, occupied_at = NOW()
+            WHERE id = $1
+              AND village_id IS NULL
+              AND (SELECT COUNT(*) FROM oases WHERE village_id = $2) < $3
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(village_id)
+        .bind(max_oases as i64)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(oasis)
+    }
+
+    /// Free an oasis held by a village. Returns false if the village did not hold it.
+    pub async fn release<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        village_id: Uuid,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oases
+            SET village_id = NULL, occupied_at = NULL
+            WHERE id = $1 AND village_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(village_id)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
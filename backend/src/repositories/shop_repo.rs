@@ -1,5 +1,5 @@
 use chrono::{DateTime, Duration, Utc};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppResult;
@@ -73,7 +73,11 @@ impl ShopRepository {
     }
 
     /// Deduct gold from user's balance (returns new balance or error if insufficient)
-    pub async fn deduct_gold(pool: &PgPool, user_id: Uuid, amount: i32) -> AppResult<i32> {
+    pub async fn deduct_gold<'e>(
+        executor: impl PgExecutor<'e>,
+        user_id: Uuid,
+        amount: i32,
+    ) -> AppResult<i32> {
         let result: (i32,) = sqlx::query_as(
             r#"
             UPDATE users
@@ -84,17 +88,54 @@ impl ShopRepository {
         )
         .bind(user_id)
         .bind(amount)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    // ==================== User Silver Balance ====================
+
+    /// Get user's silver balance
+    pub async fn get_silver_balance(pool: &PgPool, user_id: Uuid) -> AppResult<i32> {
+        let result: (i32,) = sqlx::query_as(
+            r#"SELECT silver_balance FROM users WHERE id = $1"#,
+        )
+        .bind(user_id)
         .fetch_one(pool)
         .await?;
 
         Ok(result.0)
     }
 
+    /// Add silver to user's balance; accepts a transaction so the credit can be
+    /// committed together with whatever earned it
+    pub async fn add_silver<'e>(
+        executor: impl PgExecutor<'e>,
+        user_id: Uuid,
+        amount: i32,
+    ) -> AppResult<i32> {
+        let result: (i32,) = sqlx::query_as(
+            r#"
+            UPDATE users
+            SET silver_balance = silver_balance + $2
+            WHERE id = $1
+            RETURNING silver_balance
+            "#,
+        )
+        .bind(user_id)
+        .bind(amount)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(result.0)
+    }
+
     // ==================== Transactions ====================
 
     /// Create a new transaction
-    pub async fn create_transaction(
-        pool: &PgPool,
+    pub async fn create_transaction<'e>(
+        executor: impl PgExecutor<'e>,
         user_id: Uuid,
         transaction_type: TransactionType,
         gold_amount: i32,
@@ -122,7 +163,7 @@ impl ShopRepository {
         .bind(stripe_session_id)
         .bind(gold_package_id)
         .bind(description)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(tx)
@@ -175,10 +216,12 @@ impl ShopRepository {
         Ok(tx)
     }
 
-    /// Get user's transaction history
+    /// Get user's transaction history, optionally filtered by type and status
     pub async fn get_user_transactions(
         pool: &PgPool,
         user_id: Uuid,
+        transaction_type: Option<TransactionType>,
+        status: Option<TransactionStatus>,
         limit: i32,
         offset: i32,
     ) -> AppResult<Vec<Transaction>> {
@@ -186,11 +229,15 @@ impl ShopRepository {
             r#"
             SELECT * FROM transactions
             WHERE user_id = $1
+                AND ($2::transaction_type IS NULL OR transaction_type = $2)
+                AND ($3::transaction_status IS NULL OR status = $3)
             ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
+            LIMIT $4 OFFSET $5
             "#,
         )
         .bind(user_id)
+        .bind(transaction_type)
+        .bind(status)
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
@@ -199,6 +246,30 @@ impl ShopRepository {
         Ok(txs)
     }
 
+    /// Count user's transactions matching the same filters as get_user_transactions
+    pub async fn count_user_transactions(
+        pool: &PgPool,
+        user_id: Uuid,
+        transaction_type: Option<TransactionType>,
+        status: Option<TransactionStatus>,
+    ) -> AppResult<i64> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM transactions
+            WHERE user_id = $1
+                AND ($2::transaction_type IS NULL OR transaction_type = $2)
+                AND ($3::transaction_status IS NULL OR status = $3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(transaction_type)
+        .bind(status)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
     // ==================== Subscriptions ====================
 
     /// Get user's active subscription
@@ -301,8 +372,8 @@ impl ShopRepository {
     }
 
     /// Record gold usage
-    pub async fn record_gold_usage(
-        pool: &PgPool,
+    pub async fn record_gold_usage<'e>(
+        executor: impl PgExecutor<'e>,
         user_id: Uuid,
         feature: GoldFeature,
         gold_spent: i32,
@@ -325,7 +396,7 @@ impl ShopRepository {
         .bind(target_id)
         .bind(effect_data)
         .bind(expires_at)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(usage)
@@ -382,10 +453,11 @@ impl ShopRepository {
         Ok(result.is_some())
     }
 
-    /// Get user's gold usage history
+    /// Get user's gold usage history, optionally filtered by feature
     pub async fn get_user_gold_usage(
         pool: &PgPool,
         user_id: Uuid,
+        feature: Option<GoldFeature>,
         limit: i32,
         offset: i32,
     ) -> AppResult<Vec<GoldUsage>> {
@@ -393,11 +465,13 @@ impl ShopRepository {
             r#"
             SELECT * FROM gold_usage
             WHERE user_id = $1
+                AND ($2::gold_feature IS NULL OR feature = $2)
             ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
+            LIMIT $3 OFFSET $4
             "#,
         )
         .bind(user_id)
+        .bind(feature)
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
@@ -406,6 +480,27 @@ impl ShopRepository {
         Ok(usage)
     }
 
+    /// Count user's gold usage rows matching the same filter as get_user_gold_usage
+    pub async fn count_user_gold_usage(
+        pool: &PgPool,
+        user_id: Uuid,
+        feature: Option<GoldFeature>,
+    ) -> AppResult<i64> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM gold_usage
+            WHERE user_id = $1
+                AND ($2::gold_feature IS NULL OR feature = $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(feature)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
     /// Get production bonus multiplier for a village
     pub async fn get_production_multiplier(
         pool: &PgPool,
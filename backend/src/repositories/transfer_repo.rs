@@ -0,0 +1,105 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::transfer::{NewTransfer, ResourceTransfer};
+
+pub struct TransferRepository;
+
+impl TransferRepository {
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        input: NewTransfer,
+    ) -> AppResult<ResourceTransfer> {
+        let transfer = sqlx::query_as::<_, ResourceTransfer>(
+            r#"
+            INSERT INTO resource_transfers (sender_id, from_village_id, to_village_id,
+                                            wood, clay, iron, crop, tax_percent,
+                                            departed_at, arrives_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, sender_id, from_village_id, to_village_id, wood, clay, iron, crop,
+                      tax_percent, departed_at, arrives_at, created_at
+            "#,
+        )
+        .bind(input.sender_id)
+        .bind(input.from_village_id)
+        .bind(input.to_village_id)
+        .bind(input.wood)
+        .bind(input.clay)
+        .bind(input.iron)
+        .bind(input.crop)
+        .bind(input.tax_percent)
+        .bind(input.departed_at)
+        .bind(input.arrives_at)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(transfer)
+    }
+
+    /// Find transfers leaving from a village
+    pub async fn find_outgoing<'e>(
+        executor: impl PgExecutor<'e>,
+        village_id: Uuid,
+    ) -> AppResult<Vec<ResourceTransfer>> {
+        let transfers = sqlx::query_as::<_, ResourceTransfer>(
+            r#"
+            SELECT id, sender_id, from_village_id, to_village_id, wood, clay, iron, crop,
+                   tax_percent, departed_at, arrives_at, created_at
+            FROM resource_transfers
+            WHERE from_village_id = $1
+            ORDER BY arrives_at ASC
+            "#,
+        )
+        .bind(village_id)
+        .fetch_all(executor)
+        .await?;
+
+        Ok(transfers)
+    }
+
+    /// Find transfers heading to a village
+    pub async fn find_incoming(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<ResourceTransfer>> {
+        let transfers = sqlx::query_as::<_, ResourceTransfer>(
+            r#"
+            SELECT id, sender_id, from_village_id, to_village_id, wood, clay, iron, crop,
+                   tax_percent, departed_at, arrives_at, created_at
+            FROM resource_transfers
+            WHERE to_village_id = $1
+            ORDER BY arrives_at ASC
+            "#,
+        )
+        .bind(village_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transfers)
+    }
+
+    /// Find transfers that have reached their destination
+    pub async fn find_arrived(pool: &PgPool) -> AppResult<Vec<ResourceTransfer>> {
+        let transfers = sqlx::query_as::<_, ResourceTransfer>(
+            r#"
+            SELECT id, sender_id, from_village_id, to_village_id, wood, clay, iron, crop,
+                   tax_percent, departed_at, arrives_at, created_at
+            FROM resource_transfers
+            WHERE arrives_at <= NOW()
+            ORDER BY arrives_at ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transfers)
+    }
+
+    /// Delete a transfer, returning false if it was already removed
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM resource_transfers WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
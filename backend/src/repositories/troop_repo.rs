@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::error::AppResult;
-use crate::models::troop::{Troop, TroopDefinition, TroopQueue, TroopType};
+use crate::models::building::BuildingType;
+use crate::models::troop::{Troop, TroopDefinition, TroopQueue, TroopReserve, TroopType};
 
 pub struct TroopRepository;
 
@@ -17,7 +19,7 @@ impl TroopRepository {
                    attack, defense_infantry, defense_cavalry, speed,
                    carry_capacity, crop_consumption, training_time_seconds,
                    wood_cost, clay_cost, iron_cost, crop_cost,
-                   required_building, required_building_level, loyalty_reduction, created_at
+                   required_building, required_building_level, loyalty_reduction, is_scout, created_at
             FROM troop_definitions
             ORDER BY tribe, troop_type
             "#,
@@ -35,7 +37,7 @@ impl TroopRepository {
                    attack, defense_infantry, defense_cavalry, speed,
                    carry_capacity, crop_consumption, training_time_seconds,
                    wood_cost, clay_cost, iron_cost, crop_cost,
-                   required_building, required_building_level, loyalty_reduction, created_at
+                   required_building, required_building_level, loyalty_reduction, is_scout, created_at
             FROM troop_definitions
             WHERE troop_type = $1
             "#,
@@ -85,8 +87,8 @@ impl TroopRepository {
         Ok(troop)
     }
 
-    pub async fn add_troops(
-        pool: &PgPool,
+    pub async fn add_troops<'e>(
+        executor: impl PgExecutor<'e>,
         village_id: Uuid,
         troop_type: TroopType,
         count: i32,
@@ -107,39 +109,43 @@ impl TroopRepository {
         .bind(village_id)
         .bind(&troop_type)
         .bind(count)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(troop)
     }
 
-    pub async fn remove_troops_from_village(
-        pool: &PgPool,
+    pub async fn remove_troops_from_village<'e>(
+        executor: impl PgExecutor<'e>,
         village_id: Uuid,
         troop_type: TroopType,
         count: i32,
     ) -> AppResult<Troop> {
-        // Reduce in_village count (troops sent on mission)
+        // Reduce in_village count (troops sent on mission); fails rather than going
+        // negative if another send took the troops first
         let troop = sqlx::query_as::<_, Troop>(
             r#"
             UPDATE troops
             SET in_village = in_village - $3,
                 updated_at = NOW()
-            WHERE village_id = $1 AND troop_type = $2
+            WHERE village_id = $1 AND troop_type = $2 AND in_village >= $3
             RETURNING id, village_id, troop_type, count, in_village, created_at, updated_at
             "#,
         )
         .bind(village_id)
         .bind(&troop_type)
         .bind(count)
-        .fetch_one(pool)
-        .await?;
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| {
+            crate::error::AppError::BadRequest(format!("Not enough {:?} in the village", troop_type))
+        })?;
 
         Ok(troop)
     }
 
-    pub async fn return_troops_to_village(
-        pool: &PgPool,
+    pub async fn return_troops_to_village<'e>(
+        executor: impl PgExecutor<'e>,
         village_id: Uuid,
         troop_type: TroopType,
         count: i32,
@@ -157,12 +163,38 @@ impl TroopRepository {
         .bind(village_id)
         .bind(&troop_type)
         .bind(count)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(troop)
     }
 
+    /// Permanently remove troops that are at home. Returns false, changing nothing,
+    /// if fewer than `count` are in the village (e.g. some just left on a mission).
+    pub async fn disband_from_village<'e>(
+        executor: impl PgExecutor<'e>,
+        village_id: Uuid,
+        troop_type: TroopType,
+        count: i32,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE troops
+            SET count = count - $3,
+                in_village = in_village - $3,
+                updated_at = NOW()
+            WHERE village_id = $1 AND troop_type = $2 AND in_village >= $3
+            "#,
+        )
+        .bind(village_id)
+        .bind(troop_type)
+        .bind(count)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn kill_troops(
         pool: &PgPool,
         village_id: Uuid,
@@ -266,7 +298,7 @@ impl TroopRepository {
         // Get queue entry
         let queue = Self::find_queue_by_id(pool, queue_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound("Queue entry not found".into()))?;
+            .ok_or_else(|| crate::error::AppError::not_found("Queue entry", queue_id))?;
 
         // Add troops to village
         Self::add_troops(pool, queue.village_id, queue.troop_type.clone(), queue.count).await?;
@@ -277,19 +309,85 @@ impl TroopRepository {
         Ok(())
     }
 
-    pub async fn find_completed_training(pool: &PgPool) -> AppResult<Vec<TroopQueue>> {
-        let completed = sqlx::query_as::<_, TroopQueue>(
+    /// Find queue entries with at least one finished unit not yet delivered
+    pub async fn find_training_with_ready_units(pool: &PgPool) -> AppResult<Vec<TroopQueue>> {
+        let ready = sqlx::query_as::<_, TroopQueue>(
             r#"
             SELECT id, village_id, troop_type, count, each_duration_seconds,
                    started_at, ends_at, created_at
             FROM troop_queue
-            WHERE ends_at <= NOW()
+            WHERE started_at + make_interval(secs => each_duration_seconds) <= NOW()
+               OR ends_at <= NOW()
             "#,
         )
         .fetch_all(pool)
         .await?;
 
-        Ok(completed)
+        Ok(ready)
+    }
+
+    /// Take `units` finished units off the front of a queue entry.
+    /// The remaining batch starts where the delivered units ended; the entry is
+    /// deleted once nothing remains. Returns false if the entry changed since
+    /// it was read (already processed concurrently).
+    pub async fn take_trained_units(
+        conn: &mut PgConnection,
+        entry: &TroopQueue,
+        units: i32,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM troop_queue
+            WHERE id = $1 AND count = $2 AND started_at = $3
+            "#,
+        )
+        .bind(entry.id)
+        .bind(units)
+        .bind(entry.started_at)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            return Ok(true);
+        }
+
+        let result = sqlx::query(
+            r#"
+            UPDATE troop_queue
+            SET count = count - $2,
+                started_at = started_at + make_interval(secs => each_duration_seconds * $2)
+            WHERE id = $1 AND count > $2 AND started_at = $3
+            "#,
+        )
+        .bind(entry.id)
+        .bind(units)
+        .bind(entry.started_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count queued training in a village for troops that require the given building
+    pub async fn count_queue_requiring_building<'e>(
+        executor: impl PgExecutor<'e>,
+        village_id: Uuid,
+        building_type: &BuildingType,
+    ) -> AppResult<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM troop_queue q
+            JOIN troop_definitions td ON q.troop_type = td.troop_type
+            WHERE q.village_id = $1 AND td.required_building = $2
+            "#,
+        )
+        .bind(village_id)
+        .bind(building_type)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(count.0)
     }
 
     pub async fn get_last_queue_end_time(pool: &PgPool, village_id: Uuid) -> AppResult<Option<DateTime<Utc>>> {
@@ -307,13 +405,28 @@ impl TroopRepository {
 
     // ==================== Crop Consumption ====================
 
+    /// Hourly crop eaten by troops a village feeds: its own troops at home and on
+    /// the move, plus support stationed there. Its own support stationed elsewhere
+    /// is fed by the host instead.
     pub async fn get_total_crop_consumption(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
         let result: (i64,) = sqlx::query_as(
             r#"
-            SELECT COALESCE(SUM(t.count * td.crop_consumption), 0)
-            FROM troops t
-            JOIN troop_definitions td ON t.troop_type = td.troop_type
-            WHERE t.village_id = $1
+            SELECT (
+                COALESCE((
+                    SELECT SUM(t.in_village * td.crop_consumption)
+                    FROM troops t
+                    JOIN troop_definitions td ON t.troop_type = td.troop_type
+                    WHERE t.village_id = $1
+                ), 0)
+                + COALESCE((
+                    SELECT SUM(s.value::INT * td.crop_consumption)
+                    FROM armies a
+                    CROSS JOIN LATERAL jsonb_each_text(a.troops) s
+                    JOIN troop_definitions td ON td.troop_type::TEXT = s.key
+                    WHERE (a.from_village_id = $1 AND a.is_stationed = FALSE)
+                       OR (a.to_village_id = $1 AND a.is_stationed = TRUE)
+                ), 0)
+            )::BIGINT
             "#,
         )
         .bind(village_id)
@@ -322,4 +435,73 @@ impl TroopRepository {
 
         Ok(result.0 as i32)
     }
+
+    /// Troops a village feeds per type, counted the same way as
+    /// `get_total_crop_consumption`
+    pub async fn get_fed_troops(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<(TroopType, i32)>> {
+        let troops = sqlx::query_as::<_, (TroopType, i32)>(
+            r#"
+            SELECT fed.troop_type, SUM(fed.count)::INT
+            FROM (
+                SELECT t.troop_type, t.in_village AS count
+                FROM troops t
+                WHERE t.village_id = $1
+                UNION ALL
+                SELECT td.troop_type, s.value::INT AS count
+                FROM armies a
+                CROSS JOIN LATERAL jsonb_each_text(a.troops) s
+                JOIN troop_definitions td ON td.troop_type::TEXT = s.key
+                WHERE (a.from_village_id = $1 AND a.is_stationed = FALSE)
+                   OR (a.to_village_id = $1 AND a.is_stationed = TRUE)
+            ) fed
+            GROUP BY fed.troop_type
+            HAVING SUM(fed.count) > 0
+            ORDER BY fed.troop_type
+            "#,
+        )
+        .bind(village_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(troops)
+    }
+
+    // ==================== Reserves ====================
+
+    pub async fn find_reserve(pool: &PgPool, village_id: Uuid) -> AppResult<Option<TroopReserve>> {
+        let reserve = sqlx::query_as::<_, TroopReserve>(
+            r#"
+            SELECT village_id, reserves, updated_at
+            FROM troop_reserves
+            WHERE village_id = $1
+            "#,
+        )
+        .bind(village_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(reserve)
+    }
+
+    pub async fn upsert_reserve(
+        pool: &PgPool,
+        village_id: Uuid,
+        reserves: &HashMap<TroopType, i32>,
+    ) -> AppResult<TroopReserve> {
+        let reserve = sqlx::query_as::<_, TroopReserve>(
+            r#"
+            INSERT INTO troop_reserves (village_id, reserves)
+            VALUES ($1, $2)
+            ON CONFLICT (village_id)
+            DO UPDATE SET reserves = EXCLUDED.reserves, updated_at = NOW()
+            RETURNING village_id, reserves, updated_at
+            "#,
+        )
+        .bind(village_id)
+        .bind(sqlx::types::Json(reserves))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(reserve)
+    }
 }
@@ -2,7 +2,10 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::AppResult;
-use crate::models::user::{CreateUser, UpdateUser, User};
+use crate::models::troop::TribeType;
+use crate::models::user::{
+    CreateUser, PlayerSettings, PublicPlayerProfile, UpdatePlayerSettings, UpdateUser, User,
+};
 
 pub struct UserRepository;
 
@@ -82,6 +85,89 @@ impl UserRepository {
         Ok(user)
     }
 
+    pub async fn get_settings(pool: &PgPool, id: Uuid) -> AppResult<Option<PlayerSettings>> {
+        let settings = sqlx::query_as::<_, PlayerSettings>(
+            r#"
+            SELECT forward_loot_overflow, tribe
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Public profile of a player with population, village count and rank
+    pub async fn get_public_profile(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> AppResult<Option<PublicPlayerProfile>> {
+        let profile = sqlx::query_as::<_, PublicPlayerProfile>(
+            r#"
+            WITH totals AS (
+                SELECT user_id, SUM(population)::BIGINT AS population, COUNT(*) AS village_count
+                FROM villages
+                GROUP BY user_id
+            )
+            SELECT u.id, u.display_name, u.tribe,
+                   a.id AS alliance_id, a.name AS alliance_name, a.tag AS alliance_tag,
+                   COALESCE(t.population, 0) AS population,
+                   COALESCE(t.village_count, 0) AS village_count,
+                   (SELECT COUNT(*) + 1 FROM totals o
+                    WHERE o.population > COALESCE(t.population, 0)) AS rank,
+                   u.created_at AS joined_at
+            FROM users u
+            LEFT JOIN totals t ON t.user_id = u.id
+            LEFT JOIN alliance_members am ON am.user_id = u.id
+            LEFT JOIN alliances a ON a.id = am.alliance_id
+            WHERE u.id = $1 AND u.deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    /// Tribe a player has chosen, if any
+    pub async fn get_tribe(pool: &PgPool, id: Uuid) -> AppResult<Option<TribeType>> {
+        let tribe: Option<Option<TribeType>> =
+            sqlx::query_scalar("SELECT tribe FROM users WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(tribe.flatten())
+    }
+
+    pub async fn update_settings(
+        pool: &PgPool,
+        id: Uuid,
+        input: &UpdatePlayerSettings,
+    ) -> AppResult<PlayerSettings> {
+        let settings = sqlx::query_as::<_, PlayerSettings>(
+            r#"
+            UPDATE users
+            SET forward_loot_overflow = COALESCE($2, forward_loot_overflow),
+                tribe = COALESCE(tribe, $3),
+                updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING forward_loot_overflow, tribe
+            "#,
+        )
+        .bind(id)
+        .bind(input.forward_loot_overflow)
+        .bind(input.tribe)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
     pub async fn update_last_login(pool: &PgPool, firebase_uid: &str) -> AppResult<()> {
         sqlx::query(
             r#"
@@ -1,4 +1,4 @@
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppResult;
@@ -7,7 +7,10 @@ use crate::models::village::{CreateVillage, UpdateVillage, Village, VillageMapIn
 pub struct VillageRepository;
 
 impl VillageRepository {
-    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> AppResult<Option<Village>> {
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> AppResult<Option<Village>> {
         let village = sqlx::query_as::<_, Village>(
             r#"
             SELECT id, user_id, name, x, y, is_capital,
@@ -20,12 +23,42 @@ impl VillageRepository {
             "#,
         )
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(village)
     }
 
+    /// Lock a village row for the rest of the transaction, so concurrent spends
+    /// from the same village run one after another
+    pub async fn lock<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> AppResult<()> {
+        sqlx::query("SELECT id FROM villages WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Villages whose granary is empty
+    pub async fn find_without_crop(pool: &PgPool) -> AppResult<Vec<Village>> {
+        let villages = sqlx::query_as::<_, Village>(
+            r#"
+            SELECT id, user_id, name, x, y, is_capital,
+                   wood, clay, iron, crop,
+                   warehouse_capacity, granary_capacity,
+                   population, culture_points, loyalty,
+                   resources_updated_at, created_at, updated_at
+            FROM villages
+            WHERE crop <= 0
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(villages)
+    }
+
     pub async fn find_by_user_id(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<Village>> {
         let villages = sqlx::query_as::<_, Village>(
             r#"
@@ -75,9 +108,11 @@ impl VillageRepository {
         let villages = sqlx::query_as::<_, VillageMapInfo>(
             r#"
             SELECT v.id, v.user_id, v.name, v.x, v.y, v.population,
-                   u.display_name as player_name
+                   u.display_name as player_name, a.tag as alliance_tag
             FROM villages v
             LEFT JOIN users u ON v.user_id = u.id
+            LEFT JOIN alliance_members am ON am.user_id = v.user_id
+            LEFT JOIN alliances a ON a.id = am.alliance_id
             WHERE v.x BETWEEN $1 AND $2
               AND v.y BETWEEN $3 AND $4
             "#,
@@ -92,7 +127,57 @@ impl VillageRepository {
         Ok(villages)
     }
 
-    pub async fn create(pool: &PgPool, input: CreateVillage) -> AppResult<Village> {
+    /// Villages whose owner's display name or alliance (tag or name) matches, case-insensitively.
+    /// When `fog_viewer` is set, only villages that user can see are returned: their own,
+    /// those on discovered tiles and those within `visibility_radius` of one of their villages.
+    pub async fn search_by_owner(
+        pool: &PgPool,
+        player_name: Option<&str>,
+        alliance: Option<&str>,
+        fog_viewer: Option<Uuid>,
+        visibility_radius: i32,
+        limit: i64,
+    ) -> AppResult<Vec<VillageMapInfo>> {
+        let villages = sqlx::query_as::<_, VillageMapInfo>(
+            r#"
+            SELECT v.id, v.user_id, v.name, v.x, v.y, v.population,
+                   u.display_name as player_name, a.tag as alliance_tag
+            FROM villages v
+            LEFT JOIN users u ON v.user_id = u.id
+            LEFT JOIN alliance_members am ON am.user_id = v.user_id
+            LEFT JOIN alliances a ON a.id = am.alliance_id
+            WHERE ($1::TEXT IS NULL OR LOWER(u.display_name) = LOWER($1))
+              AND ($2::TEXT IS NULL OR LOWER(a.tag) = LOWER($2) OR LOWER(a.name) = LOWER($2))
+              AND ($3::UUID IS NULL
+                   OR v.user_id = $3
+                   OR EXISTS (
+                       SELECT 1 FROM discovered_tiles d
+                       WHERE d.user_id = $3 AND d.x = v.x AND d.y = v.y
+                   )
+                   OR EXISTS (
+                       SELECT 1 FROM villages own
+                       WHERE own.user_id = $3
+                         AND ABS(own.x - v.x) <= $4 AND ABS(own.y - v.y) <= $4
+                   ))
+            ORDER BY u.display_name, v.population DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(player_name)
+        .bind(alliance)
+        .bind(fog_viewer)
+        .bind(visibility_radius)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(villages)
+    }
+
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        input: CreateVillage,
+    ) -> AppResult<Village> {
         let village = sqlx::query_as::<_, Village>(
             r#"
             INSERT INTO villages (user_id, name, x, y, is_capital)
@@ -109,7 +194,7 @@ impl VillageRepository {
         .bind(input.x)
         .bind(input.y)
         .bind(input.is_capital)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(village)
@@ -137,8 +222,8 @@ impl VillageRepository {
         Ok(village)
     }
 
-    pub async fn update_resources(
-        pool: &PgPool,
+    pub async fn update_resources<'e>(
+        executor: impl PgExecutor<'e>,
         id: Uuid,
         wood: i32,
         clay: i32,
@@ -164,14 +249,14 @@ impl VillageRepository {
         .bind(clay)
         .bind(iron)
         .bind(crop)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(village)
     }
 
-    pub async fn deduct_resources(
-        pool: &PgPool,
+    pub async fn deduct_resources<'e>(
+        executor: impl PgExecutor<'e>,
         id: Uuid,
         wood: i32,
         clay: i32,
@@ -203,7 +288,7 @@ impl VillageRepository {
         .bind(clay)
         .bind(iron)
         .bind(crop)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(village)
@@ -238,11 +323,21 @@ impl VillageRepository {
         Ok(village)
     }
 
-    pub async fn update_population(pool: &PgPool, id: Uuid, population: i32) -> AppResult<Village> {
+    /// Set population to the given building population plus the upkeep of the village's troops
+    pub async fn update_population<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        building_population: i32,
+    ) -> AppResult<Village> {
         let village = sqlx::query_as::<_, Village>(
             r#"
             UPDATE villages
-            SET population = $2,
+            SET population = $2 + COALESCE((
+                    SELECT SUM(t.count * td.crop_consumption)::INT
+                    FROM troops t
+                    JOIN troop_definitions td ON t.troop_type = td.troop_type
+                    WHERE t.village_id = villages.id
+                ), 0),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING id, user_id, name, x, y, is_capital,
@@ -253,8 +348,8 @@ impl VillageRepository {
             "#,
         )
         .bind(id)
-        .bind(population)
-        .fetch_one(pool)
+        .bind(building_population)
+        .fetch_one(executor)
         .await?;
 
         Ok(village)
@@ -273,10 +368,12 @@ impl VillageRepository {
         Ok(count.0)
     }
 
+    /// Whether a tile is free for a new village (no village or oasis on it)
     pub async fn is_coordinate_available(pool: &PgPool, x: i32, y: i32) -> AppResult<bool> {
         let exists: (bool,) = sqlx::query_as(
             r#"
             SELECT EXISTS(SELECT 1 FROM villages WHERE x = $1 AND y = $2)
+                OR EXISTS(SELECT 1 FROM oases WHERE x = $1 AND y = $2)
             "#,
         )
         .bind(x)
@@ -287,8 +384,8 @@ impl VillageRepository {
         Ok(!exists.0)
     }
 
-    pub async fn add_resources(
-        pool: &PgPool,
+    pub async fn add_resources<'e>(
+        executor: impl PgExecutor<'e>,
         id: Uuid,
         wood: i32,
         clay: i32,
@@ -316,7 +413,7 @@ impl VillageRepository {
         .bind(clay)
         .bind(iron)
         .bind(crop)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(village)
@@ -346,6 +443,24 @@ impl VillageRepository {
         Ok(village)
     }
 
+    /// Regenerate loyalty of every village below the maximum; returns villages updated
+    pub async fn regenerate_loyalty(pool: &PgPool, amount: i32, max_loyalty: i32) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE villages
+            SET loyalty = LEAST(loyalty + $1, $2),
+                updated_at = NOW()
+            WHERE loyalty < $2
+            "#,
+        )
+        .bind(amount)
+        .bind(max_loyalty)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn transfer_ownership(pool: &PgPool, id: Uuid, new_owner_id: Uuid) -> AppResult<Village> {
         let village = sqlx::query_as::<_, Village>(
             r#"
@@ -368,4 +483,30 @@ impl VillageRepository {
 
         Ok(village)
     }
+
+    /// Make the player's oldest village their capital if they have none
+    pub async fn promote_oldest_to_capital(pool: &PgPool, user_id: Uuid) -> AppResult<Option<Uuid>> {
+        let promoted: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE villages
+            SET is_capital = true,
+                updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM villages
+                WHERE user_id = $1
+                ORDER BY created_at ASC
+                LIMIT 1
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM villages WHERE user_id = $1 AND is_capital = true
+            )
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(promoted.map(|(id,)| id))
+    }
 }
@@ -0,0 +1,135 @@
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::activity::{VillageActivityListResponse, VillageActivityType};
+use crate::models::army::Army;
+use crate::models::building::Building;
+use crate::repositories::activity_repo::ActivityRepository;
+
+pub struct ActivityService;
+
+impl ActivityService {
+    /// Append an entry to a village's activity log. The insert runs in the
+    /// background so gameplay never waits on (or fails because of) the log.
+    pub fn record(
+        pool: &PgPool,
+        village_id: Uuid,
+        activity_type: VillageActivityType,
+        details: serde_json::Value,
+    ) {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ActivityRepository::create(&pool, village_id, activity_type, &details).await {
+                warn!(
+                    "Failed to record {:?} activity for village {}: {}",
+                    activity_type, village_id, e
+                );
+            }
+        });
+    }
+
+    /// Details of a BuildingStarted entry for `building` heading to `level`
+    pub fn building_started(building: &Building, level: i32) -> serde_json::Value {
+        serde_json::json!({
+            "building_type": building.building_type,
+            "slot": building.slot,
+            "level": level,
+        })
+    }
+
+    /// Details of an ArmySent entry
+    pub fn army_sent(army: &Army) -> serde_json::Value {
+        serde_json::json!({
+            "army_id": army.id,
+            "mission": army.mission,
+            "to_x": army.to_x,
+            "to_y": army.to_y,
+            "troops": army.troops.0,
+            "arrives_at": army.arrives_at,
+        })
+    }
+
+    /// Get a page of a village's activity, newest first
+    pub async fn get_activity(
+        pool: &PgPool,
+        village_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<VillageActivityListResponse> {
+        let limit = limit.clamp(1, 100);
+        let offset = offset.max(0);
+        let activities = ActivityRepository::find_by_village(pool, village_id, limit, offset).await?;
+        let total = ActivityRepository::count_by_village(pool, village_id).await?;
+
+        Ok(VillageActivityListResponse {
+            activities: activities.into_iter().map(|a| a.into()).collect(),
+            total,
+            limit,
+            offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::activity::{VillageActivity, VillageActivityResponse};
+    use crate::models::army::{ArmyTroops, MissionType};
+    use crate::models::building::BuildingType;
+    use crate::models::troop::TroopType;
+    use crate::test_support::army;
+    use chrono::Utc;
+
+    fn logged(
+        village_id: Uuid,
+        activity_type: VillageActivityType,
+        details: serde_json::Value,
+    ) -> VillageActivityResponse {
+        VillageActivity {
+            id: Uuid::new_v4(),
+            village_id,
+            activity_type,
+            details: sqlx::types::Json(details),
+            created_at: Utc::now(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn building_and_sending_an_army_show_up_in_the_owners_log() {
+        let sent = army(MissionType::Raid, ArmyTroops::from([(TroopType::Infantry, 25)]));
+        let village_id = sent.from_village_id;
+        let barracks = Building {
+            id: Uuid::new_v4(),
+            village_id,
+            building_type: BuildingType::Barracks,
+            slot: 7,
+            level: 0,
+            is_upgrading: true,
+            upgrade_ends_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let log = [
+            logged(
+                village_id,
+                VillageActivityType::BuildingStarted,
+                ActivityService::building_started(&barracks, 1),
+            ),
+            logged(village_id, VillageActivityType::ArmySent, ActivityService::army_sent(&sent)),
+        ];
+        let json = serde_json::to_value(&log).unwrap();
+
+        assert_eq!(json[0]["activity_type"], "building_started");
+        assert_eq!(json[0]["details"]["building_type"], "barracks");
+        assert_eq!(json[0]["details"]["slot"], 7);
+        assert_eq!(json[0]["details"]["level"], 1);
+        assert_eq!(json[1]["activity_type"], "army_sent");
+        assert_eq!(json[1]["details"]["army_id"], sent.id.to_string());
+        assert_eq!(json[1]["details"]["mission"], "raid");
+        assert_eq!(json[1]["details"]["troops"]["infantry"], 25);
+    }
+}
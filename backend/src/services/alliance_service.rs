@@ -3,8 +3,9 @@ use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::models::alliance::{
-    Alliance, AllianceDiplomacy, AllianceInvitation, AllianceListItem, AllianceMemberResponse,
-    AllianceResponse, AllianceRole, CreateAllianceRequest, DiplomacyStatus, InvitationStatus,
+    Alliance, AllianceDiplomacy, AllianceInvitation, AllianceListItem, AllianceMember,
+    AllianceMemberResponse, AlliancePermission, AllianceResponse, AllianceRole,
+    CreateAllianceRequest, DiplomacyStatus, InvitationStatus,
 };
 use crate::repositories::alliance_repo::AllianceRepository;
 
@@ -62,7 +63,7 @@ impl AllianceService {
     pub async fn get_alliance(pool: &PgPool, alliance_id: Uuid) -> AppResult<AllianceResponse> {
         let alliance = AllianceRepository::find_by_id(pool, alliance_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Alliance not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Alliance", alliance_id))?;
 
         let member_count = AllianceRepository::get_member_count(pool, alliance_id).await?;
 
@@ -80,8 +81,7 @@ impl AllianceService {
         name: Option<String>,
         description: Option<String>,
     ) -> AppResult<AllianceResponse> {
-        // Check permission
-        Self::check_permission(pool, alliance_id, user_id, &[AllianceRole::Leader, AllianceRole::Officer]).await?;
+        Self::require_permission(pool, alliance_id, user_id, AlliancePermission::UpdateAlliance).await?;
 
         let alliance = AllianceRepository::update(
             pool,
@@ -100,7 +100,7 @@ impl AllianceService {
 
     /// Disband alliance (leader only)
     pub async fn disband_alliance(pool: &PgPool, user_id: Uuid, alliance_id: Uuid) -> AppResult<()> {
-        Self::check_permission(pool, alliance_id, user_id, &[AllianceRole::Leader]).await?;
+        Self::require_permission(pool, alliance_id, user_id, AlliancePermission::Disband).await?;
         AllianceRepository::delete(pool, alliance_id).await?;
         Ok(())
     }
@@ -138,8 +138,7 @@ impl AllianceService {
         invitee_id: Uuid,
         message: Option<String>,
     ) -> AppResult<AllianceInvitation> {
-        // Check permission (leader or officer)
-        Self::check_permission(pool, alliance_id, inviter_id, &[AllianceRole::Leader, AllianceRole::Officer]).await?;
+        Self::require_permission(pool, alliance_id, inviter_id, AlliancePermission::Invite).await?;
 
         // Check if invitee is already in an alliance
         if let Some(_) = AllianceRepository::get_user_alliance(pool, invitee_id).await? {
@@ -154,7 +153,7 @@ impl AllianceService {
         // Check member limit
         let alliance = AllianceRepository::find_by_id(pool, alliance_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Alliance not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Alliance", alliance_id))?;
 
         let member_count = AllianceRepository::get_member_count(pool, alliance_id).await?;
         if member_count >= alliance.max_members {
@@ -240,22 +239,15 @@ impl AllianceService {
             .await?
             .ok_or_else(|| AppError::NotFound("Member not found".into()))?;
 
-        // Permission check: Leader can kick anyone, Officer can kick members
-        match kicker.role {
+        // The leader can never be kicked; officers need the extra permission
+        let required = match target.role {
             AllianceRole::Leader => {
-                if target.role == AllianceRole::Leader {
-                    return Err(AppError::BadRequest("Cannot kick yourself".into()));
-                }
+                return Err(AppError::BadRequest("The leader cannot be kicked".into()));
             }
-            AllianceRole::Officer => {
-                if target.role != AllianceRole::Member {
-                    return Err(AppError::Forbidden("Officers can only kick members".into()));
-                }
-            }
-            AllianceRole::Member => {
-                return Err(AppError::Forbidden("You don't have permission to kick members".into()));
-            }
-        }
+            AllianceRole::Officer => AlliancePermission::KickOfficer,
+            AllianceRole::Member => AlliancePermission::KickMember,
+        };
+        Self::ensure_role_can(kicker.role, required)?;
 
         AllianceRepository::remove_member(pool, kicker.alliance_id, target_user_id).await?;
 
@@ -273,10 +265,7 @@ impl AllianceService {
             .await?
             .ok_or_else(|| AppError::BadRequest("You are not in an alliance".into()))?;
 
-        // Only leader can change roles
-        if actor.role != AllianceRole::Leader {
-            return Err(AppError::Forbidden("Only the leader can change roles".into()));
-        }
+        Self::ensure_role_can(actor.role, AlliancePermission::ChangeRoles)?;
 
         let target = AllianceRepository::get_member(pool, actor.alliance_id, target_user_id)
             .await?
@@ -306,10 +295,7 @@ impl AllianceService {
             .await?
             .ok_or_else(|| AppError::BadRequest("You are not in an alliance".into()))?;
 
-        // Only leader can set diplomacy
-        if member.role != AllianceRole::Leader {
-            return Err(AppError::Forbidden("Only the leader can set diplomacy".into()));
-        }
+        Self::ensure_role_can(member.role, AlliancePermission::SetDiplomacy)?;
 
         // Cannot set diplomacy with own alliance
         if member.alliance_id == target_alliance_id {
@@ -318,7 +304,7 @@ impl AllianceService {
 
         // Check target alliance exists
         if AllianceRepository::find_by_id(pool, target_alliance_id).await?.is_none() {
-            return Err(AppError::NotFound("Target alliance not found".into()));
+            return Err(AppError::not_found("Target alliance", target_alliance_id));
         }
 
         AllianceRepository::set_diplomacy(pool, member.alliance_id, target_alliance_id, status, user_id).await
@@ -331,17 +317,32 @@ impl AllianceService {
 
     // ==================== Helpers ====================
 
-    async fn check_permission(
+    /// Require that the user is a member of the alliance with the given permission
+    pub async fn require_permission(
         pool: &PgPool,
         alliance_id: Uuid,
         user_id: Uuid,
-        allowed_roles: &[AllianceRole],
-    ) -> AppResult<()> {
-        let member = AllianceRepository::get_member(pool, alliance_id, user_id)
-            .await?
-            .ok_or_else(|| AppError::Forbidden("You are not a member of this alliance".into()))?;
+        permission: AlliancePermission,
+    ) -> AppResult<AllianceMember> {
+        let member = AllianceRepository::get_member(pool, alliance_id, user_id).await?;
+        Self::check_member_permission(member, permission)
+    }
+
+    /// Only members whose role grants `permission` get through; outsiders are refused
+    pub fn check_member_permission(
+        member: Option<AllianceMember>,
+        permission: AlliancePermission,
+    ) -> AppResult<AllianceMember> {
+        let member =
+            member.ok_or_else(|| AppError::Forbidden("You are not a member of this alliance".into()))?;
+
+        Self::ensure_role_can(member.role, permission)?;
+
+        Ok(member)
+    }
 
-        if !allowed_roles.contains(&member.role) {
+    fn ensure_role_can(role: AllianceRole, permission: AlliancePermission) -> AppResult<()> {
+        if !role.can(permission) {
             return Err(AppError::Forbidden("You don't have permission for this action".into()));
         }
 
@@ -0,0 +1,174 @@
+use axum::http::Method;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::api_token::{
+    ApiToken, ApiTokenResponse, ApiTokenScope, CreateApiTokenRequest, CreatedApiTokenResponse,
+    API_TOKEN_PREFIX,
+};
+use crate::models::user::User;
+use crate::repositories::api_token_repo::ApiTokenRepository;
+use crate::repositories::user_repo::UserRepository;
+
+/// Maximum number of unrevoked tokens per player
+const MAX_ACTIVE_TOKENS: i64 = 10;
+
+pub struct ApiTokenService;
+
+impl ApiTokenService {
+    /// Create a read-only token; the plaintext is returned only here
+    pub async fn create_token(
+        pool: &PgPool,
+        user_id: Uuid,
+        request: CreateApiTokenRequest,
+    ) -> AppResult<CreatedApiTokenResponse> {
+        let name = request.name.trim();
+        if name.is_empty() || name.len() > 100 {
+            return Err(AppError::BadRequest("Name must be 1-100 characters".into()));
+        }
+        if request.scopes.is_empty() {
+            return Err(AppError::BadRequest("At least one scope is required".into()));
+        }
+
+        if ApiTokenRepository::count_active(pool, user_id).await? >= MAX_ACTIVE_TOKENS {
+            return Err(AppError::BadRequest(format!(
+                "You can have at most {} active API tokens",
+                MAX_ACTIVE_TOKENS
+            )));
+        }
+
+        let mut scopes: Vec<String> = request.scopes.iter().map(|s| s.as_str().to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+
+        let secret: [u8; 32] = rand::thread_rng().gen();
+        let token = format!("{}{}", API_TOKEN_PREFIX, hex::encode(secret));
+
+        let created =
+            ApiTokenRepository::create(pool, user_id, name, &Self::hash(&token), &scopes).await?;
+
+        Ok(CreatedApiTokenResponse {
+            token,
+            details: created.into(),
+        })
+    }
+
+    pub async fn list_tokens(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<ApiTokenResponse>> {
+        let tokens = ApiTokenRepository::find_by_user(pool, user_id).await?;
+        Ok(tokens.into_iter().map(|t| t.into()).collect())
+    }
+
+    pub async fn revoke_token(pool: &PgPool, user_id: Uuid, token_id: Uuid) -> AppResult<()> {
+        if !ApiTokenRepository::revoke(pool, user_id, token_id).await? {
+            return Err(AppError::not_found("API token", token_id));
+        }
+        Ok(())
+    }
+
+    /// Resolve an API token to its owner, allowing only GET requests to
+    /// paths covered by the token's scopes
+    pub async fn authenticate(
+        pool: &PgPool,
+        token: &str,
+        method: &Method,
+        path: &str,
+    ) -> AppResult<User> {
+        let api_token = ApiTokenRepository::find_active_by_hash(pool, &Self::hash(token))
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        Self::check_access(&api_token, method, path)?;
+
+        let user = UserRepository::find_by_id(pool, api_token.user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        ApiTokenRepository::touch(pool, api_token.id).await?;
+
+        Ok(user)
+    }
+
+    /// API tokens may only GET paths covered by their scopes
+    fn check_access(api_token: &ApiToken, method: &Method, path: &str) -> AppResult<()> {
+        if method != Method::GET {
+            return Err(AppError::Forbidden("API tokens are read-only".into()));
+        }
+
+        let allowed = ApiTokenScope::for_path(path).is_some_and(|scope| api_token.has_scope(scope));
+        if !allowed {
+            return Err(AppError::Forbidden(
+                "This API token does not have access to this endpoint".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn hash(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn token(scopes: &[ApiTokenScope]) -> ApiToken {
+        ApiToken {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Dashboard".to_string(),
+            scopes: scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn token_cannot_send_armies() {
+        let token = token(&[ApiTokenScope::Villages, ApiTokenScope::Armies]);
+
+        assert!(matches!(
+            ApiTokenService::check_access(&token, &Method::POST, "/api/armies"),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn token_cannot_reach_the_shop() {
+        let token = token(&[ApiTokenScope::Villages, ApiTokenScope::Armies]);
+
+        assert!(matches!(
+            ApiTokenService::check_access(&token, &Method::GET, "/api/shop/gold-usage"),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            ApiTokenService::check_access(&token, &Method::POST, "/api/shop/npc-merchant"),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn token_with_the_villages_scope_can_read_villages() {
+        let token = token(&[ApiTokenScope::Villages]);
+
+        assert!(ApiTokenService::check_access(&token, &Method::GET, "/api/villages").is_ok());
+        assert!(ApiTokenService::check_access(&token, &Method::GET, "/api/villages/abc/troops").is_ok());
+    }
+
+    #[test]
+    fn token_without_the_villages_scope_cannot_read_villages() {
+        let token = token(&[ApiTokenScope::Reports]);
+
+        assert!(matches!(
+            ApiTokenService::check_access(&token, &Method::GET, "/api/villages"),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+}
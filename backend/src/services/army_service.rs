@@ -1,19 +1,45 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use redis::aio::ConnectionManager;
 use sqlx::PgPool;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::{AppError, AppResult};
+use crate::models::activity::VillageActivityType;
 use crate::models::army::{
-    Army, ArmyResponse, ArmyTroops, BattleReport, CarriedResources, MissionType, ScoutReport,
-    SendArmyRequest,
+    Army, ArmyResponse, ArmyTroops, BattleHeroResult, BattleReport, CarriedResources, ConquerBlocker,
+    ConquerPreviewQuery, ConquerPreviewResponse, DefenseSummaryResponse, HeroBattleResult, MissionType,
+    NewArmy, NewBattleReport, NewScoutReport, PlayerArmiesResponse, ScoutReport, SendArmyRequest,
+    StationedSender, StationedSupportResponse, TravelTimeRequest, TravelTimeResponse,
 };
-use crate::models::troop::TroopDefinition;
-use crate::models::village::Village;
+use crate::models::building::{Building, BuildingType};
+use crate::models::hero::{Hero, HeroStatus};
+use crate::models::troop::{TribeType, TroopDefinition, TroopType};
+use crate::models::village::{CreateVillage, Village};
+use crate::repositories::alliance_repo::AllianceRepository;
 use crate::repositories::army_repo::ArmyRepository;
+use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::hero_repo::HeroRepository;
+use crate::repositories::map_repo::MapRepository;
 use crate::repositories::troop_repo::TroopRepository;
+use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
-use crate::services::ws_service::{ArmyArrivedData, WsEvent, WsManager};
+use crate::services::activity_service::ActivityService;
+use crate::services::building_service::BuildingService;
+use crate::services::cache_service::CacheService;
+use crate::services::message_service::MessageService;
+use crate::services::resource_service::ResourceService;
+use crate::services::transfer_service::TransferService;
+use crate::services::village_service::{VillageService, MAX_LOYALTY};
+use crate::services::ws_service::{
+    ArmyArrivedData, IncomingCountChangedData, SupportAutoReturnedData, TroopsStarvedData, WsEvent,
+    WsManager,
+};
+
+/// Longest max-station duration a support army can be sent with
+const MAX_STATION_HOURS: i32 = 720;
 
 /// Internal struct for battle calculation results
 struct BattleResult {
@@ -22,6 +48,51 @@ struct BattleResult {
     defender_survivors: ArmyTroops,
     attacker_losses: ArmyTroops,
     defender_losses: ArmyTroops,
+    /// Share of the attacking army that fell, 0.0 - 1.0
+    attacker_loss_ratio: f64,
+    /// Attack power the attacking hero contributed after its bonus
+    hero_attack_power: f64,
+}
+
+/// Side effects beyond raw troop stats that shape a battle
+struct BattleModifiers {
+    attacker_tribe: Option<TribeType>,
+    defender_tribe: Option<TribeType>,
+    /// Defense bonus (percent) from the defender's Wall, already capped
+    wall_bonus_percent: i32,
+    /// Hero leading the attacking army
+    attacker_hero: Option<Hero>,
+}
+
+/// Distance and timings of a planned journey
+struct JourneyPlan {
+    distance: f64,
+    travel_duration: Duration,
+    departs_at: DateTime<Utc>,
+    arrives_at: DateTime<Utc>,
+    returns_at: Option<DateTime<Utc>>,
+}
+
+/// Which handler resolves an army whose current leg has ended
+#[derive(Debug, PartialEq)]
+enum ArrivalRoute {
+    Return,
+    Hostile,
+    Scout,
+    Support,
+    Conquer,
+    Settle,
+}
+
+/// What a winning conquer wave does to the target's ownership
+#[derive(Debug, PartialEq)]
+enum ConquestStep {
+    /// Loyalty is still above zero
+    LoyaltyRemains,
+    /// Loyalty reached zero but the attacker is at the village cap; the owner keeps it
+    VillageCapReached,
+    /// Loyalty reached zero and the village changes hands
+    Conquered,
 }
 
 pub struct ArmyService;
@@ -30,6 +101,7 @@ impl ArmyService {
     /// Send an army from a village to target coordinates
     pub async fn send_army(
         pool: &PgPool,
+        game: &GameConfig,
         player_id: Uuid,
         from_village_id: Uuid,
         request: SendArmyRequest,
@@ -37,13 +109,21 @@ impl ArmyService {
         // Validate mission type
         if !matches!(
             request.mission,
-            MissionType::Raid | MissionType::Attack | MissionType::Scout | MissionType::Support | MissionType::Conquer
+            MissionType::Raid
+                | MissionType::Attack
+                | MissionType::Scout
+                | MissionType::Support
+                | MissionType::Conquer
+                | MissionType::Settle
         ) {
             return Err(AppError::BadRequest(
-                "Only Raid, Attack, Scout, Support, and Conquer missions are currently supported".into(),
+                "Only Raid, Attack, Scout, Support, Conquer, and Settle missions are currently supported".into(),
             ));
         }
 
+        // Reject negative or oversized counts before touching the database
+        let total_troops = Self::validate_troop_counts(&request.troops, game.max_army_size)?;
+
         // Conquer mission requires at least one Chief troop
         if request.mission == MissionType::Conquer {
             let has_chief = request.troops.iter().any(|(troop_type, count)| {
@@ -56,37 +136,85 @@ impl ArmyService {
             }
         }
 
+        // Settle mission needs enough settlers, an empty tile and room for another village
+        if request.mission == MissionType::Settle {
+            let settlers = Self::count_settlers(&request.troops);
+            if settlers < game.settlers_per_village {
+                return Err(AppError::BadRequest(format!(
+                    "Settle mission requires {} Settlers (got {})",
+                    game.settlers_per_village, settlers
+                )));
+            }
+            if !VillageRepository::is_coordinate_available(pool, request.to_x, request.to_y).await? {
+                return Err(AppError::BadRequest("Target tile is already occupied".into()));
+            }
+            if !VillageService::can_own_another_village(pool, game, player_id).await? {
+                return Err(AppError::BadRequest(format!(
+                    "You already own the maximum of {} villages",
+                    game.max_villages_per_player
+                )));
+            }
+        }
+
         // Get source village
         let from_village = VillageRepository::find_by_id(pool, from_village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Source village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Source village", from_village_id))?;
 
         // Validate ownership
         if from_village.user_id != player_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
-        // Validate troops are available
-        let village_troops = TroopRepository::find_by_village(pool, from_village_id).await?;
-        for (troop_type, count) in &request.troops {
-            if *count <= 0 {
-                continue;
+        // A hero may lead a fighting army out of the village it is staying in
+        if let Some(hero_id) = request.hero_id {
+            if !request.mission.requires_combat() {
+                return Err(AppError::BadRequest(
+                    "Heroes can only join Raid, Attack, and Conquer missions".into(),
+                ));
             }
-            let available = village_troops
-                .iter()
-                .find(|t| t.troop_type == *troop_type)
-                .map(|t| t.in_village)
-                .unwrap_or(0);
-            if available < *count {
-                return Err(AppError::BadRequest(format!(
-                    "Not enough {:?}: have {}, need {}",
-                    troop_type, available, count
-                )));
+            let hero = HeroRepository::find_by_id(pool, hero_id)
+                .await?
+                .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+            if hero.user_id != player_id {
+                return Err(AppError::Forbidden("Access denied".into()));
+            }
+            if !hero.is_available() {
+                return Err(AppError::BadRequest("Hero is not available".into()));
             }
+            if hero.current_village_id != Some(from_village_id) {
+                return Err(AppError::BadRequest("Hero is not in the source village".into()));
+            }
+        }
+
+        // Limit armies in motion per village and per player
+        let active_from_village = ArmyRepository::count_active_from_village(pool, from_village_id).await?;
+        if Self::army_limit_reached(game.max_armies_per_village, active_from_village) {
+            return Err(AppError::BadRequest(format!(
+                "This village already has the maximum of {} armies in motion",
+                game.max_armies_per_village
+            )));
+        }
+        let active_by_player = ArmyRepository::count_active_by_player(pool, player_id).await?;
+        if Self::army_limit_reached(game.max_armies_per_player, active_by_player) {
+            return Err(AppError::BadRequest(format!(
+                "You already have the maximum of {} armies in motion",
+                game.max_armies_per_player
+            )));
         }
 
-        // Get total troops being sent
-        let total_troops: i32 = request.troops.values().sum();
+        // Validate troops are available, keeping the village's configured reserve home
+        let in_village: ArmyTroops = TroopRepository::find_by_village(pool, from_village_id)
+            .await?
+            .iter()
+            .map(|t| (t.troop_type, t.in_village))
+            .collect();
+        let reserves = TroopRepository::find_reserve(pool, from_village_id)
+            .await?
+            .map(|r| r.reserves.0)
+            .unwrap_or_default();
+        Self::check_troops_available(&request.troops, &in_village, &reserves)?;
+
         if total_troops <= 0 {
             return Err(AppError::BadRequest("Must send at least one troop".into()));
         }
@@ -99,6 +227,20 @@ impl ArmyService {
             if target.user_id == player_id && request.mission.is_hostile() {
                 return Err(AppError::BadRequest("Cannot attack your own village".into()));
             }
+
+            if request.mission.is_hostile() && !game.allow_internal_alliance_attacks {
+                let own_alliance = AllianceRepository::get_user_alliance(pool, player_id)
+                    .await?
+                    .map(|m| m.alliance_id);
+                let target_alliance = AllianceRepository::get_user_alliance(pool, target.user_id)
+                    .await?
+                    .map(|m| m.alliance_id);
+                if Self::is_blocked_by_alliance(game, request.mission, own_alliance, target_alliance) {
+                    return Err(AppError::BadRequest(
+                        "Cannot attack a member of your own alliance".into(),
+                    ));
+                }
+            }
         }
 
         // Support mission requires a target village
@@ -106,107 +248,528 @@ impl ArmyService {
             return Err(AppError::BadRequest("Support mission requires a target village".into()));
         }
 
+        let max_station_hours =
+            Self::validate_max_station_hours(request.mission, request.max_station_hours)?;
+
         // Get troop definitions for travel time calculation
         let definitions = TroopRepository::get_all_definitions(pool).await?;
 
-        // Calculate travel time
-        let distance = Self::calculate_distance(
-            from_village.x,
-            from_village.y,
-            request.to_x,
-            request.to_y,
-        );
-        let travel_duration = Self::calculate_travel_time(distance, &request.troops, &definitions);
+        // Scout missions may only carry scout units
+        if request.mission == MissionType::Scout {
+            let non_scouts = Self::units_not_matching(&request.troops, &definitions, |d| d.is_scout);
+            if !non_scouts.is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "Scout missions can only include scout units; remove {:?}",
+                    non_scouts
+                )));
+            }
+        }
 
-        // Calculate timestamps
-        let now = Utc::now();
-        let arrives_at = now + travel_duration;
-        let returns_at = if request.mission.returns() {
-            Some(arrives_at + travel_duration)
-        } else {
-            None
-        };
+        // Some servers forbid parking offensive units in other villages as support
+        if request.mission == MissionType::Support && game.defensive_support_only {
+            let offensive =
+                Self::units_not_matching(&request.troops, &definitions, TroopDefinition::is_defensive);
+            if !offensive.is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "Support missions can only include defensive units; remove {:?}",
+                    offensive
+                )));
+            }
+        }
+
+        // Only support can carry resources, and no more than the troops can hold
+        let resources = &request.resources;
+        if resources.wood < 0 || resources.clay < 0 || resources.iron < 0 || resources.crop < 0 {
+            return Err(AppError::BadRequest("Cannot send negative resources".into()));
+        }
+        if resources.total() > 0 {
+            if request.mission != MissionType::Support {
+                return Err(AppError::BadRequest(
+                    "Only support missions can carry resources".into(),
+                ));
+            }
+
+            let capacity: i64 = request
+                .troops
+                .iter()
+                .filter_map(|(troop_type, count)| {
+                    definitions
+                        .iter()
+                        .find(|d| d.troop_type == *troop_type)
+                        .map(|d| d.carry_capacity as i64 * *count as i64)
+                })
+                .sum();
+            if resources.total() as i64 > capacity {
+                return Err(AppError::BadRequest(format!(
+                    "These troops can carry at most {} resources",
+                    capacity
+                )));
+            }
+
+            let village = ResourceService::update_village_resources(pool, game, from_village_id).await?;
+            if village.wood < resources.wood
+                || village.clay < resources.clay
+                || village.iron < resources.iron
+                || village.crop < resources.crop
+            {
+                return Err(AppError::BadRequest("Not enough resources".into()));
+            }
+        }
+
+        Self::check_can_fight(request.mission, &request.troops, &definitions)?;
+
+        let plan = Self::plan_journey(
+            game,
+            (from_village.x, from_village.y),
+            (request.to_x, request.to_y),
+            request.mission,
+            &request.troops,
+            request.depart_at,
+            &definitions,
+        )?;
+
+        // Claim the hero first so it cannot also leave on an adventure. The claim,
+        // the troops and resources leaving and the army record commit together, so a
+        // failed step never strands the hero or the troops without an army.
+        let mut tx = pool.begin().await?;
+        if let Some(hero_id) = request.hero_id {
+            if !HeroRepository::transition_status(&mut *tx, hero_id, HeroStatus::Idle, HeroStatus::Moving)
+                .await?
+            {
+                return Err(AppError::BadRequest("Hero is not available".into()));
+            }
+        }
 
         // Remove troops from village
         for (troop_type, count) in &request.troops {
             if *count > 0 {
-                TroopRepository::remove_troops_from_village(pool, from_village_id, *troop_type, *count)
+                TroopRepository::remove_troops_from_village(&mut *tx, from_village_id, *troop_type, *count)
                     .await?;
             }
         }
 
+        if request.resources.total() > 0 {
+            VillageRepository::deduct_resources(
+                &mut *tx,
+                from_village_id,
+                request.resources.wood,
+                request.resources.clay,
+                request.resources.iron,
+                request.resources.crop,
+            )
+            .await?;
+        }
+
         // Create army record
-        let army = ArmyRepository::create(
-            pool,
+        let new_army = Self::new_army(
             player_id,
             from_village_id,
-            request.to_x,
-            request.to_y,
             target_village.as_ref().map(|v| v.id),
-            request.mission,
-            &request.troops,
-            &request.resources,
-            now,
-            arrives_at,
-            returns_at,
-        )
-        .await?;
+            request,
+            &plan,
+            max_station_hours,
+        );
+        let army = ArmyRepository::create(&mut *tx, new_army).await?;
+        tx.commit().await?;
+
+        ActivityService::record(
+            pool,
+            from_village_id,
+            VillageActivityType::ArmySent,
+            ActivityService::army_sent(&army),
+        );
 
         info!(
             "Army sent from village {} to ({}, {}) with {} troops, arrives at {}",
-            from_village_id, request.to_x, request.to_y, total_troops, arrives_at
+            from_village_id, army.to_x, army.to_y, total_troops, army.arrives_at
         );
 
         Ok(army.into())
     }
 
-    /// Process all armies that have arrived at their destination
-    pub async fn process_arrived_armies(pool: &PgPool) -> AppResult<i32> {
-        let arrived = ArmyRepository::find_arrived(pool).await?;
-        let mut processed = 0;
+    /// Preview distance and timings for an army without sending it
+    pub async fn preview_travel(
+        pool: &PgPool,
+        game: &GameConfig,
+        from_village: &Village,
+        request: TravelTimeRequest,
+    ) -> AppResult<TravelTimeResponse> {
+        if request.troops.values().all(|count| *count <= 0) {
+            return Err(AppError::BadRequest("Must include at least one troop".into()));
+        }
 
-        for army in arrived {
-            let result = if army.is_returning {
-                Self::handle_returning_army(pool, &army).await
-            } else {
-                match army.mission {
-                    MissionType::Raid | MissionType::Attack => {
-                        Self::handle_hostile_arrival(pool, &army).await
-                    }
-                    MissionType::Scout => {
-                        Self::handle_scout_arrival(pool, &army).await
-                    }
-                    MissionType::Support => {
-                        Self::handle_support_arrival(pool, &army).await
-                    }
-                    MissionType::Conquer => {
-                        Self::handle_conquer_arrival(pool, &army).await
-                    }
-                    _ => {
-                        // Other mission types not implemented yet
-                        error!("Unhandled mission type: {:?}", army.mission);
-                        continue;
-                    }
-                }
-            };
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let plan = Self::plan_journey(
+            game,
+            (from_village.x, from_village.y),
+            (request.to_x, request.to_y),
+            request.mission,
+            &request.troops,
+            request.depart_at,
+            &definitions,
+        )?;
 
-            match result {
-                Ok(_) => processed += 1,
-                Err(e) => {
-                    error!("Failed to process army {}: {:?}", army.id, e);
+        Ok(Self::travel_response(&plan))
+    }
+
+    fn travel_response(plan: &JourneyPlan) -> TravelTimeResponse {
+        TravelTimeResponse {
+            distance: plan.distance,
+            travel_seconds: plan.travel_duration.num_seconds(),
+            departs_at: plan.departs_at,
+            arrives_at: plan.arrives_at,
+            returns_at: plan.returns_at,
+        }
+    }
+
+    /// The army record a send persists for its planned journey
+    fn new_army(
+        player_id: Uuid,
+        from_village_id: Uuid,
+        to_village_id: Option<Uuid>,
+        request: SendArmyRequest,
+        plan: &JourneyPlan,
+        max_station_hours: Option<i32>,
+    ) -> NewArmy {
+        NewArmy {
+            player_id,
+            from_village_id,
+            to_x: request.to_x,
+            to_y: request.to_y,
+            to_village_id,
+            mission: request.mission,
+            troops: request.troops,
+            resources: request.resources,
+            departed_at: plan.departs_at,
+            arrives_at: plan.arrives_at,
+            returns_at: plan.returns_at,
+            max_station_hours,
+            hero_id: request.hero_id,
+        }
+    }
+
+    /// Check whether the village at the given tile could be conquered from
+    /// `from_village`, and estimate how many chief waves it would take
+    pub async fn conquer_preview(
+        pool: &PgPool,
+        game: &GameConfig,
+        from_village: &Village,
+        query: ConquerPreviewQuery,
+    ) -> AppResult<ConquerPreviewResponse> {
+        let player_id = from_village.user_id;
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+
+        // One wave is every chief currently home, subject to the per-attack cap
+        let chiefs: ArmyTroops = TroopRepository::find_by_village(pool, from_village.id)
+            .await?
+            .iter()
+            .filter(|t| t.in_village > 0 && t.troop_type.is_chief())
+            .map(|t| (t.troop_type, t.in_village))
+            .collect();
+        let loyalty_per_wave =
+            Self::capped_loyalty_reduction(game, Self::chief_loyalty_reduction(&chiefs, &definitions));
+
+        // A village hidden by fog is reported exactly like an empty tile
+        let target = match VillageRepository::find_by_coordinates(pool, query.to_x, query.to_y).await? {
+            Some(target) if game.map_fog_enabled => {
+                let own_villages = VillageRepository::find_by_user_id(pool, player_id).await?;
+                let discovered =
+                    MapRepository::find_discovered_in_range(pool, player_id, target.x, target.y, 0)
+                        .await?;
+                VillageService::is_visible_through_fog(
+                    player_id,
+                    target.user_id,
+                    (target.x, target.y),
+                    &own_villages,
+                    &discovered,
+                    game.map_visibility_radius,
+                )
+                .then_some(target)
+            }
+            target => target,
+        };
+        let Some(target) = target else {
+            return Ok(ConquerPreviewResponse {
+                target_village_id: None,
+                conquerable: false,
+                blocker: Some(ConquerBlocker::NoVillage),
+                loyalty: None,
+                loyalty_per_wave,
+                estimated_waves: None,
+            });
+        };
+
+        let owner_village_count = if target.is_capital && target.user_id != player_id {
+            VillageRepository::count_by_user_id(pool, target.user_id).await?
+        } else {
+            0
+        };
+        let blocker = Self::conquer_blocker(
+            game,
+            player_id,
+            &target,
+            owner_village_count,
+            VillageService::can_own_another_village(pool, game, player_id).await?,
+        );
+
+        let loyalty_visible = target.user_id == player_id
+            || ArmyRepository::has_successful_scout_since(
+                pool,
+                player_id,
+                target.id,
+                Utc::now() - Duration::hours(game.scout_intel_hours),
+            )
+            .await?;
+        let loyalty = loyalty_visible.then_some(target.loyalty);
+
+        let estimated_waves = (blocker.is_none() && loyalty_per_wave > 0)
+            .then(|| Self::waves_to_conquer(loyalty.unwrap_or(MAX_LOYALTY), loyalty_per_wave));
+
+        Ok(ConquerPreviewResponse {
+            target_village_id: Some(target.id),
+            conquerable: blocker.is_none(),
+            blocker,
+            loyalty,
+            loyalty_per_wave,
+            estimated_waves,
+        })
+    }
+
+    /// Why `player_id` could not conquer `target` right now, by the same rules
+    /// handle_conquer_arrival applies (None if it is conquerable)
+    fn conquer_blocker(
+        game: &GameConfig,
+        player_id: Uuid,
+        target: &Village,
+        owner_village_count: i64,
+        can_own_another: bool,
+    ) -> Option<ConquerBlocker> {
+        if target.user_id == player_id {
+            Some(ConquerBlocker::OwnVillage)
+        } else if target.is_capital && Self::is_capital_protected(game, owner_village_count) {
+            Some(ConquerBlocker::ProtectedCapital)
+        } else if !can_own_another {
+            Some(ConquerBlocker::VillageCapReached)
+        } else {
+            None
+        }
+    }
+
+    /// What a conquer wave that left the target at `new_loyalty` does to its ownership.
+    /// Re-checked on arrival, since the player may have hit the village cap in transit.
+    fn conquest_step(new_loyalty: i32, can_own_another: bool) -> ConquestStep {
+        if new_loyalty > 0 {
+            ConquestStep::LoyaltyRemains
+        } else if can_own_another {
+            ConquestStep::Conquered
+        } else {
+            ConquestStep::VillageCapReached
+        }
+    }
+
+    /// Every requested troop must be home, leaving at least the village's reserve behind
+    fn check_troops_available(
+        requested: &ArmyTroops,
+        in_village: &ArmyTroops,
+        reserves: &ArmyTroops,
+    ) -> AppResult<()> {
+        for (troop_type, count) in requested {
+            if *count <= 0 {
+                continue;
+            }
+            let available = in_village.get(troop_type).copied().unwrap_or(0);
+            if available < *count {
+                return Err(AppError::BadRequest(format!(
+                    "Not enough {:?}: have {}, need {}",
+                    troop_type, available, count
+                )));
+            }
+            let reserved = reserves.get(troop_type).copied().unwrap_or(0);
+            if available - *count < reserved {
+                return Err(AppError::BadRequest(format!(
+                    "Sending {} {:?} would breach the village reserve of {} (at most {} can be sent)",
+                    count,
+                    troop_type,
+                    reserved,
+                    (available - reserved).max(0)
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Max station duration only applies to support, and within 1..=MAX_STATION_HOURS
+    fn validate_max_station_hours(
+        mission: MissionType,
+        max_station_hours: Option<i32>,
+    ) -> AppResult<Option<i32>> {
+        match max_station_hours {
+            Some(_) if mission != MissionType::Support => Err(AppError::BadRequest(
+                "Max station duration is only allowed for support missions".into(),
+            )),
+            Some(hours) if !(1..=MAX_STATION_HOURS).contains(&hours) => {
+                Err(AppError::BadRequest(format!(
+                    "Max station duration must be between 1 and {} hours",
+                    MAX_STATION_HOURS
+                )))
+            }
+            hours => Ok(hours),
+        }
+    }
+
+    /// Armies of only non-combat units (e.g. wagons, merchant ships) cannot fight
+    fn check_can_fight(
+        mission: MissionType,
+        troops: &ArmyTroops,
+        definitions: &[TroopDefinition],
+    ) -> AppResult<()> {
+        if mission.requires_combat() && Self::calculate_attack_power(troops, definitions) <= 0.0 {
+            return Err(AppError::BadRequest(
+                "This mission needs troops with attack power".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Work out when an army would leave, arrive and (if the mission returns) get back.
+    /// Shared by send_army and the travel-time preview so both always agree.
+    fn plan_journey(
+        game: &GameConfig,
+        from: (i32, i32),
+        to: (i32, i32),
+        mission: MissionType,
+        troops: &ArmyTroops,
+        depart_at: Option<DateTime<Utc>>,
+        definitions: &[TroopDefinition],
+    ) -> AppResult<JourneyPlan> {
+        let now = Utc::now();
+        let distance = Self::calculate_distance(from.0, from.1, to.0, to.1);
+        let travel_duration = Self::calculate_travel_time(distance, troops, definitions);
+
+        // Scheduled armies depart later
+        let departs_at = match depart_at {
+            Some(depart_at) if depart_at > now => {
+                if depart_at > now + Duration::hours(game.max_schedule_ahead_hours) {
+                    return Err(AppError::BadRequest(format!(
+                        "Departure can be scheduled at most {} hours ahead",
+                        game.max_schedule_ahead_hours
+                    )));
                 }
+                depart_at
             }
+            _ => now,
+        };
+        let arrives_at = departs_at + travel_duration;
+        let returns_at = if mission.returns() {
+            Some(arrives_at + travel_duration)
+        } else {
+            None
+        };
+
+        Ok(JourneyPlan {
+            distance,
+            travel_duration,
+            departs_at,
+            arrives_at,
+            returns_at,
+        })
+    }
+
+    /// Unit types sent (count > 0) whose definition fails `allowed`; unknown types fail too
+    fn units_not_matching(
+        troops: &ArmyTroops,
+        definitions: &[TroopDefinition],
+        allowed: impl Fn(&TroopDefinition) -> bool,
+    ) -> Vec<TroopType> {
+        troops
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .filter(|(troop_type, _)| {
+                !definitions
+                    .iter()
+                    .any(|d| d.troop_type == **troop_type && allowed(d))
+            })
+            .map(|(troop_type, _)| *troop_type)
+            .collect()
+    }
+
+    /// Whether a capital is shielded from conquest: always, unless the server allows
+    /// it, and even then while it is the owner's only village
+    fn is_capital_protected(game: &GameConfig, owner_village_count: i64) -> bool {
+        !game.allow_capital_conquest || owner_village_count <= 1
+    }
+
+    /// Loyalty a conquered village starts with under its new owner
+    fn conquest_loyalty(game: &GameConfig) -> i32 {
+        game.conquest_loyalty_reset.clamp(1, MAX_LOYALTY)
+    }
+
+    /// Attacker's share of the total scout power (1.0 when nobody has any)
+    fn scout_power_share(attacker_power: f64, defender_power: f64) -> f64 {
+        let total_power = attacker_power + defender_power;
+        if total_power > 0.0 {
+            attacker_power / total_power
+        } else {
+            1.0
         }
+    }
 
-        Ok(processed)
+    /// Whether scouting with this power share succeeds, and whether it reports
+    /// exact troop counts; between the success and exact ratios counts are approximate
+    fn scout_outcome(game: &GameConfig, attacker_ratio: f64) -> (bool, bool) {
+        (attacker_ratio > game.scout_success_ratio, attacker_ratio >= game.scout_exact_ratio)
+    }
+
+    /// Loyalty one conquer attack removes once the per-attack cap (0 = no cap) is applied
+    fn capped_loyalty_reduction(game: &GameConfig, reduction: i32) -> i32 {
+        if game.max_loyalty_reduction_per_attack > 0 {
+            reduction.min(game.max_loyalty_reduction_per_attack)
+        } else {
+            reduction
+        }
+    }
+
+    /// Attacks needed to bring `loyalty` to zero removing `per_wave` each time
+    fn waves_to_conquer(loyalty: i32, per_wave: i32) -> i32 {
+        let remaining = loyalty.max(0);
+        (remaining + per_wave - 1) / per_wave
+    }
+
+    /// Whether `active` armies already fill a limit (0 = unlimited)
+    fn army_limit_reached(limit: i64, active: i64) -> bool {
+        limit > 0 && active >= limit
+    }
+
+    /// Whether alliance rules forbid this mission: hostile missions between members
+    /// of the same alliance are blocked unless the server allows internal attacks
+    fn is_blocked_by_alliance(
+        game: &GameConfig,
+        mission: MissionType,
+        own_alliance: Option<Uuid>,
+        target_alliance: Option<Uuid>,
+    ) -> bool {
+        mission.is_hostile()
+            && !game.allow_internal_alliance_attacks
+            && own_alliance.is_some()
+            && own_alliance == target_alliance
     }
 
     /// Process all armies that have arrived at their destination (with WebSocket notifications)
-    pub async fn process_arrived_armies_with_ws(pool: &PgPool, ws_manager: &WsManager) -> AppResult<i32> {
+    pub async fn process_arrived_armies_with_ws(
+        pool: &PgPool,
+        redis: &ConnectionManager,
+        ws_manager: &WsManager,
+        game: &GameConfig,
+    ) -> AppResult<i32> {
+        // Armies come back ordered by arrival and are resolved one at a time, so
+        // each wave at a target fights whatever the previous wave left behind
         let arrived = ArmyRepository::find_arrived(pool).await?;
         let mut processed = 0;
 
-        for army in arrived {
+        // The query goes by the database clock; players see times from ours, so a leg
+        // that is not over by our clock yet waits for the next run
+        let now = Utc::now();
+        for army in arrived.into_iter().filter(|a| a.is_due(now)) {
             // Get home village owner for notifications
             let home_village = VillageRepository::find_by_id(pool, army.from_village_id).await?;
             let home_owner_id = home_village.as_ref().map(|v| v.user_id);
@@ -219,38 +782,29 @@ impl ArmyService {
             };
             let target_owner_id = target_village.as_ref().map(|v| v.user_id);
 
-            let result = if army.is_returning {
-                Self::handle_returning_army(pool, &army).await
-            } else {
-                match army.mission {
-                    MissionType::Raid | MissionType::Attack => {
-                        Self::handle_hostile_arrival(pool, &army).await
-                    }
-                    MissionType::Scout => {
-                        Self::handle_scout_arrival(pool, &army).await
-                    }
-                    MissionType::Support => {
-                        Self::handle_support_arrival(pool, &army).await
-                    }
-                    MissionType::Conquer => {
-                        Self::handle_conquer_arrival(pool, &army).await
-                    }
-                    _ => {
-                        error!("Unhandled mission type: {:?}", army.mission);
-                        continue;
-                    }
-                }
-            };
+            let result = Self::handle_arrival(pool, game, &army).await;
 
             match result {
                 Ok(_) => {
                     processed += 1;
 
-                    // Send WebSocket notifications
-                    let event = WsEvent::ArmyArrived(ArmyArrivedData {
-                        army_id: army.id,
-                        village_id: if army.is_returning {
-                            army.from_village_id
+                    if !army.is_returning {
+                        if let Err(e) = Self::discover_target(pool, &army).await {
+                            error!("Failed to mark tiles discovered for army {}: {:?}", army.id, e);
+                        }
+                    }
+
+                    // Battles, loot, returns and settling change both ends of the journey
+                    CacheService::invalidate_village(redis, army.from_village_id).await;
+                    if let Some(village) = target_village.as_ref() {
+                        CacheService::invalidate_village(redis, village.id).await;
+                    }
+
+                    // Send WebSocket notifications
+                    let event = WsEvent::ArmyArrived(ArmyArrivedData {
+                        army_id: army.id,
+                        village_id: if army.is_returning {
+                            army.from_village_id
                         } else {
                             army.to_village_id.unwrap_or(army.from_village_id)
                         },
@@ -267,6 +821,14 @@ impl ArmyService {
                         if let Some(target_id) = target_owner_id {
                             if home_owner_id != Some(target_id) {
                                 ws_manager.send_to_user(target_id, &event).await;
+
+                                if let Some(village) = target_village.as_ref().filter(|_| army.mission.is_hostile()) {
+                                    if let Err(e) =
+                                        Self::notify_incoming_count(pool, ws_manager, target_id, village.id).await
+                                    {
+                                        error!("Failed to push incoming count for army {}: {:?}", army.id, e);
+                                    }
+                                }
                             }
                         }
                     }
@@ -280,8 +842,198 @@ impl ArmyService {
         Ok(processed)
     }
 
+    /// Route an arrived army to the handler for its mission.
+    /// The match is exhaustive so new mission types must be handled explicitly.
+    async fn handle_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
+        match Self::arrival_route(army) {
+            ArrivalRoute::Return => Self::handle_returning_army(pool, game, army).await?,
+            ArrivalRoute::Hostile => Self::handle_hostile_arrival(pool, game, army).await?,
+            ArrivalRoute::Scout => Self::handle_scout_arrival(pool, game, army).await?,
+            ArrivalRoute::Support => Self::handle_support_arrival(pool, game, army).await?,
+            ArrivalRoute::Conquer => Self::handle_conquer_arrival(pool, game, army).await?,
+            ArrivalRoute::Settle => Self::handle_settle_arrival(pool, game, army).await?,
+        }
+
+        // Troop losses and returns change upkeep on both sides
+        for village_id in std::iter::once(army.from_village_id).chain(army.to_village_id) {
+            if let Err(e) = VillageService::recompute_population(pool, village_id).await {
+                error!("Failed to recompute population for village {}: {:?}", village_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Route an army to its handler. No wildcard arm: a new mission type does not
+    /// compile until it is routed, so no army can get stuck unhandled.
+    fn arrival_route(army: &Army) -> ArrivalRoute {
+        if army.is_returning {
+            return ArrivalRoute::Return;
+        }
+        match army.mission {
+            MissionType::Raid | MissionType::Attack => ArrivalRoute::Hostile,
+            MissionType::Scout => ArrivalRoute::Scout,
+            MissionType::Support => ArrivalRoute::Support,
+            MissionType::Conquer => ArrivalRoute::Conquer,
+            MissionType::Settle => ArrivalRoute::Settle,
+        }
+    }
+
+    /// Total settlers in a set of troops
+    fn count_settlers(troops: &ArmyTroops) -> i32 {
+        troops
+            .iter()
+            .filter(|(troop_type, count)| **count > 0 && troop_type.is_settler())
+            .map(|(_, count)| *count)
+            .sum()
+    }
+
+    /// Handle settle arrival: found a new village on the empty tile, using up the
+    /// required settlers. Escorts and spare settlers head back home. If the tile was
+    /// taken in transit (or the player hit the village cap) everyone returns intact.
+    async fn handle_settle_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
+        let occupied = VillageRepository::find_by_coordinates(pool, army.to_x, army.to_y)
+            .await?
+            .is_some();
+        let can_settle = Self::can_settle(
+            game,
+            &army.troops.0,
+            occupied,
+            VillageService::can_own_another_village(pool, game, army.player_id).await?,
+        );
+
+        if !can_settle {
+            info!(
+                "Settle army {} could not found a village at ({}, {}), returning home",
+                army.id, army.to_x, army.to_y
+            );
+            let reason = if occupied {
+                "the tile was taken while they were on the way"
+            } else {
+                "you cannot found another village right now"
+            };
+            Self::send_settle_message(
+                pool,
+                army,
+                &format!("Settlers returning from ({}|{})", army.to_x, army.to_y),
+                &format!(
+                    "Your settlers could not found a village at ({}|{}) because {}. They are returning home.",
+                    army.to_x, army.to_y, reason
+                ),
+            )
+            .await;
+            return Self::initiate_return(
+                pool,
+                army,
+                Self::settle_returning_troops(game, &army.troops.0, false),
+                army.resources.0.clone(),
+                None,
+            )
+            .await;
+        }
+
+        // The settlers stay to live in the new village; anyone else goes home
+        let remaining = Self::settle_returning_troops(game, &army.troops.0, true);
+        let returns_at = if remaining.is_empty() {
+            None
+        } else {
+            Some(Self::return_time(pool, army, &remaining).await?)
+        };
+
+        // The village, its garrison and the army leaving the queue commit together,
+        // so a failure part-way cannot leave the army to found the village again
+        let mut tx = pool.begin().await?;
+        let (village, _) = VillageService::create_village_with_buildings(
+            &mut tx,
+            CreateVillage {
+                user_id: army.player_id,
+                name: format!("New Village ({}|{})", army.to_x, army.to_y),
+                x: army.to_x,
+                y: army.to_y,
+                is_capital: false,
+            },
+        )
+        .await?;
+        VillageService::grant_starting_garrison(&mut tx, game, village.id).await?;
+        match returns_at {
+            None => ArmyRepository::delete(&mut *tx, army.id).await?,
+            Some(returns_at) => {
+                ArmyRepository::set_returning(
+                    &mut *tx,
+                    army.id,
+                    returns_at,
+                    &army.resources.0,
+                    &remaining,
+                    None,
+                )
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        info!(
+            "Settle army {} founded village {} at ({}, {}) for player {}",
+            army.id, village.id, army.to_x, army.to_y, army.player_id
+        );
+
+        ActivityService::record(
+            pool,
+            army.from_village_id,
+            VillageActivityType::VillageFounded,
+            serde_json::json!({
+                "army_id": army.id,
+                "village_id": village.id,
+                "x": village.x,
+                "y": village.y,
+            }),
+        );
+        Self::send_settle_message(
+            pool,
+            army,
+            &format!("New village founded at ({}|{})", village.x, village.y),
+            &format!(
+                "Your settlers founded {} at ({}|{}).",
+                village.name, village.x, village.y
+            ),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Whether a settle army can found a village: enough settlers, an empty tile
+    /// and room for another village
+    fn can_settle(game: &GameConfig, troops: &ArmyTroops, occupied: bool, can_own_another: bool) -> bool {
+        !occupied && Self::count_settlers(troops) >= game.settlers_per_village && can_own_another
+    }
+
+    /// Troops a settle army brings back home: everyone if no village was founded,
+    /// otherwise everyone but the settlers who stayed to live in it
+    fn settle_returning_troops(game: &GameConfig, troops: &ArmyTroops, founded: bool) -> ArmyTroops {
+        let mut remaining = troops.clone();
+        if founded {
+            let mut to_consume = game.settlers_per_village;
+            for (troop_type, count) in remaining.iter_mut() {
+                if troop_type.is_settler() && to_consume > 0 {
+                    let used = (*count).min(to_consume);
+                    *count -= used;
+                    to_consume -= used;
+                }
+            }
+        }
+        remaining.retain(|_, count| *count > 0);
+        remaining
+    }
+
+    /// Tell the player how their settle mission went
+    async fn send_settle_message(pool: &PgPool, army: &Army, subject: &str, body: &str) {
+        if let Err(e) = MessageService::send_system_message(pool, army.player_id, subject, body).await {
+            error!("Failed to send settle message for army {}: {:?}", army.id, e);
+        }
+    }
+
     /// Handle raid/attack arrival at target
-    async fn handle_hostile_arrival(pool: &PgPool, army: &Army) -> AppResult<()> {
+    async fn handle_hostile_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
         let definitions = TroopRepository::get_all_definitions(pool).await?;
 
         // Get target village
@@ -305,6 +1057,10 @@ impl ArmyService {
             .await;
         };
 
+        // Bring the defender's stock up to date so loot, Crannies and the report
+        // work from what the village holds now rather than at its last update
+        let target = ResourceService::update_village_resources(pool, game, target.id).await?;
+
         // Get defender troops (village's own troops)
         let defender_troops_list = TroopRepository::find_by_village(pool, target.id).await?;
         let village_troops: ArmyTroops = defender_troops_list
@@ -325,11 +1081,14 @@ impl ArmyService {
         }
 
         // Calculate battle with combined defense
+        let modifiers = Self::battle_modifiers(pool, game, army, &target).await?;
         let battle = Self::calculate_battle(
+            game,
             &army.troops.0,
             &total_defender_troops,
             &definitions,
             army.mission,
+            &modifiers,
         );
 
         // Apply losses to village's own troops
@@ -386,9 +1145,22 @@ impl ArmyService {
             }
         }
 
+        // Snapshot the defender's stock before anything is taken
+        let defender_resources = CarriedResources::held_by(&target);
+
         // Calculate stolen resources if attacker won
         let stolen_resources = if battle.attacker_wins {
-            Self::calculate_stolen_resources(&target, &battle.attacker_survivors, &definitions, army.mission)
+            let carry_bonus =
+                BuildingService::carry_bonus_percent(pool, game, army.from_village_id).await?;
+            let hidden = BuildingService::hidden_resources(pool, target.id).await?;
+            Self::calculate_stolen_resources(
+                &target,
+                &battle.attacker_survivors,
+                &definitions,
+                army.mission,
+                carry_bonus,
+                hidden,
+            )
         } else {
             CarriedResources::default()
         };
@@ -415,23 +1187,45 @@ impl ArmyService {
             "draw"
         };
 
+        let hero_result =
+            Self::resolve_attacker_hero(pool, game, army, &modifiers, &battle, &definitions).await?;
+
         let report = ArmyRepository::create_battle_report(
             pool,
-            army.player_id,
-            Some(target.user_id),
-            army.from_village_id,
-            Some(target.id),
-            army.mission,
-            &army.troops.0,
-            &total_defender_troops,
-            &battle.attacker_losses,
-            &battle.defender_losses,
-            &stolen_resources,
-            winner,
-            Utc::now(),
+            NewBattleReport {
+                attacker_player_id: army.player_id,
+                defender_player_id: Some(target.user_id),
+                attacker_village_id: army.from_village_id,
+                defender_village_id: Some(target.id),
+                mission: army.mission,
+                attacker_troops: &army.troops.0,
+                defender_troops: &total_defender_troops,
+                attacker_losses: &battle.attacker_losses,
+                defender_losses: &battle.defender_losses,
+                resources_stolen: &stolen_resources,
+                defender_resources: Some(&defender_resources),
+                winner,
+                loyalty_reduced: 0,
+                hero_result: hero_result.as_ref(),
+                wall_bonus_percent: modifiers.wall_bonus_percent,
+                occurred_at: Utc::now(),
+            },
         )
         .await?;
 
+        ActivityService::record(
+            pool,
+            target.id,
+            VillageActivityType::AttackReceived,
+            serde_json::json!({
+                "report_id": report.id,
+                "mission": report.mission,
+                "attacker_player_id": army.player_id,
+                "from_village_id": army.from_village_id,
+                "winner": winner,
+            }),
+        );
+
         info!(
             "Battle at ({}, {}): {} wins! Attacker lost {:?}, Defender lost {:?} (including {} support armies)",
             army.to_x, army.to_y, winner,
@@ -459,8 +1253,42 @@ impl ArmyService {
         Ok(())
     }
 
+    /// Reveal the tiles an army passed through on its way out and its target tile
+    /// to the army owner (scouts also reveal the surroundings of the target)
+    async fn discover_target(pool: &PgPool, army: &Army) -> AppResult<()> {
+        if let Some(home) = VillageRepository::find_by_id(pool, army.from_village_id).await? {
+            let route = Self::route_tiles((home.x, home.y), (army.to_x, army.to_y));
+            MapRepository::mark_tiles_discovered(pool, army.player_id, &route).await?;
+        }
+        let radius = Self::discovery_radius(army.mission);
+        MapRepository::mark_discovered(pool, army.player_id, army.to_x, army.to_y, radius).await
+    }
+
+    /// Tiles on the straight line from `from` to `to`, both ends included
+    fn route_tiles(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = dx.abs().max(dy.abs());
+        if steps == 0 {
+            return vec![from];
+        }
+        (0..=steps)
+            .map(|step| {
+                let t = step as f64 / steps as f64;
+                (
+                    from.0 + (dx as f64 * t).round() as i32,
+                    from.1 + (dy as f64 * t).round() as i32,
+                )
+            })
+            .collect()
+    }
+
+    /// How many tiles around its target an arriving army reveals
+    fn discovery_radius(mission: MissionType) -> i32 {
+        if mission == MissionType::Scout { 2 } else { 0 }
+    }
+
     /// Handle scout mission arrival at target
-    async fn handle_scout_arrival(pool: &PgPool, army: &Army) -> AppResult<()> {
+    async fn handle_scout_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
         let definitions = TroopRepository::get_all_definitions(pool).await?;
 
         // Get target village
@@ -491,37 +1319,43 @@ impl ArmyService {
             .map(|t| (t.troop_type, t.in_village))
             .collect();
 
+        // Only the defender's scout units take part in scout combat
+        let defender_scouts: ArmyTroops = defender_troops
+            .iter()
+            .filter(|(troop_type, _)| {
+                definitions
+                    .iter()
+                    .any(|d| d.troop_type == **troop_type && d.is_scout)
+            })
+            .map(|(troop_type, count)| (*troop_type, *count))
+            .collect();
+
         // Calculate scout power (using speed as scout effectiveness)
         let attacker_scout_power = Self::calculate_scout_power(&army.troops.0, &definitions);
-        let defender_scout_power = Self::calculate_scout_power(&defender_troops, &definitions);
+        let defender_scout_power = Self::calculate_scout_power(&defender_scouts, &definitions);
 
         // Count scouts sent
         let attacker_scout_count: i32 = army.troops.0.values().sum();
-        let defender_scout_count: i32 = defender_troops.values().sum();
-
-        // Scout combat: attacker needs > defender's power to succeed
-        // Ratio determines success and losses
-        let total_power = attacker_scout_power + defender_scout_power;
-        let attacker_ratio = if total_power > 0.0 {
-            attacker_scout_power / total_power
-        } else {
-            1.0
-        };
+        let defender_scout_count: i32 = defender_scouts.values().sum();
 
-        let success = attacker_ratio > 0.4; // Attacker needs at least 40% power ratio to succeed
+        // Scout combat: the attacker's share of scout power determines success and losses
+        let attacker_ratio = Self::scout_power_share(attacker_scout_power, defender_scout_power);
+        let (success, troops_exact) = Self::scout_outcome(game, attacker_ratio);
 
         // Calculate losses
         let (attacker_losses, defender_losses) = if defender_scout_power > 0.0 {
             // Scout combat - both sides lose scouts
             let attacker_loss_ratio = if success {
-                (1.0 - attacker_ratio) * 0.8 // Winner loses less
+                (1.0 - attacker_ratio) * game.scout_winner_loss_factor // Winner loses less
             } else {
-                0.9 + (1.0 - attacker_ratio) * 0.1 // Loser loses 90-100%
+                // Loser loses between the minimum and everything
+                let min_loss = game.scout_loser_min_loss.clamp(0.0, 1.0);
+                min_loss + (1.0 - attacker_ratio) * (1.0 - min_loss)
             };
             let defender_loss_ratio = if success {
-                attacker_ratio * 0.5 // Defender loses based on attacker power
+                attacker_ratio * game.scout_defender_loss_factor // Defender loses based on attacker power
             } else {
-                0.1 // Defender barely loses if they win
+                game.scout_defender_win_loss // Defender barely loses if they win
             };
 
             let attacker_lost = (attacker_scout_count as f64 * attacker_loss_ratio).ceil() as i32;
@@ -536,7 +1370,7 @@ impl ArmyService {
         // Kill defender scouts
         if defender_losses > 0 {
             // Distribute losses proportionally across troop types
-            for (troop_type, count) in &defender_troops {
+            for (troop_type, count) in &defender_scouts {
                 let ratio = *count as f64 / defender_scout_count as f64;
                 let losses = (defender_losses as f64 * ratio).ceil() as i32;
                 if losses > 0 {
@@ -548,32 +1382,36 @@ impl ArmyService {
 
         // Prepare scouted info (only if successful)
         let (scouted_resources, scouted_troops) = if success {
-            let resources = CarriedResources {
-                wood: target.wood,
-                clay: target.clay,
-                iron: target.iron,
-                crop: target.crop,
-            };
-            (Some(resources), Some(defender_troops.clone()))
+            let resources = CarriedResources::held_by(&target);
+            let troops = Self::scouted_troops(&mut rand::thread_rng(), &defender_troops, troops_exact);
+            (Some(resources), Some(troops))
         } else {
             (None, None)
         };
 
+        // Unless every scouting is reported, a defender only learns of it when
+        // their own scouts fought or the attempt failed
+        let defender_notified =
+            game.scout_always_notify_defender || !success || defender_scout_count > 0;
+
         // Create scout report
         let _report = ArmyRepository::create_scout_report(
             pool,
-            army.player_id,
-            Some(target.user_id),
-            army.from_village_id,
-            Some(target.id),
-            attacker_scout_count,
-            defender_scout_count,
-            attacker_losses,
-            defender_losses,
-            success,
-            scouted_resources.as_ref(),
-            scouted_troops.as_ref(),
-            Utc::now(),
+            NewScoutReport {
+                attacker_player_id: army.player_id,
+                defender_player_id: defender_notified.then_some(target.user_id),
+                attacker_village_id: army.from_village_id,
+                defender_village_id: Some(target.id),
+                attacker_scouts: attacker_scout_count,
+                defender_scouts: defender_scout_count,
+                attacker_scouts_lost: attacker_losses,
+                defender_scouts_lost: defender_losses,
+                success,
+                scouted_resources: scouted_resources.as_ref(),
+                scouted_troops: scouted_troops.as_ref(),
+                troops_exact,
+                occurred_at: Utc::now(),
+            },
         )
         .await?;
 
@@ -607,7 +1445,7 @@ impl ArmyService {
     }
 
     /// Handle support mission arrival at target village
-    async fn handle_support_arrival(pool: &PgPool, army: &Army) -> AppResult<()> {
+    async fn handle_support_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
         // Get target village
         let target_village = if let Some(village_id) = army.to_village_id {
             VillageRepository::find_by_id(pool, village_id).await?
@@ -615,8 +1453,8 @@ impl ArmyService {
             VillageRepository::find_by_coordinates(pool, army.to_x, army.to_y).await?
         };
 
-        // If no target village exists, troops return home
-        let Some(_target) = target_village else {
+        // If no target village exists, troops return home with whatever they carried
+        let Some(target) = target_village else {
             info!(
                 "Support army {} arrived at empty tile ({}, {}), returning home",
                 army.id, army.to_x, army.to_y
@@ -625,14 +1463,46 @@ impl ArmyService {
                 pool,
                 army,
                 army.troops.0.clone(),
-                CarriedResources::default(),
+                army.resources.0.clone(),
                 None,
             )
             .await;
         };
 
+        // Hand carried resources to the host; what does not fit stays with the
+        // troops and comes back home when they are recalled
+        let carried = &army.resources.0;
+        let mut leftover = CarriedResources::default();
+        if carried.total() > 0 {
+            // Accrue the host's production first so free storage is measured
+            // against its current stock, as add_resources will clamp it
+            let target = ResourceService::update_village_resources(pool, game, target.id).await?;
+            let (delivered, rest) = Self::split_delivery(carried, &target);
+            leftover = rest;
+
+            if delivered.total() > 0 {
+                VillageRepository::add_resources(
+                    pool,
+                    target.id,
+                    delivered.wood,
+                    delivered.clay,
+                    delivered.iron,
+                    delivered.crop,
+                )
+                .await?;
+            }
+
+            info!(
+                "Support army {} delivered {} resources to village {} ({} did not fit)",
+                army.id,
+                delivered.total(),
+                target.id,
+                leftover.total()
+            );
+        }
+
         // Mark army as stationed at target village
-        ArmyRepository::set_stationed(pool, army.id).await?;
+        ArmyRepository::set_stationed(pool, army.id, &leftover).await?;
 
         info!(
             "Support army {} is now stationed at ({}, {}) with {} troops",
@@ -645,9 +1515,27 @@ impl ArmyService {
         Ok(())
     }
 
+    /// Split carried resources into what fits in a village's free storage and what does not
+    pub fn split_delivery(carried: &CarriedResources, village: &Village) -> (CarriedResources, CarriedResources) {
+        let fits = |amount: i32, stock: i32, capacity: i32| amount.min((capacity - stock).max(0));
+        let delivered = CarriedResources {
+            wood: fits(carried.wood, village.wood, village.warehouse_capacity),
+            clay: fits(carried.clay, village.clay, village.warehouse_capacity),
+            iron: fits(carried.iron, village.iron, village.warehouse_capacity),
+            crop: fits(carried.crop, village.crop, village.granary_capacity),
+        };
+        let leftover = CarriedResources {
+            wood: carried.wood - delivered.wood,
+            clay: carried.clay - delivered.clay,
+            iron: carried.iron - delivered.iron,
+            crop: carried.crop - delivered.crop,
+        };
+        (delivered, leftover)
+    }
+
     /// Handle conquer mission arrival at target village
     /// Similar to attack, but also reduces loyalty if attacker wins with surviving Chiefs
-    async fn handle_conquer_arrival(pool: &PgPool, army: &Army) -> AppResult<()> {
+    async fn handle_conquer_arrival(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
         let definitions = TroopRepository::get_all_definitions(pool).await?;
 
         // Get target village
@@ -683,8 +1571,14 @@ impl ArmyService {
             .await;
         }
 
-        // Can't conquer capital
-        if target.is_capital {
+        // Capitals are protected unless the server allows taking them from
+        // players who would still have another village left
+        let capital_protected = target.is_capital
+            && Self::is_capital_protected(
+                game,
+                VillageRepository::count_by_user_id(pool, target.user_id).await?,
+            );
+        if capital_protected {
             info!("Conquer army {} cannot conquer capital, returning home", army.id);
             return Self::initiate_return(
                 pool,
@@ -713,11 +1607,14 @@ impl ArmyService {
         }
 
         // Calculate battle (similar to Attack mission)
+        let modifiers = Self::battle_modifiers(pool, game, army, &target).await?;
         let battle = Self::calculate_battle(
+            game,
             &army.troops.0,
             &total_defender_troops,
             &definitions,
             MissionType::Attack, // Use Attack calculation for combat
+            &modifiers,
         );
 
         // Apply defender losses (same as handle_hostile_arrival)
@@ -770,15 +1667,17 @@ impl ArmyService {
         let mut village_conquered = false;
 
         if battle.attacker_wins {
-            // Calculate loyalty reduction from surviving Chiefs
-            for (troop_type, count) in &battle.attacker_survivors {
-                if *count > 0 && troop_type.is_chief() {
-                    if let Some(def) = definitions.iter().find(|d| d.troop_type == *troop_type) {
-                        loyalty_reduced += def.loyalty_reduction * count;
-                    }
-                }
+            loyalty_reduced = Self::chief_loyalty_reduction(&battle.attacker_survivors, &definitions);
+            if loyalty_reduced == 0 {
+                info!(
+                    "Conquer army {} won at ({}, {}) but no chiefs survived; loyalty unchanged",
+                    army.id, army.to_x, army.to_y
+                );
             }
 
+            // Stacking chiefs in one wave cannot remove more than the per-attack cap
+            loyalty_reduced = Self::capped_loyalty_reduction(game, loyalty_reduced);
+
             if loyalty_reduced > 0 {
                 let new_loyalty = (target.loyalty - loyalty_reduced).max(0);
                 VillageRepository::update_loyalty(pool, target.id, new_loyalty).await?;
@@ -788,18 +1687,47 @@ impl ArmyService {
                     army.to_x, army.to_y, loyalty_reduced, target.loyalty, new_loyalty
                 );
 
-                // Check if village is conquered (loyalty <= 0)
-                if new_loyalty <= 0 {
-                    // Transfer village ownership
-                    VillageRepository::transfer_ownership(pool, target.id, army.player_id).await?;
-                    // Reset loyalty to 25% (so it can be defended)
-                    VillageRepository::update_loyalty(pool, target.id, 25).await?;
-                    village_conquered = true;
+                // Check if village is conquered (loyalty <= 0); a player at the
+                // village cap cannot take it over and the village stays with its owner
+                let can_own_another = new_loyalty > 0
+                    || VillageService::can_own_another_village(pool, game, army.player_id).await?;
+                match Self::conquest_step(new_loyalty, can_own_another) {
+                    ConquestStep::LoyaltyRemains => {}
+                    ConquestStep::VillageCapReached => info!(
+                        "Conquer army {} brought loyalty to 0 but player {} is at the village cap",
+                        army.id, army.player_id
+                    ),
+                    ConquestStep::Conquered => {
+                        // Transfer village ownership
+                        VillageRepository::transfer_ownership(pool, target.id, army.player_id)
+                            .await?;
+                        // Reset loyalty (25 by default) so it can be defended
+                        VillageRepository::update_loyalty(
+                            pool,
+                            target.id,
+                            Self::conquest_loyalty(game),
+                        )
+                        .await?;
+                        village_conquered = true;
+
+                        // The previous owner needs a new capital
+                        if target.is_capital {
+                            if let Some(new_capital) =
+                                VillageRepository::promote_oldest_to_capital(pool, target.user_id)
+                                    .await?
+                            {
+                                info!(
+                                    "Village {} is now the capital of player {}",
+                                    new_capital, target.user_id
+                                );
+                            }
+                        }
 
-                    info!(
-                        "Village {} at ({}, {}) conquered by player {}!",
-                        target.name, army.to_x, army.to_y, army.player_id
-                    );
+                        info!(
+                            "Village {} at ({}, {}) conquered by player {}!",
+                            target.name, army.to_x, army.to_y, army.player_id
+                        );
+                    }
                 }
             }
         }
@@ -813,23 +1741,45 @@ impl ArmyService {
             "draw"
         };
 
+        let hero_result =
+            Self::resolve_attacker_hero(pool, game, army, &modifiers, &battle, &definitions).await?;
+
         let report = ArmyRepository::create_battle_report(
             pool,
-            army.player_id,
-            Some(target.user_id),
-            army.from_village_id,
-            Some(target.id),
-            MissionType::Conquer,
-            &army.troops.0,
-            &total_defender_troops,
-            &battle.attacker_losses,
-            &battle.defender_losses,
-            &CarriedResources::default(), // No resources stolen in conquer
-            winner,
-            Utc::now(),
+            NewBattleReport {
+                attacker_player_id: army.player_id,
+                defender_player_id: Some(target.user_id),
+                attacker_village_id: army.from_village_id,
+                defender_village_id: Some(target.id),
+                mission: MissionType::Conquer,
+                attacker_troops: &army.troops.0,
+                defender_troops: &total_defender_troops,
+                attacker_losses: &battle.attacker_losses,
+                defender_losses: &battle.defender_losses,
+                resources_stolen: &CarriedResources::default(), // No resources stolen in conquer
+                defender_resources: None,
+                winner,
+                loyalty_reduced,
+                hero_result: hero_result.as_ref(),
+                wall_bonus_percent: modifiers.wall_bonus_percent,
+                occurred_at: Utc::now(),
+            },
         )
         .await?;
 
+        ActivityService::record(
+            pool,
+            target.id,
+            VillageActivityType::AttackReceived,
+            serde_json::json!({
+                "report_id": report.id,
+                "mission": report.mission,
+                "attacker_player_id": army.player_id,
+                "from_village_id": army.from_village_id,
+                "winner": winner,
+            }),
+        );
+
         info!(
             "Conquer battle at ({}, {}): {} wins! Loyalty: -{}, Conquered: {}",
             army.to_x, army.to_y, winner, loyalty_reduced, village_conquered
@@ -866,6 +1816,35 @@ impl ArmyService {
             .sum()
     }
 
+    /// Defender troops as a successful scouting reports them: exact counts, or
+    /// approximate ones when the scouts only got a partial look
+    fn scouted_troops(rng: &mut impl Rng, troops: &ArmyTroops, exact: bool) -> ArmyTroops {
+        if exact {
+            troops.clone()
+        } else {
+            Self::approximate_troops(rng, troops)
+        }
+    }
+
+    /// Blur troop counts for a partially successful scouting: each count is
+    /// jittered by up to 20% and rounded to a bucket that grows with its size
+    fn approximate_troops(rng: &mut impl Rng, troops: &ArmyTroops) -> ArmyTroops {
+        troops
+            .iter()
+            .map(|(troop_type, count)| {
+                let jittered = *count as f64 * rng.gen_range(0.8..=1.2);
+                let bucket = match *count {
+                    0..=19 => 5.0,
+                    20..=199 => 10.0,
+                    200..=1999 => 50.0,
+                    _ => 100.0,
+                };
+                let approx = ((jittered / bucket).round() * bucket) as i32;
+                (*troop_type, approx.max(1))
+            })
+            .collect()
+    }
+
     /// Calculate scout survivors after losses
     fn calculate_scout_survivors(troops: &ArmyTroops, total_losses: i32, total_count: i32) -> ArmyTroops {
         if total_count <= 0 || total_losses <= 0 {
@@ -884,7 +1863,34 @@ impl ArmyService {
     }
 
     /// Handle army returning to home village
-    async fn handle_returning_army(pool: &PgPool, army: &Army) -> AppResult<()> {
+    async fn handle_returning_army(pool: &PgPool, game: &GameConfig, army: &Army) -> AppResult<()> {
+        let Some(home) = VillageRepository::find_by_id(pool, army.from_village_id).await? else {
+            warn!(
+                "Army {} returned to missing village {}, disbanding",
+                army.id, army.from_village_id
+            );
+            Self::release_hero(pool, army).await?;
+            ArmyRepository::delete(pool, army.id).await?;
+            return Ok(());
+        };
+
+        // Troops and loot belong to whoever holds the village when they arrive, so
+        // a home conquered in the meantime hands both to its new owner
+        if let Some(notices) = Self::captured_home_notices(army, &home) {
+            info!(
+                "Army {} returned to village {} now owned by player {}; troops and loot go to the new owner",
+                army.id, home.id, home.user_id
+            );
+            for (user_id, subject, body) in notices {
+                if let Err(e) = MessageService::send_system_message(pool, user_id, &subject, &body).await {
+                    error!("Failed to send capture notice for army {}: {:?}", army.id, e);
+                }
+            }
+            Self::rehome_hero(pool, army, &home).await?;
+        } else {
+            Self::release_hero(pool, army).await?;
+        }
+
         // Return troops to village
         for (troop_type, count) in army.troops.0.iter() {
             if *count > 0 {
@@ -896,15 +1902,23 @@ impl ArmyService {
         // Add carried resources to village
         let resources = &army.resources.0;
         if resources.total() > 0 {
-            VillageRepository::add_resources(
-                pool,
-                army.from_village_id,
-                resources.wood,
-                resources.clay,
-                resources.iron,
-                resources.crop,
-            )
-            .await?;
+            // Accrue production first so the overflow is measured against the current stock
+            let home = ResourceService::update_village_resources(pool, game, home.id).await?;
+            let (stored, overflow) = Self::split_delivery(resources, &home);
+            if stored.total() > 0 {
+                VillageRepository::add_resources(
+                    pool,
+                    home.id,
+                    stored.wood,
+                    stored.clay,
+                    stored.iron,
+                    stored.crop,
+                )
+                .await?;
+            }
+
+            // Whatever did not fit is lost unless the owner forwards it
+            TransferService::forward_overflow(pool, game, &home, &overflow).await?;
         }
 
         info!(
@@ -920,6 +1934,37 @@ impl ArmyService {
         Ok(())
     }
 
+    /// Messages for both players when an army comes home to a village that was conquered
+    /// while it was away; None while the village still belongs to the army's owner
+    fn captured_home_notices(army: &Army, home: &Village) -> Option<[(Uuid, String, String); 2]> {
+        if home.user_id == army.player_id {
+            return None;
+        }
+
+        let troops: i32 = army.troops.0.values().sum();
+        let resources = army.resources.0.total();
+        Some([
+            (
+                army.player_id,
+                format!("{} was lost", home.name),
+                format!(
+                    "Your army came home to {} after it was conquered. Its {} troops and {} resources \
+                     now serve the new owner.",
+                    home.name, troops, resources
+                ),
+            ),
+            (
+                home.user_id,
+                format!("Troops arrived at {}", home.name),
+                format!(
+                    "An army of the former owner came home to {}. Its {} troops and {} resources \
+                     are now yours.",
+                    home.name, troops, resources
+                ),
+            ),
+        ])
+    }
+
     /// Initiate return journey for an army
     async fn initiate_return(
         pool: &PgPool,
@@ -928,18 +1973,7 @@ impl ArmyService {
         resources: CarriedResources,
         battle_report_id: Option<Uuid>,
     ) -> AppResult<()> {
-        // Calculate return travel time based on survivors
-        let definitions = TroopRepository::get_all_definitions(pool).await?;
-        let from_village = VillageRepository::find_by_id(pool, army.from_village_id).await?;
-
-        let distance = if let Some(village) = from_village {
-            Self::calculate_distance(army.to_x, army.to_y, village.x, village.y)
-        } else {
-            Self::calculate_distance(army.to_x, army.to_y, 0, 0) // Fallback
-        };
-
-        let travel_duration = Self::calculate_travel_time(distance, &survivors, &definitions);
-        let returns_at = Utc::now() + travel_duration;
+        let returns_at = Self::return_time(pool, army, &survivors).await?;
 
         ArmyRepository::set_returning(
             pool,
@@ -954,8 +1988,45 @@ impl ArmyService {
         Ok(())
     }
 
+    /// When an army heading home now gets back, travelling at its survivors' pace
+    async fn return_time(
+        pool: &PgPool,
+        army: &Army,
+        survivors: &ArmyTroops,
+    ) -> AppResult<DateTime<Utc>> {
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let from_village = VillageRepository::find_by_id(pool, army.from_village_id).await?;
+
+        let distance = if let Some(village) = from_village {
+            Self::calculate_distance(army.to_x, army.to_y, village.x, village.y)
+        } else {
+            Self::calculate_distance(army.to_x, army.to_y, 0, 0) // Fallback
+        };
+
+        let travel_duration = Self::calculate_travel_time(distance, survivors, &definitions);
+        Ok(Utc::now() + travel_duration)
+    }
+
+    /// Loyalty removed by the surviving chiefs of a winning conquer army: each
+    /// chief type's loyalty_reduction times its surviving count
+    fn chief_loyalty_reduction(survivors: &ArmyTroops, definitions: &[TroopDefinition]) -> i32 {
+        survivors
+            .iter()
+            .filter(|(troop_type, count)| **count > 0 && troop_type.is_chief())
+            .map(|(troop_type, count)| {
+                match definitions.iter().find(|d| d.troop_type == *troop_type) {
+                    Some(def) => def.loyalty_reduction.saturating_mul(*count),
+                    None => {
+                        warn!("No definition for chief {:?}; it reduces no loyalty", troop_type);
+                        0
+                    }
+                }
+            })
+            .fold(0, i32::saturating_add)
+    }
+
     /// Calculate Euclidean distance between two points
-    fn calculate_distance(from_x: i32, from_y: i32, to_x: i32, to_y: i32) -> f64 {
+    pub fn calculate_distance(from_x: i32, from_y: i32, to_x: i32, to_y: i32) -> f64 {
         let dx = (to_x - from_x) as f64;
         let dy = (to_y - from_y) as f64;
         (dx * dx + dy * dy).sqrt()
@@ -988,33 +2059,274 @@ impl ArmyService {
         Duration::seconds(seconds.max(60))
     }
 
-    /// Calculate battle using Travian-style formula
-    fn calculate_battle(
-        attacker_troops: &ArmyTroops,
-        defender_troops: &ArmyTroops,
+    /// Completed Wall level of a village; an upgrade in progress does not count yet
+    async fn wall_level(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
+        let walls = BuildingRepository::find_by_type(pool, village_id, BuildingType::Wall).await?;
+        Ok(Self::completed_level(&walls))
+    }
+
+    /// Highest completed level among `buildings`; a level only rises when its upgrade finishes
+    fn completed_level(buildings: &[Building]) -> i32 {
+        buildings.iter().map(|b| b.level).max().unwrap_or(0)
+    }
+
+    /// Defense bonus (percent) a Wall of the given level gives, capped by config
+    fn wall_bonus_percent(game: &GameConfig, wall_level: i32) -> i32 {
+        (wall_level.max(0) * game.wall_defense_bonus_percent_per_level)
+            .clamp(0, game.wall_defense_bonus_max_percent.max(0))
+    }
+
+    /// Everything besides troops that shapes `army`'s battle against `target`
+    async fn battle_modifiers(
+        pool: &PgPool,
+        game: &GameConfig,
+        army: &Army,
+        target: &Village,
+    ) -> AppResult<BattleModifiers> {
+        let attacker_hero = match army.hero_id {
+            Some(hero_id) => HeroRepository::find_by_id(pool, hero_id)
+                .await?
+                .filter(|hero| hero.status == HeroStatus::Moving && hero.health > 0),
+            None => None,
+        };
+
+        Ok(BattleModifiers {
+            attacker_tribe: UserRepository::get_tribe(pool, army.player_id).await?,
+            defender_tribe: UserRepository::get_tribe(pool, target.user_id).await?,
+            wall_bonus_percent: Self::wall_bonus_percent(
+                game,
+                Self::wall_level(pool, target.id).await?,
+            ),
+            attacker_hero,
+        })
+    }
+
+    /// Apply a battle's outcome to the hero leading the attacking army: it takes
+    /// damage in proportion to the army's losses (falling with an army that is
+    /// wiped out) and earns experience for the upkeep of the defenders killed
+    async fn resolve_attacker_hero(
+        pool: &PgPool,
+        game: &GameConfig,
+        army: &Army,
+        modifiers: &BattleModifiers,
+        battle: &BattleResult,
         definitions: &[TroopDefinition],
-        mission: MissionType,
-    ) -> BattleResult {
-        // Calculate attack power
-        let attack_power = Self::calculate_attack_power(attacker_troops, definitions);
+    ) -> AppResult<Option<BattleHeroResult>> {
+        let Some(hero) = modifiers.attacker_hero.as_ref() else {
+            return Ok(None);
+        };
 
-        // Calculate infantry/cavalry ratio for defense calculation
-        let (infantry_attack, cavalry_attack) =
-            Self::calculate_attack_by_type(attacker_troops, definitions);
-        let total_attack = infantry_attack + cavalry_attack;
-        let infantry_ratio = if total_attack > 0.0 {
-            infantry_attack / total_attack
+        let (damage, experience) = Self::attacker_hero_toll(hero, battle, definitions);
+
+        // A surviving hero stays on the march the whole time; it never passes
+        // through idle, where it could be equipped or sent elsewhere
+        let mut tx = pool.begin().await?;
+        let damaged = HeroRepository::wound_hero(&mut *tx, hero.id, damage).await?;
+        let died = damaged.health <= 0;
+        if died {
+            ArmyRepository::clear_hero(&mut *tx, army.id).await?;
+        }
+        tx.commit().await?;
+
+        let leveled_up = if experience > 0 {
+            let mut conn = pool.acquire().await?;
+            HeroRepository::add_experience(&mut conn, game, hero.id, experience).await?.level
+                > hero.level
         } else {
-            0.5
+            false
         };
 
-        // Calculate defense power
-        let defense_power =
-            Self::calculate_defense_power(defender_troops, definitions, infantry_ratio);
+        info!(
+            "Hero {} fought with army {}: took {} damage, gained {} experience{}",
+            hero.id,
+            army.id,
+            hero.health - damaged.health,
+            experience,
+            if died { " and fell" } else { "" }
+        );
 
-        // Determine winner and calculate losses
+        Ok(Some(Self::attacker_hero_result(
+            hero,
+            battle,
+            damaged.health,
+            leveled_up,
+            experience,
+        )))
+    }
+
+    /// Damage an attacking hero takes and experience it earns from a battle
+    fn attacker_hero_toll(
+        hero: &Hero,
+        battle: &BattleResult,
+        definitions: &[TroopDefinition],
+    ) -> (i32, i32) {
+        let wiped_out = battle.attacker_survivors.values().sum::<i32>() <= 0;
+        let damage = if wiped_out {
+            hero.health
+        } else {
+            (battle.attacker_loss_ratio * 100.0).round() as i32
+        };
+
+        let experience = battle
+            .defender_losses
+            .iter()
+            .filter_map(|(troop_type, count)| {
+                definitions
+                    .iter()
+                    .find(|d| d.troop_type == *troop_type)
+                    .map(|d| d.crop_consumption.saturating_mul(*count))
+            })
+            .fold(0, i32::saturating_add);
+
+        (damage, experience)
+    }
+
+    /// Hero section of a battle report for the hero that led the attack
+    fn attacker_hero_result(
+        hero: &Hero,
+        battle: &BattleResult,
+        health_after: i32,
+        leveled_up: bool,
+        experience: i32,
+    ) -> BattleHeroResult {
+        BattleHeroResult {
+            attacker: Some(HeroBattleResult {
+                hero_id: hero.id,
+                hero_name: hero.name.clone(),
+                damage_dealt: battle.hero_attack_power.round() as i32,
+                damage_taken: hero.health - health_after,
+                died: health_after <= 0,
+                leveled_up,
+                experience_gained: experience,
+            }),
+            defender: None,
+        }
+    }
+
+    /// Send a hero that travelled with an army back to idle where it started
+    async fn release_hero(pool: &PgPool, army: &Army) -> AppResult<()> {
+        if let Some(hero_id) = army.hero_id {
+            HeroRepository::transition_status(pool, hero_id, HeroStatus::Moving, HeroStatus::Idle)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The hero of an army whose home was conquered moves to another of its owner's
+    /// villages. With none left it stays moving rather than idling in the enemy's village.
+    async fn rehome_hero(pool: &PgPool, army: &Army, captured: &Village) -> AppResult<()> {
+        let Some(hero_id) = army.hero_id else {
+            return Ok(());
+        };
+
+        let villages = VillageRepository::find_by_user_id(pool, army.player_id).await?;
+        match Self::new_hero_home(&villages, captured) {
+            Some(village_id) => {
+                HeroRepository::rehome_moving_hero(pool, hero_id, village_id).await?;
+                info!(
+                    "Hero {} moved to village {} after its home {} was conquered",
+                    hero_id, village_id, captured.id
+                );
+            }
+            None => warn!(
+                "Hero {} has no village left after its home {} was conquered",
+                hero_id, captured.id
+            ),
+        }
+        Ok(())
+    }
+
+    /// Where a hero whose home was conquered goes: the owner's capital, otherwise
+    /// their village nearest the lost one
+    fn new_hero_home(villages: &[Village], captured: &Village) -> Option<Uuid> {
+        let remaining = villages.iter().filter(|v| v.id != captured.id);
+        if let Some(capital) = remaining.clone().find(|v| v.is_capital) {
+            return Some(capital.id);
+        }
+        remaining
+            .min_by(|a, b| {
+                let da = Self::calculate_distance(captured.x, captured.y, a.x, a.y);
+                let db = Self::calculate_distance(captured.x, captured.y, b.x, b.y);
+                da.total_cmp(&db)
+            })
+            .map(|v| v.id)
+    }
+
+    /// Attack and defense multipliers a player's tribe brings to battle;
+    /// players without a tribe fight unmodified
+    fn tribe_combat_multipliers(game: &GameConfig, tribe: Option<TribeType>) -> (f64, f64) {
+        let (attack_percent, defense_percent) = match tribe {
+            Some(TribeType::Phasuttha) => (
+                game.tribe_phasuttha_attack_bonus_percent,
+                game.tribe_phasuttha_defense_bonus_percent,
+            ),
+            Some(TribeType::Nava) => (
+                game.tribe_nava_attack_bonus_percent,
+                game.tribe_nava_defense_bonus_percent,
+            ),
+            Some(TribeType::Kiri) => (
+                game.tribe_kiri_attack_bonus_percent,
+                game.tribe_kiri_defense_bonus_percent,
+            ),
+            Some(TribeType::Special) | None => (0, 0),
+        };
+        (
+            (100 + attack_percent).max(0) as f64 / 100.0,
+            (100 + defense_percent).max(0) as f64 / 100.0,
+        )
+    }
+
+    /// Calculate battle using Travian-style formula
+    fn calculate_battle(
+        game: &GameConfig,
+        attacker_troops: &ArmyTroops,
+        defender_troops: &ArmyTroops,
+        definitions: &[TroopDefinition],
+        mission: MissionType,
+        modifiers: &BattleModifiers,
+    ) -> BattleResult {
+        let (attack_multiplier, _) = Self::tribe_combat_multipliers(game, modifiers.attacker_tribe);
+        let (_, tribe_defense_multiplier) =
+            Self::tribe_combat_multipliers(game, modifiers.defender_tribe);
+        let defense_multiplier =
+            tribe_defense_multiplier * (100 + modifiers.wall_bonus_percent) as f64 / 100.0;
+
+        // Calculate attack power; a leading hero adds its own attack and its
+        // offense bonus boosts the whole army
+        let (hero_attack, hero_multiplier) = modifiers
+            .attacker_hero
+            .as_ref()
+            .map(|hero| (hero.total_attack().max(0) as f64, 1.0 + hero.off_bonus_percent() / 100.0))
+            .unwrap_or((0.0, 1.0));
+        let attack_power = (Self::calculate_attack_power(attacker_troops, definitions)
+            * attack_multiplier
+            + hero_attack)
+            * hero_multiplier;
+
+        // Calculate infantry/cavalry ratio for defense calculation
+        let (infantry_attack, cavalry_attack) =
+            Self::calculate_attack_by_type(attacker_troops, definitions);
+        let total_attack = infantry_attack + cavalry_attack;
+        let infantry_ratio = if total_attack > 0.0 {
+            infantry_attack / total_attack
+        } else {
+            0.5
+        };
+
+        // Calculate defense power
+        let defense_power = Self::calculate_defense_power(
+            defender_troops,
+            definitions,
+            infantry_ratio,
+            defense_multiplier,
+        );
+
+        // Determine winner and calculate losses
         let (attacker_wins, attacker_loss_ratio, defender_loss_ratio) =
-            if attack_power > defense_power && defense_power > 0.0 {
+            if attack_power <= 0.0 && defense_power > 0.0 {
+                // Nothing to fight with - the whole army is lost and defenders are untouched
+                (false, 1.0, 0.0)
+            } else if attack_power > defense_power && defense_power > 0.0 {
                 // Attacker wins
                 let ratio = defense_power / attack_power;
                 let attacker_losses = ratio.powf(1.5);
@@ -1035,6 +2347,18 @@ impl ArmyService {
                 (true, 0.0, 0.0)
             };
 
+        // Even a crushing win costs the winner something, but only in a real fight
+        let min_winner_loss = game.battle_min_winner_loss_percent.clamp(0, 100) as f64 / 100.0;
+        let (attacker_loss_ratio, defender_loss_ratio) = if attack_power > 0.0 && defense_power > 0.0 {
+            if attacker_wins {
+                (attacker_loss_ratio.max(min_winner_loss), defender_loss_ratio)
+            } else {
+                (attacker_loss_ratio, defender_loss_ratio.max(min_winner_loss))
+            }
+        } else {
+            (attacker_loss_ratio, defender_loss_ratio)
+        };
+
         // Calculate actual losses
         let attacker_losses = Self::apply_losses(attacker_troops, attacker_loss_ratio);
         let defender_losses = Self::apply_losses(defender_troops, defender_loss_ratio);
@@ -1049,6 +2373,8 @@ impl ArmyService {
             defender_survivors,
             attacker_losses,
             defender_losses,
+            attacker_loss_ratio,
+            hero_attack_power: hero_attack * hero_multiplier,
         }
     }
 
@@ -1087,11 +2413,13 @@ impl ArmyService {
         (infantry, cavalry)
     }
 
-    /// Calculate total defense power based on attacker composition
+    /// Calculate total defense power based on attacker composition, scaled by
+    /// the defender's bonuses (Wall, tribe)
     fn calculate_defense_power(
         troops: &ArmyTroops,
         definitions: &[TroopDefinition],
         infantry_ratio: f64,
+        multiplier: f64,
     ) -> f64 {
         let cavalry_ratio = 1.0 - infantry_ratio;
 
@@ -1104,7 +2432,8 @@ impl ArmyService {
                     effective_defense * *count as f64
                 })
             })
-            .sum()
+            .sum::<f64>()
+            * multiplier
     }
 
     /// Apply loss ratio to troops
@@ -1137,17 +2466,21 @@ impl ArmyService {
         survivors: &ArmyTroops,
         definitions: &[TroopDefinition],
         mission: MissionType,
+        carry_bonus_percent: i32,
+        hidden_per_resource: i32,
     ) -> CarriedResources {
-        // Calculate total carry capacity
-        let total_capacity: i32 = survivors
+        // Calculate total carry capacity, boosted by the home village's Trade Office
+        let base_capacity: i64 = survivors
             .iter()
             .filter_map(|(troop_type, count)| {
                 definitions
                     .iter()
                     .find(|d| d.troop_type == *troop_type)
-                    .map(|d| d.carry_capacity * count)
+                    .map(|d| d.carry_capacity as i64 * *count as i64)
             })
             .sum();
+        let total_capacity = (base_capacity * (100 + carry_bonus_percent.max(0)) as i64 / 100)
+            .min(i32::MAX as i64) as i32;
 
         if total_capacity <= 0 {
             return CarriedResources::default();
@@ -1160,11 +2493,12 @@ impl ArmyService {
             _ => 0.0,
         };
 
-        // Calculate available resources
-        let available_wood = (target.wood as f64 * raid_percent) as i32;
-        let available_clay = (target.clay as f64 * raid_percent) as i32;
-        let available_iron = (target.iron as f64 * raid_percent) as i32;
-        let available_crop = (target.crop as f64 * raid_percent) as i32;
+        // Calculate available resources; Crannies hide the same amount of each
+        let hidden = hidden_per_resource.max(0);
+        let available_wood = ((target.wood - hidden).max(0) as f64 * raid_percent) as i32;
+        let available_clay = ((target.clay - hidden).max(0) as f64 * raid_percent) as i32;
+        let available_iron = ((target.iron - hidden).max(0) as f64 * raid_percent) as i32;
+        let available_crop = ((target.crop - hidden).max(0) as f64 * raid_percent) as i32;
         let total_available = available_wood + available_clay + available_iron + available_crop;
 
         if total_available <= 0 {
@@ -1195,11 +2529,190 @@ impl ArmyService {
         Ok(armies.into_iter().map(|a| a.into()).collect())
     }
 
+    /// Get all armies owned by a player across every village, grouped by state
+    pub async fn get_player_armies(
+        pool: &PgPool,
+        player_id: Uuid,
+    ) -> AppResult<PlayerArmiesResponse> {
+        let armies = ArmyRepository::find_by_player(pool, player_id).await?;
+        Ok(Self::group_player_armies(armies))
+    }
+
+    /// Sort a player's armies by state, whichever village they came from
+    fn group_player_armies(armies: Vec<Army>) -> PlayerArmiesResponse {
+        let mut response = PlayerArmiesResponse {
+            scheduled: Vec::new(),
+            outgoing: Vec::new(),
+            returning: Vec::new(),
+            stationed: Vec::new(),
+        };
+
+        for army in armies {
+            let army: ArmyResponse = army.into();
+            if army.is_stationed {
+                response.stationed.push(army);
+            } else if army.is_returning {
+                response.returning.push(army);
+            } else if army.is_scheduled {
+                response.scheduled.push(army);
+            } else {
+                response.outgoing.push(army);
+            }
+        }
+
+        response
+    }
+
+    /// Summarize a village's defense: own troops plus stationed support
+    pub async fn get_defense_summary(
+        pool: &PgPool,
+        game: &GameConfig,
+        village: &Village,
+    ) -> AppResult<DefenseSummaryResponse> {
+        let village_id = village.id;
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+
+        let village_troops: ArmyTroops = TroopRepository::find_by_village(pool, village_id)
+            .await?
+            .iter()
+            .filter(|t| t.in_village > 0)
+            .map(|t| (t.troop_type, t.in_village))
+            .collect();
+
+        let stationed_armies = ArmyRepository::find_stationed_at_village(pool, village_id).await?;
+        let (support_troops, total_troops) =
+            Self::garrison_with_support(&village_troops, &stationed_armies);
+
+        let wall_level = Self::wall_level(pool, village_id).await?;
+        let wall_bonus_percent = Self::wall_bonus_percent(game, wall_level);
+        let (_, tribe_defense_multiplier) = Self::tribe_combat_multipliers(
+            game,
+            UserRepository::get_tribe(pool, village.user_id).await?,
+        );
+        let defense_multiplier =
+            tribe_defense_multiplier * (100 + wall_bonus_percent) as f64 / 100.0;
+
+        let hero_defense = HeroRepository::find_idle_at_village(pool, village_id)
+            .await?
+            .iter()
+            .map(|h| h.total_defense())
+            .sum();
+
+        Ok(DefenseSummaryResponse {
+            village_id,
+            village_troops,
+            support_troops,
+            support_armies: stationed_armies.len() as i32,
+            defense_vs_infantry: Self::calculate_defense_power(
+                &total_troops,
+                &definitions,
+                1.0,
+                defense_multiplier,
+            ),
+            defense_vs_cavalry: Self::calculate_defense_power(
+                &total_troops,
+                &definitions,
+                0.0,
+                defense_multiplier,
+            ),
+            wall_level,
+            wall_bonus_percent,
+            hero_defense,
+        })
+    }
+
+    /// Support stationed at a village, and the whole garrison the battle would
+    /// defend with: the village's own troops plus that support
+    fn garrison_with_support(
+        village_troops: &ArmyTroops,
+        stationed: &[Army],
+    ) -> (ArmyTroops, ArmyTroops) {
+        let mut support_troops = ArmyTroops::new();
+        for army in stationed {
+            for (troop_type, count) in army.troops.0.iter() {
+                *support_troops.entry(*troop_type).or_insert(0) += count;
+            }
+        }
+
+        let mut total_troops = village_troops.clone();
+        for (troop_type, count) in &support_troops {
+            *total_troops.entry(*troop_type).or_insert(0) += count;
+        }
+
+        (support_troops, total_troops)
+    }
+
     /// Get armies incoming to a village
     pub async fn get_incoming_armies(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<Army>> {
         ArmyRepository::find_incoming_to_village(pool, village_id).await
     }
 
+    /// Push the defender's current incoming attack count after it changed for one of their villages
+    pub async fn notify_incoming_count(
+        pool: &PgPool,
+        ws_manager: &WsManager,
+        defender_id: Uuid,
+        village_id: Uuid,
+    ) -> AppResult<()> {
+        let incoming_count = ArmyRepository::count_incoming_hostile_for_player(pool, defender_id).await?;
+        Self::push_incoming_count(ws_manager, defender_id, village_id, incoming_count).await;
+
+        Ok(())
+    }
+
+    /// Update the incoming badge of defenders targeted by scheduled armies that
+    /// departed in `(since, until]`; until then those armies are hidden from them
+    pub async fn notify_released_scheduled_armies(
+        pool: &PgPool,
+        ws_manager: &WsManager,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> AppResult<usize> {
+        let targets = ArmyRepository::find_released_hostile_targets(pool, since, until).await?;
+        for (defender_id, village_id) in &targets {
+            Self::notify_incoming_count(pool, ws_manager, *defender_id, *village_id).await?;
+        }
+
+        Ok(targets.len())
+    }
+
+    async fn push_incoming_count(
+        ws_manager: &WsManager,
+        defender_id: Uuid,
+        village_id: Uuid,
+        incoming_count: i64,
+    ) {
+        let event = WsEvent::IncomingCountChanged(IncomingCountChangedData {
+            village_id,
+            incoming_count,
+        });
+        ws_manager.send_to_user(defender_id, &event).await;
+    }
+
+    /// Total number of units in a requested army; zero entries are ignored,
+    /// negative counts and totals above `max_army_size` are rejected
+    fn validate_troop_counts(troops: &ArmyTroops, max_army_size: i32) -> AppResult<i32> {
+        let mut total: i32 = 0;
+        for (troop_type, count) in troops {
+            if *count < 0 {
+                return Err(AppError::BadRequest(format!(
+                    "Troop count for {:?} cannot be negative (got {})",
+                    troop_type, count
+                )));
+            }
+            total = total
+                .checked_add(*count)
+                .filter(|t| *t <= max_army_size)
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "An army cannot contain more than {} units",
+                        max_army_size
+                    ))
+                })?;
+        }
+        Ok(total)
+    }
+
     /// Get battle reports for a player
     pub async fn get_reports(pool: &PgPool, player_id: Uuid) -> AppResult<Vec<BattleReport>> {
         ArmyRepository::find_reports_by_player(pool, player_id).await
@@ -1218,7 +2731,7 @@ impl ArmyService {
     ) -> AppResult<()> {
         let report = ArmyRepository::find_report_by_id(pool, report_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Report not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Report", report_id))?;
 
         let is_attacker = report.attacker_player_id == player_id;
         let is_defender = report.defender_player_id == Some(player_id);
@@ -1253,7 +2766,7 @@ impl ArmyService {
     ) -> AppResult<()> {
         let report = ArmyRepository::find_scout_report_by_id(pool, report_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Scout report not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Scout report", report_id))?;
 
         let is_attacker = report.attacker_player_id == player_id;
         let is_defender = report.defender_player_id == Some(player_id);
@@ -1277,10 +2790,38 @@ impl ArmyService {
     /// Get troops stationed at a village (support from allies)
     pub async fn get_stationed_at_village(
         pool: &PgPool,
+        game: &GameConfig,
         village_id: Uuid,
-    ) -> AppResult<Vec<ArmyResponse>> {
+    ) -> AppResult<Vec<StationedSupportResponse>> {
         let armies = ArmyRepository::find_stationed_at_village(pool, village_id).await?;
-        Ok(armies.into_iter().map(|a| a.into()).collect())
+        let senders = ArmyRepository::find_stationed_senders(pool, village_id).await?;
+        Ok(Self::with_senders(game, armies, &senders))
+    }
+
+    /// Attach who sent each stationed army; home coordinates only if the server shows them
+    fn with_senders(
+        game: &GameConfig,
+        armies: Vec<Army>,
+        senders: &[StationedSender],
+    ) -> Vec<StationedSupportResponse> {
+        armies
+            .into_iter()
+            .map(|a| {
+                let sender = senders.iter().find(|s| s.army_id == a.id);
+                let origin = sender
+                    .filter(|_| game.show_support_origin)
+                    .and_then(|s| s.from_x.zip(s.from_y));
+
+                StationedSupportResponse {
+                    sender_name: sender.and_then(|s| s.sender_name.clone()),
+                    sender_alliance_tag: sender.and_then(|s| s.sender_alliance_tag.clone()),
+                    from_village_name: sender.and_then(|s| s.from_village_name.clone()),
+                    from_x: origin.map(|(x, _)| x),
+                    from_y: origin.map(|(_, y)| y),
+                    army: a.into(),
+                }
+            })
+            .collect()
     }
 
     /// Get support troops sent by player to other villages
@@ -1292,32 +2833,13 @@ impl ArmyService {
         Ok(armies.into_iter().map(|a| a.into()).collect())
     }
 
-    /// Recall stationed support troops back to home village
-    pub async fn recall_support(
-        pool: &PgPool,
-        army_id: Uuid,
-        player_id: Uuid,
-    ) -> AppResult<ArmyResponse> {
-        // Get the army
-        let army = ArmyRepository::find_by_id(pool, army_id)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Army not found".into()))?;
-
-        // Verify ownership
-        if army.player_id != player_id {
-            return Err(AppError::Forbidden("Access denied".into()));
-        }
-
-        // Must be stationed
-        if !army.is_stationed {
-            return Err(AppError::BadRequest("Army is not stationed".into()));
-        }
-
+    /// Send stationed support back to its home village
+    async fn start_return_home(pool: &PgPool, army: &Army) -> AppResult<Army> {
         // Calculate return travel time
         let definitions = TroopRepository::get_all_definitions(pool).await?;
         let from_village = VillageRepository::find_by_id(pool, army.from_village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Home village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Home village", army.from_village_id))?;
 
         let distance =
             Self::calculate_distance(army.to_x, army.to_y, from_village.x, from_village.y);
@@ -1325,13 +2847,1806 @@ impl ArmyService {
         let returns_at = Utc::now() + travel_duration;
 
         // Start recall
-        let updated = ArmyRepository::start_recall(pool, army_id, returns_at).await?;
+        ArmyRepository::start_recall(pool, army.id, returns_at).await
+    }
 
-        info!(
-            "Support army {} recalled, returning to village {} at {}",
-            army_id, army.from_village_id, returns_at
-        );
+    /// Send home support armies that reached their max station duration (called by background job)
+    pub async fn process_expired_support(pool: &PgPool, ws_manager: &WsManager) -> AppResult<i32> {
+        let expired = ArmyRepository::find_expired_stationed(pool).await?;
+        let mut count = 0;
 
-        Ok(updated.into())
+        // As with arrivals, go by our clock rather than the database's
+        let now = Utc::now();
+        for army in expired.into_iter().filter(|a| a.is_station_expired(now)) {
+            match Self::start_return_home(pool, &army).await {
+                Ok(updated) => {
+                    info!(
+                        "Support army {} reached max station time, returning to village {} at {}",
+                        army.id, army.from_village_id, updated.current_leg_ends_at()
+                    );
+
+                    Self::notify_support_expired(ws_manager, &army, updated.current_leg_ends_at())
+                        .await;
+
+                    count += 1;
+                }
+                Err(e) => {
+                    error!("Failed to auto-return support army {}: {:?}", army.id, e);
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Tell the owner their support outlived its max station duration and is heading home
+    async fn notify_support_expired(
+        ws_manager: &WsManager,
+        army: &Army,
+        returns_at: DateTime<Utc>,
+    ) {
+        let event = WsEvent::SupportAutoReturned(SupportAutoReturnedData {
+            army_id: army.id,
+            from_village_id: army.from_village_id,
+            to_village_id: army.to_village_id,
+            returns_at,
+            reason: "expired".into(),
+        });
+        ws_manager.send_to_user(army.player_id, &event).await;
+    }
+
+    /// Send home enough of the support stationed at a village to cover its crop
+    /// `deficit` (called by the starvation job); both the senders and the host
+    /// are notified. Returns how many armies were sent home.
+    async fn return_support_from_starving_village(
+        pool: &PgPool,
+        ws_manager: &WsManager,
+        definitions: &[TroopDefinition],
+        village_id: Uuid,
+        host_id: Uuid,
+        deficit: i64,
+    ) -> AppResult<i32> {
+        let stationed = ArmyRepository::find_stationed_at_village(pool, village_id).await?;
+        let mut count = 0;
+
+        for army in Self::support_to_send_home(stationed, definitions, deficit) {
+            match Self::start_return_home(pool, &army).await {
+                Ok(updated) => {
+                    info!(
+                        "Support army {} sent home from starving village {}, arriving at {}",
+                        army.id, village_id, updated.current_leg_ends_at()
+                    );
+
+                    let event = WsEvent::SupportAutoReturned(SupportAutoReturnedData {
+                        army_id: army.id,
+                        from_village_id: army.from_village_id,
+                        to_village_id: Some(village_id),
+                        returns_at: updated.current_leg_ends_at(),
+                        reason: "starvation".into(),
+                    });
+                    ws_manager.send_to_user(army.player_id, &event).await;
+                    if army.player_id != host_id {
+                        ws_manager.send_to_user(host_id, &event).await;
+                    }
+
+                    count += 1;
+                }
+                Err(e) => {
+                    error!("Failed to send support army {} home from starving village: {:?}", army.id, e);
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Starve troops in villages that ran out of crop while eating more than they grow.
+    /// Just enough troops die, spread across unit types by their share of the upkeep,
+    /// to bring net crop back to zero. The village's own troops go first; after that
+    /// just enough stationed support is sent home or, if the server keeps it, starves in turn.
+    pub async fn process_starvation(
+        pool: &PgPool,
+        redis: &ConnectionManager,
+        ws_manager: &WsManager,
+        game: &GameConfig,
+    ) -> AppResult<i32> {
+        let starving = VillageRepository::find_without_crop(pool).await?;
+        if starving.is_empty() {
+            return Ok(0);
+        }
+
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let mut total_killed = 0;
+
+        for village in starving {
+            match Self::starve_village(pool, ws_manager, game, &definitions, village.id).await {
+                Ok(killed) => {
+                    if killed > 0 {
+                        CacheService::invalidate_village(redis, village.id).await;
+                    }
+                    total_killed += killed;
+                }
+                Err(e) => error!("Failed to process starvation in village {}: {:?}", village.id, e),
+            }
+        }
+
+        Ok(total_killed)
+    }
+
+    /// Starve one village's troops down to what its crop production can feed
+    async fn starve_village(
+        pool: &PgPool,
+        ws_manager: &WsManager,
+        game: &GameConfig,
+        definitions: &[TroopDefinition],
+        village_id: Uuid,
+    ) -> AppResult<i32> {
+        let village = ResourceService::update_village_resources(pool, game, village_id).await?;
+        if village.crop > 0 {
+            return Ok(0);
+        }
+        let production = ResourceService::calculate_production(pool, game, village.id).await?;
+        if production.net_crop_per_hour >= 0 {
+            return Ok(0);
+        }
+        let mut deficit = -(production.net_crop_per_hour as i64);
+        let mut killed = 0;
+
+        // The village's own troops at home starve first
+        let home_troops: ArmyTroops = TroopRepository::find_by_village(pool, village.id)
+            .await?
+            .into_iter()
+            .filter(|t| t.in_village > 0)
+            .map(|t| (t.troop_type, t.in_village))
+            .collect();
+        let losses = Self::starvation_losses(&home_troops, definitions, deficit);
+        for (troop_type, count) in &losses {
+            TroopRepository::kill_troops(pool, village.id, *troop_type, *count).await?;
+            Self::record_starvation(pool, ws_manager, &village, None, *troop_type, *count, &[village.user_id])
+                .await;
+            killed += count;
+        }
+        if !losses.is_empty() {
+            VillageService::recompute_population(pool, village.id).await?;
+        }
+        deficit -= Self::troop_upkeep(&losses, definitions);
+
+        if deficit <= 0 {
+            return Ok(killed);
+        }
+
+        if game.starvation_returns_support {
+            Self::return_support_from_starving_village(
+                pool,
+                ws_manager,
+                definitions,
+                village.id,
+                village.user_id,
+                deficit,
+            )
+            .await?;
+            return Ok(killed);
+        }
+
+        for army in ArmyRepository::find_stationed_at_village(pool, village.id).await? {
+            if deficit <= 0 {
+                break;
+            }
+            let losses = Self::starvation_losses(&army.troops.0, definitions, deficit);
+            if losses.is_empty() {
+                continue;
+            }
+
+            let mut survivors = army.troops.0.clone();
+            for (troop_type, count) in &losses {
+                if let Some(remaining) = survivors.get_mut(troop_type) {
+                    *remaining -= count;
+                }
+            }
+            survivors.retain(|_, count| *count > 0);
+            ArmyRepository::update_stationed_troops(pool, army.id, &survivors).await?;
+
+            for (troop_type, count) in &losses {
+                Self::record_starvation(
+                    pool,
+                    ws_manager,
+                    &village,
+                    Some(&army),
+                    *troop_type,
+                    *count,
+                    &[army.player_id, village.user_id],
+                )
+                .await;
+                killed += count;
+            }
+            deficit -= Self::troop_upkeep(&losses, definitions);
+        }
+
+        Ok(killed)
+    }
+
+    /// Stationed support armies to send home so the host stops running `deficit`
+    /// crop per hour short. The most recent arrivals leave first, and only as many
+    /// as it takes to cover the deficit; armies that eat nothing stay.
+    fn support_to_send_home(
+        mut stationed: Vec<Army>,
+        definitions: &[TroopDefinition],
+        mut deficit: i64,
+    ) -> Vec<Army> {
+        stationed.sort_by_key(|army| std::cmp::Reverse(army.arrives_at));
+
+        let mut leaving = Vec::new();
+        for army in stationed {
+            if deficit <= 0 {
+                break;
+            }
+            let upkeep = Self::troop_upkeep(&army.troops.0, definitions);
+            if upkeep > 0 {
+                deficit -= upkeep;
+                leaving.push(army);
+            }
+        }
+        leaving
+    }
+
+    /// Troops that must starve to free `deficit` crop per hour. Each unit type loses
+    /// its share in proportion to its upkeep, rounded up so the deficit is covered;
+    /// if the troops cannot cover it, all of them starve.
+    fn starvation_losses(troops: &ArmyTroops, definitions: &[TroopDefinition], deficit: i64) -> ArmyTroops {
+        let upkeep = Self::troop_upkeep(troops, definitions);
+        if deficit <= 0 || upkeep <= 0 {
+            return ArmyTroops::new();
+        }
+
+        troops
+            .iter()
+            .filter(|(troop_type, count)| {
+                **count > 0
+                    && definitions
+                        .iter()
+                        .any(|d| d.troop_type == **troop_type && d.crop_consumption > 0)
+            })
+            .map(|(troop_type, count)| {
+                let lost = if deficit >= upkeep {
+                    *count as i64
+                } else {
+                    (*count as i64 * deficit + upkeep - 1) / upkeep
+                };
+                (*troop_type, lost.min(*count as i64) as i32)
+            })
+            .filter(|(_, lost)| *lost > 0)
+            .collect()
+    }
+
+    /// Hourly crop upkeep of a set of troops
+    fn troop_upkeep(troops: &ArmyTroops, definitions: &[TroopDefinition]) -> i64 {
+        troops
+            .iter()
+            .filter_map(|(troop_type, count)| {
+                definitions
+                    .iter()
+                    .find(|d| d.troop_type == *troop_type)
+                    .map(|d| d.crop_consumption as i64 * *count as i64)
+            })
+            .sum()
+    }
+
+    /// Log troops lost to hunger in the village's history and tell the players involved,
+    /// live and with a system message
+    async fn record_starvation(
+        pool: &PgPool,
+        ws_manager: &WsManager,
+        village: &Village,
+        support_army: Option<&Army>,
+        troop_type: TroopType,
+        count: i32,
+        notify: &[Uuid],
+    ) {
+        info!(
+            "Starvation: {} {:?}{} died in village {} due to lack of food",
+            count,
+            troop_type,
+            support_army.map(|a| format!(" of support army {}", a.id)).unwrap_or_default(),
+            village.id
+        );
+
+        ActivityService::record(
+            pool,
+            village.id,
+            VillageActivityType::TroopsStarved,
+            serde_json::json!({
+                "troop_type": troop_type,
+                "count": count,
+                "support_army_id": support_army.map(|a| a.id),
+            }),
+        );
+
+        let event = WsEvent::TroopsStarved(TroopsStarvedData {
+            village_id: village.id,
+            troop_type: format!("{:?}", troop_type),
+            quantity: count,
+        });
+        let subject = format!("Troops starved in {}", village.name);
+        let body = format!(
+            "{} {:?}{} died of hunger in {} ({}|{}) because the village ran out of crop.",
+            count,
+            troop_type,
+            if support_army.is_some() { " of support troops" } else { "" },
+            village.name,
+            village.x,
+            village.y
+        );
+        let mut notified = Vec::new();
+        for user_id in notify {
+            if !notified.contains(user_id) {
+                ws_manager.send_to_user(*user_id, &event).await;
+                if let Err(e) =
+                    MessageService::send_system_message(pool, *user_id, &subject, &body).await
+                {
+                    error!("Failed to send starvation message to user {}: {:?}", user_id, e);
+                }
+                notified.push(*user_id);
+            }
+        }
+    }
+
+    /// Recall stationed support troops back to home village
+    pub async fn recall_support(
+        pool: &PgPool,
+        army_id: Uuid,
+        player_id: Uuid,
+    ) -> AppResult<ArmyResponse> {
+        // Get the army
+        let army = ArmyRepository::find_by_id(pool, army_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Army", army_id))?;
+
+        // Verify ownership
+        if army.player_id != player_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        // Must be stationed
+        if !army.is_stationed {
+            return Err(AppError::BadRequest("Army is not stationed".into()));
+        }
+
+        let updated = Self::start_return_home(pool, &army).await?;
+
+        info!(
+            "Support army {} recalled, returning to village {} at {}",
+            army_id, army.from_village_id, updated.current_leg_ends_at()
+        );
+
+        Ok(updated.into())
+    }
+
+    /// Recall every support army the player has stationed at a village
+    pub async fn recall_all_support_at(
+        pool: &PgPool,
+        village_id: Uuid,
+        player_id: Uuid,
+    ) -> AppResult<Vec<ArmyResponse>> {
+        let stationed = ArmyRepository::find_stationed_at_village(pool, village_id).await?;
+
+        let mut recalled = Vec::new();
+        for army in Self::own_support(&stationed, player_id) {
+            let updated = Self::start_return_home(pool, army).await?;
+
+            info!(
+                "Support army {} recalled, returning to village {} at {}",
+                army.id, army.from_village_id, updated.current_leg_ends_at()
+            );
+
+            recalled.push(updated.into());
+        }
+
+        Ok(recalled)
+    }
+
+    /// The stationed armies at a village that `player_id` sent there
+    fn own_support(stationed: &[Army], player_id: Uuid) -> Vec<&Army> {
+        stationed.iter().filter(|a| a.player_id == player_id).collect()
+    }
+
+    /// Cancel a scheduled army before it departs (troops return immediately)
+    pub async fn cancel_scheduled(
+        pool: &PgPool,
+        army_id: Uuid,
+        player_id: Uuid,
+    ) -> AppResult<()> {
+        let army = ArmyRepository::find_by_id(pool, army_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Army", army_id))?;
+
+        if army.player_id != player_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        // Removing the army claims it: a second cancel or the departure job
+        // racing this one finds nothing left to refund
+        let mut tx = pool.begin().await?;
+        let army = ArmyRepository::delete_scheduled(&mut *tx, army_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Army has already departed".into()))?;
+
+        for (troop_type, count) in army.troops.0.iter() {
+            if *count > 0 {
+                TroopRepository::return_troops_to_village(&mut *tx, army.from_village_id, *troop_type, *count)
+                    .await?;
+            }
+        }
+
+        let resources = &army.resources.0;
+        if resources.total() > 0 {
+            VillageRepository::add_resources(
+                &mut *tx,
+                army.from_village_id,
+                resources.wood,
+                resources.clay,
+                resources.iron,
+                resources.crop,
+            )
+            .await?;
+        }
+
+        if let Some(hero_id) = army.hero_id {
+            HeroRepository::transition_status(&mut *tx, hero_id, HeroStatus::Moving, HeroStatus::Idle)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        info!("Scheduled army {} cancelled, troops returned to village {}", army_id, army.from_village_id);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::resource_service::ProductionRates;
+    use crate::test_support::{army, game_config, hero, troop_definition, village};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn support_delivery_credits_what_fits_and_keeps_the_rest() {
+        let carried = CarriedResources { wood: 300, clay: 100, iron: 0, crop: 500 };
+        let (delivered, leftover) = ArmyService::split_delivery(&carried, &village(800, 1000));
+
+        assert_eq!((delivered.wood, delivered.clay, delivered.iron, delivered.crop), (200, 100, 0, 200));
+        assert_eq!((leftover.wood, leftover.clay, leftover.iron, leftover.crop), (100, 0, 0, 300));
+    }
+
+    #[test]
+    fn support_delivery_to_full_storage_delivers_nothing() {
+        let carried = CarriedResources { wood: 50, clay: 50, iron: 50, crop: 50 };
+        let (delivered, leftover) = ArmyService::split_delivery(&carried, &village(1000, 1000));
+
+        assert_eq!(delivered.total(), 0);
+        assert_eq!(leftover.total(), 200);
+    }
+
+    #[test]
+    fn only_scouts_reveal_the_tiles_around_their_target() {
+        assert_eq!(ArmyService::discovery_radius(MissionType::Scout), 2);
+        assert_eq!(ArmyService::discovery_radius(MissionType::Raid), 0);
+    }
+
+    #[test]
+    fn scheduled_army_does_not_arrive_before_its_departure() {
+        let game = game_config();
+        let definitions = vec![troop_definition(TroopType::Infantry, 40, 35, 50, 6)];
+        let troops: ArmyTroops = [(TroopType::Infantry, 10)].into_iter().collect();
+        let depart_at = Utc::now() + Duration::hours(3);
+
+        let plan = ArmyService::plan_journey(
+            &game,
+            (0, 0),
+            (6, 8),
+            MissionType::Attack,
+            &troops,
+            Some(depart_at),
+            &definitions,
+        )
+        .unwrap();
+
+        assert_eq!(plan.departs_at, depart_at);
+        assert!(plan.travel_duration > Duration::zero());
+        assert_eq!(plan.arrives_at, depart_at + plan.travel_duration);
+        assert_eq!(plan.returns_at, Some(plan.arrives_at + plan.travel_duration));
+    }
+
+    #[test]
+    fn travel_preview_matches_the_send_for_identical_inputs() {
+        let game = game_config();
+        let definitions = vec![
+            troop_definition(TroopType::Infantry, 40, 35, 50, 6),
+            troop_definition(TroopType::HighlandPony, 120, 65, 50, 12),
+        ];
+        let troops: ArmyTroops =
+            [(TroopType::Infantry, 10), (TroopType::HighlandPony, 5)].into_iter().collect();
+        let depart_at = Utc::now() + Duration::hours(1);
+        let plan = || {
+            ArmyService::plan_journey(
+                &game,
+                (0, 0),
+                (6, 8),
+                MissionType::Raid,
+                &troops,
+                Some(depart_at),
+                &definitions,
+            )
+            .unwrap()
+        };
+
+        let preview = ArmyService::travel_response(&plan());
+        let request = SendArmyRequest {
+            to_x: 6,
+            to_y: 8,
+            mission: MissionType::Raid,
+            troops: troops.clone(),
+            resources: CarriedResources::default(),
+            depart_at: Some(depart_at),
+            max_station_hours: None,
+            hero_id: None,
+        };
+        let sent =
+            ArmyService::new_army(Uuid::new_v4(), Uuid::new_v4(), None, request, &plan(), None);
+        assert_eq!(preview.departs_at, sent.departed_at);
+        assert_eq!(preview.arrives_at, sent.arrives_at);
+        assert_eq!(preview.returns_at, sent.returns_at);
+
+        // 10 fields at the infantry's 6 fields per hour
+        assert_eq!(preview.distance, 10.0);
+        assert_eq!(preview.travel_seconds, 6000);
+        assert_eq!(preview.departs_at, depart_at);
+        assert_eq!(preview.arrives_at, depart_at + Duration::seconds(6000));
+        assert_eq!(preview.returns_at, Some(depart_at + Duration::seconds(12000)));
+    }
+
+    #[test]
+    fn recall_all_takes_only_the_callers_support() {
+        let player_id = Uuid::new_v4();
+        let support = |owner: Uuid| {
+            let mut a = army(MissionType::Support, ArmyTroops::from([(TroopType::Infantry, 10)]));
+            a.player_id = owner;
+            a.is_stationed = true;
+            a
+        };
+        let stationed = vec![support(player_id), support(Uuid::new_v4()), support(player_id)];
+
+        let recalled = ArmyService::own_support(&stationed, player_id);
+
+        assert_eq!(recalled.len(), 2);
+        assert_eq!(recalled[0].id, stationed[0].id);
+        assert_eq!(recalled[1].id, stationed[2].id);
+    }
+
+    #[test]
+    fn host_sees_who_sent_stationed_support() {
+        let mut support = army(MissionType::Support, ArmyTroops::from([(TroopType::Infantry, 10)]));
+        support.is_stationed = true;
+        let sender = StationedSender {
+            army_id: support.id,
+            sender_name: Some("Naresuan".to_string()),
+            sender_alliance_tag: Some("SIAM".to_string()),
+            from_village_name: Some("Ayutthaya".to_string()),
+            from_x: Some(12),
+            from_y: Some(-4),
+        };
+        let listing = |game: &GameConfig| {
+            ArmyService::with_senders(game, vec![support.clone()], std::slice::from_ref(&sender))
+        };
+
+        let hidden = listing(&game_config());
+        assert_eq!(hidden[0].sender_name.as_deref(), Some("Naresuan"));
+        assert_eq!(hidden[0].sender_alliance_tag.as_deref(), Some("SIAM"));
+        assert_eq!(hidden[0].from_village_name.as_deref(), Some("Ayutthaya"));
+        assert_eq!((hidden[0].from_x, hidden[0].from_y), (None, None));
+
+        let shown = listing(&GameConfig { show_support_origin: true, ..game_config() });
+        assert_eq!((shown[0].from_x, shown[0].from_y), (Some(12), Some(-4)));
+    }
+
+    #[test]
+    fn player_armies_from_every_village_are_grouped_by_state() {
+        let troops = ArmyTroops::from([(TroopType::Infantry, 10)]);
+        let outgoing = army(MissionType::Attack, troops.clone());
+        let mut returning = army(MissionType::Raid, troops.clone());
+        returning.is_returning = true;
+        let mut stationed = army(MissionType::Support, troops.clone());
+        stationed.is_stationed = true;
+        let mut scheduled = army(MissionType::Attack, troops);
+        scheduled.departed_at = Utc::now() + Duration::hours(1);
+        // Each fixture army leaves from its own village
+        let sources: std::collections::HashSet<Uuid> =
+            [&outgoing, &returning, &stationed, &scheduled]
+                .iter()
+                .map(|a| a.from_village_id)
+                .collect();
+        assert_eq!(sources.len(), 4);
+
+        let grouped = ArmyService::group_player_armies(vec![
+            outgoing.clone(),
+            returning.clone(),
+            stationed.clone(),
+            scheduled.clone(),
+        ]);
+
+        assert_eq!(grouped.outgoing.iter().map(|a| a.id).collect::<Vec<_>>(), vec![outgoing.id]);
+        assert_eq!(grouped.returning.iter().map(|a| a.id).collect::<Vec<_>>(), vec![returning.id]);
+        assert_eq!(grouped.stationed.iter().map(|a| a.id).collect::<Vec<_>>(), vec![stationed.id]);
+        assert_eq!(grouped.scheduled.iter().map(|a| a.id).collect::<Vec<_>>(), vec![scheduled.id]);
+    }
+
+    #[test]
+    fn departure_beyond_the_schedule_window_is_rejected() {
+        let mut game = game_config();
+        game.max_schedule_ahead_hours = 2;
+        let definitions = vec![troop_definition(TroopType::Infantry, 40, 35, 50, 6)];
+        let troops: ArmyTroops = [(TroopType::Infantry, 10)].into_iter().collect();
+
+        let plan = ArmyService::plan_journey(
+            &game,
+            (0, 0),
+            (6, 8),
+            MissionType::Raid,
+            &troops,
+            Some(Utc::now() + Duration::hours(3)),
+            &definitions,
+        );
+
+        assert!(matches!(plan, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn only_support_may_set_a_max_station_duration() {
+        let validate = ArmyService::validate_max_station_hours;
+        assert_eq!(validate(MissionType::Support, Some(24)).unwrap(), Some(24));
+        assert_eq!(validate(MissionType::Attack, None).unwrap(), None);
+        assert!(validate(MissionType::Attack, Some(24)).is_err());
+        assert!(validate(MissionType::Support, Some(0)).is_err());
+        assert!(validate(MissionType::Support, Some(MAX_STATION_HOURS + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_support_owner_is_told_it_is_heading_home() {
+        let ws_manager = WsManager::new();
+        let mut support = army(MissionType::Support, ArmyTroops::from([(TroopType::Infantry, 10)]));
+        support.is_stationed = true;
+        support.to_village_id = Some(Uuid::new_v4());
+        let mut owner_socket = ws_manager.register(support.player_id).await;
+        let returns_at = Utc::now() + Duration::hours(1);
+
+        ArmyService::notify_support_expired(&ws_manager, &support, returns_at).await;
+
+        let Ok(axum::extract::ws::Message::Text(json)) = owner_socket.try_recv() else {
+            panic!("owner should be told about the auto-return");
+        };
+        let event: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(event["type"], "support_auto_returned");
+        assert_eq!(event["data"]["army_id"], support.id.to_string());
+        assert_eq!(event["data"]["from_village_id"], support.from_village_id.to_string());
+        assert_eq!(event["data"]["reason"], "expired");
+    }
+
+    #[tokio::test]
+    async fn incoming_count_is_pushed_to_the_defenders_socket() {
+        let ws_manager = WsManager::new();
+        let defender_id = Uuid::new_v4();
+        let village_id = Uuid::new_v4();
+        let mut defender_socket = ws_manager.register(defender_id).await;
+        let mut bystander_socket = ws_manager.register(Uuid::new_v4()).await;
+
+        ArmyService::push_incoming_count(&ws_manager, defender_id, village_id, 3).await;
+
+        let Ok(axum::extract::ws::Message::Text(json)) = defender_socket.try_recv() else {
+            panic!("defender should receive the incoming count");
+        };
+        let event: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(event["type"], "incoming_count_changed");
+        assert_eq!(event["data"]["village_id"], village_id.to_string());
+        assert_eq!(event["data"]["incoming_count"], 3);
+        assert!(bystander_socket.try_recv().is_err());
+    }
+
+    #[test]
+    fn scheduled_army_is_cancellable_only_before_departure() {
+        let mut scheduled = army(MissionType::Attack, ArmyTroops::new());
+        scheduled.departed_at = Utc::now() + Duration::minutes(30);
+        assert!(scheduled.is_scheduled(Utc::now()));
+
+        // Once its departure time has passed, it is on the road
+        assert!(!scheduled.is_scheduled(scheduled.departed_at + Duration::seconds(1)));
+        assert!(!army(MissionType::Attack, ArmyTroops::new()).is_scheduled(Utc::now()));
+    }
+
+    #[test]
+    fn attacking_an_alliance_member_is_blocked() {
+        let game = game_config();
+        let alliance = Some(Uuid::new_v4());
+
+        assert!(ArmyService::is_blocked_by_alliance(&game, MissionType::Attack, alliance, alliance));
+        assert!(ArmyService::is_blocked_by_alliance(&game, MissionType::Raid, alliance, alliance));
+        assert!(!ArmyService::is_blocked_by_alliance(
+            &game,
+            MissionType::Attack,
+            alliance,
+            Some(Uuid::new_v4())
+        ));
+        assert!(!ArmyService::is_blocked_by_alliance(&game, MissionType::Attack, None, None));
+    }
+
+    #[test]
+    fn supporting_an_alliance_member_is_allowed() {
+        let game = game_config();
+        let alliance = Some(Uuid::new_v4());
+
+        assert!(!ArmyService::is_blocked_by_alliance(&game, MissionType::Support, alliance, alliance));
+    }
+
+    #[test]
+    fn internal_attacks_are_allowed_when_configured() {
+        let game = GameConfig {
+            allow_internal_alliance_attacks: true,
+            ..game_config()
+        };
+        let alliance = Some(Uuid::new_v4());
+
+        assert!(!ArmyService::is_blocked_by_alliance(&game, MissionType::Attack, alliance, alliance));
+    }
+
+    #[test]
+    fn armies_can_be_sent_up_to_the_limit() {
+        let limit = game_config().max_armies_per_village;
+
+        assert!(!ArmyService::army_limit_reached(limit, 0));
+        assert!(!ArmyService::army_limit_reached(limit, limit - 1));
+    }
+
+    #[test]
+    fn sending_beyond_the_limit_is_rejected() {
+        let limit = game_config().max_armies_per_village;
+
+        assert!(ArmyService::army_limit_reached(limit, limit));
+        assert!(ArmyService::army_limit_reached(limit, limit + 5));
+        // A limit of zero disables the cap
+        assert!(!ArmyService::army_limit_reached(0, 1_000));
+    }
+
+    #[test]
+    fn raid_report_uses_the_defenders_accrued_stock() {
+        // Last updated two hours ago at 100 of everything, producing 200/h (net)
+        let mut target = village(100, 10_000);
+        target.resources_updated_at = Utc::now() - Duration::hours(2);
+        let production = ProductionRates {
+            wood_per_hour: 200,
+            clay_per_hour: 200,
+            iron_per_hour: 200,
+            crop_per_hour: 250,
+            crop_consumption: 50,
+            net_crop_per_hour: 200,
+        };
+        let target = ResourceService::accrue(&target, &production, Utc::now());
+
+        let before = CarriedResources::held_by(&target);
+        assert_eq!((before.wood, before.clay, before.iron, before.crop), (500, 500, 500, 500));
+
+        // 100 of each hidden by Crannies, a raid takes half of the rest
+        let definitions = vec![troop_definition(TroopType::Infantry, 40, 35, 50, 6)];
+        let survivors = ArmyTroops::from([(TroopType::Infantry, 100)]);
+        let stolen = ArmyService::calculate_stolen_resources(
+            &target,
+            &survivors,
+            &definitions,
+            MissionType::Raid,
+            0,
+            100,
+        );
+        assert_eq!((stolen.wood, stolen.clay, stolen.iron, stolen.crop), (200, 200, 200, 200));
+    }
+
+    fn scout_definitions() -> Vec<TroopDefinition> {
+        let mut scout = troop_definition(TroopType::SeaDiver, 0, 10, 10, 16);
+        scout.is_scout = true;
+        vec![scout, troop_definition(TroopType::Infantry, 40, 35, 50, 6)]
+    }
+
+    #[test]
+    fn scout_only_army_may_scout() {
+        let troops = ArmyTroops::from([(TroopType::SeaDiver, 5), (TroopType::Infantry, 0)]);
+
+        assert!(ArmyService::units_not_matching(&troops, &scout_definitions(), |d| d.is_scout).is_empty());
+    }
+
+    #[test]
+    fn mixed_or_non_scout_army_cannot_scout() {
+        let mixed = ArmyTroops::from([(TroopType::SeaDiver, 5), (TroopType::Infantry, 10)]);
+        let regular = ArmyTroops::from([(TroopType::Infantry, 10)]);
+
+        assert_eq!(
+            ArmyService::units_not_matching(&mixed, &scout_definitions(), |d| d.is_scout),
+            vec![TroopType::Infantry]
+        );
+        assert_eq!(
+            ArmyService::units_not_matching(&regular, &scout_definitions(), |d| d.is_scout),
+            vec![TroopType::Infantry]
+        );
+    }
+
+    #[test]
+    fn conquered_village_loyalty_reset_is_configurable() {
+        assert_eq!(ArmyService::conquest_loyalty(&game_config()), 25);
+
+        let game = GameConfig { conquest_loyalty_reset: 60, ..game_config() };
+        assert_eq!(ArmyService::conquest_loyalty(&game), 60);
+
+        // Out-of-range settings still leave a defendable, valid loyalty
+        let game = GameConfig { conquest_loyalty_reset: 0, ..game_config() };
+        assert_eq!(ArmyService::conquest_loyalty(&game), 1);
+        let game = GameConfig { conquest_loyalty_reset: 500, ..game_config() };
+        assert_eq!(ArmyService::conquest_loyalty(&game), MAX_LOYALTY);
+    }
+
+    #[test]
+    fn capital_conquest_is_blocked_unless_allowed() {
+        let game = GameConfig { allow_capital_conquest: false, ..game_config() };
+
+        assert!(ArmyService::is_capital_protected(&game, 1));
+        assert!(ArmyService::is_capital_protected(&game, 5));
+    }
+
+    #[test]
+    fn allowed_capital_conquest_needs_another_owner_village() {
+        let game = GameConfig { allow_capital_conquest: true, ..game_config() };
+
+        assert!(ArmyService::is_capital_protected(&game, 1));
+        assert!(!ArmyService::is_capital_protected(&game, 2));
+    }
+
+    #[test]
+    fn defensive_support_is_allowed() {
+        // Phalanx-style unit: defends better than it attacks
+        let definitions = vec![troop_definition(TroopType::Spearman, 20, 45, 40, 5)];
+        let troops = ArmyTroops::from([(TroopType::Spearman, 50)]);
+
+        assert!(ArmyService::units_not_matching(&troops, &definitions, TroopDefinition::is_defensive)
+            .is_empty());
+    }
+
+    #[test]
+    fn offensive_support_is_rejected() {
+        let definitions = vec![
+            troop_definition(TroopType::Spearman, 20, 45, 40, 5),
+            troop_definition(TroopType::Infantry, 60, 20, 10, 6),
+        ];
+        let troops = ArmyTroops::from([(TroopType::Spearman, 50), (TroopType::Infantry, 30)]);
+
+        assert_eq!(
+            ArmyService::units_not_matching(&troops, &definitions, TroopDefinition::is_defensive),
+            vec![TroopType::Infantry]
+        );
+    }
+
+    #[test]
+    fn troop_counts_sum_for_a_normal_send() {
+        let troops = ArmyTroops::from([(TroopType::Infantry, 120), (TroopType::Spearman, 80)]);
+
+        assert_eq!(ArmyService::validate_troop_counts(&troops, i32::MAX).unwrap(), 200);
+    }
+
+    #[test]
+    fn zero_count_entries_are_skipped() {
+        let troops = ArmyTroops::from([(TroopType::Infantry, 10), (TroopType::Spearman, 0)]);
+
+        assert_eq!(ArmyService::validate_troop_counts(&troops, i32::MAX).unwrap(), 10);
+    }
+
+    #[test]
+    fn overflowing_or_negative_counts_are_rejected() {
+        let overflowing = ArmyTroops::from([(TroopType::Infantry, i32::MAX), (TroopType::Spearman, 1)]);
+        let negative = ArmyTroops::from([(TroopType::Infantry, 10), (TroopType::Spearman, -5)]);
+
+        assert!(matches!(
+            ArmyService::validate_troop_counts(&overflowing, i32::MAX),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            ArmyService::validate_troop_counts(&negative, i32::MAX),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    fn battle_definitions() -> Vec<TroopDefinition> {
+        vec![
+            troop_definition(TroopType::Infantry, 10, 5, 5, 6),
+            troop_definition(TroopType::Spearman, 5, 10, 10, 6),
+        ]
+    }
+
+    /// No tribe, wall or hero effects
+    fn plain_modifiers() -> BattleModifiers {
+        BattleModifiers {
+            attacker_tribe: None,
+            defender_tribe: None,
+            wall_bonus_percent: 0,
+            attacker_hero: None,
+        }
+    }
+
+    #[test]
+    fn decisive_win_costs_the_winner_at_least_the_loss_floor() {
+        let attackers = ArmyTroops::from([(TroopType::Infantry, 1000)]);
+        let defenders = ArmyTroops::from([(TroopType::Spearman, 10)]);
+        let mut game = game_config();
+        let battle = |game: &GameConfig| {
+            ArmyService::calculate_battle(
+                game,
+                &attackers,
+                &defenders,
+                &battle_definitions(),
+                MissionType::Attack,
+                &plain_modifiers(),
+            )
+        };
+
+        game.battle_min_winner_loss_percent = 0;
+        let without_floor = battle(&game);
+        game.battle_min_winner_loss_percent = 10;
+        let with_floor = battle(&game);
+
+        assert!(without_floor.attacker_wins && with_floor.attacker_wins);
+        assert_eq!(without_floor.attacker_losses.get(&TroopType::Infantry), Some(&1));
+        assert_eq!(with_floor.attacker_losses.get(&TroopType::Infantry), Some(&100));
+        assert!(with_floor.defender_survivors.is_empty());
+    }
+
+    #[test]
+    fn loss_floor_does_not_apply_against_an_undefended_village() {
+        let attackers = ArmyTroops::from([(TroopType::Infantry, 1000)]);
+        let mut game = game_config();
+        game.battle_min_winner_loss_percent = 10;
+
+        let battle = ArmyService::calculate_battle(
+            &game,
+            &attackers,
+            &ArmyTroops::new(),
+            &battle_definitions(),
+            MissionType::Attack,
+            &plain_modifiers(),
+        );
+
+        assert!(battle.attacker_wins);
+        assert!(battle.attacker_losses.is_empty());
+        assert_eq!(battle.attacker_survivors, attackers);
+    }
+
+    #[test]
+    fn hostile_missions_without_attack_power_are_blocked_at_send() {
+        let mut definitions = battle_definitions();
+        definitions.push(troop_definition(TroopType::BuffaloWagon, 0, 10, 10, 4));
+        let wagons = ArmyTroops::from([(TroopType::BuffaloWagon, 5)]);
+
+        for mission in [MissionType::Attack, MissionType::Raid, MissionType::Conquer] {
+            assert!(matches!(
+                ArmyService::check_can_fight(mission, &wagons, &definitions),
+                Err(AppError::BadRequest(_))
+            ));
+        }
+        assert!(ArmyService::check_can_fight(MissionType::Support, &wagons, &definitions).is_ok());
+
+        // One combat unit is enough
+        let escorted = ArmyTroops::from([(TroopType::BuffaloWagon, 5), (TroopType::Infantry, 1)]);
+        assert!(ArmyService::check_can_fight(MissionType::Attack, &escorted, &definitions).is_ok());
+    }
+
+    #[test]
+    fn zero_attack_army_is_cleanly_defeated() {
+        let mut definitions = battle_definitions();
+        definitions.push(troop_definition(TroopType::BuffaloWagon, 0, 10, 10, 4));
+        let wagons = ArmyTroops::from([(TroopType::BuffaloWagon, 5)]);
+        let defenders = ArmyTroops::from([(TroopType::Spearman, 10)]);
+
+        let battle = ArmyService::calculate_battle(
+            &game_config(),
+            &wagons,
+            &defenders,
+            &definitions,
+            MissionType::Raid,
+            &plain_modifiers(),
+        );
+
+        assert!(!battle.attacker_wins);
+        assert_eq!(battle.attacker_loss_ratio, 1.0);
+        assert_eq!(battle.attacker_losses, wagons);
+        assert!(battle.attacker_survivors.is_empty());
+        assert!(battle.defender_losses.is_empty());
+        assert_eq!(battle.defender_survivors, defenders);
+    }
+
+    #[test]
+    fn battle_led_by_a_hero_reports_its_result() {
+        let mut leader = hero(HeroStatus::Moving);
+        leader.fighting_strength = 5;
+        let modifiers = BattleModifiers {
+            attacker_hero: Some(leader.clone()),
+            ..plain_modifiers()
+        };
+        let battle = ArmyService::calculate_battle(
+            &game_config(),
+            &ArmyTroops::from([(TroopType::Infantry, 100)]),
+            &ArmyTroops::from([(TroopType::Spearman, 100)]),
+            &battle_definitions(),
+            MissionType::Attack,
+            &modifiers,
+        );
+        assert!(battle.attacker_wins);
+
+        // Losses of (1000 / 1400)^1.5 cost 60 health; 100 spearmen eat 100 crop
+        let (damage, experience) =
+            ArmyService::attacker_hero_toll(&leader, &battle, &battle_definitions());
+        assert_eq!((damage, experience), (60, 100));
+
+        let result = ArmyService::attacker_hero_result(&leader, &battle, 40, true, experience);
+        let attacker = result.attacker.unwrap();
+        assert_eq!(attacker.hero_id, leader.id);
+        assert_eq!(attacker.damage_dealt, 400);
+        assert_eq!(attacker.damage_taken, 60);
+        assert!(!attacker.died && attacker.leveled_up);
+        assert_eq!(attacker.experience_gained, 100);
+        assert!(result.defender.is_none());
+    }
+
+    #[test]
+    fn hero_falls_with_a_wiped_out_army() {
+        let leader = hero(HeroStatus::Moving);
+        let modifiers = BattleModifiers {
+            attacker_hero: Some(leader.clone()),
+            ..plain_modifiers()
+        };
+        let battle = ArmyService::calculate_battle(
+            &game_config(),
+            &ArmyTroops::from([(TroopType::Infantry, 10)]),
+            &ArmyTroops::from([(TroopType::Spearman, 100)]),
+            &battle_definitions(),
+            MissionType::Attack,
+            &modifiers,
+        );
+
+        let (damage, _) = ArmyService::attacker_hero_toll(&leader, &battle, &battle_definitions());
+        assert_eq!(damage, leader.health);
+        let result = ArmyService::attacker_hero_result(&leader, &battle, 0, false, 0);
+        assert!(result.attacker.unwrap().died);
+    }
+
+    fn chief_definitions() -> Vec<TroopDefinition> {
+        let mut chief = troop_definition(TroopType::RoyalAdvisor, 20, 10, 10, 4);
+        chief.loyalty_reduction = 25;
+        vec![chief]
+    }
+
+    #[test]
+    fn winning_conquer_with_surviving_chiefs_lowers_loyalty() {
+        let mut definitions = battle_definitions();
+        definitions.extend(chief_definitions());
+        let mut elder = troop_definition(TroopType::ElderChief, 15, 10, 10, 4);
+        elder.loyalty_reduction = 30;
+        definitions.push(elder);
+        let attackers = ArmyTroops::from([
+            (TroopType::Infantry, 1000),
+            (TroopType::RoyalAdvisor, 2),
+            (TroopType::ElderChief, 1),
+        ]);
+
+        let battle = ArmyService::calculate_battle(
+            &game_config(),
+            &attackers,
+            &ArmyTroops::from([(TroopType::Spearman, 10)]),
+            &definitions,
+            MissionType::Conquer,
+            &plain_modifiers(),
+        );
+
+        assert!(battle.attacker_wins);
+        // Each chief type counts its own loyalty_reduction: 2 * 25 + 1 * 30
+        assert_eq!(
+            ArmyService::chief_loyalty_reduction(&battle.attacker_survivors, &definitions),
+            80
+        );
+    }
+
+    #[test]
+    fn winning_conquer_whose_chiefs_died_leaves_loyalty_unchanged() {
+        let survivors =
+            ArmyTroops::from([(TroopType::Infantry, 900), (TroopType::RoyalAdvisor, 0)]);
+        assert_eq!(ArmyService::chief_loyalty_reduction(&survivors, &chief_definitions()), 0);
+
+        // Chiefs without a definition reduce nothing rather than guessing
+        let undefined = ArmyTroops::from([(TroopType::HarborMaster, 3)]);
+        assert_eq!(ArmyService::chief_loyalty_reduction(&undefined, &chief_definitions()), 0);
+    }
+
+    /// Send identical conquer waves until the village falls, returning how many it took
+    fn conquer_in_waves(game: &GameConfig, chiefs: i32) -> i32 {
+        let wave = ArmyTroops::from([(TroopType::RoyalAdvisor, chiefs)]);
+        let per_wave = ArmyService::capped_loyalty_reduction(
+            game,
+            ArmyService::chief_loyalty_reduction(&wave, &chief_definitions()),
+        );
+        let mut loyalty = MAX_LOYALTY;
+        let mut waves = 0;
+        while loyalty > 0 {
+            loyalty = (loyalty - per_wave).max(0);
+            waves += 1;
+        }
+        assert_eq!(waves, ArmyService::waves_to_conquer(MAX_LOYALTY, per_wave));
+        waves
+    }
+
+    #[test]
+    fn one_stacked_wave_conquers_without_a_cap() {
+        let mut game = game_config();
+        game.max_loyalty_reduction_per_attack = 0;
+
+        assert_eq!(conquer_in_waves(&game, 4), 1);
+    }
+
+    #[test]
+    fn capped_loyalty_reduction_needs_several_waves() {
+        let mut game = game_config();
+        game.max_loyalty_reduction_per_attack = 30;
+
+        assert_eq!(ArmyService::capped_loyalty_reduction(&game, 100), 30);
+        assert_eq!(ArmyService::capped_loyalty_reduction(&game, 25), 25);
+        assert_eq!(conquer_in_waves(&game, 4), 4);
+    }
+
+    #[test]
+    fn marginal_scouts_fail_under_a_higher_threshold() {
+        // 45 against 55 power: just above the default 0.4 success ratio
+        let attacker_ratio = ArmyService::scout_power_share(45.0, 55.0);
+        let mut game = game_config();
+        game.scout_success_ratio = 0.4;
+        game.scout_exact_ratio = 0.6;
+
+        assert_eq!(ArmyService::scout_outcome(&game, attacker_ratio), (true, false));
+
+        game.scout_success_ratio = 0.5;
+        assert_eq!(ArmyService::scout_outcome(&game, attacker_ratio), (false, false));
+    }
+
+    #[test]
+    fn unopposed_scouts_see_exact_counts() {
+        let game = game_config();
+
+        assert_eq!(ArmyService::scout_power_share(30.0, 0.0), 1.0);
+        assert_eq!(ArmyService::scout_outcome(&game, 1.0), (true, true));
+    }
+
+    fn scouted_garrison() -> ArmyTroops {
+        ArmyTroops::from([
+            (TroopType::Infantry, 7),
+            (TroopType::Spearman, 120),
+            (TroopType::WarElephant, 850),
+            (TroopType::KrisWarrior, 4000),
+        ])
+    }
+
+    #[test]
+    fn successful_scouts_report_exact_counts() {
+        let garrison = scouted_garrison();
+
+        for seed in 0..20 {
+            let seen = ArmyService::scouted_troops(&mut StdRng::seed_from_u64(seed), &garrison, true);
+            assert_eq!(seen, garrison);
+        }
+    }
+
+    #[test]
+    fn partial_scouts_report_counts_within_the_fuzz_band() {
+        let garrison = scouted_garrison();
+
+        for seed in 0..200 {
+            let seen = ArmyService::scouted_troops(&mut StdRng::seed_from_u64(seed), &garrison, false);
+            assert_eq!(seen.len(), garrison.len());
+
+            for (troop_type, actual) in &garrison {
+                let bucket = match *actual {
+                    0..=19 => 5,
+                    20..=199 => 10,
+                    200..=1999 => 50,
+                    _ => 100,
+                };
+                let reported = seen[troop_type];
+                let low = (*actual as f64 * 0.8 - bucket as f64 / 2.0).floor().max(1.0) as i32;
+                let high = (*actual as f64 * 1.2 + bucket as f64 / 2.0).ceil() as i32;
+                assert!(
+                    (low..=high).contains(&reported),
+                    "{:?}: {} reported for {}",
+                    troop_type,
+                    reported,
+                    actual
+                );
+                assert!(reported == 1 || reported % bucket == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn army_at_the_size_cap_can_be_sent() {
+        let troops = ArmyTroops::from([(TroopType::Infantry, 600), (TroopType::Spearman, 400)]);
+
+        assert_eq!(ArmyService::validate_troop_counts(&troops, 1000).unwrap(), 1000);
+    }
+
+    #[test]
+    fn army_over_the_size_cap_is_rejected() {
+        let troops = ArmyTroops::from([(TroopType::Infantry, 600), (TroopType::Spearman, 401)]);
+
+        assert!(matches!(
+            ArmyService::validate_troop_counts(&troops, 1000),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn battle_between_capped_armies_does_not_overflow() {
+        let game = game_config();
+        let half = game.max_army_size / 2;
+        let definitions = vec![
+            troop_definition(TroopType::Infantry, i32::MAX, 1, 1, 6),
+            troop_definition(TroopType::WarElephant, i32::MAX, 1, 1, 12),
+            troop_definition(TroopType::Spearman, 1, i32::MAX, i32::MAX, 6),
+        ];
+        let attackers =
+            ArmyTroops::from([(TroopType::Infantry, half), (TroopType::WarElephant, half)]);
+        let defenders = ArmyTroops::from([(TroopType::Spearman, game.max_army_size / 4)]);
+
+        // Each side's power is far beyond i32::MAX, so it must be summed as f64
+        let attack_power = ArmyService::calculate_attack_power(&attackers, &definitions);
+        assert!(attack_power > i32::MAX as f64 * 1000.0);
+
+        let battle = ArmyService::calculate_battle(
+            &game,
+            &attackers,
+            &defenders,
+            &definitions,
+            MissionType::Attack,
+            &plain_modifiers(),
+        );
+
+        assert!(battle.attacker_wins);
+        assert!(battle.attacker_loss_ratio.is_finite());
+        assert!(battle.attacker_loss_ratio > 0.0 && battle.attacker_loss_ratio < 1.0);
+        for (troop_type, count) in &attackers {
+            let lost = battle.attacker_losses.get(troop_type).copied().unwrap_or(0);
+            let survived = battle.attacker_survivors.get(troop_type).copied().unwrap_or(0);
+            assert_eq!(lost + survived, *count);
+        }
+        assert!(battle.defender_survivors.is_empty());
+    }
+
+    /// 100 infantry attacking 100 spearmen, evenly matched before tribe bonuses
+    fn even_battle(game: &GameConfig, attacker: TribeType, defender: TribeType) -> BattleResult {
+        let modifiers = BattleModifiers {
+            attacker_tribe: Some(attacker),
+            defender_tribe: Some(defender),
+            ..plain_modifiers()
+        };
+        ArmyService::calculate_battle(
+            game,
+            &ArmyTroops::from([(TroopType::Infantry, 100)]),
+            &ArmyTroops::from([(TroopType::Spearman, 100)]),
+            &battle_definitions(),
+            MissionType::Attack,
+            &modifiers,
+        )
+    }
+
+    #[test]
+    fn tribe_attack_bonus_turns_an_even_battle() {
+        // Tribes are neutral until a bonus is configured
+        let mut game = game_config();
+        assert!(!even_battle(&game, TribeType::Kiri, TribeType::Phasuttha).attacker_wins);
+
+        game.tribe_kiri_attack_bonus_percent = 10;
+        assert!(even_battle(&game, TribeType::Kiri, TribeType::Phasuttha).attacker_wins);
+    }
+
+    #[test]
+    fn tribe_defense_bonus_holds_against_a_stronger_attack() {
+        let mut game = game_config();
+        game.tribe_kiri_attack_bonus_percent = 10;
+        game.tribe_nava_defense_bonus_percent = 0;
+        assert!(even_battle(&game, TribeType::Kiri, TribeType::Nava).attacker_wins);
+
+        game.tribe_nava_defense_bonus_percent = 20;
+        let battle = even_battle(&game, TribeType::Kiri, TribeType::Nava);
+        assert!(!battle.attacker_wins);
+        assert!(battle.attacker_survivors.is_empty());
+    }
+
+    fn raid_loot(target: &Village, hidden_per_resource: i32) -> CarriedResources {
+        ArmyService::calculate_stolen_resources(
+            target,
+            &ArmyTroops::from([(TroopType::Infantry, 1000)]),
+            &battle_definitions(),
+            MissionType::Raid,
+            0,
+            hidden_per_resource,
+        )
+    }
+
+    #[test]
+    fn higher_trade_office_level_carries_more_raid_loot() {
+        let game = game_config();
+        let target = village(2000, 5000);
+        let loot = |trade_office_level: i32| {
+            ArmyService::calculate_stolen_resources(
+                &target,
+                &ArmyTroops::from([(TroopType::Infantry, 8)]),
+                &battle_definitions(),
+                MissionType::Raid,
+                BuildingService::trade_office_bonus(&game, trade_office_level),
+                0,
+            )
+            .total()
+        };
+
+        // Eight infantry carry 400; each Trade Office level adds 10%
+        assert_eq!(loot(0), 400);
+        assert_eq!(loot(5), 600);
+        assert_eq!(loot(10), 800);
+    }
+
+    #[test]
+    fn level_ten_cranny_reduces_raid_loot() {
+        let target = village(2000, 5000);
+        let hidden = BuildingType::Cranny.hidden_capacity(10);
+
+        let unprotected = raid_loot(&target, 0);
+        let protected = raid_loot(&target, hidden);
+
+        assert_eq!(unprotected.wood, 1000);
+        assert_eq!(protected.wood, (2000 - hidden) / 2);
+        assert_eq!(
+            (protected.clay, protected.iron, protected.crop),
+            (protected.wood, protected.wood, protected.wood)
+        );
+    }
+
+    #[test]
+    fn cranny_capacity_is_capped_and_never_goes_negative() {
+        let max_level = BuildingType::Cranny.max_level();
+
+        assert_eq!(
+            BuildingType::Cranny.hidden_capacity(max_level + 5),
+            BuildingType::Cranny.hidden_capacity(max_level)
+        );
+        assert_eq!(BuildingType::Cranny.hidden_capacity(-1), 0);
+        assert_eq!(BuildingType::Warehouse.hidden_capacity(10), 0);
+
+        // Hiding more than the village holds leaves nothing to take, not a debt
+        let loot = raid_loot(&village(500, 5000), BuildingType::Cranny.hidden_capacity(max_level));
+        assert_eq!(loot.total(), 0);
+    }
+
+    #[test]
+    fn wall_bonus_grows_per_level_up_to_the_cap() {
+        let mut game = game_config();
+        game.wall_defense_bonus_percent_per_level = 3;
+        game.wall_defense_bonus_max_percent = 50;
+
+        assert_eq!(ArmyService::wall_bonus_percent(&game, 0), 0);
+        assert_eq!(ArmyService::wall_bonus_percent(&game, 5), 15);
+        assert_eq!(ArmyService::wall_bonus_percent(&game, 20), 50);
+        assert_eq!(ArmyService::wall_bonus_percent(&game, -3), 0);
+    }
+
+    fn walled_battle(game: &GameConfig, attackers: i32, wall_level: i32) -> BattleResult {
+        let modifiers = BattleModifiers {
+            wall_bonus_percent: ArmyService::wall_bonus_percent(game, wall_level),
+            ..plain_modifiers()
+        };
+        ArmyService::calculate_battle(
+            game,
+            &ArmyTroops::from([(TroopType::Infantry, attackers)]),
+            &ArmyTroops::from([(TroopType::Spearman, 90)]),
+            &battle_definitions(),
+            MissionType::Attack,
+            &modifiers,
+        )
+    }
+
+    #[test]
+    fn wall_strengthens_only_the_defenders() {
+        let game = game_config();
+
+        // 1000 attack beats 900 defense, but not 900 behind a 30% wall
+        let open = walled_battle(&game, 100, 0);
+        let walled = walled_battle(&game, 100, 10);
+        assert!(open.attacker_wins);
+        assert!(!walled.attacker_wins);
+        assert!(walled.attacker_survivors.is_empty());
+        assert!(walled.defender_losses.values().sum::<i32>() > 0);
+    }
+
+    #[test]
+    fn capped_wall_still_falls_to_overwhelming_force() {
+        let game = game_config();
+        let battle = walled_battle(&game, 1000, 20);
+
+        assert_eq!(ArmyService::wall_bonus_percent(&game, 20), game.wall_defense_bonus_max_percent);
+        assert!(battle.attacker_wins);
+        assert!(battle.defender_survivors.is_empty());
+    }
+
+    #[test]
+    fn wall_mid_upgrade_defends_at_its_completed_level() {
+        let wall = Building {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            building_type: BuildingType::Wall,
+            slot: 19,
+            level: 4,
+            is_upgrading: true,
+            upgrade_ends_at: Some(Utc::now() + Duration::hours(1)),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        assert_eq!(ArmyService::completed_level(&[wall]), 4);
+        assert_eq!(ArmyService::completed_level(&[]), 0);
+    }
+
+    /// Infantry eat 1 crop an hour, war elephants 3 and settlers nothing
+    fn upkeep_definitions() -> Vec<TroopDefinition> {
+        let mut elephant = troop_definition(TroopType::WarElephant, 50, 40, 30, 10);
+        elephant.crop_consumption = 3;
+        let mut settler = troop_definition(TroopType::Settler, 0, 20, 20, 5);
+        settler.crop_consumption = 0;
+        vec![troop_definition(TroopType::Infantry, 10, 5, 5, 6), elephant, settler]
+    }
+
+    fn hungry_village_troops() -> ArmyTroops {
+        ArmyTroops::from([
+            (TroopType::Infantry, 100),
+            (TroopType::WarElephant, 10),
+            (TroopType::Settler, 5),
+        ])
+    }
+
+    #[test]
+    fn troop_upkeep_sums_crop_per_unit() {
+        let definitions = upkeep_definitions();
+
+        assert_eq!(ArmyService::troop_upkeep(&hungry_village_troops(), &definitions), 130);
+        assert_eq!(ArmyService::troop_upkeep(&ArmyTroops::new(), &definitions), 0);
+    }
+
+    #[test]
+    fn starvation_spreads_losses_by_upkeep_share() {
+        let definitions = upkeep_definitions();
+
+        let losses = ArmyService::starvation_losses(&hungry_village_troops(), &definitions, 13);
+
+        assert_eq!(
+            losses,
+            ArmyTroops::from([(TroopType::Infantry, 10), (TroopType::WarElephant, 1)])
+        );
+        assert!(ArmyService::troop_upkeep(&losses, &definitions) >= 13);
+    }
+
+    #[test]
+    fn deficit_beyond_total_upkeep_starves_every_fed_troop() {
+        let definitions = upkeep_definitions();
+
+        let losses = ArmyService::starvation_losses(&hungry_village_troops(), &definitions, 500);
+
+        assert_eq!(
+            losses,
+            ArmyTroops::from([(TroopType::Infantry, 100), (TroopType::WarElephant, 10)])
+        );
+    }
+
+    #[test]
+    fn zero_upkeep_troops_never_starve() {
+        let definitions = upkeep_definitions();
+        let settlers = ArmyTroops::from([(TroopType::Settler, 5)]);
+
+        assert_eq!(ArmyService::troop_upkeep(&settlers, &definitions), 0);
+        assert!(ArmyService::starvation_losses(&settlers, &definitions, 50).is_empty());
+        assert!(ArmyService::starvation_losses(&hungry_village_troops(), &definitions, 0).is_empty());
+    }
+
+    fn stationed_support(troops: ArmyTroops, arrived_minutes_ago: i64) -> Army {
+        let mut support = army(MissionType::Support, troops);
+        support.is_stationed = true;
+        support.arrives_at = Utc::now() - Duration::minutes(arrived_minutes_ago);
+        support
+    }
+
+    #[test]
+    fn starving_host_sends_home_the_latest_support_until_upkeep_is_covered() {
+        let definitions = upkeep_definitions();
+        let oldest = stationed_support(ArmyTroops::from([(TroopType::Infantry, 40)]), 90);
+        let middle = stationed_support(ArmyTroops::from([(TroopType::WarElephant, 5)]), 60);
+        let newest = stationed_support(ArmyTroops::from([(TroopType::Infantry, 10)]), 30);
+        let stationed = vec![oldest.clone(), middle.clone(), newest.clone()];
+
+        // 10 infantry free 10 crop, not enough; the elephants (15 crop) cover the rest
+        let leaving = ArmyService::support_to_send_home(stationed.clone(), &definitions, 20);
+        assert_eq!(
+            leaving.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![newest.id, middle.id]
+        );
+
+        // A deficit the newest army alone covers leaves the others stationed
+        let leaving = ArmyService::support_to_send_home(stationed.clone(), &definitions, 5);
+        assert_eq!(leaving.iter().map(|a| a.id).collect::<Vec<_>>(), vec![newest.id]);
+
+        // A deficit beyond all of their upkeep sends every one of them home
+        assert_eq!(ArmyService::support_to_send_home(stationed, &definitions, 500).len(), 3);
+    }
+
+    #[test]
+    fn support_that_eats_nothing_is_not_sent_home_for_starvation() {
+        let definitions = upkeep_definitions();
+        let settlers = stationed_support(ArmyTroops::from([(TroopType::Settler, 5)]), 10);
+
+        assert!(ArmyService::support_to_send_home(vec![settlers], &definitions, 10).is_empty());
+    }
+
+    #[test]
+    fn later_waves_fight_what_earlier_waves_left_behind() {
+        let game = game_config();
+        let wave = ArmyTroops::from([(TroopType::Infantry, 45)]);
+        let mut defenders = ArmyTroops::from([(TroopType::Spearman, 100)]);
+        let mut results = Vec::new();
+
+        // Resolve the waves one after another, each against the previous survivors
+        for _ in 0..3 {
+            let battle = ArmyService::calculate_battle(
+                &game,
+                &wave,
+                &defenders,
+                &battle_definitions(),
+                MissionType::Attack,
+                &plain_modifiers(),
+            );
+            defenders = battle.defender_survivors.clone();
+            results.push(battle);
+        }
+
+        let defender_lost = |b: &BattleResult| b.defender_losses.values().sum::<i32>();
+        let attacker_lost = |b: &BattleResult| b.attacker_losses.values().sum::<i32>();
+
+        // The first two waves are wiped out but wear the defenders down
+        assert!(!results[0].attacker_wins && !results[1].attacker_wins);
+        assert_eq!((defender_lost(&results[0]), defender_lost(&results[1])), (30, 36));
+        assert_eq!((attacker_lost(&results[0]), attacker_lost(&results[1])), (45, 45));
+
+        // The third meets only 34 spearmen and breaks through
+        assert!(results[2].attacker_wins);
+        assert!(defenders.is_empty());
+        assert_eq!(results[2].attacker_survivors.get(&TroopType::Infantry), Some(&16));
+
+        assert_eq!(results.iter().map(defender_lost).sum::<i32>(), 100);
+        assert_eq!(results.iter().map(attacker_lost).sum::<i32>(), 45 + 45 + 29);
+    }
+
+    #[test]
+    fn ordinary_enemy_village_is_conquerable() {
+        let game = game_config();
+        let attacker = Uuid::new_v4();
+        let target = village(0, 1000);
+
+        assert_eq!(ArmyService::conquer_blocker(&game, attacker, &target, 3, true), None);
+        assert_eq!(
+            ArmyService::conquer_blocker(&game, attacker, &target, 3, false),
+            Some(ConquerBlocker::VillageCapReached)
+        );
+        assert_eq!(
+            ArmyService::conquer_blocker(&game, target.user_id, &target, 3, true),
+            Some(ConquerBlocker::OwnVillage)
+        );
+    }
+
+    #[test]
+    fn capital_target_is_reported_as_not_conquerable() {
+        let mut game = game_config();
+        game.allow_capital_conquest = false;
+        let target = Village { is_capital: true, ..village(0, 1000) };
+
+        assert_eq!(
+            ArmyService::conquer_blocker(&game, Uuid::new_v4(), &target, 3, true),
+            Some(ConquerBlocker::ProtectedCapital)
+        );
+    }
+
+    #[test]
+    fn send_keeping_the_reserve_home_is_allowed() {
+        let in_village = ArmyTroops::from([(TroopType::Infantry, 100), (TroopType::Spearman, 20)]);
+        let reserves = ArmyTroops::from([(TroopType::Infantry, 30)]);
+        let requested = ArmyTroops::from([(TroopType::Infantry, 70), (TroopType::Spearman, 20)]);
+
+        assert!(ArmyService::check_troops_available(&requested, &in_village, &reserves).is_ok());
+    }
+
+    #[test]
+    fn send_breaching_the_reserve_is_rejected() {
+        let in_village = ArmyTroops::from([(TroopType::Infantry, 100)]);
+        let reserves = ArmyTroops::from([(TroopType::Infantry, 30)]);
+        let requested = ArmyTroops::from([(TroopType::Infantry, 71)]);
+
+        let Err(AppError::BadRequest(message)) =
+            ArmyService::check_troops_available(&requested, &in_village, &reserves)
+        else {
+            panic!("breaching the reserve should be rejected");
+        };
+        assert!(message.contains("at most 70 can be sent"), "{}", message);
+    }
+
+    #[test]
+    fn defense_summary_counts_stationed_support() {
+        let game = GameConfig {
+            wall_defense_bonus_percent_per_level: 4,
+            wall_defense_bonus_max_percent: 60,
+            ..game_config()
+        };
+        let definitions = vec![
+            troop_definition(TroopType::Infantry, 40, 35, 50, 6),
+            troop_definition(TroopType::Spearman, 10, 60, 40, 7),
+        ];
+        let village_troops = ArmyTroops::from([(TroopType::Infantry, 10)]);
+        let mut spears = army(MissionType::Support, ArmyTroops::from([(TroopType::Spearman, 5)]));
+        spears.is_stationed = true;
+        let mut infantry = army(MissionType::Support, ArmyTroops::from([(TroopType::Infantry, 3)]));
+        infantry.is_stationed = true;
+
+        let (support, total) =
+            ArmyService::garrison_with_support(&village_troops, &[spears, infantry]);
+        assert_eq!(support, ArmyTroops::from([(TroopType::Spearman, 5), (TroopType::Infantry, 3)]));
+        assert_eq!(total, ArmyTroops::from([(TroopType::Infantry, 13), (TroopType::Spearman, 5)]));
+
+        // Wall level 5 gives +20%: (13 * 35 + 5 * 60) * 1.2 and (13 * 50 + 5 * 40) * 1.2
+        let multiplier = (100 + ArmyService::wall_bonus_percent(&game, 5)) as f64 / 100.0;
+        let defense = |infantry_ratio| {
+            ArmyService::calculate_defense_power(&total, &definitions, infantry_ratio, multiplier)
+        };
+        assert!((defense(1.0) - 906.0).abs() < 1e-9);
+        assert!((defense(0.0) - 1020.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn every_mission_is_routed_to_its_arrival_handler() {
+        let route = |mission| ArmyService::arrival_route(&army(mission, ArmyTroops::new()));
+        assert_eq!(route(MissionType::Settle), ArrivalRoute::Settle);
+        assert_eq!(route(MissionType::Raid), ArrivalRoute::Hostile);
+        assert_eq!(route(MissionType::Attack), ArrivalRoute::Hostile);
+        assert_eq!(route(MissionType::Scout), ArrivalRoute::Scout);
+        assert_eq!(route(MissionType::Support), ArrivalRoute::Support);
+        assert_eq!(route(MissionType::Conquer), ArrivalRoute::Conquer);
+
+        let mut returning = army(MissionType::Settle, ArmyTroops::new());
+        returning.is_returning = true;
+        assert_eq!(ArmyService::arrival_route(&returning), ArrivalRoute::Return);
+    }
+
+    #[test]
+    fn conquest_at_the_village_cap_leaves_the_village_with_its_owner() {
+        assert_eq!(ArmyService::conquest_step(0, true), ConquestStep::Conquered);
+        assert_eq!(ArmyService::conquest_step(0, false), ConquestStep::VillageCapReached);
+        assert_eq!(ArmyService::conquest_step(12, true), ConquestStep::LoyaltyRemains);
+    }
+
+    #[test]
+    fn settling_is_refused_on_arrival_once_the_cap_is_reached() {
+        let mut game = game_config();
+        game.settlers_per_village = 3;
+        game.max_villages_per_player = 2;
+        let troops = ArmyTroops::from([(TroopType::Settler, 3)]);
+
+        // Below the cap when the army left, at the cap by the time it lands
+        let at_departure = VillageService::is_below_village_cap(&game, 1);
+        let on_arrival = VillageService::is_below_village_cap(&game, 2);
+        assert!(ArmyService::can_settle(&game, &troops, false, at_departure));
+        assert!(!ArmyService::can_settle(&game, &troops, false, on_arrival));
+        assert_eq!(ArmyService::settle_returning_troops(&game, &troops, false), troops);
+    }
+
+    #[test]
+    fn settlers_return_intact_from_an_occupied_tile() {
+        let mut game = game_config();
+        game.settlers_per_village = 3;
+        let troops = ArmyTroops::from([(TroopType::Settler, 3), (TroopType::Infantry, 20)]);
+
+        assert!(!ArmyService::can_settle(&game, &troops, true, true));
+        assert_eq!(ArmyService::settle_returning_troops(&game, &troops, false), troops);
+    }
+
+    #[test]
+    fn founding_a_village_uses_up_the_settlers() {
+        let mut game = game_config();
+        game.settlers_per_village = 3;
+        let troops = ArmyTroops::from([(TroopType::Settler, 4), (TroopType::Infantry, 20)]);
+
+        assert!(ArmyService::can_settle(&game, &troops, false, true));
+        assert!(!ArmyService::can_settle(&game, &troops, false, false));
+        assert_eq!(
+            ArmyService::settle_returning_troops(&game, &troops, true),
+            ArmyTroops::from([(TroopType::Settler, 1), (TroopType::Infantry, 20)])
+        );
+    }
+
+    #[test]
+    fn route_covers_every_tile_on_the_way() {
+        assert_eq!(
+            ArmyService::route_tiles((0, 0), (3, 0)),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+        assert_eq!(
+            ArmyService::route_tiles((2, 2), (-1, -1)),
+            vec![(2, 2), (1, 1), (0, 0), (-1, -1)]
+        );
+        assert_eq!(ArmyService::route_tiles((5, 5), (5, 5)), vec![(5, 5)]);
+
+        // Off-axis routes step one tile at a time along the longer axis
+        let route = ArmyService::route_tiles((0, 0), (6, -3));
+        assert_eq!(route.len(), 7);
+        assert_eq!((route[0], route[6]), ((0, 0), (6, -3)));
+        assert!(route
+            .windows(2)
+            .all(|w| (w[1].0 - w[0].0).abs() <= 1 && (w[1].1 - w[0].1).abs() <= 1));
+    }
+
+    fn returning_raid(home: &Village) -> Army {
+        let mut raid = army(MissionType::Raid, ArmyTroops::from([(TroopType::Infantry, 40)]));
+        raid.from_village_id = home.id;
+        raid.player_id = home.user_id;
+        raid.is_returning = true;
+        raid.resources = sqlx::types::Json(CarriedResources { wood: 100, clay: 100, iron: 50, crop: 0 });
+        raid
+    }
+
+    #[test]
+    fn army_coming_home_to_its_own_village_needs_no_notice() {
+        let home = village(0, 1000);
+        assert!(ArmyService::captured_home_notices(&returning_raid(&home), &home).is_none());
+    }
+
+    #[test]
+    fn troops_and_loot_coming_home_to_a_conquered_village_go_to_its_new_owner() {
+        let mut home = village(0, 1000);
+        let raid = returning_raid(&home);
+        home.user_id = Uuid::new_v4();
+
+        let [(loser, _, lost), (winner, _, gained)] =
+            ArmyService::captured_home_notices(&raid, &home).expect("home changed owner");
+        assert_eq!(loser, raid.player_id);
+        assert_eq!(winner, home.user_id);
+        assert!(lost.contains("40 troops and 250 resources"));
+        assert!(gained.contains("40 troops and 250 resources"));
+    }
+
+    #[test]
+    fn hero_of_a_conquered_home_moves_to_the_capital_or_nearest_village() {
+        let captured = village(0, 1000);
+        let at = |x: i32, y: i32, is_capital: bool| {
+            let mut v = village(0, 1000);
+            v.user_id = captured.user_id;
+            (v.x, v.y, v.is_capital) = (x, y, is_capital);
+            v
+        };
+        let (near, far, capital) = (at(3, 4, false), at(30, 40, false), at(90, 90, true));
+
+        let villages = [far.clone(), near.clone(), captured.clone()];
+        assert_eq!(ArmyService::new_hero_home(&villages, &captured), Some(near.id));
+        let villages = [far, near, capital.clone()];
+        assert_eq!(ArmyService::new_hero_home(&villages, &captured), Some(capital.id));
+        assert_eq!(ArmyService::new_hero_home(std::slice::from_ref(&captured), &captured), None);
     }
 }
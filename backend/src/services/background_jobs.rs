@@ -1,70 +1,140 @@
+use chrono::Utc;
+use redis::aio::ConnectionManager;
 use sqlx::PgPool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::interval;
 use tracing::{error, info};
 
+use crate::config::GameConfig;
+
 use crate::repositories::building_repo::BuildingRepository;
 use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
 use crate::services::army_service::ArmyService;
 use crate::services::building_service::BuildingService;
+use crate::services::cache_service::CacheService;
+use crate::services::hero_service::HeroService;
+use crate::services::job_status_service::JobStatusTracker;
 use crate::services::resource_service::ResourceService;
-use crate::services::ws_service::{BuildingCompleteData, TroopTrainingCompleteData, TroopsStarvedData, WsEvent, WsManager};
+use crate::services::transfer_service::TransferService;
+use crate::services::troop_service::TroopService;
+use crate::services::village_service::VillageService;
+use crate::services::ws_service::{BuildingCompleteData, TroopTrainingCompleteData, WsEvent, WsManager};
 
 /// Start all background jobs
-pub async fn start_background_jobs(pool: PgPool, ws_manager: WsManager) {
+pub async fn start_background_jobs(
+    pool: PgPool,
+    redis: ConnectionManager,
+    ws_manager: WsManager,
+    game: GameConfig,
+    jobs: JobStatusTracker,
+) {
     // Spawn building completion job
     let pool_clone = pool.clone();
+    let redis_clone = redis.clone();
     let ws_clone = ws_manager.clone();
+    let jobs_clone = jobs.clone();
     tokio::spawn(async move {
-        run_building_completion_job(pool_clone, ws_clone).await;
+        run_building_completion_job(pool_clone, redis_clone, ws_clone, jobs_clone).await;
     });
 
     // Spawn resource production job
     let pool_clone = pool.clone();
     let ws_clone = ws_manager.clone();
+    let game_clone = game.clone();
+    let jobs_clone = jobs.clone();
     tokio::spawn(async move {
-        run_resource_production_job(pool_clone, ws_clone).await;
+        run_resource_production_job(pool_clone, ws_clone, game_clone, jobs_clone).await;
     });
 
     // Spawn army processing job
     let pool_clone = pool.clone();
+    let redis_clone = redis.clone();
     let ws_clone = ws_manager.clone();
+    let game_clone = game.clone();
+    let jobs_clone = jobs.clone();
     tokio::spawn(async move {
-        run_army_processing_job(pool_clone, ws_clone).await;
+        run_army_processing_job(pool_clone, redis_clone, ws_clone, game_clone, jobs_clone).await;
     });
 
     // Spawn troop training completion job
     let pool_clone = pool.clone();
+    let redis_clone = redis.clone();
     let ws_clone = ws_manager.clone();
+    let jobs_clone = jobs.clone();
     tokio::spawn(async move {
-        run_troop_training_job(pool_clone, ws_clone).await;
+        run_troop_training_job(pool_clone, redis_clone, ws_clone, jobs_clone).await;
     });
 
     // Spawn starvation job
     let pool_clone = pool.clone();
+    let redis_clone = redis.clone();
     let ws_clone = ws_manager.clone();
+    let game_clone = game.clone();
+    let jobs_clone = jobs.clone();
+    tokio::spawn(async move {
+        run_starvation_job(pool_clone, redis_clone, ws_clone, game_clone, jobs_clone).await;
+    });
+
+    // Spawn merchant delivery job
+    let pool_clone = pool.clone();
+    let jobs_clone = jobs.clone();
     tokio::spawn(async move {
-        run_starvation_job(pool_clone, ws_clone).await;
+        run_transfer_delivery_job(pool_clone, redis, jobs_clone).await;
+    });
+
+    // Spawn stationed support expiry job
+    let pool_clone = pool.clone();
+    let ws_clone = ws_manager.clone();
+    let jobs_clone = jobs.clone();
+    tokio::spawn(async move {
+        run_support_expiry_job(pool_clone, ws_clone, jobs_clone).await;
+    });
+
+    // Spawn loyalty regeneration job
+    let pool_clone = pool.clone();
+    let jobs_clone = jobs.clone();
+    tokio::spawn(async move {
+        run_loyalty_regen_job(pool_clone, jobs_clone).await;
+    });
+
+    // Spawn hero adventure completion job
+    let pool_clone = pool.clone();
+    let jobs_clone = jobs.clone();
+    tokio::spawn(async move {
+        run_adventure_completion_job(pool_clone, game, jobs_clone).await;
+    });
+
+    // Spawn hero health regeneration job
+    tokio::spawn(async move {
+        run_hero_health_regen_job(pool, jobs).await;
     });
 
     info!("Background jobs started");
 }
 
 /// Check and complete building upgrades every 10 seconds
-async fn run_building_completion_job(pool: PgPool, ws_manager: WsManager) {
+async fn run_building_completion_job(
+    pool: PgPool,
+    redis: ConnectionManager,
+    ws_manager: WsManager,
+    jobs: JobStatusTracker,
+) {
     let mut ticker = interval(Duration::from_secs(10));
 
     loop {
         ticker.tick().await;
+        let started = Instant::now();
 
-        match complete_building_upgrades(&pool, &ws_manager).await {
+        match complete_building_upgrades(&pool, &redis, &ws_manager).await {
             Ok(count) => {
+                jobs.record_success("building_completion", started.elapsed(), count as i64).await;
                 if count > 0 {
                     info!("Completed {} building upgrades", count);
                 }
             }
             Err(e) => {
+                jobs.record_failure("building_completion", started.elapsed(), format!("{:?}", e)).await;
                 error!("Error completing building upgrades: {:?}", e);
             }
         }
@@ -72,7 +142,11 @@ async fn run_building_completion_job(pool: PgPool, ws_manager: WsManager) {
 }
 
 /// Complete all buildings that have finished upgrading
-async fn complete_building_upgrades(pool: &PgPool, ws_manager: &WsManager) -> anyhow::Result<i32> {
+async fn complete_building_upgrades(
+    pool: &PgPool,
+    redis: &ConnectionManager,
+    ws_manager: &WsManager,
+) -> anyhow::Result<i32> {
     let buildings = BuildingRepository::find_completed_upgrades(pool).await?;
     let mut completed = 0;
 
@@ -84,6 +158,7 @@ async fn complete_building_upgrades(pool: &PgPool, ws_manager: &WsManager) -> an
                     "Building {:?} upgraded to level {} in village {}",
                     updated.building_type, updated.level, updated.village_id
                 );
+                CacheService::invalidate_village(redis, updated.village_id).await;
 
                 // Broadcast to village owner
                 if let Ok(Some(village)) = VillageRepository::find_by_id(pool, updated.village_id).await {
@@ -108,14 +183,21 @@ async fn complete_building_upgrades(pool: &PgPool, ws_manager: &WsManager) -> an
 }
 
 /// Update resource production every 5 minutes
-async fn run_resource_production_job(pool: PgPool, _ws_manager: WsManager) {
+async fn run_resource_production_job(
+    pool: PgPool,
+    _ws_manager: WsManager,
+    game: GameConfig,
+    jobs: JobStatusTracker,
+) {
     let mut ticker = interval(Duration::from_secs(300)); // 5 minutes
 
     loop {
         ticker.tick().await;
+        let started = Instant::now();
 
-        match ResourceService::update_all_village_resources(&pool).await {
+        match ResourceService::update_all_village_resources(&pool, &game).await {
             Ok(count) => {
+                jobs.record_success("resource_production", started.elapsed(), count as i64).await;
                 if count > 0 {
                     info!("Updated resources for {} villages", count);
                     // Note: Resource updates are frequent and for all villages
@@ -124,6 +206,7 @@ async fn run_resource_production_job(pool: PgPool, _ws_manager: WsManager) {
                 }
             }
             Err(e) => {
+                jobs.record_failure("resource_production", started.elapsed(), format!("{:?}", e)).await;
                 error!("Error updating village resources: {:?}", e);
             }
         }
@@ -131,71 +214,217 @@ async fn run_resource_production_job(pool: PgPool, _ws_manager: WsManager) {
 }
 
 /// Process army arrivals every 5 seconds
-async fn run_army_processing_job(pool: PgPool, ws_manager: WsManager) {
+async fn run_army_processing_job(
+    pool: PgPool,
+    redis: ConnectionManager,
+    ws_manager: WsManager,
+    game: GameConfig,
+    jobs: JobStatusTracker,
+) {
     let mut ticker = interval(Duration::from_secs(5));
+    let mut released_until = Utc::now();
 
     loop {
         ticker.tick().await;
+        let started = Instant::now();
+
+        // Scheduled armies that departed since the last tick now show up as incoming
+        let now = Utc::now();
+        if let Err(e) =
+            ArmyService::notify_released_scheduled_armies(&pool, &ws_manager, released_until, now).await
+        {
+            error!("Error pushing incoming counts for released armies: {:?}", e);
+        }
+        released_until = now;
 
-        match ArmyService::process_arrived_armies_with_ws(&pool, &ws_manager).await {
+        match ArmyService::process_arrived_armies_with_ws(&pool, &redis, &ws_manager, &game).await {
             Ok(count) => {
+                jobs.record_success("army_processing", started.elapsed(), count as i64).await;
                 if count > 0 {
                     info!("Processed {} army arrivals", count);
                 }
             }
             Err(e) => {
+                jobs.record_failure("army_processing", started.elapsed(), format!("{:?}", e)).await;
                 error!("Error processing army arrivals: {:?}", e);
             }
         }
     }
 }
 
+/// Deliver arrived merchant transfers every 10 seconds
+async fn run_transfer_delivery_job(pool: PgPool, redis: ConnectionManager, jobs: JobStatusTracker) {
+    let mut ticker = interval(Duration::from_secs(10));
+
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+
+        match TransferService::process_arrived_transfers(&pool, &redis).await {
+            Ok(count) => {
+                jobs.record_success("transfer_delivery", started.elapsed(), count as i64).await;
+                if count > 0 {
+                    info!("Delivered {} merchant transfers", count);
+                }
+            }
+            Err(e) => {
+                jobs.record_failure("transfer_delivery", started.elapsed(), format!("{:?}", e)).await;
+                error!("Error delivering merchant transfers: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Regenerate village loyalty every hour
+async fn run_loyalty_regen_job(pool: PgPool, jobs: JobStatusTracker) {
+    let mut ticker = interval(Duration::from_secs(3600));
+    // The first tick fires immediately; skip it so restarts don't grant extra loyalty
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+
+        match VillageService::regenerate_loyalty(&pool).await {
+            Ok(count) => {
+                jobs.record_success("loyalty_regen", started.elapsed(), count as i64).await;
+                if count > 0 {
+                    info!("Regenerated loyalty for {} villages", count);
+                }
+            }
+            Err(e) => {
+                jobs.record_failure("loyalty_regen", started.elapsed(), format!("{:?}", e)).await;
+                error!("Error regenerating loyalty: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Complete finished hero adventures every 10 seconds
+async fn run_adventure_completion_job(pool: PgPool, game: GameConfig, jobs: JobStatusTracker) {
+    let mut ticker = interval(Duration::from_secs(10));
+
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+
+        match HeroService::process_completed_adventures(&pool, &game).await {
+            Ok(count) => {
+                jobs.record_success("adventure_completion", started.elapsed(), count as i64).await;
+                if count > 0 {
+                    info!("Completed {} hero adventures", count);
+                }
+            }
+            Err(e) => {
+                jobs.record_failure("adventure_completion", started.elapsed(), format!("{:?}", e)).await;
+                error!("Error completing hero adventures: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Regenerate hero health every 60 seconds
+async fn run_hero_health_regen_job(pool: PgPool, jobs: JobStatusTracker) {
+    let mut ticker = interval(Duration::from_secs(60));
+
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+
+        match HeroService::process_health_regen(&pool).await {
+            Ok(count) => {
+                jobs.record_success("hero_health_regen", started.elapsed(), count as i64).await;
+                if count > 0 {
+                    info!("Regenerated health for {} heroes", count);
+                }
+            }
+            Err(e) => {
+                jobs.record_failure("hero_health_regen", started.elapsed(), format!("{:?}", e)).await;
+                error!("Error regenerating hero health: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Return expired stationed support every 60 seconds
+async fn run_support_expiry_job(pool: PgPool, ws_manager: WsManager, jobs: JobStatusTracker) {
+    let mut ticker = interval(Duration::from_secs(60));
+
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+
+        match ArmyService::process_expired_support(&pool, &ws_manager).await {
+            Ok(count) => {
+                jobs.record_success("support_expiry", started.elapsed(), count as i64).await;
+                if count > 0 {
+                    info!("Auto-returned {} stationed support armies", count);
+                }
+            }
+            Err(e) => {
+                jobs.record_failure("support_expiry", started.elapsed(), format!("{:?}", e)).await;
+                error!("Error processing stationed support expiry: {:?}", e);
+            }
+        }
+    }
+}
+
 /// Process troop training completion every 10 seconds
-async fn run_troop_training_job(pool: PgPool, ws_manager: WsManager) {
+async fn run_troop_training_job(
+    pool: PgPool,
+    redis: ConnectionManager,
+    ws_manager: WsManager,
+    jobs: JobStatusTracker,
+) {
     let mut ticker = interval(Duration::from_secs(10));
 
     loop {
         ticker.tick().await;
+        let started = Instant::now();
 
-        match complete_troop_training(&pool, &ws_manager).await {
+        match complete_troop_training(&pool, &redis, &ws_manager).await {
             Ok(count) => {
+                jobs.record_success("troop_training", started.elapsed(), count as i64).await;
                 if count > 0 {
-                    info!("Completed {} troop training batches", count);
+                    info!("Delivered troops from {} training batches", count);
                 }
             }
             Err(e) => {
+                jobs.record_failure("troop_training", started.elapsed(), format!("{:?}", e)).await;
                 error!("Error completing troop training: {:?}", e);
             }
         }
     }
 }
 
-/// Complete all troop training that has finished
-async fn complete_troop_training(pool: &PgPool, ws_manager: &WsManager) -> anyhow::Result<i32> {
-    let completed = TroopRepository::find_completed_training(pool).await?;
+/// Deliver troops that have finished training, including partial batches
+async fn complete_troop_training(
+    pool: &PgPool,
+    redis: &ConnectionManager,
+    ws_manager: &WsManager,
+) -> anyhow::Result<i32> {
+    let ready = TroopRepository::find_training_with_ready_units(pool).await?;
     let mut count = 0;
 
-    for entry in completed {
-        // Add troops to village
-        match TroopRepository::add_troops(pool, entry.village_id, entry.troop_type, entry.count).await {
-            Ok(_) => {
-                // Remove from queue
-                if let Err(e) = TroopRepository::remove_from_queue(pool, entry.id).await {
-                    error!("Failed to remove training queue entry {}: {:?}", entry.id, e);
-                    continue;
-                }
-
+    for entry in ready {
+        match TroopService::deliver_trained_units(pool, &entry).await {
+            Ok(0) => {}
+            Ok(units) => {
                 info!(
-                    "Troop training complete: {} x {:?} in village {}",
-                    entry.count, entry.troop_type, entry.village_id
+                    "Troop training delivered: {} x {:?} in village {} ({} remaining)",
+                    units,
+                    entry.troop_type,
+                    entry.village_id,
+                    entry.count - units
                 );
+                CacheService::invalidate_village(redis, entry.village_id).await;
 
                 // Broadcast to village owner
                 if let Ok(Some(village)) = VillageRepository::find_by_id(pool, entry.village_id).await {
                     let event = WsEvent::TroopTrainingComplete(TroopTrainingCompleteData {
                         village_id: entry.village_id,
                         troop_type: format!("{:?}", entry.troop_type),
-                        quantity: entry.count,
+                        quantity: units,
                     });
                     ws_manager.send_to_user(village.user_id, &event).await;
                 }
@@ -203,7 +432,7 @@ async fn complete_troop_training(pool: &PgPool, ws_manager: &WsManager) -> anyho
                 count += 1;
             }
             Err(e) => {
-                error!("Failed to add troops for queue entry {}: {:?}", entry.id, e);
+                error!("Failed to deliver troops for queue entry {}: {:?}", entry.id, e);
             }
         }
     }
@@ -212,97 +441,30 @@ async fn complete_troop_training(pool: &PgPool, ws_manager: &WsManager) -> anyho
 }
 
 /// Process starvation every 60 seconds
-async fn run_starvation_job(pool: PgPool, ws_manager: WsManager) {
+async fn run_starvation_job(
+    pool: PgPool,
+    redis: ConnectionManager,
+    ws_manager: WsManager,
+    game: GameConfig,
+    jobs: JobStatusTracker,
+) {
     let mut ticker = interval(Duration::from_secs(60));
 
     loop {
         ticker.tick().await;
+        let started = Instant::now();
 
-        match process_starvation(&pool, &ws_manager).await {
+        match ArmyService::process_starvation(&pool, &redis, &ws_manager, &game).await {
             Ok(count) => {
+                jobs.record_success("starvation", started.elapsed(), count as i64).await;
                 if count > 0 {
                     info!("Starvation: {} troops died from hunger", count);
                 }
             }
             Err(e) => {
+                jobs.record_failure("starvation", started.elapsed(), format!("{:?}", e)).await;
                 error!("Error processing starvation: {:?}", e);
             }
         }
     }
 }
-
-/// Troop with consumption info for starvation calculation
-#[derive(Debug, sqlx::FromRow)]
-struct TroopWithConsumption {
-    village_id: uuid::Uuid,
-    troop_type: crate::models::troop::TroopType,
-    in_village: i32,
-    crop_consumption: i32,
-}
-
-/// Process starvation for villages with no crop
-async fn process_starvation(pool: &PgPool, ws_manager: &WsManager) -> anyhow::Result<i32> {
-    // Find villages with crop <= 0
-    let starving_villages: Vec<(uuid::Uuid, uuid::Uuid)> = sqlx::query_as(
-        r#"
-        SELECT id, user_id FROM villages
-        WHERE crop <= 0
-        "#,
-    )
-    .fetch_all(pool)
-    .await?;
-
-    if starving_villages.is_empty() {
-        return Ok(0);
-    }
-
-    let mut total_killed = 0;
-
-    for (village_id, user_id) in starving_villages {
-        // Get troops with highest crop consumption first
-        let troops: Vec<TroopWithConsumption> = sqlx::query_as(
-            r#"
-            SELECT t.village_id, t.troop_type, t.in_village, td.crop_consumption
-            FROM troops t
-            JOIN troop_definitions td ON t.troop_type = td.troop_type
-            WHERE t.village_id = $1 AND t.in_village > 0
-            ORDER BY td.crop_consumption DESC
-            "#,
-        )
-        .bind(village_id)
-        .fetch_all(pool)
-        .await?;
-
-        if troops.is_empty() {
-            continue;
-        }
-
-        // Kill 1 troop from the type with highest consumption
-        let victim = &troops[0];
-        let kill_count = 1.min(victim.in_village);
-
-        if kill_count > 0 {
-            if let Err(e) = TroopRepository::kill_troops(pool, village_id, victim.troop_type, kill_count).await {
-                error!("Failed to kill starving troops in village {}: {:?}", village_id, e);
-                continue;
-            }
-
-            info!(
-                "Starvation: {} {:?} died in village {} due to lack of food",
-                kill_count, victim.troop_type, village_id
-            );
-
-            // Broadcast to village owner
-            let event = WsEvent::TroopsStarved(TroopsStarvedData {
-                village_id,
-                troop_type: format!("{:?}", victim.troop_type),
-                quantity: kill_count,
-            });
-            ws_manager.send_to_user(user_id, &event).await;
-
-            total_killed += kill_count;
-        }
-    }
-
-    Ok(total_killed)
-}
@@ -1,13 +1,36 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+use tracing::info;
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::{AppError, AppResult};
-use crate::models::building::{Building, BuildingType};
+use crate::models::activity::VillageActivityType;
+use crate::models::building::{
+    Building, BuildingCost, BuildingQueueEntry, BuildingSlotResponse, BuildingType,
+    CancelQueueResponse, CancelledUpgrade, SlotKind, RESOURCE_SLOTS, VILLAGE_SLOTS,
+};
+use crate::models::oasis::max_oases;
+use crate::models::village::Village;
 use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::oasis_repo::OasisRepository;
+use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::activity_service::ActivityService;
+use crate::services::oasis_service::OasisService;
+use crate::services::resource_service::ResourceService;
+use crate::services::village_service::VillageService;
 
 pub struct BuildingService;
 
+/// One level of a chained upgrade and when it is built
+#[derive(Debug)]
+struct PlannedUpgrade {
+    level: i32,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+}
+
 #[derive(Debug)]
 pub struct MissingPrerequisite {
     pub building_type: BuildingType,
@@ -16,6 +39,208 @@ pub struct MissingPrerequisite {
 }
 
 impl BuildingService {
+    /// List every slot of a village, with empty slots reported as `building: None`
+    pub async fn list_slots(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<BuildingSlotResponse>> {
+        let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
+        Ok(Self::fill_slots(buildings))
+    }
+
+    /// Every village and resource slot in order, holding its building if one exists
+    fn fill_slots(mut buildings: Vec<Building>) -> Vec<BuildingSlotResponse> {
+        VILLAGE_SLOTS
+            .map(|slot| (slot, SlotKind::Village))
+            .chain(RESOURCE_SLOTS.map(|slot| (slot, SlotKind::Resource)))
+            .map(|(slot, kind)| {
+                let building = buildings
+                    .iter()
+                    .position(|b| b.slot == slot)
+                    .map(|i| buildings.swap_remove(i).into());
+                BuildingSlotResponse { slot, kind, building }
+            })
+            .collect()
+    }
+
+    /// Carry capacity bonus (percent) a village's Trade Office gives its merchants and raiders
+    pub async fn carry_bonus_percent(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<i32> {
+        let level = BuildingRepository::find_by_type(pool, village_id, BuildingType::TradeOffice)
+            .await?
+            .iter()
+            .map(|b| b.level)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self::trade_office_bonus(game, level))
+    }
+
+    /// Carry capacity bonus (percent) of a Trade Office at `level`
+    pub fn trade_office_bonus(game: &GameConfig, level: i32) -> i32 {
+        level.max(0) * game.trade_office_carry_bonus_percent.max(0)
+    }
+
+    /// Amount of each resource hidden from raiders by all of a village's Crannies
+    pub async fn hidden_resources(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
+        Ok(BuildingRepository::find_by_type(pool, village_id, BuildingType::Cranny)
+            .await?
+            .iter()
+            .map(|b| b.building_type.hidden_capacity(b.level))
+            .fold(0, i32::saturating_add))
+    }
+
+    /// Resources returned for demolishing a building: a share of what all its completed levels cost
+    pub fn demolition_refund(game: &GameConfig, building: &Building) -> BuildingCost {
+        let percent = game.demolish_refund_percent.clamp(0, 100);
+        let total = building.building_type.cumulative_cost(building.level);
+
+        BuildingCost {
+            wood: total.wood * percent / 100,
+            clay: total.clay * percent / 100,
+            iron: total.iron * percent / 100,
+            crop: total.crop * percent / 100,
+            time_seconds: 0,
+        }
+    }
+
+    /// Reject demolishing a building with levels in progress or one the village cannot lose
+    pub fn check_can_demolish(building: &Building) -> AppResult<()> {
+        // Levels in progress or queued are already paid for; cancelling refunds them
+        if building.is_upgrading {
+            return Err(AppError::BadRequest(
+                "Cancel the upgrade in progress before demolishing this building".into(),
+            ));
+        }
+
+        if building.building_type.is_demolition_protected() && building.level > 0 {
+            return Err(AppError::BadRequest(format!(
+                "Cannot demolish {:?}",
+                building.building_type
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Demolish a building and refund part of its cost. Production is settled at
+    /// the old rate and population, oases and the refund follow in the same
+    /// transaction; only the request whose delete removed the row is refunded.
+    /// Returns the released oases and the refund.
+    pub async fn demolish(
+        pool: &PgPool,
+        game: &GameConfig,
+        building: &Building,
+    ) -> AppResult<(Vec<Uuid>, BuildingCost)> {
+        Self::check_can_demolish(building)?;
+
+        let village_id = building.village_id;
+
+        // Demolishing fields or releasing oases changes production, so settle at the old rate
+        let production = ResourceService::calculate_production(pool, game, village_id).await?;
+
+        // Check the training queue with the building's row locked, so the check and the
+        // demolition see the same queue
+        let mut tx = pool.begin().await?;
+        if BuildingRepository::find_by_id_for_update(&mut *tx, building.id).await?.is_none() {
+            return Err(AppError::Conflict("Building was already demolished".into()));
+        }
+        let buildings = BuildingRepository::find_by_village_id(&mut *tx, village_id).await?;
+        let training = TroopRepository::count_queue_requiring_building(
+            &mut *tx,
+            village_id,
+            &building.building_type,
+        )
+        .await?;
+        Self::check_not_needed_for_training(&buildings, building, training)?;
+
+        if BuildingRepository::demolish(&mut *tx, building.id).await?.is_none() {
+            return Err(AppError::Conflict(
+                "Building was already demolished or started upgrading".into(),
+            ));
+        }
+
+        let village = VillageRepository::find_by_id(&mut *tx, village_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
+        let settled = ResourceService::accrue(&village, &production, Utc::now());
+        VillageRepository::update_resources(
+            &mut *tx,
+            village_id,
+            settled.wood,
+            settled.clay,
+            settled.iron,
+            settled.crop,
+        )
+        .await?;
+
+        let remaining = BuildingRepository::find_by_village_id(&mut *tx, village_id).await?;
+        VillageRepository::update_population(
+            &mut *tx,
+            village_id,
+            VillageService::building_population(&remaining),
+        )
+        .await?;
+
+        // A smaller Hero's Mansion holds fewer oases
+        let mut released_oases = Vec::new();
+        if building.building_type == BuildingType::HeroMansion {
+            let mansion_level = remaining
+                .iter()
+                .filter(|b| b.building_type == BuildingType::HeroMansion)
+                .map(|b| b.level)
+                .max()
+                .unwrap_or(0);
+            let oases = OasisRepository::find_by_village(&mut *tx, village_id).await?;
+            released_oases = OasisService::oases_to_release(&oases, max_oases(mansion_level));
+            for oasis_id in &released_oases {
+                OasisRepository::release(&mut *tx, *oasis_id, village_id).await?;
+                info!(
+                    "Village {} lost oasis {} after its Hero's Mansion was demolished",
+                    village_id, oasis_id
+                );
+            }
+        }
+
+        let refund = Self::demolition_refund(game, building);
+        if refund.wood > 0 || refund.clay > 0 || refund.iron > 0 || refund.crop > 0 {
+            VillageRepository::add_resources(
+                &mut *tx,
+                village_id,
+                refund.wood,
+                refund.clay,
+                refund.iron,
+                refund.crop,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok((released_oases, refund))
+    }
+
+    /// Troops in training need their building; block demolition unless another one remains
+    fn check_not_needed_for_training(
+        buildings: &[Building],
+        building: &Building,
+        training: i64,
+    ) -> AppResult<()> {
+        if training > 0 && !Self::has_replacement(buildings, building) {
+            return Err(AppError::BadRequest(format!(
+                "Cannot demolish {:?} while troops are training there",
+                building.building_type
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether another completed building of the same type would remain after demolishing this one
+    fn has_replacement(buildings: &[Building], building: &Building) -> bool {
+        buildings
+            .iter()
+            .any(|b| b.id != building.id && b.building_type == building.building_type && b.level > 0)
+    }
+
     /// Check if prerequisites are met for building a new building
     pub async fn check_prerequisites(
         pool: &PgPool,
@@ -85,11 +310,298 @@ impl BuildingService {
         }
 
         // Always update population after any building upgrade
-        Self::update_village_population(pool, building.village_id).await?;
+        VillageService::recompute_population(pool, building.village_id).await?;
+
+        ActivityService::record(
+            pool,
+            building.village_id,
+            VillageActivityType::BuildingCompleted,
+            serde_json::json!({
+                "building_type": building.building_type,
+                "slot": building.slot,
+                "level": building.level,
+            }),
+        );
+
+        // Start the next chained level; a queue that no longer follows on from
+        // the completed level cannot be built, so its prepaid cost is returned
+        let queued = BuildingRepository::find_queue_by_building(pool, building.id).await?;
+        if let Some(next) = queued.first() {
+            let mut tx = pool.begin().await?;
+            if next.target_level == building.level + 1 {
+                // Taking the level off the queue and starting it commit together, so a
+                // failed start never drops a prepaid level
+                BuildingRepository::delete_queued(&mut *tx, next.id).await?;
+                let ends_at = Self::chained_upgrade_end(next, Utc::now());
+                let started =
+                    BuildingRepository::start_upgrade(&mut *tx, building.id, ends_at).await?;
+                tx.commit().await?;
+                return Ok(started);
+            }
+            Self::refund_queued(&mut tx, &building, &queued).await?;
+            tx.commit().await?;
+        }
 
         Ok(building)
     }
 
+    /// When a chained level that starts now finishes: its planned end, or sooner
+    /// if the previous level was finished early
+    fn chained_upgrade_end(next: &BuildingQueueEntry, now: DateTime<Utc>) -> DateTime<Utc> {
+        next.ends_at.min(now + (next.ends_at - next.starts_at))
+    }
+
+    /// Drop queued levels and give back what was paid for them in full
+    async fn refund_queued(
+        conn: &mut PgConnection,
+        building: &Building,
+        queued: &[BuildingQueueEntry],
+    ) -> AppResult<()> {
+        let mut refund = BuildingCost {
+            wood: 0,
+            clay: 0,
+            iron: 0,
+            crop: 0,
+            time_seconds: 0,
+        };
+        for entry in queued {
+            BuildingRepository::delete_queued(&mut *conn, entry.id).await?;
+            let cost = building.building_type.cost_at_level(entry.target_level);
+            refund.wood += cost.wood;
+            refund.clay += cost.clay;
+            refund.iron += cost.iron;
+            refund.crop += cost.crop;
+        }
+
+        VillageRepository::add_resources(
+            &mut *conn,
+            building.village_id,
+            refund.wood,
+            refund.clay,
+            refund.iron,
+            refund.crop,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cancel a building's upgrade in progress and any levels chained after it,
+    /// refunding the configured share of what they cost. A new construction that
+    /// never reached level 1 is removed; resource fields keep their slot at level 0.
+    /// Returns None if nothing was in progress.
+    async fn cancel_upgrade(
+        conn: &mut PgConnection,
+        game: &GameConfig,
+        building: &Building,
+    ) -> AppResult<Option<CancelledUpgrade>> {
+        // Claim first so the completion job cannot finish the level we refund
+        let Some(cancelled) = BuildingRepository::cancel_upgrade(&mut *conn, building.id).await? else {
+            return Ok(None);
+        };
+
+        let mut cancelled_levels = vec![cancelled.level + 1];
+        for queued in BuildingRepository::find_queue_by_building(&mut *conn, building.id).await? {
+            BuildingRepository::delete_queued(&mut *conn, queued.id).await?;
+            cancelled_levels.push(queued.target_level);
+        }
+
+        if cancelled.level == 0 && !building.building_type.is_resource_field() {
+            BuildingRepository::demolish(&mut *conn, building.id).await?;
+        }
+
+        let refund = Self::cancel_refund(game, &building.building_type, &cancelled_levels);
+        if refund.wood > 0 || refund.clay > 0 || refund.iron > 0 || refund.crop > 0 {
+            VillageRepository::add_resources(
+                &mut *conn,
+                building.village_id,
+                refund.wood,
+                refund.clay,
+                refund.iron,
+                refund.crop,
+            )
+            .await?;
+        }
+
+        Ok(Some(CancelledUpgrade {
+            building_id: building.id,
+            building_type: building.building_type.clone(),
+            slot: building.slot,
+            level: cancelled.level,
+            cancelled_levels,
+            refund,
+        }))
+    }
+
+    /// The configured share of what the cancelled levels cost
+    fn cancel_refund(game: &GameConfig, building_type: &BuildingType, levels: &[i32]) -> BuildingCost {
+        let percent = game.upgrade_cancel_refund_percent.clamp(0, 100);
+        let mut refund = BuildingCost {
+            wood: 0,
+            clay: 0,
+            iron: 0,
+            crop: 0,
+            time_seconds: 0,
+        };
+        for level in levels {
+            let cost = building_type.cost_at_level(*level);
+            refund.wood += cost.wood * percent / 100;
+            refund.clay += cost.clay * percent / 100;
+            refund.iron += cost.iron * percent / 100;
+            refund.crop += cost.crop * percent / 100;
+        }
+        refund
+    }
+
+    /// Cancel every upgrade in progress in a village. All or nothing: if one
+    /// building fails, no upgrade is cancelled and nothing is refunded.
+    pub async fn cancel_village_queue(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<CancelQueueResponse> {
+        let mut tx = pool.begin().await?;
+        let mut cancelled = Vec::new();
+        for building in BuildingRepository::find_upgrading_by_village(&mut *tx, village_id).await? {
+            if let Some(upgrade) = Self::cancel_upgrade(&mut tx, game, &building).await? {
+                cancelled.push(upgrade);
+            }
+        }
+        tx.commit().await?;
+
+        let refund = Self::total_refund(&cancelled);
+        Ok(CancelQueueResponse { cancelled, refund })
+    }
+
+    /// Sum of the refunds of several cancelled upgrades
+    fn total_refund(cancelled: &[CancelledUpgrade]) -> BuildingCost {
+        let mut refund = BuildingCost {
+            wood: 0,
+            clay: 0,
+            iron: 0,
+            crop: 0,
+            time_seconds: 0,
+        };
+        for upgrade in cancelled {
+            refund.wood += upgrade.refund.wood;
+            refund.clay += upgrade.refund.clay;
+            refund.iron += upgrade.refund.iron;
+            refund.crop += upgrade.refund.crop;
+        }
+        refund
+    }
+
+    /// Queue sequential upgrades of a building up to `target_level`.
+    /// All levels are charged up front; each level starts when the previous one ends.
+    pub async fn queue_upgrades_to(
+        pool: &PgPool,
+        village: &Village,
+        building: &Building,
+        target_level: i32,
+    ) -> AppResult<(Building, Vec<BuildingQueueEntry>, BuildingCost)> {
+        let queued = BuildingRepository::find_queue_by_building(pool, building.id).await?;
+        if !queued.is_empty() {
+            return Err(AppError::Conflict("Building already has queued upgrades".to_string()));
+        }
+
+        let (levels, total) = Self::plan_upgrade_chain(building, target_level, Utc::now())?;
+
+        if village.wood < total.wood
+            || village.clay < total.clay
+            || village.iron < total.iron
+            || village.crop < total.crop
+        {
+            return Err(AppError::BadRequest("Not enough resources".to_string()));
+        }
+
+        // Charge and queue together so a failed insert leaves the resources untouched
+        let mut tx = pool.begin().await?;
+        VillageRepository::deduct_resources(
+            &mut *tx,
+            village.id,
+            total.wood,
+            total.clay,
+            total.iron,
+            total.crop,
+        )
+        .await?;
+
+        let mut updated = building.clone();
+        let mut entries = Vec::new();
+        for planned in levels {
+            if planned.level == building.level + 1 {
+                // Nothing in progress: the first level starts right away
+                updated = BuildingRepository::start_upgrade(&mut *tx, building.id, planned.ends_at).await?;
+            } else {
+                let entry = BuildingRepository::enqueue_upgrade(
+                    &mut *tx,
+                    building.id,
+                    village.id,
+                    planned.level,
+                    planned.starts_at,
+                    planned.ends_at,
+                )
+                .await?;
+                entries.push(entry);
+            }
+        }
+        tx.commit().await?;
+
+        Ok((updated, entries, total))
+    }
+
+    /// Lay out the levels needed to take a building to `target_level`, each
+    /// starting when the previous ends, and what they cost altogether
+    fn plan_upgrade_chain(
+        building: &Building,
+        target_level: i32,
+        now: DateTime<Utc>,
+    ) -> AppResult<(Vec<PlannedUpgrade>, BuildingCost)> {
+        if target_level > building.building_type.max_level() {
+            return Err(AppError::BadRequest(format!(
+                "Target level exceeds max level {}",
+                building.building_type.max_level()
+            )));
+        }
+
+        // An upgrade already in progress is chained onto rather than replaced
+        let (first_level, mut starts_at) = if building.is_upgrading {
+            (building.level + 2, building.upgrade_ends_at.unwrap_or(now))
+        } else {
+            (building.level + 1, now)
+        };
+
+        if target_level < first_level {
+            return Err(AppError::BadRequest(format!(
+                "Target level must be at least {}",
+                first_level
+            )));
+        }
+
+        let mut total = BuildingCost {
+            wood: 0,
+            clay: 0,
+            iron: 0,
+            crop: 0,
+            time_seconds: 0,
+        };
+        let mut levels = Vec::new();
+        for level in first_level..=target_level {
+            let cost = building.building_type.cost_at_level(level);
+            total.wood += cost.wood;
+            total.clay += cost.clay;
+            total.iron += cost.iron;
+            total.crop += cost.crop;
+            total.time_seconds += cost.time_seconds;
+
+            let ends_at = starts_at + Duration::seconds(cost.time_seconds as i64);
+            levels.push(PlannedUpgrade { level, starts_at, ends_at });
+            starts_at = ends_at;
+        }
+
+        Ok((levels, total))
+    }
+
     /// Recalculate and update village storage capacity based on all Warehouse/Granary buildings
     pub async fn update_village_storage(pool: &PgPool, village_id: Uuid) -> AppResult<()> {
         let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
@@ -114,18 +626,202 @@ impl BuildingService {
 
         Ok(())
     }
+}
 
-    /// Recalculate and update village population based on all buildings
-    pub async fn update_village_population(pool: &PgPool, village_id: Uuid) -> AppResult<()> {
-        let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::game_config;
 
-        let population: i32 = buildings
-            .iter()
-            .map(|b| b.building_type.population_at_level(b.level))
-            .sum();
+    fn building(level: i32, upgrade_ends_at: Option<DateTime<Utc>>) -> Building {
+        Building {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            building_type: BuildingType::Warehouse,
+            slot: 5,
+            level,
+            is_upgrading: upgrade_ends_at.is_some(),
+            upgrade_ends_at,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
 
-        VillageRepository::update_population(pool, village_id, population).await?;
+    #[test]
+    fn slot_list_includes_empty_slots_of_a_partly_built_village() {
+        let mut field = building(2, None);
+        field.building_type = BuildingType::Woodcutter;
+        field.slot = 101;
+        let slots = BuildingService::fill_slots(vec![building(1, None), field]);
 
-        Ok(())
+        assert_eq!(slots.len(), VILLAGE_SLOTS.count() + RESOURCE_SLOTS.count());
+        assert_eq!(slots.iter().filter(|s| s.building.is_some()).count(), 2);
+
+        let slot = |n: i32| slots.iter().find(|s| s.slot == n).unwrap();
+        assert_eq!(slot(5).kind, SlotKind::Village);
+        assert_eq!(slot(5).building.as_ref().map(|b| b.level), Some(1));
+        assert!(slot(1).building.is_none());
+        assert_eq!(slot(101).kind, SlotKind::Resource);
+        assert!(matches!(
+            slot(101).building.as_ref().map(|b| &b.building_type),
+            Some(BuildingType::Woodcutter)
+        ));
+        assert!(slot(118).building.is_none());
+    }
+
+    #[test]
+    fn three_level_chain_starts_each_level_when_the_previous_ends() {
+        let now = Utc::now();
+        let warehouse = building(1, None);
+
+        let (levels, total) = BuildingService::plan_upgrade_chain(&warehouse, 4, now).unwrap();
+
+        assert_eq!(levels.iter().map(|l| l.level).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(levels[0].starts_at, now);
+        assert_eq!(levels[1].starts_at, levels[0].ends_at);
+        assert_eq!(levels[2].starts_at, levels[1].ends_at);
+
+        let costs: Vec<BuildingCost> = (2..=4).map(|l| BuildingType::Warehouse.cost_at_level(l)).collect();
+        assert_eq!(total.wood, costs.iter().map(|c| c.wood).sum::<i32>());
+        assert_eq!(total.crop, costs.iter().map(|c| c.crop).sum::<i32>());
+        assert_eq!(
+            levels[2].ends_at,
+            now + Duration::seconds(costs.iter().map(|c| c.time_seconds as i64).sum())
+        );
+    }
+
+    #[test]
+    fn chain_continues_after_an_upgrade_in_progress() {
+        let now = Utc::now();
+        let in_progress_until = now + Duration::minutes(10);
+        let warehouse = building(1, Some(in_progress_until));
+
+        let (levels, _) = BuildingService::plan_upgrade_chain(&warehouse, 4, now).unwrap();
+
+        assert_eq!(levels.iter().map(|l| l.level).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(levels[0].starts_at, in_progress_until);
+        assert!(BuildingService::plan_upgrade_chain(&warehouse, 2, now).is_err());
+    }
+
+    #[test]
+    fn chained_level_keeps_its_duration_when_started_early() {
+        let now = Utc::now();
+        let next = BuildingQueueEntry {
+            id: Uuid::new_v4(),
+            building_id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            target_level: 3,
+            starts_at: now + Duration::hours(1),
+            ends_at: now + Duration::hours(3),
+            created_at: now,
+        };
+
+        // Finished early with gold: the next level takes its own two hours from now
+        assert_eq!(BuildingService::chained_upgrade_end(&next, now), now + Duration::hours(2));
+        // Picked up late by the job: the planned end still holds
+        assert_eq!(
+            BuildingService::chained_upgrade_end(&next, now + Duration::hours(1) + Duration::seconds(5)),
+            next.ends_at
+        );
+    }
+
+    #[test]
+    fn demolition_refunds_the_configured_share_of_every_completed_level() {
+        let warehouse = building(4, None);
+        let game = GameConfig {
+            demolish_refund_percent: 40,
+            ..game_config()
+        };
+
+        let refund = BuildingService::demolition_refund(&game, &warehouse);
+
+        let total = BuildingType::Warehouse.cumulative_cost(4);
+        assert_eq!(refund.wood, total.wood * 40 / 100);
+        assert_eq!(refund.clay, total.clay * 40 / 100);
+        assert_eq!(refund.iron, total.iron * 40 / 100);
+        assert_eq!(refund.crop, total.crop * 40 / 100);
+    }
+
+    #[test]
+    fn protected_buildings_cannot_be_demolished_once_built() {
+        let mut main_building = building(3, None);
+        main_building.building_type = BuildingType::MainBuilding;
+        assert!(matches!(
+            BuildingService::check_can_demolish(&main_building),
+            Err(AppError::BadRequest(_))
+        ));
+
+        // An unbuilt slot holds nothing to protect
+        main_building.level = 0;
+        assert!(BuildingService::check_can_demolish(&main_building).is_ok());
+        assert!(BuildingService::check_can_demolish(&building(3, None)).is_ok());
+    }
+
+    #[test]
+    fn upgrading_buildings_must_be_cancelled_before_demolishing() {
+        let warehouse = building(3, Some(Utc::now() + Duration::minutes(5)));
+        assert!(BuildingService::check_can_demolish(&warehouse).is_err());
+    }
+
+    #[test]
+    fn cancelling_a_village_queue_refunds_every_building_and_chained_level() {
+        let game = GameConfig {
+            upgrade_cancel_refund_percent: 50,
+            ..game_config()
+        };
+        let cancel = |building_type: BuildingType, levels: Vec<i32>| CancelledUpgrade {
+            building_id: Uuid::new_v4(),
+            refund: BuildingService::cancel_refund(&game, &building_type, &levels),
+            building_type,
+            slot: 1,
+            level: levels[0] - 1,
+            cancelled_levels: levels,
+        };
+        // A warehouse going to 3 with level 4 chained after it, and a granary going to 2
+        let cancelled = vec![
+            cancel(BuildingType::Warehouse, vec![3, 4]),
+            cancel(BuildingType::Granary, vec![2]),
+        ];
+
+        let refund = BuildingService::total_refund(&cancelled);
+
+        let paid = [
+            BuildingType::Warehouse.cost_at_level(3),
+            BuildingType::Warehouse.cost_at_level(4),
+            BuildingType::Granary.cost_at_level(2),
+        ];
+        assert_eq!(refund.wood, paid.iter().map(|c| c.wood * 50 / 100).sum::<i32>());
+        assert_eq!(refund.clay, paid.iter().map(|c| c.clay * 50 / 100).sum::<i32>());
+        assert_eq!(refund.iron, paid.iter().map(|c| c.iron * 50 / 100).sum::<i32>());
+        assert_eq!(refund.crop, paid.iter().map(|c| c.crop * 50 / 100).sum::<i32>());
+    }
+
+    #[test]
+    fn barracks_training_troops_cannot_be_demolished_without_a_replacement() {
+        let mut barracks = building(3, None);
+        barracks.building_type = BuildingType::Barracks;
+        let buildings = vec![barracks.clone(), building(5, None)];
+
+        assert!(matches!(
+            BuildingService::check_not_needed_for_training(&buildings, &barracks, 2),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(BuildingService::check_not_needed_for_training(&buildings, &barracks, 0).is_ok());
+    }
+
+    #[test]
+    fn barracks_training_troops_may_go_when_another_one_remains() {
+        let mut barracks = building(3, None);
+        barracks.building_type = BuildingType::Barracks;
+        let mut second = building(1, None);
+        second.building_type = BuildingType::Barracks;
+        let buildings = vec![barracks.clone(), second.clone()];
+
+        assert!(BuildingService::check_not_needed_for_training(&buildings, &barracks, 2).is_ok());
+
+        // A replacement still under construction at level 0 cannot take over
+        second.level = 0;
+        let buildings = vec![barracks.clone(), second];
+        assert!(BuildingService::check_not_needed_for_training(&buildings, &barracks, 2).is_err());
     }
 }
@@ -0,0 +1,277 @@
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::village::VillageResponse;
+
+pub struct CacheService;
+
+impl CacheService {
+    fn village_overview_key(village_id: Uuid) -> String {
+        format!("village:{}:overview", village_id)
+    }
+
+    /// Get a cached village overview. Redis errors are treated as a cache miss.
+    pub async fn get_village_overview(
+        redis: &ConnectionManager,
+        village_id: Uuid,
+    ) -> Option<VillageResponse> {
+        let mut conn = redis.clone();
+        let cached: Option<String> = match conn.get(Self::village_overview_key(village_id)).await {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Redis error reading village {} overview: {:?}", village_id, e);
+                return None;
+            }
+        };
+
+        cached.and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    /// Cache a village overview for `ttl_seconds` (0 disables caching).
+    /// Failures are logged and ignored.
+    pub async fn set_village_overview(
+        redis: &ConnectionManager,
+        response: &VillageResponse,
+        ttl_seconds: u64,
+    ) {
+        if ttl_seconds == 0 {
+            return;
+        }
+
+        let json = match serde_json::to_string(response) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize village {} overview: {:?}", response.id, e);
+                return;
+            }
+        };
+
+        let mut conn = redis.clone();
+        let result: redis::RedisResult<()> = conn
+            .set_ex(Self::village_overview_key(response.id), json, ttl_seconds)
+            .await;
+
+        if let Err(e) = result {
+            warn!("Redis error caching village {} overview: {:?}", response.id, e);
+        }
+    }
+
+    /// Drop the cached overview after a mutation to the village
+    pub async fn invalidate_village(redis: &ConnectionManager, village_id: Uuid) {
+        let mut conn = redis.clone();
+        let result: redis::RedisResult<()> = conn.del(Self::village_overview_key(village_id)).await;
+
+        if let Err(e) = result {
+            warn!("Redis error invalidating village {} overview: {:?}", village_id, e);
+        }
+    }
+
+    fn adventure_generation_lock_key(user_id: Uuid) -> String {
+        format!("lock:adventures:{}", user_id)
+    }
+
+    /// Take the player's adventure generation lock for `ttl_seconds`. Returns false
+    /// when someone else holds it or Redis is unavailable, so callers skip the work.
+    pub async fn lock_adventure_generation(
+        redis: &ConnectionManager,
+        user_id: Uuid,
+        ttl_seconds: u64,
+    ) -> bool {
+        let mut conn = redis.clone();
+        let acquired: redis::RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(Self::adventure_generation_lock_key(user_id))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds.max(1))
+            .query_async(&mut conn)
+            .await;
+
+        match acquired {
+            Ok(reply) => reply.is_some(),
+            Err(e) => {
+                warn!("Redis error locking adventure generation for {}: {:?}", user_id, e);
+                false
+            }
+        }
+    }
+
+    /// Release the adventure generation lock; it also expires on its own
+    pub async fn unlock_adventure_generation(redis: &ConnectionManager, user_id: Uuid) {
+        let mut conn = redis.clone();
+        let result: redis::RedisResult<()> =
+            conn.del(Self::adventure_generation_lock_key(user_id)).await;
+
+        if let Err(e) = result {
+            warn!("Redis error unlocking adventure generation for {}: {:?}", user_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::task::{JoinHandle, JoinSet};
+
+    use crate::test_support::village;
+
+    type Store = Arc<Mutex<HashMap<String, String>>>;
+
+    /// Minimal in-process Redis speaking just enough RESP for the cache:
+    /// GET, SET (with NX)/SETEX (expiry ignored) and DEL
+    async fn start_fake_redis() -> (ConnectionManager, Store, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let store = Store::default();
+
+        let server_store = store.clone();
+        let server = tokio::spawn(async move {
+            // Connections live in the set so aborting the server drops them too
+            let mut connections = JoinSet::new();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                connections.spawn(serve_connection(socket, server_store.clone()));
+            }
+        });
+
+        // A single quick reconnect attempt keeps the outage test fast
+        let client = redis::Client::open(format!("redis://{}", addr)).unwrap();
+        let manager = ConnectionManager::new_with_backoff(client, 2, 10, 1).await.unwrap();
+        (manager, store, server)
+    }
+
+    async fn serve_connection(socket: TcpStream, store: Store) {
+        let mut reader = BufReader::new(socket);
+        while let Some(args) = read_command(&mut reader).await {
+            let reply = match args[0].to_ascii_uppercase().as_str() {
+                "GET" => match store.lock().unwrap().get(&args[1]) {
+                    Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+                    None => "$-1\r\n".to_string(),
+                },
+                "SET" => {
+                    let mut store = store.lock().unwrap();
+                    let only_if_absent = args[3..].iter().any(|arg| arg.eq_ignore_ascii_case("NX"));
+                    if only_if_absent && store.contains_key(&args[1]) {
+                        "$-1\r\n".to_string()
+                    } else {
+                        store.insert(args[1].clone(), args[2].clone());
+                        "+OK\r\n".to_string()
+                    }
+                }
+                "SETEX" => {
+                    store.lock().unwrap().insert(args[1].clone(), args[3].clone());
+                    "+OK\r\n".to_string()
+                }
+                "DEL" => {
+                    let mut store = store.lock().unwrap();
+                    let removed = args[1..].iter().filter(|key| store.remove(*key).is_some()).count();
+                    format!(":{}\r\n", removed)
+                }
+                _ => "+OK\r\n".to_string(),
+            };
+            if reader.get_mut().write_all(reply.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok().filter(|n| *n > 0)?;
+        let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+            let mut buf = vec![0; len + 2];
+            reader.read_exact(&mut buf).await.ok()?;
+            buf.truncate(len);
+            args.push(String::from_utf8(buf).ok()?);
+        }
+        Some(args)
+    }
+
+    #[tokio::test]
+    async fn cached_overview_is_served_until_invalidated() {
+        let (redis, store, _server) = start_fake_redis().await;
+        let overview = VillageResponse::from(village(500, 1000));
+
+        assert!(CacheService::get_village_overview(&redis, overview.id).await.is_none());
+
+        CacheService::set_village_overview(&redis, &overview, 15).await;
+        let cached = CacheService::get_village_overview(&redis, overview.id).await.unwrap();
+        assert_eq!(cached.id, overview.id);
+        assert_eq!(cached.wood, 500);
+
+        CacheService::invalidate_village(&redis, overview.id).await;
+        assert!(CacheService::get_village_overview(&redis, overview.id).await.is_none());
+        assert!(store.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalidation_only_drops_the_mutated_village() {
+        let (redis, _store, _server) = start_fake_redis().await;
+        let mutated = VillageResponse::from(village(500, 1000));
+        let untouched = VillageResponse::from(village(800, 1000));
+
+        CacheService::set_village_overview(&redis, &mutated, 15).await;
+        CacheService::set_village_overview(&redis, &untouched, 15).await;
+        CacheService::invalidate_village(&redis, mutated.id).await;
+
+        assert!(CacheService::get_village_overview(&redis, mutated.id).await.is_none());
+        assert!(CacheService::get_village_overview(&redis, untouched.id).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn cache_fails_open_when_redis_is_down() {
+        let (redis, _store, server) = start_fake_redis().await;
+        let overview = VillageResponse::from(village(500, 1000));
+        CacheService::set_village_overview(&redis, &overview, 15).await;
+
+        server.abort();
+        let _ = server.await;
+
+        // Every call degrades to a miss or a logged no-op instead of an error
+        assert!(CacheService::get_village_overview(&redis, overview.id).await.is_none());
+        CacheService::set_village_overview(&redis, &overview, 15).await;
+        CacheService::invalidate_village(&redis, overview.id).await;
+        assert!(CacheService::get_village_overview(&redis, overview.id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_disables_the_overview_cache() {
+        let (redis, store, _server) = start_fake_redis().await;
+        let overview = VillageResponse::from(village(500, 1000));
+
+        CacheService::set_village_overview(&redis, &overview, 0).await;
+
+        assert!(CacheService::get_village_overview(&redis, overview.id).await.is_none());
+        assert!(store.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_adventure_generation_gets_the_lock_once() {
+        let (redis, _store, _server) = start_fake_redis().await;
+        let user_id = Uuid::new_v4();
+
+        let (first, second) = tokio::join!(
+            CacheService::lock_adventure_generation(&redis, user_id, 30),
+            CacheService::lock_adventure_generation(&redis, user_id, 30),
+        );
+        assert!(first ^ second, "exactly one generation may run");
+
+        // Another player's generation is not held up
+        assert!(CacheService::lock_adventure_generation(&redis, Uuid::new_v4(), 30).await);
+
+        CacheService::unlock_adventure_generation(&redis, user_id).await;
+        assert!(CacheService::lock_adventure_generation(&redis, user_id, 30).await);
+    }
+}
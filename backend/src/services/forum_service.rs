@@ -0,0 +1,257 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::alliance::AlliancePermission;
+use crate::models::forum::{
+    CreatePostRequest, CreateThreadRequest, ForumPostResponse, ForumThread, ForumThreadListItem,
+    ForumThreadResponse,
+};
+use crate::repositories::forum_repo::ForumRepository;
+use crate::services::alliance_service::AllianceService;
+
+pub struct ForumService;
+
+impl ForumService {
+    // ==================== Threads ====================
+
+    /// Start a new thread with its opening post
+    pub async fn create_thread(
+        pool: &PgPool,
+        user_id: Uuid,
+        alliance_id: Uuid,
+        request: CreateThreadRequest,
+    ) -> AppResult<ForumThreadListItem> {
+        let title = Self::validate_thread(&request)?;
+
+        AllianceService::require_permission(pool, alliance_id, user_id, AlliancePermission::ForumPost)
+            .await?;
+
+        // A thread never exists without its opening post
+        let mut tx = pool.begin().await?;
+        let thread = ForumRepository::create_thread(&mut *tx, alliance_id, user_id, title).await?;
+        ForumRepository::create_post(&mut tx, thread.id, user_id, &request.body).await?;
+        tx.commit().await?;
+
+        ForumRepository::get_thread_item(pool, thread.id)
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to fetch created thread")))
+    }
+
+    /// List threads of an alliance (members only)
+    pub async fn list_threads(
+        pool: &PgPool,
+        user_id: Uuid,
+        alliance_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<Vec<ForumThreadListItem>> {
+        AllianceService::require_permission(pool, alliance_id, user_id, AlliancePermission::ForumPost)
+            .await?;
+
+        let limit = limit.clamp(1, 50);
+        ForumRepository::list_threads(pool, alliance_id, limit, offset.max(0)).await
+    }
+
+    /// Get a thread with a page of its posts (members only)
+    pub async fn get_thread(
+        pool: &PgPool,
+        user_id: Uuid,
+        alliance_id: Uuid,
+        thread_id: Uuid,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<ForumThreadResponse> {
+        AllianceService::require_permission(pool, alliance_id, user_id, AlliancePermission::ForumPost)
+            .await?;
+        Self::find_alliance_thread(pool, alliance_id, thread_id).await?;
+
+        let thread = ForumRepository::get_thread_item(pool, thread_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Thread", thread_id))?;
+
+        let limit = limit.clamp(1, 50);
+        let posts = ForumRepository::list_posts(pool, thread_id, limit, offset.max(0)).await?;
+
+        Ok(ForumThreadResponse { thread, posts })
+    }
+
+    /// Pin or unpin a thread (officers and leader)
+    pub async fn set_pinned(
+        pool: &PgPool,
+        user_id: Uuid,
+        alliance_id: Uuid,
+        thread_id: Uuid,
+        pinned: bool,
+    ) -> AppResult<()> {
+        AllianceService::require_permission(
+            pool,
+            alliance_id,
+            user_id,
+            AlliancePermission::PinForumThread,
+        )
+        .await?;
+        Self::find_alliance_thread(pool, alliance_id, thread_id).await?;
+
+        ForumRepository::set_pinned(pool, thread_id, pinned).await
+    }
+
+    // ==================== Posts ====================
+
+    /// Reply to a thread
+    pub async fn create_post(
+        pool: &PgPool,
+        user_id: Uuid,
+        alliance_id: Uuid,
+        thread_id: Uuid,
+        request: CreatePostRequest,
+    ) -> AppResult<ForumPostResponse> {
+        Self::validate_body(&request.body)?;
+
+        AllianceService::require_permission(pool, alliance_id, user_id, AlliancePermission::ForumPost)
+            .await?;
+        Self::find_alliance_thread(pool, alliance_id, thread_id).await?;
+
+        let mut tx = pool.begin().await?;
+        let post_id =
+            ForumRepository::create_post(&mut tx, thread_id, user_id, &request.body).await?;
+        tx.commit().await?;
+
+        ForumRepository::get_post(pool, post_id)
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to fetch created post")))
+    }
+
+    // ==================== Helpers ====================
+
+    /// Load a thread, treating threads of other alliances as missing
+    async fn find_alliance_thread(
+        pool: &PgPool,
+        alliance_id: Uuid,
+        thread_id: Uuid,
+    ) -> AppResult<ForumThread> {
+        let thread = ForumRepository::find_thread(pool, thread_id).await?;
+        Self::thread_of_alliance(thread, alliance_id, thread_id)
+    }
+
+    fn thread_of_alliance(
+        thread: Option<ForumThread>,
+        alliance_id: Uuid,
+        thread_id: Uuid,
+    ) -> AppResult<ForumThread> {
+        thread
+            .filter(|t| t.alliance_id == alliance_id)
+            .ok_or_else(|| AppError::not_found("Thread", thread_id))
+    }
+
+    /// Trimmed title of a new thread, which also needs a non-empty opening post
+    fn validate_thread(request: &CreateThreadRequest) -> AppResult<&str> {
+        let title = request.title.trim();
+        if title.is_empty() {
+            return Err(AppError::BadRequest("Title cannot be empty".into()));
+        }
+        if title.chars().count() > 200 {
+            return Err(AppError::BadRequest("Title cannot exceed 200 characters".into()));
+        }
+        Self::validate_body(&request.body)?;
+
+        Ok(title)
+    }
+
+    fn validate_body(body: &str) -> AppResult<()> {
+        if body.trim().is_empty() {
+            return Err(AppError::BadRequest("Post body cannot be empty".into()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::alliance::{AllianceMember, AllianceRole};
+
+    fn member(role: AllianceRole) -> AllianceMember {
+        AllianceMember {
+            alliance_id: Uuid::new_v4(),
+            role,
+        }
+    }
+
+    #[test]
+    fn thread_title_is_trimmed_and_needs_an_opening_post() {
+        let request = CreateThreadRequest {
+            title: "  Attack plan  ".to_string(),
+            body: "Everyone hits at 20:00".to_string(),
+        };
+        assert_eq!(ForumService::validate_thread(&request).unwrap(), "Attack plan");
+
+        let no_body = CreateThreadRequest {
+            title: "Attack plan".to_string(),
+            body: "   ".to_string(),
+        };
+        assert!(matches!(ForumService::validate_thread(&no_body), Err(AppError::BadRequest(_))));
+
+        let long_title = CreateThreadRequest {
+            title: "x".repeat(201),
+            body: "text".to_string(),
+        };
+        assert!(ForumService::validate_thread(&long_title).is_err());
+
+        // The limit counts characters, not bytes: 200 Thai characters are 600 bytes
+        let thai_title = CreateThreadRequest {
+            title: "ก".repeat(200),
+            body: "text".to_string(),
+        };
+        assert!(ForumService::validate_thread(&thai_title).is_ok());
+    }
+
+    #[test]
+    fn reply_needs_a_body_and_a_thread_of_the_same_alliance() {
+        assert!(ForumService::validate_body("On my way").is_ok());
+        assert!(ForumService::validate_body(" \n ").is_err());
+
+        let alliance_id = Uuid::new_v4();
+        let thread = ForumThread {
+            id: Uuid::new_v4(),
+            alliance_id,
+        };
+        assert!(ForumService::thread_of_alliance(Some(thread.clone()), alliance_id, thread.id).is_ok());
+        // Another alliance's thread looks missing rather than forbidden
+        assert!(matches!(
+            ForumService::thread_of_alliance(Some(thread.clone()), Uuid::new_v4(), thread.id),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn only_members_may_read_and_post() {
+        assert!(matches!(
+            AllianceService::check_member_permission(None, AlliancePermission::ForumPost),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(AllianceService::check_member_permission(
+            Some(member(AllianceRole::Member)),
+            AlliancePermission::ForumPost
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn only_officers_and_the_leader_pin_threads() {
+        assert!(matches!(
+            AllianceService::check_member_permission(
+                Some(member(AllianceRole::Member)),
+                AlliancePermission::PinForumThread
+            ),
+            Err(AppError::Forbidden(_))
+        ));
+        for role in [AllianceRole::Officer, AllianceRole::Leader] {
+            assert!(AllianceService::check_member_permission(
+                Some(member(role)),
+                AlliancePermission::PinForumThread
+            )
+            .is_ok());
+        }
+    }
+}
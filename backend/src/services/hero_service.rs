@@ -1,38 +1,86 @@
-use chrono::{Duration, Utc};
-use rand::Rng;
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use redis::aio::ConnectionManager;
+use rand::{Rng, SeedableRng};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::{AppError, AppResult};
 use crate::models::hero::{
-    AdventureDifficulty, AssignAttributesRequest, AvailableAdventureResponse, CreateHeroRequest,
-    EquippedItemsResponse, Hero, HeroAdventureResponse, HeroItemResponse, HeroListResponse,
-    HeroResponse, HeroSlotPurchaseResponse, HeroStatus, InventoryResponse, ItemDefinitionResponse,
-    ItemRarity, ItemSlot, ReviveInfoResponse, ReviveResourceCost,
+    AdventureDifficulty, AssignAttributesRequest, AvailableAdventure, AvailableAdventureResponse,
+    BulkSellItemsRequest, BulkSellItemsResponse, CreateHeroRequest, EquippedItemsResponse, Hero,
+    HeroAdventure, HeroAdventureResponse, HeroItem, HeroItemResponse, HeroListResponse,
+    HeroResponse, HeroSlotPurchaseResponse, HeroStatus, InventoryResponse, ItemDefinition,
+    ItemDefinitionResponse, ItemRarity, ItemSlot, ReviveInfoResponse, ReviveMethod,
+    ReviveResourceCost, SellItemResponse, TransferHeroRequest,
 };
+use crate::models::village::Village;
 use crate::repositories::hero_repo::HeroRepository;
 use crate::repositories::shop_repo::ShopRepository;
+use crate::repositories::user_repo::UserRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::cache_service::CacheService;
+use crate::services::resource_service::ResourceService;
 
 pub struct HeroService;
 
+/// Most finished adventures returned by the adventure history
+const ADVENTURE_HISTORY_LIMIT: i32 = 20;
+
+/// A rolled adventure offer, ready to be stored
+#[derive(Debug)]
+struct AdventureOffer {
+    difficulty: AdventureDifficulty,
+    min_duration: i32,
+    max_duration: i32,
+    reward_type: Option<&'static str>,
+    item_rarity: Option<ItemRarity>,
+    expires_at: DateTime<Utc>,
+}
+
+/// Random outcome of a finished adventure, rolled before any database work
+struct AdventureRewards {
+    base_exp: i32,
+    base_silver: i32,
+    health_damage: i32,
+    resources: serde_json::Value,
+    item_rarity: Option<ItemRarity>,
+    item_index_seed: usize,
+}
+
 impl HeroService {
+    // ==================== Reference Data ====================
+
+    /// Get every item definition
+    pub async fn get_item_definitions(pool: &PgPool) -> AppResult<Vec<ItemDefinitionResponse>> {
+        let items = HeroRepository::get_all_items(pool).await?;
+        Ok(Self::item_definition_responses(items))
+    }
+
+    /// Public shape of the item definitions, in the order they were loaded
+    fn item_definition_responses(items: Vec<ItemDefinition>) -> Vec<ItemDefinitionResponse> {
+        items.into_iter().map(|i| i.into()).collect()
+    }
+
     // ==================== Hero CRUD ====================
 
     /// Get all heroes for a user
-    pub async fn get_user_heroes(pool: &PgPool, user_id: Uuid) -> AppResult<HeroListResponse> {
+    pub async fn get_user_heroes(
+        pool: &PgPool,
+        user_id: Uuid,
+        max_slots: i32,
+    ) -> AppResult<HeroListResponse> {
         let heroes = HeroRepository::get_user_heroes(pool, user_id).await?;
         let total_slots = HeroRepository::get_user_slots(pool, user_id).await?;
         let used_slots = heroes.len() as i32;
 
-        // Get next slot cost
-        let next_slot = used_slots + 1;
-        let next_slot_cost = if next_slot <= 5 {
-            HeroRepository::get_slot_price(pool, next_slot)
+        // Get cost of the next purchasable slot
+        let next_slot_cost = match Self::next_purchasable_slot(total_slots, max_slots) {
+            Some(next_slot) => HeroRepository::get_slot_price(pool, next_slot)
                 .await?
-                .map(|p| p.gold_cost)
-        } else {
-            None
+                .map(|p| p.gold_cost),
+            None => None,
         };
 
         Ok(HeroListResponse {
@@ -40,14 +88,21 @@ impl HeroService {
             total_slots,
             used_slots,
             next_slot_cost,
+            silver_balance: ShopRepository::get_silver_balance(pool, user_id).await?,
         })
     }
 
+    /// The slot a player owning `total_slots` would buy next, if still under the maximum
+    fn next_purchasable_slot(total_slots: i32, max_slots: i32) -> Option<i32> {
+        let next_slot = total_slots + 1;
+        (next_slot <= max_slots).then_some(next_slot)
+    }
+
     /// Get hero by ID
     pub async fn get_hero(pool: &PgPool, user_id: Uuid, hero_id: Uuid) -> AppResult<HeroResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -59,6 +114,8 @@ impl HeroService {
     /// Create a new hero
     pub async fn create_hero(
         pool: &PgPool,
+        redis: &ConnectionManager,
+        game: &GameConfig,
         user_id: Uuid,
         request: CreateHeroRequest,
     ) -> AppResult<HeroResponse> {
@@ -73,7 +130,7 @@ impl HeroService {
         // Verify village ownership
         let village = VillageRepository::find_by_id(pool, request.home_village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", request.home_village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Village does not belong to you".into()));
@@ -94,7 +151,7 @@ impl HeroService {
         .await?;
 
         // Generate initial adventures
-        Self::generate_adventures(pool, user_id).await?;
+        Self::generate_adventures(pool, redis, game, user_id).await?;
 
         Ok(hero.into())
     }
@@ -108,7 +165,7 @@ impl HeroService {
     ) -> AppResult<HeroResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -117,7 +174,7 @@ impl HeroService {
         // Verify village ownership
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Village does not belong to you".into()));
@@ -127,6 +184,91 @@ impl HeroService {
         Ok(hero.into())
     }
 
+    /// Gift a hero to another player. The hero must be idle and alive, and the
+    /// recipient needs a free hero slot; equipped and inventory items go along.
+    pub async fn transfer_hero(
+        pool: &PgPool,
+        user_id: Uuid,
+        hero_id: Uuid,
+        request: TransferHeroRequest,
+    ) -> AppResult<HeroResponse> {
+        let hero = HeroRepository::find_by_id(pool, hero_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+
+        Self::check_transferable(&hero, user_id, request.recipient_id)?;
+
+        UserRepository::find_by_id(pool, request.recipient_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User", request.recipient_id))?;
+
+        let total_slots = HeroRepository::get_user_slots(pool, request.recipient_id).await?;
+        let recipient_heroes = HeroRepository::get_user_heroes(pool, request.recipient_id).await?;
+        let slot_number = Self::free_hero_slot(total_slots, &recipient_heroes)?;
+
+        let villages = VillageRepository::find_by_user_id(pool, request.recipient_id).await?;
+        let home_id = Self::transfer_home(&villages, request.village_id)?;
+
+        let hero = HeroRepository::transfer_owner(
+            pool,
+            hero_id,
+            user_id,
+            request.recipient_id,
+            slot_number,
+            home_id,
+        )
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Hero is no longer available".into()))?;
+
+        Ok(hero.into())
+    }
+
+    /// Only the owner may hand over a living hero that is idle at home, and not to themselves
+    fn check_transferable(hero: &Hero, user_id: Uuid, recipient_id: Uuid) -> AppResult<()> {
+        if hero.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        if recipient_id == user_id {
+            return Err(AppError::BadRequest("Cannot transfer a hero to yourself".into()));
+        }
+
+        if hero.is_dead() {
+            return Err(AppError::BadRequest("Cannot transfer a dead hero".into()));
+        }
+
+        if !hero.is_available() {
+            return Err(AppError::BadRequest(
+                "Hero must be idle at home to be transferred".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Lowest slot number the recipient is not using yet
+    fn free_hero_slot(total_slots: i32, heroes: &[Hero]) -> AppResult<i32> {
+        (1..=total_slots)
+            .find(|slot| !heroes.iter().any(|h| h.slot_number == *slot))
+            .ok_or_else(|| AppError::BadRequest("Recipient has no free hero slot".into()))
+    }
+
+    /// The recipient village chosen for the hero, or their first village
+    fn transfer_home(villages: &[Village], village_id: Option<Uuid>) -> AppResult<Uuid> {
+        let home = match village_id {
+            Some(village_id) => villages
+                .iter()
+                .find(|v| v.id == village_id)
+                .ok_or_else(|| {
+                    AppError::BadRequest("Village does not belong to the recipient".into())
+                })?,
+            None => villages
+                .first()
+                .ok_or_else(|| AppError::BadRequest("Recipient has no village".into()))?,
+        };
+        Ok(home.id)
+    }
+
     /// Assign attribute points
     pub async fn assign_attributes(
         pool: &PgPool,
@@ -136,12 +278,64 @@ impl HeroService {
     ) -> AppResult<HeroResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
+        let total_points = Self::validate_attribute_points(&hero, &request)?;
+
+        let hero = HeroRepository::assign_attributes(
+            pool,
+            hero_id,
+            request.fighting_strength,
+            request.off_bonus,
+            request.def_bonus,
+            request.resources_bonus,
+            total_points,
+        )
+        .await?;
+
+        Ok(hero.into())
+    }
+
+    /// Preview attribute assignment without persisting it
+    pub async fn preview_attributes(
+        pool: &PgPool,
+        user_id: Uuid,
+        hero_id: Uuid,
+        request: AssignAttributesRequest,
+    ) -> AppResult<HeroResponse> {
+        let hero = HeroRepository::find_by_id(pool, hero_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+
+        if hero.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        Ok(Self::apply_attribute_points(hero, &request)?.into())
+    }
+
+    /// Apply the same deltas as HeroRepository::assign_attributes, in memory only
+    fn apply_attribute_points(
+        mut hero: Hero,
+        request: &AssignAttributesRequest,
+    ) -> AppResult<Hero> {
+        let total_points = Self::validate_attribute_points(&hero, request)?;
+
+        hero.fighting_strength += request.fighting_strength;
+        hero.off_bonus += request.off_bonus;
+        hero.def_bonus += request.def_bonus;
+        hero.resources_bonus += request.resources_bonus;
+        hero.unassigned_points -= total_points;
+
+        Ok(hero)
+    }
+
+    /// Validate an attribute assignment, returning the total points spent
+    fn validate_attribute_points(hero: &Hero, request: &AssignAttributesRequest) -> AppResult<i32> {
         // Calculate total points being spent
         let total_points =
             request.fighting_strength + request.off_bonus + request.def_bonus + request.resources_bonus;
@@ -163,35 +357,27 @@ impl HeroService {
             return Err(AppError::BadRequest("Cannot assign negative points".into()));
         }
 
-        let hero = HeroRepository::assign_attributes(
-            pool,
-            hero_id,
-            request.fighting_strength,
-            request.off_bonus,
-            request.def_bonus,
-            request.resources_bonus,
-            total_points,
-        )
-        .await?;
-
-        Ok(hero.into())
+        Ok(total_points)
     }
 
     // ==================== Hero Slots ====================
 
     /// Buy additional hero slot with gold
-    pub async fn buy_hero_slot(pool: &PgPool, user_id: Uuid) -> AppResult<HeroSlotPurchaseResponse> {
+    pub async fn buy_hero_slot(
+        pool: &PgPool,
+        user_id: Uuid,
+        max_slots: i32,
+    ) -> AppResult<HeroSlotPurchaseResponse> {
         let current_slots = HeroRepository::get_user_slots(pool, user_id).await?;
-        let next_slot = current_slots + 1;
-
-        if next_slot > 5 {
-            return Err(AppError::BadRequest("Maximum hero slots reached".into()));
-        }
+        let next_slot = Self::next_purchasable_slot(current_slots, max_slots)
+            .ok_or_else(|| AppError::BadRequest("Maximum hero slots reached".into()))?;
 
         // Get price
         let price = HeroRepository::get_slot_price(pool, next_slot)
             .await?
-            .ok_or_else(|| AppError::BadRequest("Invalid slot".into()))?;
+            .ok_or_else(|| {
+                AppError::BadRequest(format!("No price configured for slot {}", next_slot))
+            })?;
 
         // Check gold balance
         let balance = ShopRepository::get_gold_balance(pool, user_id).await?;
@@ -251,7 +437,7 @@ impl HeroService {
     ) -> AppResult<InventoryResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -327,12 +513,14 @@ impl HeroService {
     ) -> AppResult<HeroItemResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
+        Self::ensure_loadout_changeable(&hero)?;
+
         // Get item
         let (hero_item, item_def) = HeroRepository::get_hero_item(pool, item_id)
             .await?
@@ -354,6 +542,14 @@ impl HeroService {
             )));
         }
 
+        // Tribe-restricted items only fit heroes of that tribe
+        if !item_def.is_usable_by(hero.tribe) {
+            return Err(AppError::BadRequest(format!(
+                "Item can only be equipped by {:?} heroes",
+                item_def.allowed_tribe.unwrap_or(hero.tribe)
+            )));
+        }
+
         // Consumables cannot be equipped
         if item_def.is_consumable {
             return Err(AppError::BadRequest("Consumables cannot be equipped".into()));
@@ -364,6 +560,7 @@ impl HeroService {
 
         // Equip new item
         let equipped = HeroRepository::equip_item(pool, item_id, item_def.slot).await?;
+        HeroRepository::recalculate_health_regen(&mut *pool.acquire().await?, hero_id).await?;
 
         Ok(HeroItemResponse {
             id: equipped.id,
@@ -384,16 +581,32 @@ impl HeroService {
     ) -> AppResult<()> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
+        Self::ensure_loadout_changeable(&hero)?;
+
         HeroRepository::unequip_slot(pool, hero_id, slot).await?;
+        HeroRepository::recalculate_health_regen(&mut *pool.acquire().await?, hero_id).await?;
         Ok(())
     }
 
+    /// Equipment can only change while the hero is idle; a dead, moving or
+    /// adventuring hero is away and keeps its loadout
+    fn ensure_loadout_changeable(hero: &Hero) -> AppResult<()> {
+        if hero.status == HeroStatus::Idle && !hero.is_dead() {
+            return Ok(());
+        }
+
+        Err(AppError::BadRequest(format!(
+            "Cannot change items while the hero is {:?}",
+            hero.status
+        )))
+    }
+
     /// Use consumable item
     pub async fn use_item(
         pool: &PgPool,
@@ -403,12 +616,14 @@ impl HeroService {
     ) -> AppResult<HeroResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
+        Self::ensure_loadout_changeable(&hero)?;
+
         // Get item
         let (hero_item, item_def) = HeroRepository::get_hero_item(pool, item_id)
             .await?
@@ -441,10 +656,10 @@ impl HeroService {
         user_id: Uuid,
         hero_id: Uuid,
         item_id: Uuid,
-    ) -> AppResult<i32> {
+    ) -> AppResult<SellItemResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -465,12 +680,100 @@ impl HeroService {
 
         let sell_value = item_def.sell_value * hero_item.quantity;
 
-        // Delete item
-        HeroRepository::delete_item(pool, item_id).await?;
+        // Remove the item and pay for it together so neither can happen alone
+        let mut tx = pool.begin().await?;
+        let sold = HeroRepository::delete_unequipped_items(&mut *tx, hero_id, &[item_id]).await?;
+        if sold.is_empty() {
+            return Err(AppError::BadRequest("Cannot sell equipped items".into()));
+        }
+        let silver_balance = ShopRepository::add_silver(&mut *tx, user_id, sell_value).await?;
+        tx.commit().await?;
 
-        // TODO: Add silver to user's balance
+        Ok(SellItemResponse {
+            success: true,
+            silver_gained: sell_value,
+            silver_balance,
+        })
+    }
 
-        Ok(sell_value)
+    /// Sell many items at once: the listed ids and/or everything up to a rarity.
+    /// Equipped items are skipped; listed ids must belong to the hero.
+    pub async fn sell_items(
+        pool: &PgPool,
+        user_id: Uuid,
+        hero_id: Uuid,
+        request: BulkSellItemsRequest,
+    ) -> AppResult<BulkSellItemsResponse> {
+        if request.item_ids.is_none() && request.max_rarity.is_none() {
+            return Err(AppError::BadRequest(
+                "Provide item_ids or max_rarity to select items to sell".into(),
+            ));
+        }
+
+        let hero = HeroRepository::find_by_id(pool, hero_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+
+        if hero.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        let inventory = HeroRepository::get_hero_items(pool, hero_id).await?;
+
+        if let Some(ids) = &request.item_ids {
+            if let Some(missing) = ids
+                .iter()
+                .find(|id| !inventory.iter().any(|(i, _)| i.id == **id))
+            {
+                return Err(AppError::Forbidden(format!(
+                    "Item {} does not belong to this hero",
+                    missing
+                )));
+            }
+        }
+
+        let selected = Self::items_for_sale(&inventory, &request);
+
+        if selected.is_empty() {
+            return Ok(BulkSellItemsResponse {
+                items_sold: 0,
+                silver_gained: 0,
+                silver_balance: ShopRepository::get_silver_balance(pool, user_id).await?,
+            });
+        }
+
+        let ids: Vec<Uuid> = selected.iter().map(|(item, _)| item.id).collect();
+        let mut tx = pool.begin().await?;
+        let sold = HeroRepository::delete_unequipped_items(&mut *tx, hero_id, &ids).await?;
+
+        // Only count what was actually deleted; an item equipped meanwhile stays
+        let silver_gained = selected
+            .iter()
+            .filter(|(item, _)| sold.contains(&item.id))
+            .map(|(item, def)| def.sell_value * item.quantity)
+            .sum();
+
+        let silver_balance = ShopRepository::add_silver(&mut *tx, user_id, silver_gained).await?;
+        tx.commit().await?;
+
+        Ok(BulkSellItemsResponse {
+            items_sold: sold.len() as i32,
+            silver_gained,
+            silver_balance,
+        })
+    }
+
+    /// Unequipped items matching a bulk sell request's ids and rarity cap
+    fn items_for_sale<'a>(
+        inventory: &'a [(HeroItem, ItemDefinition)],
+        request: &BulkSellItemsRequest,
+    ) -> Vec<&'a (HeroItem, ItemDefinition)> {
+        inventory
+            .iter()
+            .filter(|(item, _)| !item.is_equipped)
+            .filter(|(item, _)| request.item_ids.as_ref().is_none_or(|ids| ids.contains(&item.id)))
+            .filter(|(_, def)| request.max_rarity.is_none_or(|max| def.rarity <= max))
+            .collect()
     }
 
     // ==================== Adventures ====================
@@ -478,92 +781,85 @@ impl HeroService {
     /// Get available adventures
     pub async fn get_available_adventures(
         pool: &PgPool,
+        game: &GameConfig,
         user_id: Uuid,
     ) -> AppResult<Vec<AvailableAdventureResponse>> {
         let adventures = HeroRepository::get_available_adventures(pool, user_id).await?;
-        Ok(adventures.into_iter().map(|a| a.into()).collect())
+        Ok(Self::adventure_responses(game, adventures))
     }
 
-    /// Generate new adventures for user
-    pub async fn generate_adventures(pool: &PgPool, user_id: Uuid) -> AppResult<()> {
-        let now = Utc::now();
+    /// Offers as shown to the player, each with its configured level requirement
+    fn adventure_responses(
+        game: &GameConfig,
+        adventures: Vec<AvailableAdventure>,
+    ) -> Vec<AvailableAdventureResponse> {
+        adventures
+            .into_iter()
+            .map(|a| {
+                let required_level = Self::adventure_required_level(game, &a.difficulty);
+                AvailableAdventureResponse::new(a, required_level)
+            })
+            .collect()
+    }
 
-        // Pre-generate all random values (scope RNG so it's dropped before await)
-        struct AdventureParams {
-            difficulty: AdventureDifficulty,
-            min_duration: i32,
-            max_duration: i32,
-            reward_type: Option<&'static str>,
-            item_rarity: Option<ItemRarity>,
-            expires_at: chrono::DateTime<Utc>,
-        }
-
-        let adventures: Vec<AdventureParams> = {
-            let mut rng = rand::thread_rng();
-            let mut params = Vec::new();
-
-            // Generate 3-5 short adventures
-            let short_count = rng.gen_range(3..=5);
-            for _ in 0..short_count {
-                let min_duration = rng.gen_range(30..60) * 60;
-                let max_duration = min_duration + rng.gen_range(30..90) * 60;
-                let expires_at = now + Duration::hours(rng.gen_range(6..24));
-
-                let reward_type = match rng.gen_range(0..3) {
-                    0 => Some("resources"),
-                    1 => Some("silver"),
-                    _ => Some("item"),
-                };
-
-                let item_rarity = if reward_type == Some("item") {
-                    Some(match rng.gen_range(0..100) {
-                        0..=60 => ItemRarity::Common,
-                        61..=85 => ItemRarity::Uncommon,
-                        86..=95 => ItemRarity::Rare,
-                        _ => ItemRarity::Epic,
-                    })
-                } else {
-                    None
-                };
-
-                params.push(AdventureParams {
-                    difficulty: AdventureDifficulty::Short,
-                    min_duration,
-                    max_duration,
-                    reward_type,
-                    item_rarity,
-                    expires_at,
-                });
-            }
+    /// Reject a hero that is already out on an adventure. The guard is per hero,
+    /// so a player's other heroes can still adventure at the same time.
+    fn check_no_active_adventure(hero_id: Uuid, active: &[HeroAdventure]) -> AppResult<()> {
+        if active.iter().any(|a| a.hero_id == hero_id) {
+            return Err(AppError::Conflict("Hero already has an active adventure".into()));
+        }
+        Ok(())
+    }
 
-            // Generate 1-2 long adventures
-            let long_count = rng.gen_range(1..=2);
-            for _ in 0..long_count {
-                let min_duration = rng.gen_range(8..12) * 3600;
-                let max_duration = min_duration + rng.gen_range(2..4) * 3600;
-                let expires_at = now + Duration::hours(rng.gen_range(12..48));
-
-                let item_rarity = Some(match rng.gen_range(0..100) {
-                    0..=30 => ItemRarity::Uncommon,
-                    31..=60 => ItemRarity::Rare,
-                    61..=85 => ItemRarity::Epic,
-                    _ => ItemRarity::Legendary,
-                });
-
-                params.push(AdventureParams {
-                    difficulty: AdventureDifficulty::Long,
-                    min_duration,
-                    max_duration,
-                    reward_type: Some("item"),
-                    item_rarity,
-                    expires_at,
-                });
-            }
+    /// Minimum hero level for an adventure difficulty
+    fn adventure_required_level(game: &GameConfig, difficulty: &AdventureDifficulty) -> i32 {
+        match difficulty {
+            AdventureDifficulty::Short => game.adventure_min_level_short,
+            AdventureDifficulty::Long => game.adventure_min_level_long,
+        }
+    }
 
-            params
-        };
+    /// Whether a hero of `level` may take an adventure of this difficulty
+    fn meets_adventure_level(
+        game: &GameConfig,
+        level: i32,
+        difficulty: &AdventureDifficulty,
+    ) -> bool {
+        level >= Self::adventure_required_level(game, difficulty)
+    }
+
+    /// Generate new adventures for user. Runs under a short per-player Redis lock
+    /// so concurrent triggers cannot over-generate; if the lock is held (or Redis
+    /// is down) generation is skipped.
+    pub async fn generate_adventures(
+        pool: &PgPool,
+        redis: &ConnectionManager,
+        game: &GameConfig,
+        user_id: Uuid,
+    ) -> AppResult<()> {
+        if !CacheService::lock_adventure_generation(
+            redis,
+            user_id,
+            game.adventure_generation_lock_seconds,
+        )
+        .await
+        {
+            tracing::info!(
+                "Adventure generation for user {} already in progress, skipping",
+                user_id
+            );
+            return Ok(());
+        }
+
+        let result = Self::create_adventures(pool, game, user_id).await;
+        CacheService::unlock_adventure_generation(redis, user_id).await;
+        result
+    }
+
+    async fn create_adventures(pool: &PgPool, game: &GameConfig, user_id: Uuid) -> AppResult<()> {
+        // Roll everything up front so the RNG is dropped before the first await
+        let adventures = Self::roll_adventure_offers(&mut Self::adventure_rng(game), Utc::now());
 
-        // Now create adventures (RNG is dropped, safe to await)
         for adv in adventures {
             HeroRepository::create_available_adventure(
                 pool,
@@ -581,16 +877,82 @@ impl HeroService {
         Ok(())
     }
 
+    /// Roll a fresh set of adventure offers: 3-5 short ones and 1-2 long ones
+    fn roll_adventure_offers(rng: &mut impl Rng, now: DateTime<Utc>) -> Vec<AdventureOffer> {
+        let mut params = Vec::new();
+
+        // Generate 3-5 short adventures
+        let short_count = rng.gen_range(3..=5);
+        for _ in 0..short_count {
+            let min_duration = rng.gen_range(30..60) * 60;
+            let max_duration = min_duration + rng.gen_range(30..90) * 60;
+            let expires_at = now + Duration::hours(rng.gen_range(6..24));
+
+            let reward_type = match rng.gen_range(0..3) {
+                0 => Some("resources"),
+                1 => Some("silver"),
+                _ => Some("item"),
+            };
+
+            let item_rarity = if reward_type == Some("item") {
+                Some(match rng.gen_range(0..100) {
+                    0..=60 => ItemRarity::Common,
+                    61..=85 => ItemRarity::Uncommon,
+                    86..=95 => ItemRarity::Rare,
+                    _ => ItemRarity::Epic,
+                })
+            } else {
+                None
+            };
+
+            params.push(AdventureOffer {
+                difficulty: AdventureDifficulty::Short,
+                min_duration,
+                max_duration,
+                reward_type,
+                item_rarity,
+                expires_at,
+            });
+        }
+
+        // Generate 1-2 long adventures
+        let long_count = rng.gen_range(1..=2);
+        for _ in 0..long_count {
+            let min_duration = rng.gen_range(8..12) * 3600;
+            let max_duration = min_duration + rng.gen_range(2..4) * 3600;
+            let expires_at = now + Duration::hours(rng.gen_range(12..48));
+
+            let item_rarity = Some(match rng.gen_range(0..100) {
+                0..=30 => ItemRarity::Uncommon,
+                31..=60 => ItemRarity::Rare,
+                61..=85 => ItemRarity::Epic,
+                _ => ItemRarity::Legendary,
+            });
+
+            params.push(AdventureOffer {
+                difficulty: AdventureDifficulty::Long,
+                min_duration,
+                max_duration,
+                reward_type: Some("item"),
+                item_rarity,
+                expires_at,
+            });
+        }
+
+        params
+    }
+
     /// Start adventure
     pub async fn start_adventure(
         pool: &PgPool,
+        game: &GameConfig,
         user_id: Uuid,
         hero_id: Uuid,
         adventure_id: Uuid,
     ) -> AppResult<HeroAdventureResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -600,10 +962,8 @@ impl HeroService {
             return Err(AppError::BadRequest("Hero is not available".into()));
         }
 
-        // Check if hero already has active adventure
-        if let Some(_) = HeroRepository::get_active_adventure(pool, hero_id).await? {
-            return Err(AppError::BadRequest("Hero already has an active adventure".into()));
-        }
+        let active = HeroRepository::get_active_adventures_by_user(pool, user_id).await?;
+        Self::check_no_active_adventure(hero_id, &active)?;
 
         // Get available adventure
         let adventure = HeroRepository::get_available_adventure(pool, adventure_id)
@@ -622,26 +982,44 @@ impl HeroService {
             return Err(AppError::BadRequest("Adventure has expired".into()));
         }
 
-        // Calculate random duration within range (scope RNG so it's dropped before await)
-        let duration = {
-            let mut rng = rand::thread_rng();
-            rng.gen_range(adventure.min_duration_seconds..=adventure.max_duration_seconds)
-        };
-
-        // Mark available adventure as taken
-        HeroRepository::mark_adventure_taken(pool, adventure_id).await?;
+        if !Self::meets_adventure_level(game, hero.level, &adventure.difficulty) {
+            return Err(AppError::BadRequest(format!(
+                "Hero must be level {} for a {:?} adventure",
+                Self::adventure_required_level(game, &adventure.difficulty),
+                adventure.difficulty
+            )));
+        }
 
-        // Start adventure
-        let hero_adventure = HeroRepository::start_adventure(
-            pool,
+        let duration = Self::roll_adventure_duration(
+            &mut Self::adventure_rng(game),
+            game,
+            adventure.min_duration_seconds,
+            adventure.max_duration_seconds,
+        );
+
+        // Claim the hero, the offer and the adventure row together so a request that
+        // loses any of the races leaves the hero idle and the offer open
+        let mut tx = pool.begin().await?;
+        let claimed = HeroRepository::transition_status(
+            &mut *tx,
             hero_id,
-            adventure.difficulty,
-            duration,
+            HeroStatus::Idle,
+            HeroStatus::InAdventure,
         )
         .await?;
+        if !claimed {
+            return Err(AppError::BadRequest("Hero is not available".into()));
+        }
+
+        if !HeroRepository::mark_adventure_taken(&mut *tx, adventure_id).await? {
+            return Err(AppError::BadRequest("Adventure already taken".into()));
+        }
 
-        // Update hero status
-        HeroRepository::update_status(pool, hero_id, HeroStatus::InAdventure).await?;
+        let hero_adventure =
+            HeroRepository::start_adventure(&mut *tx, hero_id, adventure.difficulty, duration)
+                .await?
+                .ok_or_else(|| AppError::Conflict("Hero already has an active adventure".into()))?;
+        tx.commit().await?;
 
         Ok(HeroAdventureResponse {
             id: hero_adventure.id,
@@ -666,35 +1044,77 @@ impl HeroService {
     ) -> AppResult<Option<HeroAdventureResponse>> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+
+        if hero.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        match HeroRepository::get_active_adventure(pool, hero_id).await? {
+            Some(adventure) => Ok(Some(Self::adventure_response(pool, adventure).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Most recently finished adventures of a hero, newest first
+    pub async fn get_adventure_history(
+        pool: &PgPool,
+        user_id: Uuid,
+        hero_id: Uuid,
+    ) -> AppResult<Vec<HeroAdventureResponse>> {
+        let hero = HeroRepository::find_by_id(pool, hero_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
         }
 
-        let adventure = HeroRepository::get_active_adventure(pool, hero_id).await?;
-        Ok(adventure.map(|a| HeroAdventureResponse {
-            id: a.id,
-            hero_id: a.hero_id,
-            difficulty: a.difficulty,
-            started_at: a.started_at,
-            ends_at: a.ends_at,
-            is_completed: a.is_completed,
-            reward_experience: a.reward_experience,
-            reward_silver: a.reward_silver,
-            reward_resources: a.reward_resources,
-            reward_item: None, // Would need to fetch item definition
-            health_lost: a.health_lost,
-        }))
+        let adventures =
+            HeroRepository::get_adventure_history(pool, hero_id, ADVENTURE_HISTORY_LIMIT).await?;
+        let mut responses = Vec::with_capacity(adventures.len());
+        for adventure in adventures {
+            responses.push(Self::adventure_response(pool, adventure).await?);
+        }
+
+        Ok(responses)
+    }
+
+    /// Build an adventure response with its reward item; an item whose
+    /// definition has since been deleted is left out rather than failing
+    async fn adventure_response(
+        pool: &PgPool,
+        adventure: HeroAdventure,
+    ) -> AppResult<HeroAdventureResponse> {
+        let reward_item = match adventure.reward_item_id {
+            Some(item_id) => HeroRepository::get_item_definition(pool, item_id)
+                .await?
+                .map(ItemDefinitionResponse::from),
+            None => None,
+        };
+
+        Ok(HeroAdventureResponse {
+            id: adventure.id,
+            hero_id: adventure.hero_id,
+            difficulty: adventure.difficulty,
+            started_at: adventure.started_at,
+            ends_at: adventure.ends_at,
+            is_completed: adventure.is_completed,
+            reward_experience: adventure.reward_experience,
+            reward_silver: adventure.reward_silver,
+            reward_resources: adventure.reward_resources,
+            reward_item,
+            health_lost: adventure.health_lost,
+        })
     }
 
     /// Process completed adventures (called by background job)
-    pub async fn process_completed_adventures(pool: &PgPool) -> AppResult<i32> {
+    pub async fn process_completed_adventures(pool: &PgPool, game: &GameConfig) -> AppResult<i32> {
         let completed = HeroRepository::find_completed_adventures(pool).await?;
         let mut count = 0;
 
         for adventure in completed {
-            if let Err(e) = Self::complete_adventure(pool, adventure.id).await {
+            if let Err(e) = Self::complete_adventure(pool, game, adventure.id).await {
                 tracing::error!("Failed to complete adventure {}: {}", adventure.id, e);
             } else {
                 count += 1;
@@ -704,94 +1124,37 @@ impl HeroService {
         Ok(count)
     }
 
-    /// Complete a single adventure
-    async fn complete_adventure(pool: &PgPool, adventure_id: Uuid) -> AppResult<()> {
-        let adventure = HeroRepository::get_active_adventure(pool, adventure_id)
+    /// Complete a single adventure.
+    /// Rewards are only applied by the caller that wins the completion claim, so
+    /// running this twice for the same adventure is a no-op the second time.
+    async fn complete_adventure(
+        pool: &PgPool,
+        game: &GameConfig,
+        adventure_id: Uuid,
+    ) -> AppResult<()> {
+        let adventure = HeroRepository::find_adventure_by_id(pool, adventure_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Adventure not found".into()))?;
-
-        // Pre-generate all random values (scope RNG so it's dropped before await)
-        struct RewardParams {
-            base_exp: i32,
-            base_silver: i32,
-            health_damage: i32,
-            resources: serde_json::Value,
-            should_drop_item: bool,
-            item_rarity: Option<ItemRarity>,
-            item_index_seed: usize,
-        }
-
-        let params: RewardParams = {
-            let mut rng = rand::thread_rng();
-
-            let (base_exp, base_silver, health_damage) = match adventure.difficulty {
-                AdventureDifficulty::Short => (
-                    rng.gen_range(50..150),
-                    rng.gen_range(10..50),
-                    rng.gen_range(5..20),
-                ),
-                AdventureDifficulty::Long => (
-                    rng.gen_range(200..500),
-                    rng.gen_range(50..200),
-                    rng.gen_range(15..40),
-                ),
-            };
-
-            let resources = serde_json::json!({
-                "wood": rng.gen_range(50..200),
-                "clay": rng.gen_range(50..200),
-                "iron": rng.gen_range(50..200),
-                "crop": rng.gen_range(50..200),
-            });
+            .ok_or_else(|| AppError::not_found("Adventure", adventure_id))?;
 
-            let drop_chance = match adventure.difficulty {
-                AdventureDifficulty::Short => 30,
-                AdventureDifficulty::Long => 60,
-            };
-
-            let should_drop_item = rng.gen_range(0..100) < drop_chance;
-
-            let item_rarity = if should_drop_item {
-                Some(match adventure.difficulty {
-                    AdventureDifficulty::Short => match rng.gen_range(0..100) {
-                        0..=60 => ItemRarity::Common,
-                        61..=85 => ItemRarity::Uncommon,
-                        86..=95 => ItemRarity::Rare,
-                        _ => ItemRarity::Epic,
-                    },
-                    AdventureDifficulty::Long => match rng.gen_range(0..100) {
-                        0..=20 => ItemRarity::Uncommon,
-                        21..=50 => ItemRarity::Rare,
-                        51..=80 => ItemRarity::Epic,
-                        _ => ItemRarity::Legendary,
-                    },
-                })
-            } else {
-                None
-            };
-
-            RewardParams {
-                base_exp,
-                base_silver,
-                health_damage,
-                resources,
-                should_drop_item,
-                item_rarity,
-                item_index_seed: rng.gen_range(0..1000),
-            }
-        };
+        if !adventure.is_due(Utc::now()) {
+            return Ok(());
+        }
 
-        // Now do async operations (RNG is dropped)
-        let item_id = if params.should_drop_item {
-            if let Some(rarity) = params.item_rarity {
-                let items = HeroRepository::get_items_by_rarity(pool, rarity).await?;
-                if !items.is_empty() {
-                    let item = &items[params.item_index_seed % items.len()];
-                    HeroRepository::add_item(pool, adventure.hero_id, item.id, 1).await?;
-                    Some(item.id)
-                } else {
-                    None
-                }
+        let hero_level = HeroRepository::find_by_id(pool, adventure.hero_id)
+            .await?
+            .map(|h| h.level)
+            .unwrap_or(1);
+        let params = Self::roll_adventure_rewards(
+            &mut Self::adventure_rng(game),
+            game,
+            hero_level,
+            &adventure.difficulty,
+        );
+
+        let item_id = if let Some(rarity) = params.item_rarity {
+            let items = HeroRepository::get_items_by_rarity(pool, rarity).await?;
+            if !items.is_empty() {
+                Some(items[params.item_index_seed % items.len()].id)
             } else {
                 None
             }
@@ -799,9 +1162,12 @@ impl HeroService {
             None
         };
 
-        // Complete adventure
-        HeroRepository::complete_adventure(
-            pool,
+        // Claim the adventure; another worker may have completed it in the meantime.
+        // The claim and every reward commit together, so a failure part-way never
+        // records rewards that were not granted.
+        let mut tx = pool.begin().await?;
+        let claimed = HeroRepository::complete_adventure(
+            &mut *tx,
             adventure_id,
             params.base_exp,
             params.base_silver,
@@ -811,23 +1177,209 @@ impl HeroService {
         )
         .await?;
 
+        if claimed.is_none() {
+            return Ok(());
+        }
+
+        if let Some(item_id) = item_id {
+            HeroRepository::add_item(&mut *tx, adventure.hero_id, item_id, 1).await?;
+        }
+
         // Add experience to hero
-        HeroRepository::add_experience(pool, adventure.hero_id, params.base_exp).await?;
+        HeroRepository::add_experience(&mut tx, game, adventure.hero_id, params.base_exp).await?;
 
         // Damage hero
-        HeroRepository::damage_hero(pool, adventure.hero_id, params.health_damage).await?;
+        let hero = HeroRepository::damage_hero(&mut tx, adventure.hero_id, params.health_damage)
+            .await?;
 
         // Update hero status back to idle (if not dead)
-        let hero = HeroRepository::find_by_id(pool, adventure.hero_id).await?;
-        if let Some(hero) = hero {
-            if hero.health > 0 {
-                HeroRepository::update_status(pool, adventure.hero_id, HeroStatus::Idle).await?;
-            }
+        if hero.health > 0 {
+            HeroRepository::update_status(&mut *tx, adventure.hero_id, HeroStatus::Idle).await?;
         }
+        tx.commit().await?;
 
         Ok(())
     }
 
+    /// Reward bonus and health loss reduction (both percent) for a hero of the given level
+    fn adventure_level_modifiers(game: &GameConfig, level: i32) -> (i32, i32) {
+        let levels_above_first = (level - 1).max(0);
+        let max_percent = game.adventure_level_bonus_max_percent.clamp(0, 100);
+
+        let reward_bonus =
+            (levels_above_first * game.adventure_level_bonus_percent).clamp(0, max_percent);
+        let damage_reduction = (levels_above_first * game.adventure_level_damage_reduction_percent)
+            .clamp(0, max_percent);
+
+        (reward_bonus, damage_reduction)
+    }
+
+    /// RNG for adventure rolls; seeded from config on test servers so outcomes repeat
+    fn adventure_rng(game: &GameConfig) -> StdRng {
+        match game.adventure_rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Adventure length in seconds within [min, max]
+    fn roll_adventure_duration(rng: &mut impl Rng, game: &GameConfig, min: i32, max: i32) -> i32 {
+        let max = max.max(min);
+        if game.adventure_midpoint_duration {
+            min + (max - min) / 2
+        } else {
+            rng.gen_range(min..=max)
+        }
+    }
+
+    /// Roll everything random about a finished adventure, with the modifiers for
+    /// a hero of `hero_level` applied
+    fn roll_adventure_rewards(
+        rng: &mut impl Rng,
+        game: &GameConfig,
+        hero_level: i32,
+        difficulty: &AdventureDifficulty,
+    ) -> AdventureRewards {
+        let (reward_bonus, damage_reduction) = Self::adventure_level_modifiers(game, hero_level);
+        let (base_exp, base_silver, health_damage) = Self::roll_base_rewards(rng, difficulty);
+        let base_exp = base_exp * (100 + reward_bonus) / 100;
+        let base_silver = base_silver * (100 + reward_bonus) / 100;
+        let health_damage = health_damage * (100 - damage_reduction) / 100;
+
+        let resources = serde_json::json!({
+            "wood": rng.gen_range(50..200),
+            "clay": rng.gen_range(50..200),
+            "iron": rng.gen_range(50..200),
+            "crop": rng.gen_range(50..200),
+        });
+
+        let base_drop_chance = match difficulty {
+            AdventureDifficulty::Short => 30,
+            AdventureDifficulty::Long => 60,
+        };
+        let drop_chance = base_drop_chance * (100 + reward_bonus) / 100;
+
+        let item_rarity = if rng.gen_range(0..100) < drop_chance {
+            Some(match difficulty {
+                AdventureDifficulty::Short => match rng.gen_range(0..100) {
+                    0..=60 => ItemRarity::Common,
+                    61..=85 => ItemRarity::Uncommon,
+                    86..=95 => ItemRarity::Rare,
+                    _ => ItemRarity::Epic,
+                },
+                AdventureDifficulty::Long => match rng.gen_range(0..100) {
+                    0..=20 => ItemRarity::Uncommon,
+                    21..=50 => ItemRarity::Rare,
+                    51..=80 => ItemRarity::Epic,
+                    _ => ItemRarity::Legendary,
+                },
+            })
+        } else {
+            None
+        };
+
+        AdventureRewards {
+            base_exp,
+            base_silver,
+            health_damage,
+            resources,
+            item_rarity,
+            item_index_seed: rng.gen_range(0..1000),
+        }
+    }
+
+    /// Roll base experience, silver and health damage for an adventure
+    fn roll_base_rewards(rng: &mut impl Rng, difficulty: &AdventureDifficulty) -> (i32, i32, i32) {
+        match difficulty {
+            AdventureDifficulty::Short => (
+                rng.gen_range(50..150),
+                rng.gen_range(10..50),
+                rng.gen_range(5..20),
+            ),
+            AdventureDifficulty::Long => (
+                rng.gen_range(200..500),
+                rng.gen_range(50..200),
+                rng.gen_range(15..40),
+            ),
+        }
+    }
+
+    /// Cancel an active adventure early.
+    /// Experience and silver are pro-rated by the elapsed fraction; no item or resources are awarded.
+    pub async fn cancel_adventure(
+        pool: &PgPool,
+        game: &GameConfig,
+        user_id: Uuid,
+        hero_id: Uuid,
+        adventure_id: Uuid,
+    ) -> AppResult<HeroAdventureResponse> {
+        let hero = HeroRepository::find_by_id(pool, hero_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
+
+        if hero.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        let adventure = HeroRepository::find_adventure_by_id(pool, adventure_id)
+            .await?
+            .filter(|a| a.hero_id == hero_id)
+            .ok_or_else(|| AppError::not_found("Adventure", adventure_id))?;
+
+        let now = Utc::now();
+        if !adventure.is_active(now) {
+            return Err(AppError::BadRequest("Adventure is not active".into()));
+        }
+
+        // Same roll as a completed adventure, so the hero level scales both alike
+        let rewards = Self::roll_adventure_rewards(
+            &mut Self::adventure_rng(game),
+            game,
+            hero.level,
+            &adventure.difficulty,
+        );
+        let exp = adventure.prorated_reward(rewards.base_exp, now);
+        let silver = adventure.prorated_reward(rewards.base_silver, now);
+
+        // Completing the adventure, the experience and the hero's return commit together,
+        // so the hero is never left on an adventure that is already over
+        let mut tx = pool.begin().await?;
+        let completed =
+            HeroRepository::complete_adventure(&mut *tx, adventure_id, exp, silver, None, None, 0)
+                .await?
+                .ok_or_else(|| AppError::BadRequest("Adventure is not active".into()))?;
+
+        if exp > 0 {
+            HeroRepository::add_experience(&mut tx, game, hero_id, exp).await?;
+        }
+
+        if !HeroRepository::transition_status(
+            &mut *tx,
+            hero_id,
+            HeroStatus::InAdventure,
+            HeroStatus::Idle,
+        )
+        .await?
+        {
+            return Err(AppError::BadRequest("Hero is not on an adventure".into()));
+        }
+        tx.commit().await?;
+
+        Ok(HeroAdventureResponse {
+            id: completed.id,
+            hero_id: completed.hero_id,
+            difficulty: completed.difficulty,
+            started_at: completed.started_at,
+            ends_at: completed.ends_at,
+            is_completed: completed.is_completed,
+            reward_experience: completed.reward_experience,
+            reward_silver: completed.reward_silver,
+            reward_resources: completed.reward_resources,
+            reward_item: None,
+            health_lost: completed.health_lost,
+        })
+    }
+
     // ==================== Revive ====================
 
     /// Get revive info for dead hero
@@ -838,7 +1390,7 @@ impl HeroService {
     ) -> AppResult<ReviveInfoResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -854,34 +1406,38 @@ impl HeroService {
         // Calculate gold cost for instant revive (1 gold per 30 minutes remaining)
         let gold_cost = ((remaining as f64 / 1800.0).ceil() as i32).max(1);
 
-        // Resource cost for natural revive
-        let base_cost = hero.level * 100;
-        let resource_cost = ReviveResourceCost {
-            wood: base_cost,
-            clay: base_cost,
-            iron: base_cost,
-            crop: base_cost * 2,
-        };
-
         Ok(ReviveInfoResponse {
             hero_id,
             revive_at,
             remaining_seconds: remaining,
             gold_cost_instant: gold_cost,
-            resource_cost,
+            resource_cost: Self::revive_resource_cost(&hero),
         })
     }
 
-    /// Revive hero (with gold or naturally)
+    /// Resources an instant revive costs, scaling with the hero's level
+    fn revive_resource_cost(hero: &Hero) -> ReviveResourceCost {
+        let base_cost = hero.level * 100;
+        ReviveResourceCost {
+            wood: base_cost,
+            clay: base_cost,
+            iron: base_cost,
+            crop: base_cost * 2,
+        }
+    }
+
+    /// Revive hero (with gold, resources or naturally)
     pub async fn revive_hero(
         pool: &PgPool,
+        redis: &ConnectionManager,
+        game: &GameConfig,
         user_id: Uuid,
         hero_id: Uuid,
-        use_gold: bool,
+        method: ReviveMethod,
     ) -> AppResult<HeroResponse> {
         let hero = HeroRepository::find_by_id(pool, hero_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Hero not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Hero", hero_id))?;
 
         if hero.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -891,48 +1447,170 @@ impl HeroService {
             return Err(AppError::BadRequest("Hero is not dead".into()));
         }
 
-        if use_gold {
-            let revive_info = Self::get_revive_info(pool, user_id, hero_id).await?;
+        match method {
+            ReviveMethod::Gold => Self::revive_with_gold(pool, game, user_id, hero_id).await,
+            ReviveMethod::Resources => {
+                Self::revive_with_resources(pool, redis, game, user_id, &hero).await
+            }
+            ReviveMethod::Natural => {
+                // Natural revive - check if time has passed
+                let revive_at = hero.revive_at.unwrap_or(Utc::now());
+                if Utc::now() < revive_at {
+                    return Err(AppError::BadRequest("Hero cannot be revived yet".into()));
+                }
 
-            // Check gold balance
-            let balance = ShopRepository::get_gold_balance(pool, user_id).await?;
-            if balance < revive_info.gold_cost_instant {
-                return Err(AppError::BadRequest("Insufficient gold".into()));
+                let health = Self::revive_health(game, method);
+                let hero = HeroRepository::revive_hero(pool, hero_id, health).await?;
+                Ok(hero.into())
             }
+        }
+    }
 
-            // Deduct gold
-            ShopRepository::deduct_gold(pool, user_id, revive_info.gold_cost_instant).await?;
+    /// Health (percent) a hero comes back with for each way of reviving it
+    fn revive_health(game: &GameConfig, method: ReviveMethod) -> i32 {
+        let percent = match method {
+            ReviveMethod::Gold => game.hero_revive_health_gold,
+            ReviveMethod::Resources => game.hero_revive_health_resources,
+            ReviveMethod::Natural => game.hero_revive_health_natural,
+        };
+        percent.clamp(1, 100)
+    }
 
-            // Revive with 50% health
-            let hero = HeroRepository::revive_hero(pool, hero_id, 50).await?;
-            Ok(hero.into())
-        } else {
-            // Natural revive - check if time has passed
-            let revive_at = hero.revive_at.unwrap_or(Utc::now());
-            if Utc::now() < revive_at {
-                return Err(AppError::BadRequest("Hero cannot be revived yet".into()));
-            }
+    /// Revive instantly for gold, priced by the time left on the revive timer
+    async fn revive_with_gold(
+        pool: &PgPool,
+        game: &GameConfig,
+        user_id: Uuid,
+        hero_id: Uuid,
+    ) -> AppResult<HeroResponse> {
+        let revive_info = Self::get_revive_info(pool, user_id, hero_id).await?;
+
+        // Check gold balance
+        let balance = ShopRepository::get_gold_balance(pool, user_id).await?;
+        if balance < revive_info.gold_cost_instant {
+            return Err(AppError::BadRequest("Insufficient gold".into()));
+        }
 
-            // Revive with 25% health
-            let hero = HeroRepository::revive_hero(pool, hero_id, 25).await?;
-            Ok(hero.into())
+        // Claim the revive, pay for it and record the spend together, so a revive
+        // that fails never costs gold and concurrent requests cannot both charge
+        let gold_cost = revive_info.gold_cost_instant;
+        let mut tx = pool.begin().await?;
+        if !HeroRepository::transition_status(&mut *tx, hero_id, HeroStatus::Dead, HeroStatus::Idle)
+            .await?
+        {
+            return Err(AppError::Conflict("Hero has already been revived".into()));
         }
+        ShopRepository::deduct_gold(&mut *tx, user_id, gold_cost).await?;
+
+        let health = Self::revive_health(game, ReviveMethod::Gold);
+        let hero = HeroRepository::revive_hero(&mut *tx, hero_id, health).await?;
+
+        ShopRepository::create_transaction(
+            &mut *tx,
+            user_id,
+            crate::models::shop::TransactionType::GoldSpend,
+            -gold_cost,
+            None,
+            None,
+            None,
+            None,
+            Some(&format!("Hero revive - {}", hero.name)),
+        )
+        .await?;
+
+        ShopRepository::record_gold_usage(
+            &mut *tx,
+            user_id,
+            crate::models::shop::GoldFeature::HeroRevive,
+            gold_cost,
+            Some("hero"),
+            Some(hero_id),
+            Some(serde_json::json!({ "saved_seconds": revive_info.remaining_seconds })),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(hero.into())
+    }
+
+    /// Revive instantly, paid for with resources from the hero's home village
+    async fn revive_with_resources(
+        pool: &PgPool,
+        redis: &ConnectionManager,
+        game: &GameConfig,
+        user_id: Uuid,
+        hero: &Hero,
+    ) -> AppResult<HeroResponse> {
+        let village = VillageRepository::find_by_id(pool, hero.home_village_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Village", hero.home_village_id))?;
+        if village.user_id != user_id {
+            return Err(AppError::BadRequest("Hero's home village is no longer yours".into()));
+        }
+
+        let village = ResourceService::update_village_resources(pool, game, village.id).await?;
+        let cost = Self::revive_resource_cost(hero);
+        let shortfall: Vec<String> = [
+            ("wood", cost.wood, village.wood),
+            ("clay", cost.clay, village.clay),
+            ("iron", cost.iron, village.iron),
+            ("crop", cost.crop, village.crop),
+        ]
+        .iter()
+        .filter(|(_, needed, have)| have < needed)
+        .map(|(name, needed, have)| format!("{} more {}", needed - have, name))
+        .collect();
+        if !shortfall.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "Not enough resources to revive: need {}",
+                shortfall.join(", ")
+            )));
+        }
+
+        // Claim the revive before paying, so concurrent requests cannot both charge
+        // the village; the claim, payment and revive commit or roll back together
+        let mut tx = pool.begin().await?;
+        if !HeroRepository::transition_status(&mut *tx, hero.id, HeroStatus::Dead, HeroStatus::Idle)
+            .await?
+        {
+            return Err(AppError::Conflict("Hero has already been revived".into()));
+        }
+        VillageRepository::deduct_resources(
+            &mut *tx,
+            village.id,
+            cost.wood,
+            cost.clay,
+            cost.iron,
+            cost.crop,
+        )
+        .await?;
+
+        let health = Self::revive_health(game, ReviveMethod::Resources);
+        let hero = HeroRepository::revive_hero(&mut *tx, hero.id, health).await?;
+        tx.commit().await?;
+
+        CacheService::invalidate_village(redis, village.id).await;
+        Ok(hero.into())
     }
 
     // ==================== Health Regeneration ====================
 
     /// Process health regeneration for all heroes (called by background job)
     pub async fn process_health_regen(pool: &PgPool) -> AppResult<i32> {
-        // Get all heroes that need health regen (health < 100 and not dead)
+        // Get all heroes that need health regen (health < 100 and not dead). A hero is
+        // only touched once a whole point has built up, so frequent runs never round
+        // a slow regen rate down to nothing.
         let result = sqlx::query(
             r#"
             UPDATE heroes
-            SET health = LEAST(100, health + (
+            SET health = LEAST(100, health + FLOOR(
                 EXTRACT(EPOCH FROM (NOW() - last_health_update)) / 3600.0 * health_regen_rate
             )::INTEGER),
             last_health_update = NOW(),
             updated_at = NOW()
             WHERE health < 100 AND health > 0 AND status != 'dead'
+              AND EXTRACT(EPOCH FROM (NOW() - last_health_update)) / 3600.0 * health_regen_rate >= 1
             "#,
         )
         .execute(pool)
@@ -941,3 +1619,543 @@ impl HeroService {
         Ok(result.rows_affected() as i32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::troop::TribeType;
+    use crate::test_support::{game_config, hero, village};
+    use rust_decimal::Decimal;
+
+    fn inventory_item(rarity: ItemRarity, is_equipped: bool) -> (HeroItem, ItemDefinition) {
+        let definition_id = Uuid::new_v4();
+        (
+            HeroItem {
+                id: Uuid::new_v4(),
+                hero_id: Uuid::new_v4(),
+                item_definition_id: definition_id,
+                is_equipped,
+                equipped_slot: None,
+                quantity: 1,
+                obtained_at: Utc::now(),
+                equipped_at: None,
+            },
+            ItemDefinition {
+                id: definition_id,
+                name: "Test item".to_string(),
+                description: None,
+                slot: ItemSlot::Helmet,
+                rarity,
+                required_level: 1,
+                attack_bonus: 0,
+                defense_bonus: 0,
+                speed_bonus: Decimal::ZERO,
+                health_regen_bonus: Decimal::ZERO,
+                experience_bonus: 0,
+                resource_bonus: 0,
+                carry_bonus: 0,
+                health_restore: 0,
+                is_consumable: false,
+                extra_inventory_slots: 0,
+                sell_value: 10,
+                can_drop_adventure: true,
+                can_buy_auction: true,
+                allowed_tribe: None,
+                created_at: Utc::now(),
+            },
+        )
+    }
+
+    #[test]
+    fn item_definitions_keep_their_bonuses_rarity_and_availability() {
+        let (_, helmet) = inventory_item(ItemRarity::Common, false);
+        let (_, sword) = inventory_item(ItemRarity::Epic, false);
+        let sword = ItemDefinition {
+            slot: ItemSlot::Weapon,
+            required_level: 10,
+            attack_bonus: 120,
+            sell_value: 500,
+            can_buy_auction: false,
+            allowed_tribe: Some(TribeType::Nava),
+            ..sword
+        };
+
+        let responses = HeroService::item_definition_responses(vec![helmet.clone(), sword]);
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].id, helmet.id);
+        assert_eq!(responses[0].rarity, ItemRarity::Common);
+        let sword = &responses[1];
+        assert_eq!((sword.slot, sword.rarity), (ItemSlot::Weapon, ItemRarity::Epic));
+        assert_eq!((sword.required_level, sword.attack_bonus), (10, 120));
+        assert_eq!(sword.sell_value, 500);
+        assert!(sword.can_drop_adventure && !sword.can_buy_auction);
+        assert_eq!(sword.allowed_tribe, Some(TribeType::Nava));
+    }
+
+    #[test]
+    fn attribute_preview_matches_the_assigned_stats() {
+        let mut before = hero(HeroStatus::Idle);
+        before.unassigned_points = 5;
+        let request = AssignAttributesRequest {
+            fighting_strength: 2,
+            off_bonus: 1,
+            def_bonus: 0,
+            resources_bonus: 2,
+        };
+
+        let preview = HeroService::apply_attribute_points(before.clone(), &request).unwrap();
+
+        assert_eq!(preview.fighting_strength, before.fighting_strength + 2);
+        assert_eq!(preview.off_bonus, before.off_bonus + 1);
+        assert_eq!(preview.resources_bonus, before.resources_bonus + 2);
+        assert_eq!(preview.unassigned_points, 0);
+        assert!(preview.total_attack() > before.total_attack());
+    }
+
+    #[test]
+    fn attribute_preview_rejects_invalid_totals() {
+        let mut before = hero(HeroStatus::Idle);
+        before.unassigned_points = 3;
+        let points = |fighting_strength, off_bonus| AssignAttributesRequest {
+            fighting_strength,
+            off_bonus,
+            def_bonus: 0,
+            resources_bonus: 0,
+        };
+
+        for request in [points(0, 0), points(3, 1), points(5, -2)] {
+            assert!(matches!(
+                HeroService::apply_attribute_points(before.clone(), &request),
+                Err(AppError::BadRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn transferred_hero_takes_the_lowest_free_slot_and_first_village() {
+        let owned = hero(HeroStatus::Idle);
+        let recipient_id = Uuid::new_v4();
+        assert!(HeroService::check_transferable(&owned, owned.user_id, recipient_id).is_ok());
+
+        let mut existing = hero(HeroStatus::Idle);
+        existing.slot_number = 1;
+        assert_eq!(HeroService::free_hero_slot(3, &[existing]).unwrap(), 2);
+
+        let villages = vec![village(0, 800), village(0, 800)];
+        assert_eq!(HeroService::transfer_home(&villages, None).unwrap(), villages[0].id);
+        assert_eq!(
+            HeroService::transfer_home(&villages, Some(villages[1].id)).unwrap(),
+            villages[1].id
+        );
+    }
+
+    #[test]
+    fn transfer_needs_a_free_slot_at_the_recipient() {
+        let heroes: Vec<Hero> = (1..=2)
+            .map(|slot| Hero {
+                slot_number: slot,
+                ..hero(HeroStatus::Idle)
+            })
+            .collect();
+
+        assert!(matches!(
+            HeroService::free_hero_slot(2, &heroes),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn away_hero_cannot_be_transferred() {
+        let moving = hero(HeroStatus::Moving);
+        assert!(HeroService::check_transferable(&moving, moving.user_id, Uuid::new_v4()).is_err());
+        assert!(matches!(
+            HeroService::check_transferable(&moving, Uuid::new_v4(), Uuid::new_v4()),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn idle_hero_may_change_its_loadout() {
+        assert!(HeroService::ensure_loadout_changeable(&hero(HeroStatus::Idle)).is_ok());
+    }
+
+    #[test]
+    fn away_or_dead_hero_keeps_its_loadout() {
+        for status in [HeroStatus::Moving, HeroStatus::InAdventure, HeroStatus::Dead] {
+            assert!(
+                matches!(
+                    HeroService::ensure_loadout_changeable(&hero(status)),
+                    Err(AppError::BadRequest(_))
+                ),
+                "{:?} hero should not change items",
+                status
+            );
+        }
+
+        // Idle but out of health counts as dead
+        let mut fallen = hero(HeroStatus::Idle);
+        fallen.health = 0;
+        assert!(HeroService::ensure_loadout_changeable(&fallen).is_err());
+    }
+
+    #[test]
+    fn bulk_sell_selects_unequipped_items_up_to_max_rarity() {
+        let inventory = vec![
+            inventory_item(ItemRarity::Common, false),
+            inventory_item(ItemRarity::Rare, false),
+            inventory_item(ItemRarity::Common, true),
+        ];
+        let request = BulkSellItemsRequest {
+            item_ids: None,
+            max_rarity: Some(ItemRarity::Uncommon),
+        };
+
+        let selected = HeroService::items_for_sale(&inventory, &request);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0.id, inventory[0].0.id);
+    }
+
+    #[test]
+    fn bulk_sell_selects_listed_items_only() {
+        let inventory = vec![
+            inventory_item(ItemRarity::Epic, false),
+            inventory_item(ItemRarity::Common, false),
+        ];
+        let request = BulkSellItemsRequest {
+            item_ids: Some(vec![inventory[0].0.id]),
+            max_rarity: None,
+        };
+
+        let selected = HeroService::items_for_sale(&inventory, &request);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0.id, inventory[0].0.id);
+    }
+
+    #[test]
+    fn regen_rate_increases_after_equipping_a_bandage() {
+        let (_, mut bandage) = inventory_item(ItemRarity::Common, true);
+        bandage.health_regen_bonus = Decimal::new(25, 1);
+
+        let bare = Hero::health_regen_rate(1, &[]);
+        let bandaged = Hero::health_regen_rate(1, &[bandage]);
+        assert_eq!(bare, Decimal::from(10));
+        assert_eq!(bandaged, Decimal::new(125, 1));
+    }
+
+    #[test]
+    fn regen_rate_increases_after_leveling() {
+        let (_, item) = inventory_item(ItemRarity::Common, true);
+
+        assert!(Hero::health_regen_rate(2, &[]) > Hero::health_regen_rate(1, &[]));
+        assert_eq!(Hero::health_regen_rate(5, std::slice::from_ref(&item)), Decimal::from(12));
+    }
+
+    #[test]
+    fn hero_slots_can_be_bought_up_to_the_configured_max() {
+        let max_slots = 8;
+        assert_eq!(HeroService::next_purchasable_slot(1, max_slots), Some(2));
+        assert_eq!(HeroService::next_purchasable_slot(7, max_slots), Some(8));
+        assert_eq!(HeroService::next_purchasable_slot(8, max_slots), None);
+    }
+
+    #[test]
+    fn tribe_item_equips_for_a_matching_tribe_only() {
+        let (_, mut kris) = inventory_item(ItemRarity::Rare, false);
+        kris.allowed_tribe = Some(TribeType::Nava);
+
+        assert!(kris.is_usable_by(TribeType::Nava));
+        assert!(!kris.is_usable_by(TribeType::Kiri));
+        assert!(!kris.is_usable_by(TribeType::Phasuttha));
+    }
+
+    #[test]
+    fn unrestricted_item_equips_for_every_tribe() {
+        let (_, helmet) = inventory_item(ItemRarity::Common, false);
+
+        assert!(helmet.is_usable_by(TribeType::Nava));
+        assert!(helmet.is_usable_by(TribeType::Kiri));
+    }
+
+    fn adventure(started_minutes_ago: i64, duration_minutes: i64) -> HeroAdventure {
+        let started_at = Utc::now() - Duration::minutes(started_minutes_ago);
+        HeroAdventure {
+            id: Uuid::new_v4(),
+            hero_id: Uuid::new_v4(),
+            difficulty: AdventureDifficulty::Short,
+            started_at,
+            duration_seconds: (duration_minutes * 60) as i32,
+            ends_at: started_at + Duration::minutes(duration_minutes),
+            is_completed: false,
+            completed_at: None,
+            reward_experience: None,
+            reward_silver: None,
+            reward_resources: None,
+            reward_item_id: None,
+            health_lost: None,
+            created_at: started_at,
+        }
+    }
+
+    #[test]
+    fn cancelling_mid_adventure_grants_partial_rewards() {
+        let adventure = adventure(30, 60);
+        let now = adventure.started_at + Duration::minutes(30);
+
+        assert!(adventure.is_active(now));
+        assert_eq!(adventure.prorated_reward(200, now), 100);
+        assert_eq!(adventure.prorated_reward(41, now), 20);
+        assert_eq!(adventure.prorated_reward(200, adventure.started_at), 0);
+    }
+
+    #[test]
+    fn finished_or_completed_adventures_cannot_be_cancelled() {
+        let finished = adventure(90, 60);
+        assert!(!finished.is_active(Utc::now()));
+
+        let mut completed = adventure(10, 60);
+        completed.is_completed = true;
+        assert!(!completed.is_active(Utc::now()));
+    }
+
+    #[test]
+    fn low_level_hero_is_blocked_from_long_adventures() {
+        let game = GameConfig {
+            adventure_min_level_short: 1,
+            adventure_min_level_long: 5,
+            ..game_config()
+        };
+
+        assert!(HeroService::meets_adventure_level(&game, 1, &AdventureDifficulty::Short));
+        assert!(!HeroService::meets_adventure_level(&game, 1, &AdventureDifficulty::Long));
+        assert!(!HeroService::meets_adventure_level(&game, 4, &AdventureDifficulty::Long));
+    }
+
+    #[test]
+    fn offers_show_the_configured_level_requirement() {
+        let game = GameConfig {
+            adventure_min_level_short: 2,
+            adventure_min_level_long: 7,
+            ..game_config()
+        };
+        let offer = |difficulty| AvailableAdventure {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            difficulty,
+            min_duration_seconds: 900,
+            max_duration_seconds: 2700,
+            potential_reward_type: None,
+            potential_item_rarity: None,
+            expires_at: Utc::now() + Duration::hours(12),
+            is_taken: false,
+            created_at: Utc::now(),
+        };
+
+        let responses = HeroService::adventure_responses(
+            &game,
+            vec![offer(AdventureDifficulty::Short), offer(AdventureDifficulty::Long)],
+        );
+        assert_eq!(responses[0].required_level, 2);
+        assert_eq!(responses[1].required_level, 7);
+    }
+
+    #[test]
+    fn high_level_hero_may_take_long_adventures() {
+        let game = GameConfig {
+            adventure_min_level_long: 5,
+            ..game_config()
+        };
+
+        assert!(HeroService::meets_adventure_level(&game, 5, &AdventureDifficulty::Long));
+        assert!(HeroService::meets_adventure_level(&game, 30, &AdventureDifficulty::Long));
+    }
+
+    #[test]
+    fn completed_adventure_is_no_longer_due() {
+        let mut finished = adventure(90, 60);
+        let now = Utc::now();
+        assert!(finished.is_due(now));
+
+        // Once the completion claim has been recorded the adventure is never due again
+        finished.is_completed = true;
+        finished.completed_at = Some(now);
+        assert!(!finished.is_due(now));
+        assert!(!finished.is_active(now));
+    }
+
+    #[test]
+    fn running_adventures_are_not_completed_early() {
+        assert!(!adventure(10, 60).is_due(Utc::now()));
+    }
+
+    #[test]
+    fn different_heroes_of_a_player_adventure_at_the_same_time() {
+        let first = adventure(10, 60);
+        let second_hero = Uuid::new_v4();
+
+        assert!(
+            HeroService::check_no_active_adventure(second_hero, std::slice::from_ref(&first))
+                .is_ok()
+        );
+
+        let mut second = adventure(0, 60);
+        second.hero_id = second_hero;
+        assert!(HeroService::check_no_active_adventure(Uuid::new_v4(), &[first, second]).is_ok());
+    }
+
+    #[test]
+    fn a_hero_cannot_start_a_second_adventure() {
+        let running = adventure(10, 60);
+
+        assert!(matches!(
+            HeroService::check_no_active_adventure(running.hero_id, std::slice::from_ref(&running)),
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn higher_level_heroes_earn_more_and_lose_less_health() {
+        let game = GameConfig {
+            adventure_level_bonus_percent: 2,
+            adventure_level_bonus_max_percent: 50,
+            adventure_level_damage_reduction_percent: 1,
+            ..game_config()
+        };
+
+        let novice = HeroService::roll_adventure_rewards(
+            &mut StdRng::seed_from_u64(7),
+            &game,
+            1,
+            &AdventureDifficulty::Long,
+        );
+        let veteran = HeroService::roll_adventure_rewards(
+            &mut StdRng::seed_from_u64(7),
+            &game,
+            20,
+            &AdventureDifficulty::Long,
+        );
+
+        // Level 20 is 19 levels above the first: +38% rewards, -19% health loss
+        assert_eq!(veteran.base_exp, novice.base_exp * 138 / 100);
+        assert_eq!(veteran.base_silver, novice.base_silver * 138 / 100);
+        assert_eq!(veteran.health_damage, novice.health_damage * 81 / 100);
+    }
+
+    #[test]
+    fn seeded_adventure_duration_repeats_within_its_range() {
+        let game = game_config();
+        let roll = |seed| {
+            HeroService::roll_adventure_duration(
+                &mut StdRng::seed_from_u64(seed),
+                &game,
+                1800,
+                5400,
+            )
+        };
+
+        for seed in 0..50 {
+            assert!((1800..=5400).contains(&roll(seed)));
+            assert_eq!(roll(seed), roll(seed));
+        }
+    }
+
+    #[test]
+    fn midpoint_duration_ignores_the_rng() {
+        let game = GameConfig {
+            adventure_midpoint_duration: true,
+            ..game_config()
+        };
+
+        for seed in 0..5 {
+            let duration = HeroService::roll_adventure_duration(
+                &mut StdRng::seed_from_u64(seed),
+                &game,
+                1800,
+                5400,
+            );
+            assert_eq!(duration, 3600);
+        }
+    }
+
+    #[test]
+    fn seeded_adventure_rewards_repeat_within_their_bounds() {
+        let game = game_config();
+        for seed in 0..50 {
+            let first = HeroService::roll_adventure_rewards(
+                &mut StdRng::seed_from_u64(seed),
+                &game,
+                1,
+                &AdventureDifficulty::Short,
+            );
+            let again = HeroService::roll_adventure_rewards(
+                &mut StdRng::seed_from_u64(seed),
+                &game,
+                1,
+                &AdventureDifficulty::Short,
+            );
+
+            assert!((50..150).contains(&first.base_exp));
+            assert!((10..50).contains(&first.base_silver));
+            assert!((5..20).contains(&first.health_damage));
+            assert_eq!(first.base_exp, again.base_exp);
+            assert_eq!(first.base_silver, again.base_silver);
+            assert_eq!(first.health_damage, again.health_damage);
+            assert_eq!(first.resources, again.resources);
+            assert_eq!(first.item_rarity, again.item_rarity);
+        }
+    }
+
+    #[test]
+    fn seeded_offers_hold_three_to_five_short_and_one_to_two_long_adventures() {
+        let now = Utc::now();
+        for seed in 0..50 {
+            let offers = HeroService::roll_adventure_offers(&mut StdRng::seed_from_u64(seed), now);
+            let short: Vec<_> = offers
+                .iter()
+                .filter(|o| o.difficulty == AdventureDifficulty::Short)
+                .collect();
+            let long: Vec<_> = offers
+                .iter()
+                .filter(|o| o.difficulty == AdventureDifficulty::Long)
+                .collect();
+
+            assert!((3..=5).contains(&short.len()));
+            assert!((1..=2).contains(&long.len()));
+            assert!(
+                short
+                    .iter()
+                    .all(|o| (1800..3600).contains(&o.min_duration)
+                        && o.max_duration > o.min_duration)
+            );
+            assert!(long
+                .iter()
+                .all(|o| o.item_rarity.is_some() && o.expires_at > now));
+        }
+    }
+
+    #[test]
+    fn configured_full_gold_revive_restores_full_health() {
+        let game = GameConfig {
+            hero_revive_health_gold: 100,
+            ..game_config()
+        };
+
+        assert_eq!(HeroService::revive_health(&game, ReviveMethod::Gold), 100);
+        assert_eq!(
+            HeroService::revive_health(&game, ReviveMethod::Natural),
+            game_config().hero_revive_health_natural
+        );
+    }
+
+    #[test]
+    fn revive_health_stays_between_one_and_full() {
+        let game = GameConfig {
+            hero_revive_health_gold: 250,
+            hero_revive_health_natural: 0,
+            ..game_config()
+        };
+
+        assert_eq!(HeroService::revive_health(&game, ReviveMethod::Gold), 100);
+        assert_eq!(HeroService::revive_health(&game, ReviveMethod::Natural), 1);
+    }
+}
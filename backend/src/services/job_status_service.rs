@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Outcome of the most recent runs of a background job
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    pub runs: u64,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: u64,
+    pub last_processed: i64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+impl JobStatus {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            runs: 0,
+            last_run_at: None,
+            last_duration_ms: 0,
+            last_processed: 0,
+            last_error: None,
+            last_error_at: None,
+        }
+    }
+}
+
+/// In-memory status of every background job, shared between the jobs and the admin API
+#[derive(Clone, Default)]
+pub struct JobStatusTracker {
+    statuses: Arc<RwLock<HashMap<&'static str, JobStatus>>>,
+}
+
+impl JobStatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a successful run and how many items it processed
+    pub async fn record_success(&self, name: &'static str, duration: Duration, processed: i64) {
+        let mut statuses = self.statuses.write().await;
+        let status = statuses.entry(name).or_insert_with(|| JobStatus::new(name));
+
+        status.runs += 1;
+        status.last_run_at = Some(Utc::now());
+        status.last_duration_ms = duration.as_millis() as u64;
+        status.last_processed = processed;
+        status.last_error = None;
+    }
+
+    /// Record a failed run; the error is kept until the next successful run
+    pub async fn record_failure(&self, name: &'static str, duration: Duration, error: String) {
+        let mut statuses = self.statuses.write().await;
+        let status = statuses.entry(name).or_insert_with(|| JobStatus::new(name));
+        let now = Utc::now();
+
+        status.runs += 1;
+        status.last_run_at = Some(now);
+        status.last_duration_ms = duration.as_millis() as u64;
+        status.last_processed = 0;
+        status.last_error = Some(error);
+        status.last_error_at = Some(now);
+    }
+
+    /// Status of all jobs that have run at least once, sorted by name
+    pub async fn snapshot(&self) -> Vec<JobStatus> {
+        let statuses = self.statuses.read().await;
+        let mut jobs: Vec<JobStatus> = statuses.values().cloned().collect();
+        jobs.sort_by_key(|j| j.name);
+        jobs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn successful_run_is_reported_with_its_count() {
+        let jobs = JobStatusTracker::new();
+        let before = Utc::now();
+
+        jobs.record_success("army_processing", Duration::from_millis(42), 7).await;
+
+        let snapshot = jobs.snapshot().await;
+        assert_eq!(snapshot.len(), 1);
+        let status = &snapshot[0];
+        assert_eq!(status.name, "army_processing");
+        assert_eq!(status.runs, 1);
+        assert!(status.last_run_at.is_some_and(|at| at >= before));
+        assert_eq!(status.last_duration_ms, 42);
+        assert_eq!(status.last_processed, 7);
+        assert!(status.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn failure_is_kept_until_the_next_successful_run() {
+        let jobs = JobStatusTracker::new();
+
+        jobs.record_success("loyalty_regen", Duration::ZERO, 3).await;
+        jobs.record_failure("loyalty_regen", Duration::ZERO, "database down".to_string()).await;
+        let failed = jobs.snapshot().await.remove(0);
+        assert_eq!(failed.runs, 2);
+        assert_eq!(failed.last_processed, 0);
+        assert_eq!(failed.last_error.as_deref(), Some("database down"));
+
+        jobs.record_success("loyalty_regen", Duration::ZERO, 5).await;
+        let recovered = jobs.snapshot().await.remove(0);
+        assert_eq!(recovered.runs, 3);
+        assert_eq!(recovered.last_processed, 5);
+        assert!(recovered.last_error.is_none());
+        assert_eq!(recovered.last_error_at, failed.last_error_at);
+    }
+}
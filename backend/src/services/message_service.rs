@@ -2,11 +2,13 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
+use crate::models::alliance::AlliancePermission;
 use crate::models::message::{
     AllianceMessageListItem, ConversationResponse, MessageListItem, MessageResponse,
 };
 use crate::repositories::alliance_repo::AllianceRepository;
 use crate::repositories::message_repo::MessageRepository;
+use crate::services::alliance_service::AllianceService;
 
 pub struct MessageService;
 
@@ -68,6 +70,17 @@ impl MessageService {
         Ok(response)
     }
 
+    /// Send a system message from the game to a player
+    pub async fn send_system_message(
+        pool: &PgPool,
+        recipient_id: Uuid,
+        subject: &str,
+        body: &str,
+    ) -> AppResult<()> {
+        MessageRepository::create_system_message(pool, recipient_id, subject, body).await?;
+        Ok(())
+    }
+
     /// Get inbox messages
     pub async fn get_inbox(
         pool: &PgPool,
@@ -190,6 +203,13 @@ impl MessageService {
         let member = AllianceRepository::get_user_alliance(pool, sender_id)
             .await?
             .ok_or_else(|| AppError::BadRequest("You are not in an alliance".into()))?;
+        AllianceService::require_permission(
+            pool,
+            member.alliance_id,
+            sender_id,
+            AlliancePermission::Broadcast,
+        )
+        .await?;
 
         // Create the message
         let message = MessageRepository::create_alliance_message(
@@ -1,11 +1,19 @@
+pub mod activity_service;
 pub mod alliance_service;
+pub mod api_token_service;
 pub mod army_service;
 pub mod background_jobs;
 pub mod building_service;
+pub mod cache_service;
+pub mod forum_service;
 pub mod hero_service;
+pub mod job_status_service;
 pub mod message_service;
+pub mod oasis_service;
+pub mod player_service;
 pub mod resource_service;
 pub mod shop_service;
+pub mod transfer_service;
 pub mod troop_service;
 pub mod village_service;
 pub mod ws_service;
@@ -0,0 +1,192 @@
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::GameConfig;
+use crate::error::{AppError, AppResult};
+use crate::models::building::BuildingType;
+use crate::models::oasis::{max_oases, Oasis, VillageOasesResponse, OASIS_MANSION_LEVELS};
+use crate::models::village::Village;
+use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::oasis_repo::OasisRepository;
+
+/// Production bonus (percent) per resource from occupied oases
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OasisBonus {
+    pub wood: i32,
+    pub clay: i32,
+    pub iron: i32,
+    pub crop: i32,
+}
+
+pub struct OasisService;
+
+impl OasisService {
+    /// Highest completed Hero's Mansion level in a village (0 without one)
+    pub async fn mansion_level(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
+        let mansions =
+            BuildingRepository::find_by_type(pool, village_id, BuildingType::HeroMansion).await?;
+        Ok(mansions.iter().map(|b| b.level).max().unwrap_or(0))
+    }
+
+    /// Reject occupying one more oasis when the mansion level does not allow it
+    pub fn check_capacity(mansion_level: i32, occupied: usize) -> AppResult<()> {
+        let allowed = max_oases(mansion_level);
+        if (occupied as i32) < allowed {
+            return Ok(());
+        }
+
+        match OASIS_MANSION_LEVELS.get(allowed as usize) {
+            Some(required) => Err(AppError::BadRequest(format!(
+                "Hero's Mansion level {} is required to occupy another oasis",
+                required
+            ))),
+            None => Err(AppError::BadRequest(
+                "This village already holds the maximum number of oases".into(),
+            )),
+        }
+    }
+
+    /// Oases a village must give up to fit `allowed` slots: the weakest first,
+    /// the oldest occupation among equally strong ones
+    pub fn oases_to_release(oases: &[Oasis], allowed: i32) -> Vec<Uuid> {
+        let excess = oases.len().saturating_sub(allowed.max(0) as usize);
+        let mut ranked: Vec<&Oasis> = oases.iter().collect();
+        ranked.sort_by_key(|o| (o.total_bonus(), o.occupied_at));
+        ranked.into_iter().take(excess).map(|o| o.id).collect()
+    }
+
+    /// Oases a village holds and how many its Hero's Mansion allows
+    pub async fn get_village_oases(
+        pool: &PgPool,
+        village_id: Uuid,
+    ) -> AppResult<VillageOasesResponse> {
+        let mansion_level = Self::mansion_level(pool, village_id).await?;
+        let max_oases = max_oases(mansion_level);
+
+        Ok(VillageOasesResponse {
+            village_id,
+            mansion_level,
+            max_oases,
+            next_slot_level: OASIS_MANSION_LEVELS.get(max_oases as usize).copied(),
+            oases: OasisRepository::find_by_village(pool, village_id).await?,
+        })
+    }
+
+    /// Occupy a free oasis near the village, within the Hero's Mansion cap
+    pub async fn occupy(
+        pool: &PgPool,
+        game: &GameConfig,
+        village: &Village,
+        oasis_id: Uuid,
+    ) -> AppResult<Oasis> {
+        let oasis = OasisRepository::find_by_id(pool, oasis_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Oasis", oasis_id))?;
+
+        if oasis.village_id.is_some() {
+            return Err(AppError::BadRequest("Oasis is already occupied".into()));
+        }
+
+        if (oasis.x - village.x).abs() > game.oasis_max_distance
+            || (oasis.y - village.y).abs() > game.oasis_max_distance
+        {
+            return Err(AppError::BadRequest(format!(
+                "Oasis must be within {} fields of the village",
+                game.oasis_max_distance
+            )));
+        }
+
+        let mansion_level = Self::mansion_level(pool, village.id).await?;
+        let occupied = OasisRepository::find_by_village(pool, village.id).await?;
+        Self::check_capacity(mansion_level, occupied.len())?;
+
+        // The update re-checks both conditions in case another request got there first
+        let oasis = OasisRepository::occupy(pool, oasis_id, village.id, max_oases(mansion_level))
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest("Oasis was occupied or the oasis limit was reached".into())
+            })?;
+
+        info!(
+            "Village {} occupied oasis {} at ({}|{})",
+            village.id, oasis.id, oasis.x, oasis.y
+        );
+
+        Ok(oasis)
+    }
+
+    /// Give up an oasis the village holds
+    pub async fn abandon(pool: &PgPool, village_id: Uuid, oasis_id: Uuid) -> AppResult<()> {
+        if !OasisRepository::release(pool, oasis_id, village_id).await? {
+            return Err(AppError::not_found("Oasis", oasis_id));
+        }
+        Ok(())
+    }
+
+    /// Combined production bonus of the oases a village holds
+    pub async fn production_bonus(pool: &PgPool, village_id: Uuid) -> AppResult<OasisBonus> {
+        let bonus = OasisRepository::find_by_village(pool, village_id)
+            .await?
+            .iter()
+            .fold(OasisBonus::default(), |acc, o| OasisBonus {
+                wood: acc.wood + o.wood_bonus,
+                clay: acc.clay + o.clay_bonus,
+                iron: acc.iron + o.iron_bonus,
+                crop: acc.crop + o.crop_bonus,
+            });
+        Ok(bonus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn oasis(bonus: i32, occupied_minutes_ago: i64) -> Oasis {
+        Oasis {
+            id: Uuid::new_v4(),
+            x: 0,
+            y: 0,
+            wood_bonus: bonus,
+            clay_bonus: 0,
+            iron_bonus: 0,
+            crop_bonus: 0,
+            village_id: Some(Uuid::new_v4()),
+            occupied_at: Some(Utc::now() - Duration::minutes(occupied_minutes_ago)),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn occupation_allowed_at_required_mansion_level() {
+        assert!(OasisService::check_capacity(10, 0).is_ok());
+        assert!(OasisService::check_capacity(15, 1).is_ok());
+        assert!(OasisService::check_capacity(20, 2).is_ok());
+    }
+
+    #[test]
+    fn occupation_rejected_below_required_mansion_level() {
+        assert!(OasisService::check_capacity(0, 0).is_err());
+        assert!(OasisService::check_capacity(9, 0).is_err());
+        assert!(OasisService::check_capacity(14, 1).is_err());
+        assert!(OasisService::check_capacity(20, 3).is_err());
+    }
+
+    #[test]
+    fn downgrade_releases_weakest_then_oldest() {
+        let strong = oasis(50, 30);
+        let weak_old = oasis(25, 20);
+        let weak_new = oasis(25, 10);
+        let oases = vec![strong.clone(), weak_new.clone(), weak_old.clone()];
+
+        assert_eq!(OasisService::oases_to_release(&oases, 2), vec![weak_old.id]);
+        assert_eq!(
+            OasisService::oases_to_release(&oases, 1),
+            vec![weak_old.id, weak_new.id]
+        );
+        assert!(OasisService::oases_to_release(&oases, 3).is_empty());
+        assert_eq!(OasisService::oases_to_release(&oases, 0).len(), 3);
+    }
+}
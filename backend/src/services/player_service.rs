@@ -0,0 +1,96 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::GameConfig;
+use crate::error::{AppError, AppResult};
+use crate::models::user::PublicPlayerProfile;
+use crate::repositories::user_repo::UserRepository;
+
+pub struct PlayerService;
+
+impl PlayerService {
+    /// Public profile of any player; a missing (or deleted) player is a 404
+    pub async fn get_public_profile(
+        pool: &PgPool,
+        game: &GameConfig,
+        player_id: Uuid,
+    ) -> AppResult<PublicPlayerProfile> {
+        let profile = UserRepository::get_public_profile(pool, player_id).await?;
+        Self::public_profile(game, player_id, profile, Utc::now())
+    }
+
+    /// Complete a profile row with the player's beginner protection, which lasts
+    /// a fixed number of hours after joining
+    fn public_profile(
+        game: &GameConfig,
+        player_id: Uuid,
+        profile: Option<PublicPlayerProfile>,
+        now: DateTime<Utc>,
+    ) -> AppResult<PublicPlayerProfile> {
+        let mut profile = profile.ok_or_else(|| AppError::not_found("Player", player_id))?;
+        profile.under_beginner_protection =
+            profile.joined_at + Duration::hours(game.beginner_protection_hours) > now;
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    use crate::models::troop::TribeType;
+    use crate::test_support::game_config;
+
+    fn profile(joined_at: DateTime<Utc>) -> PublicPlayerProfile {
+        PublicPlayerProfile {
+            id: Uuid::new_v4(),
+            display_name: Some("Naresuan".to_string()),
+            tribe: Some(TribeType::Phasuttha),
+            alliance_id: Some(Uuid::new_v4()),
+            alliance_name: Some("Kingdom of Siam".to_string()),
+            alliance_tag: Some("SIAM".to_string()),
+            population: 1240,
+            village_count: 3,
+            rank: 7,
+            joined_at,
+            under_beginner_protection: false,
+        }
+    }
+
+    #[test]
+    fn populated_profile_reports_beginner_protection() {
+        let game = GameConfig {
+            beginner_protection_hours: 72,
+            ..game_config()
+        };
+        let now = Utc::now();
+
+        let newcomer = profile(now - Duration::hours(71));
+        let built = PlayerService::public_profile(&game, newcomer.id, Some(newcomer), now).unwrap();
+        assert!(built.under_beginner_protection);
+        assert_eq!(built.display_name.as_deref(), Some("Naresuan"));
+        assert_eq!(built.alliance_tag.as_deref(), Some("SIAM"));
+        assert_eq!(
+            (built.population, built.village_count, built.rank),
+            (1240, 3, 7)
+        );
+
+        // Protection ends exactly when the window runs out
+        let veteran = profile(now - Duration::hours(72));
+        let built = PlayerService::public_profile(&game, veteran.id, Some(veteran), now).unwrap();
+        assert!(!built.under_beginner_protection);
+    }
+
+    #[test]
+    fn unknown_player_is_not_found() {
+        let player_id = Uuid::new_v4();
+        let error =
+            PlayerService::public_profile(&game_config(), player_id, None, Utc::now()).unwrap_err();
+
+        assert!(matches!(&error, AppError::NotFound(msg) if msg.contains(&player_id.to_string())));
+        assert_eq!(error.into_response().status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,12 +1,21 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::AppResult;
 use crate::models::building::BuildingType;
-use crate::models::village::Village;
+use crate::models::hero::{Hero, HeroStatus};
+use crate::models::village::{
+    ProductionBreakdownResponse, ResourceProductionBreakdown, ResourceProjection,
+    ResourceProjectionResponse, Village,
+};
 use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::hero_repo::HeroRepository;
+use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::oasis_service::OasisService;
+use crate::services::village_service::VillageService;
 
 pub struct ResourceService;
 
@@ -16,7 +25,7 @@ pub struct ProductionRates {
     pub clay_per_hour: i32,
     pub iron_per_hour: i32,
     pub crop_per_hour: i32,
-    pub crop_consumption: i32,  // Population eats crop
+    pub crop_consumption: i32,  // Population and troops eat crop
     pub net_crop_per_hour: i32, // crop_per_hour - crop_consumption
 }
 
@@ -24,54 +33,168 @@ impl ResourceService {
     /// Calculate production rates for a village based on its buildings
     pub async fn calculate_production(
         pool: &PgPool,
+        game: &GameConfig,
         village_id: Uuid,
     ) -> AppResult<ProductionRates> {
-        let village = VillageRepository::find_by_id(pool, village_id)
+        let breakdown = Self::calculate_breakdown(pool, game, village_id).await?;
+        Ok(Self::rates(&breakdown))
+    }
+
+    /// Net hourly rates of a production breakdown
+    fn rates(breakdown: &ProductionBreakdownResponse) -> ProductionRates {
+        ProductionRates {
+            wood_per_hour: breakdown.wood.total,
+            clay_per_hour: breakdown.clay.total,
+            iron_per_hour: breakdown.iron.total,
+            crop_per_hour: breakdown.crop.total,
+            crop_consumption: breakdown.crop_consumption,
+            net_crop_per_hour: breakdown.net_crop_per_hour,
+        }
+    }
+
+    /// Every term that goes into a village's production, per resource
+    pub async fn calculate_breakdown(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<ProductionBreakdownResponse> {
+        // Fail on unknown villages rather than reporting empty production
+        VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound("Village not found".to_string()))?;
+            .ok_or_else(|| crate::error::AppError::not_found("Village", village_id))?;
 
         let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
+        let building_population = VillageService::building_population(&buildings);
 
-        let mut wood_per_hour = 3; // Base production
-        let mut clay_per_hour = 3;
-        let mut iron_per_hour = 3;
-        let mut crop_per_hour = 3;
+        // Field production and bonus percentages per resource
+        let mut wood_fields = 0;
+        let mut clay_fields = 0;
+        let mut iron_fields = 0;
+        let mut crop_fields = 0;
+        let mut wood_bonus = 0;
+        let mut clay_bonus = 0;
+        let mut iron_bonus = 0;
+        let mut crop_bonus = 0;
 
         for building in buildings {
             if building.level == 0 {
                 continue;
             }
 
-            let production = building.building_type.production_per_hour(building.level);
+            let production = building.building_type.production_per_hour(
+                building.level,
+                game.field_production_base,
+                game.field_production_growth,
+                game.field_production_curve,
+            );
+            let bonus = building.building_type.production_bonus_percent(building.level);
 
             match building.building_type {
-                BuildingType::Woodcutter => wood_per_hour += production,
-                BuildingType::ClayPit => clay_per_hour += production,
-                BuildingType::IronMine => iron_per_hour += production,
-                BuildingType::CropField => crop_per_hour += production,
+                BuildingType::Woodcutter => wood_fields += production,
+                BuildingType::ClayPit => clay_fields += production,
+                BuildingType::IronMine => iron_fields += production,
+                BuildingType::CropField => crop_fields += production,
+                BuildingType::Sawmill => wood_bonus += bonus,
+                BuildingType::Brickyard => clay_bonus += bonus,
+                BuildingType::IronFoundry => iron_bonus += bonus,
+                BuildingType::GrainMill | BuildingType::Bakery => crop_bonus += bonus,
                 _ => {}
             }
         }
 
-        // Population consumes crop (1 crop per population per hour)
-        let crop_consumption = village.population;
-        let net_crop_per_hour = crop_per_hour - crop_consumption;
+        // Idle heroes stationed here boost every resource
+        let hero_bonus = Self::hero_production_bonus(pool, game, village_id).await?;
+        // Occupied oases boost their resource
+        let oasis_bonus = OasisService::production_bonus(pool, village_id).await?;
+
+        // Base production plus boosted field production
+        let base = game.village_base_production;
+        let breakdown = |fields: i32, building_bonus: i32, oasis_bonus: i32| {
+            Self::boosted_production(base, fields, building_bonus, hero_bonus, oasis_bonus)
+        };
+        let crop = breakdown(crop_fields, crop_bonus, oasis_bonus.crop);
 
-        Ok(ProductionRates {
-            wood_per_hour,
-            clay_per_hour,
-            iron_per_hour,
-            crop_per_hour,
+        // Buildings consume 1 crop per population per hour on top of the upkeep of
+        // every troop the village feeds; stored population already includes the
+        // village's own troops, so building population is used to avoid charging
+        // them twice. Net crop goes negative once the village cannot feed them.
+        let crop_consumption = building_population
+            + TroopRepository::get_total_crop_consumption(pool, village_id).await?;
+        let net_crop_per_hour = crop.total - crop_consumption;
+
+        Ok(ProductionBreakdownResponse {
+            village_id,
+            wood: breakdown(wood_fields, wood_bonus, oasis_bonus.wood),
+            clay: breakdown(clay_fields, clay_bonus, oasis_bonus.clay),
+            iron: breakdown(iron_fields, iron_bonus, oasis_bonus.iron),
+            crop,
             crop_consumption,
             net_crop_per_hour,
         })
     }
 
+    /// One resource's hourly production: the flat base plus field output raised
+    /// by every percentage boost
+    fn boosted_production(
+        base: i32,
+        fields: i32,
+        building_bonus: i32,
+        hero_bonus: i32,
+        oasis_bonus: i32,
+    ) -> ResourceProductionBreakdown {
+        let total = base + fields * (100 + building_bonus + hero_bonus + oasis_bonus) / 100;
+        ResourceProductionBreakdown {
+            base,
+            fields,
+            building_bonus_percent: building_bonus,
+            hero_bonus_percent: hero_bonus,
+            oasis_bonus_percent: oasis_bonus,
+            bonus: total - base - fields,
+            total,
+        }
+    }
+
+    /// Production bonus (percent) from idle heroes stationed in a village: their
+    /// resources_bonus points plus equipped resource_bonus items, capped by config.
+    /// Heroes away on adventures, moving or dead are not idle and grant nothing.
+    async fn hero_production_bonus(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<i32> {
+        let mut heroes = Vec::new();
+        for hero in HeroRepository::find_idle_at_village(pool, village_id).await? {
+            let item_bonus: i32 = HeroRepository::get_equipped_items(pool, hero.id)
+                .await?
+                .iter()
+                .map(|(_, item)| item.resource_bonus)
+                .sum();
+            heroes.push((hero, item_bonus));
+        }
+
+        Ok(Self::hero_bonus_percent(game, &heroes))
+    }
+
+    /// Combined production bonus of heroes with the resource_bonus of their equipped
+    /// items; only idle heroes count and the total is capped by config
+    fn hero_bonus_percent(game: &GameConfig, heroes: &[(Hero, i32)]) -> i32 {
+        let total: i32 = heroes
+            .iter()
+            .filter(|(hero, _)| hero.status == HeroStatus::Idle)
+            .map(|(hero, item_bonus)| hero.resources_bonus + item_bonus)
+            .sum();
+        total.clamp(0, game.hero_production_bonus_max_percent.max(0))
+    }
+
     /// Update resources for a village based on time elapsed
-    pub async fn update_village_resources(pool: &PgPool, village_id: Uuid) -> AppResult<Village> {
+    pub async fn update_village_resources(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<Village> {
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound("Village not found".to_string()))?;
+            .ok_or_else(|| crate::error::AppError::not_found("Village", village_id))?;
 
         let now = Utc::now();
         let elapsed_seconds = (now - village.resources_updated_at).num_seconds();
@@ -80,33 +203,25 @@ impl ResourceService {
             return Ok(village);
         }
 
-        let production = Self::calculate_production(pool, village_id).await?;
-
-        // Calculate resources produced
-        let hours_elapsed = elapsed_seconds as f64 / 3600.0;
-
-        let wood_produced = (production.wood_per_hour as f64 * hours_elapsed) as i32;
-        let clay_produced = (production.clay_per_hour as f64 * hours_elapsed) as i32;
-        let iron_produced = (production.iron_per_hour as f64 * hours_elapsed) as i32;
-        // Use net_crop which accounts for population consumption
-        let crop_change = (production.net_crop_per_hour as f64 * hours_elapsed) as i32;
-
-        // Calculate new resource amounts (capped at storage, min 0)
-        let new_wood = (village.wood + wood_produced).min(village.warehouse_capacity).max(0);
-        let new_clay = (village.clay + clay_produced).min(village.warehouse_capacity).max(0);
-        let new_iron = (village.iron + iron_produced).min(village.warehouse_capacity).max(0);
-        let new_crop = (village.crop + crop_change).min(village.granary_capacity).max(0);
+        let production = Self::calculate_production(pool, game, village_id).await?;
+        let accrued = Self::accrue(&village, &production, now);
 
         // Update village resources
-        let updated =
-            VillageRepository::update_resources(pool, village_id, new_wood, new_clay, new_iron, new_crop)
-                .await?;
+        let updated = VillageRepository::update_resources(
+            pool,
+            village_id,
+            accrued.wood,
+            accrued.clay,
+            accrued.iron,
+            accrued.crop,
+        )
+        .await?;
 
         Ok(updated)
     }
 
     /// Update resources for all villages (for background job)
-    pub async fn update_all_village_resources(pool: &PgPool) -> AppResult<i32> {
+    pub async fn update_all_village_resources(pool: &PgPool, game: &GameConfig) -> AppResult<i32> {
         // Get all villages that need updating (not updated in last minute)
         let villages: Vec<(Uuid,)> = sqlx::query_as(
             r#"
@@ -120,11 +235,239 @@ impl ResourceService {
         let mut updated_count = 0;
 
         for (village_id,) in villages {
-            if let Ok(_) = Self::update_village_resources(pool, village_id).await {
+            if let Ok(_) = Self::update_village_resources(pool, game, village_id).await {
                 updated_count += 1;
             }
         }
 
         Ok(updated_count)
     }
+
+    /// Project when resources reach the given targets and when stores overflow
+    pub async fn project_resources(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+        targets: [Option<i32>; 4],
+    ) -> AppResult<ResourceProjectionResponse> {
+        let village = Self::update_village_resources(pool, game, village_id).await?;
+        let production = Self::calculate_production(pool, game, village_id).await?;
+        let now = Utc::now();
+        let [target_wood, target_clay, target_iron, target_crop] = targets;
+
+        Ok(ResourceProjectionResponse {
+            village_id,
+            wood: Self::project(village.wood, production.wood_per_hour, village.warehouse_capacity, target_wood),
+            clay: Self::project(village.clay, production.clay_per_hour, village.warehouse_capacity, target_clay),
+            iron: Self::project(village.iron, production.iron_per_hour, village.warehouse_capacity, target_iron),
+            crop: Self::project(village.crop, production.net_crop_per_hour, village.granary_capacity, target_crop),
+            calculated_at: now,
+        })
+    }
+
+    /// Project a single resource from its balance, hourly rate and capacity
+    pub fn project(current: i32, per_hour: i32, capacity: i32, target: Option<i32>) -> ResourceProjection {
+        let now = Utc::now();
+        // Targets above capacity can never be reached
+        let seconds_to_target = target.and_then(|t| {
+            if t > capacity {
+                None
+            } else {
+                Self::seconds_until(current, per_hour, t)
+            }
+        });
+        let seconds_to_full = Self::seconds_until(current, per_hour, capacity);
+
+        ResourceProjection {
+            current,
+            per_hour,
+            capacity,
+            target,
+            seconds_to_target,
+            seconds_to_full,
+            target_reached_at: seconds_to_target.map(|s| now + chrono::Duration::seconds(s)),
+            full_at: seconds_to_full.map(|s| now + chrono::Duration::seconds(s)),
+        }
+    }
+
+    /// The village with production since its last update added to its stock,
+    /// capped at storage (crop can also drain to zero)
+    pub fn accrue(village: &Village, production: &ProductionRates, now: DateTime<Utc>) -> Village {
+        let hours_elapsed = (now - village.resources_updated_at).num_seconds().max(0) as f64 / 3600.0;
+        let produced = |per_hour: i32| (per_hour as f64 * hours_elapsed) as i32;
+
+        Village {
+            wood: (village.wood + produced(production.wood_per_hour)).clamp(0, village.warehouse_capacity),
+            clay: (village.clay + produced(production.clay_per_hour)).clamp(0, village.warehouse_capacity),
+            iron: (village.iron + produced(production.iron_per_hour)).clamp(0, village.warehouse_capacity),
+            // Net crop accounts for population and troop consumption
+            crop: (village.crop + produced(production.net_crop_per_hour)).clamp(0, village.granary_capacity),
+            resources_updated_at: now,
+            ..village.clone()
+        }
+    }
+
+    /// Seconds until `amount` reaches `target` at `per_hour` (None if it never will)
+    fn seconds_until(amount: i32, per_hour: i32, target: i32) -> Option<i64> {
+        if amount >= target {
+            return Some(0);
+        }
+        if per_hour <= 0 {
+            return None;
+        }
+        let missing = (target - amount) as i64;
+        // Round up so the resource is actually available at the projected time
+        Some((missing * 3600 + per_hour as i64 - 1) / per_hour as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::hero;
+
+    #[test]
+    fn projects_time_to_target_and_to_full_storage() {
+        let projection = ResourceService::project(400, 600, 1000, Some(700));
+
+        assert_eq!(projection.seconds_to_target, Some(1800));
+        assert_eq!(projection.seconds_to_full, Some(3600));
+    }
+
+    #[test]
+    fn projected_times_round_up_to_whole_seconds() {
+        // 1 unit at 7/hour takes 514.28... seconds
+        let projection = ResourceService::project(0, 7, 1000, Some(1));
+        assert_eq!(projection.seconds_to_target, Some(515));
+    }
+
+    #[test]
+    fn projection_without_production_never_reaches_its_target() {
+        let stalled = ResourceService::project(400, 0, 1000, Some(700));
+        assert_eq!(stalled.seconds_to_target, None);
+        assert_eq!(stalled.seconds_to_full, None);
+
+        let starving = ResourceService::project(400, -50, 1000, Some(700));
+        assert_eq!(starving.seconds_to_target, None);
+        assert_eq!(starving.full_at, None);
+    }
+
+    #[test]
+    fn projection_for_reached_targets_and_full_stores_is_immediate() {
+        let projection = ResourceService::project(1000, 100, 1000, Some(500));
+        assert_eq!(projection.seconds_to_target, Some(0));
+        assert_eq!(projection.seconds_to_full, Some(0));
+    }
+
+    #[test]
+    fn targets_above_capacity_are_never_reached() {
+        let projection = ResourceService::project(0, 1000, 800, Some(900));
+        assert_eq!(projection.seconds_to_target, None);
+        assert_eq!(projection.seconds_to_full, Some(2880));
+    }
+
+    #[test]
+    fn sawmill_raises_wood_production_by_its_percentage() {
+        let sawmill_bonus = BuildingType::Sawmill.production_bonus_percent(3);
+        assert_eq!(sawmill_bonus, 15);
+
+        let without = ResourceService::boosted_production(3, 200, 0, 0, 0);
+        let with = ResourceService::boosted_production(3, 200, sawmill_bonus, 0, 0);
+
+        assert_eq!(without.total, 203);
+        assert_eq!(with.total, 233);
+        assert_eq!(with.bonus, 30);
+    }
+
+    #[test]
+    fn higher_configured_base_scales_field_production() {
+        let field = |game: &GameConfig, level: i32| {
+            BuildingType::Woodcutter.production_per_hour(
+                level,
+                game.field_production_base,
+                game.field_production_growth,
+                game.field_production_curve,
+            )
+        };
+        let default = GameConfig::default();
+        let doubled = GameConfig { field_production_base: 6.0, ..GameConfig::default() };
+
+        assert_eq!(field(&default, 1), 3);
+        assert_eq!(field(&doubled, 1), 6);
+        assert_eq!(field(&default, 5), 22);
+        assert_eq!(field(&doubled, 5), 44);
+
+        // The Sawmill bonus applies on top of the larger field output
+        let bonus = BuildingType::Sawmill.production_bonus_percent(3);
+        let boosted = |game: &GameConfig| {
+            let base = game.village_base_production;
+            ResourceService::boosted_production(base, field(game, 5), bonus, 0, 0)
+        };
+        assert_eq!(boosted(&default).bonus, 3);
+        assert_eq!(boosted(&doubled).bonus, 6);
+        assert_eq!(boosted(&doubled).total, 3 + 44 + 6);
+    }
+
+    #[test]
+    fn equipped_resource_item_raises_stationed_hero_production() {
+        let game = GameConfig::default();
+        let mut stationed = hero(HeroStatus::Idle);
+        stationed.resources_bonus = 5;
+
+        let without = ResourceService::hero_bonus_percent(&game, &[(stationed.clone(), 0)]);
+        let with = ResourceService::hero_bonus_percent(&game, &[(stationed, 10)]);
+        assert_eq!((without, with), (5, 15));
+
+        let production = |hero_bonus| ResourceService::boosted_production(3, 200, 0, hero_bonus, 0);
+        assert_eq!(production(without).total, 213);
+        assert_eq!(production(with).total, 233);
+    }
+
+    #[test]
+    fn hero_item_bonus_is_capped_and_needs_an_idle_hero() {
+        let game = GameConfig { hero_production_bonus_max_percent: 25, ..GameConfig::default() };
+        let mut idle = hero(HeroStatus::Idle);
+        idle.resources_bonus = 20;
+        assert_eq!(ResourceService::hero_bonus_percent(&game, &[(idle, 30)]), 25);
+
+        for status in [HeroStatus::Dead, HeroStatus::InAdventure, HeroStatus::Moving] {
+            let away = hero(status);
+            assert_eq!(ResourceService::hero_bonus_percent(&game, &[(away, 30)]), 0);
+        }
+    }
+
+    #[test]
+    fn breakdown_components_sum_to_the_reported_production() {
+        let wood = ResourceService::boosted_production(3, 400, 15, 10, 25);
+        let clay = ResourceService::boosted_production(3, 250, 0, 10, 0);
+        let iron = ResourceService::boosted_production(3, 0, 0, 10, 0);
+        let crop = ResourceService::boosted_production(3, 600, 10, 10, 50);
+        for resource in [&wood, &clay, &iron, &crop] {
+            assert_eq!(resource.base + resource.fields + resource.bonus, resource.total);
+        }
+        assert_eq!(wood.bonus, 400 * 50 / 100);
+
+        let breakdown = ProductionBreakdownResponse {
+            village_id: Uuid::new_v4(),
+            wood,
+            clay,
+            iron,
+            crop: crop.clone(),
+            crop_consumption: 450,
+            net_crop_per_hour: crop.total - 450,
+        };
+        let rates = ResourceService::rates(&breakdown);
+
+        assert_eq!(rates.wood_per_hour, breakdown.wood.total);
+        assert_eq!(rates.iron_per_hour, 3);
+        assert_eq!(rates.crop_per_hour, 3 + 600 + 420);
+        assert_eq!(rates.net_crop_per_hour, rates.crop_per_hour - rates.crop_consumption);
+    }
+
+    #[test]
+    fn only_processing_buildings_grant_production_bonus() {
+        assert_eq!(BuildingType::Brickyard.production_bonus_percent(5), 25);
+        assert_eq!(BuildingType::Woodcutter.production_bonus_percent(5), 0);
+        assert_eq!(BuildingType::Warehouse.production_bonus_percent(5), 0);
+    }
 }
@@ -5,19 +5,22 @@ use sqlx::PgPool;
 use stripe_rust::{
     CheckoutSession, CheckoutSessionMode, Client, CreateCheckoutSession,
     CreateCheckoutSessionLineItems, CreateCheckoutSessionLineItemsPriceData,
-    CreateCheckoutSessionLineItemsPriceDataProductData, Currency,
+    CreateCheckoutSessionLineItemsPriceDataProductData, Currency, Price, PriceId,
 };
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::models::shop::{
-    CheckoutResponse, GoldBalanceResponse, GoldFeature, GoldPackage, SubscriptionPrice,
-    SubscriptionType, TransactionResponse, TransactionStatus, TransactionType, UseFeatureResponse,
+    CheckoutResponse, GoldBalanceResponse, GoldFeature, GoldPackage, GoldUsageListResponse, NpcSplitMode,
+    NpcSplitQuery, NpcSplitSuggestion, SubscriptionPrice, SubscriptionType,
+    TransactionListResponse, TransactionStatus, TransactionType, UseFeatureResponse,
 };
 use crate::repositories::building_repo::BuildingRepository;
 use crate::repositories::shop_repo::ShopRepository;
 use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::building_service::BuildingService;
+use crate::services::village_service::VillageService;
 
 pub struct ShopService;
 
@@ -60,9 +63,10 @@ impl ShopService {
             .await?
             .ok_or_else(|| AppError::NotFound("Gold package not found".into()))?;
 
-        if !package.is_active {
-            return Err(AppError::BadRequest("This package is not available".into()));
-        }
+        // A package must have a sane price in a currency Stripe knows, and a linked
+        // Stripe price must charge exactly that, or the purchase is refused
+        let currency = Self::validate_package_pricing(stripe_client, &package).await?;
+        let currency_code = currency.to_string().to_uppercase();
 
         // Calculate total gold including bonus
         let bonus_gold = (package.gold_amount * package.bonus_percent) / 100;
@@ -75,7 +79,7 @@ impl ShopService {
             TransactionType::GoldPurchase,
             total_gold,
             Some(package.price_cents),
-            Some(&package.currency),
+            Some(&currency_code),
             None, // Will be updated after checkout created
             Some(package_id),
             Some(&format!("Purchase {} Gold", total_gold)),
@@ -90,26 +94,33 @@ impl ShopService {
         params.cancel_url = Some(cancel_url);
         params.client_reference_id = Some(&client_reference_id);
 
-        let line_item = CreateCheckoutSessionLineItems {
-            price_data: Some(CreateCheckoutSessionLineItemsPriceData {
-                currency: Currency::USD,
-                unit_amount: Some(package.price_cents as i64),
-                product_data: Some(CreateCheckoutSessionLineItemsPriceDataProductData {
-                    name: format!("{} Gold", total_gold),
-                    description: if bonus_gold > 0 {
-                        Some(format!(
-                            "{} Gold + {} Bonus Gold ({}% extra)",
-                            package.gold_amount, bonus_gold, package.bonus_percent
-                        ))
-                    } else {
-                        Some(format!("{} Gold for your account", total_gold))
-                    },
+        let line_item = match &package.stripe_price_id {
+            Some(price_id) => CreateCheckoutSessionLineItems {
+                price: Some(price_id.clone()),
+                quantity: Some(1),
+                ..Default::default()
+            },
+            None => CreateCheckoutSessionLineItems {
+                price_data: Some(CreateCheckoutSessionLineItemsPriceData {
+                    currency,
+                    unit_amount: Some(package.price_cents as i64),
+                    product_data: Some(CreateCheckoutSessionLineItemsPriceDataProductData {
+                        name: format!("{} Gold", total_gold),
+                        description: if bonus_gold > 0 {
+                            Some(format!(
+                                "{} Gold + {} Bonus Gold ({}% extra)",
+                                package.gold_amount, bonus_gold, package.bonus_percent
+                            ))
+                        } else {
+                            Some(format!("{} Gold for your account", total_gold))
+                        },
+                        ..Default::default()
+                    }),
                     ..Default::default()
                 }),
+                quantity: Some(1),
                 ..Default::default()
-            }),
-            quantity: Some(1),
-            ..Default::default()
+            },
         };
         params.line_items = Some(vec![line_item]);
 
@@ -132,6 +143,65 @@ impl ShopService {
         })
     }
 
+    /// Check a gold package can be sold: active, positive gold and price, a currency
+    /// Stripe supports and, when linked to a Stripe price, that the price is active
+    /// and charges the same amount in the same currency. Returns the currency.
+    async fn validate_package_pricing(
+        stripe_client: &Client,
+        package: &GoldPackage,
+    ) -> AppResult<Currency> {
+        let currency = Self::check_package(package)?;
+
+        if let Some(price_id) = &package.stripe_price_id {
+            let price_id: PriceId = price_id.parse().map_err(|_| {
+                tracing::warn!("Gold package {} has invalid Stripe price id {}", package.id, price_id);
+                Self::package_unavailable()
+            })?;
+            let price = Price::retrieve(stripe_client, &price_id, &[])
+                .await
+                .map_err(|e| AppError::InternalError(anyhow::anyhow!("Stripe error: {}", e)))?;
+
+            if !Self::price_matches(&price, package, currency) {
+                tracing::warn!(
+                    "Gold package {} does not match Stripe price {} ({:?} {:?}, active {:?})",
+                    package.id, price_id, price.unit_amount, price.currency, price.active
+                );
+                return Err(Self::package_unavailable());
+            }
+        }
+
+        Ok(currency)
+    }
+
+    fn package_unavailable() -> AppError {
+        AppError::BadRequest("This package is not available".into())
+    }
+
+    /// The package's own checks, before asking Stripe: active, positive gold and
+    /// price, and a currency Stripe knows
+    fn check_package(package: &GoldPackage) -> AppResult<Currency> {
+        if !package.is_active {
+            return Err(Self::package_unavailable());
+        }
+
+        if package.gold_amount <= 0 || package.price_cents <= 0 {
+            tracing::warn!("Gold package {} has no gold or no price", package.id);
+            return Err(Self::package_unavailable());
+        }
+
+        package.currency.to_lowercase().parse().map_err(|_| {
+            tracing::warn!("Gold package {} has unknown currency {}", package.id, package.currency);
+            Self::package_unavailable()
+        })
+    }
+
+    /// Whether a Stripe price is active and charges exactly what the package says
+    fn price_matches(price: &Price, package: &GoldPackage, currency: Currency) -> bool {
+        price.active != Some(false)
+            && price.currency == Some(currency)
+            && price.unit_amount == Some(package.price_cents as i64)
+    }
+
     /// Handle Stripe webhook
     pub async fn handle_webhook(
         pool: &PgPool,
@@ -371,7 +441,7 @@ impl ShopService {
             "building" => {
                 let building = BuildingRepository::find_by_id(pool, target_id)
                     .await?
-                    .ok_or_else(|| AppError::NotFound("Building not found".into()))?;
+                    .ok_or_else(|| AppError::not_found("Building", target_id))?;
 
                 if !building.is_upgrading {
                     return Err(AppError::BadRequest("Building is not upgrading".into()));
@@ -387,7 +457,7 @@ impl ShopService {
             "troop_queue" => {
                 let queue = TroopRepository::find_queue_by_id(pool, target_id)
                     .await?
-                    .ok_or_else(|| AppError::NotFound("Training queue not found".into()))?;
+                    .ok_or_else(|| AppError::not_found("Training queue", target_id))?;
 
                 let remaining = (queue.ends_at - Utc::now()).num_seconds().max(0) as i32;
 
@@ -399,7 +469,7 @@ impl ShopService {
         // Verify ownership
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -420,10 +490,12 @@ impl ShopService {
         // Complete the target instantly
         match target_type {
             "building" => {
-                BuildingRepository::complete_upgrade(pool, target_id).await?;
+                // Same completion as the background job, so chained levels carry on
+                BuildingService::complete_upgrade(pool, target_id).await?;
             }
             "troop_queue" => {
                 TroopRepository::complete_training(pool, target_id).await?;
+                VillageService::recompute_population(pool, village_id).await?;
             }
             _ => {}
         }
@@ -463,6 +535,99 @@ impl ShopService {
         })
     }
 
+    /// Suggest an NPC merchant split of the village's current resources.
+    /// Uses the same stored balances and capacities use_npc_merchant validates
+    /// against, so the suggestion is accepted unchanged.
+    pub async fn suggest_npc_split(
+        pool: &PgPool,
+        user_id: Uuid,
+        village_id: Uuid,
+        query: NpcSplitQuery,
+    ) -> AppResult<NpcSplitSuggestion> {
+        let village = VillageRepository::find_by_id(pool, village_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
+
+        if village.user_id != user_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        let weights = match query.mode {
+            NpcSplitMode::Even => [1, 1, 1, 1],
+            NpcSplitMode::Ratio => [query.wood, query.clay, query.iron, query.crop],
+        };
+        if weights.iter().all(|w| *w == 0) {
+            return Err(AppError::BadRequest("Ratio needs at least one positive weight".into()));
+        }
+
+        let total = village.wood + village.clay + village.iron + village.crop;
+        let caps = [
+            village.warehouse_capacity,
+            village.warehouse_capacity,
+            village.warehouse_capacity,
+            village.granary_capacity,
+        ];
+        let [wood, clay, iron, crop] = Self::split_by_weights(total, weights, caps);
+
+        Ok(NpcSplitSuggestion {
+            village_id,
+            total,
+            wood,
+            clay,
+            iron,
+            crop,
+        })
+    }
+
+    /// Split `total` by `weights` using largest remainders so the parts always
+    /// sum to `total`. Amounts over a cap spill to the other resources; once every
+    /// weighted resource is full the rest goes to any resource with room.
+    fn split_by_weights(total: i32, weights: [u32; 4], caps: [i32; 4]) -> [i32; 4] {
+        let mut parts = [0i32; 4];
+        let mut remaining = total.max(0) as i64;
+        let mut active: Vec<usize> = (0..4).filter(|&i| weights[i] > 0).collect();
+
+        while remaining > 0 {
+            active.retain(|&i| parts[i] < caps[i]);
+            if active.is_empty() {
+                // Weighted resources are full; fill whatever still has room
+                active = (0..4).filter(|&i| parts[i] < caps[i]).collect();
+                if active.is_empty() {
+                    break;
+                }
+            }
+
+            let weight_sum: i64 = active.iter().map(|&i| weights[i].max(1) as i64).sum();
+            let mut shares: Vec<(usize, i64, i64)> = active
+                .iter()
+                .map(|&i| {
+                    let exact = remaining * weights[i].max(1) as i64;
+                    (i, exact / weight_sum, exact % weight_sum)
+                })
+                .collect();
+
+            // Hand out the rounding leftovers to the largest remainders
+            let mut leftover = remaining - shares.iter().map(|s| s.1).sum::<i64>();
+            shares.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+            for share in shares.iter_mut() {
+                if leftover == 0 {
+                    break;
+                }
+                share.1 += 1;
+                leftover -= 1;
+            }
+
+            for (i, share, _) in shares {
+                let room = (caps[i] - parts[i]) as i64;
+                let given = share.min(room);
+                parts[i] += given as i32;
+                remaining -= given;
+            }
+        }
+
+        parts
+    }
+
     /// Use NPC Merchant to exchange resources
     pub async fn use_npc_merchant(
         pool: &PgPool,
@@ -478,7 +643,7 @@ impl ShopService {
         // Verify village ownership
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -608,7 +773,7 @@ impl ShopService {
         // Verify village ownership
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -684,7 +849,7 @@ impl ShopService {
         // Verify village ownership
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.user_id != user_id {
             return Err(AppError::Forbidden("Access denied".into()));
@@ -749,11 +914,150 @@ impl ShopService {
     pub async fn get_transactions(
         pool: &PgPool,
         user_id: Uuid,
+        transaction_type: Option<TransactionType>,
+        status: Option<TransactionStatus>,
         limit: i32,
         offset: i32,
-    ) -> AppResult<Vec<TransactionResponse>> {
-        let limit = limit.min(50).max(1);
-        let transactions = ShopRepository::get_user_transactions(pool, user_id, limit, offset).await?;
-        Ok(transactions.into_iter().map(|t| t.into()).collect())
+    ) -> AppResult<TransactionListResponse> {
+        let (limit, offset) = Self::page_bounds(limit, offset);
+        let transactions = ShopRepository::get_user_transactions(
+            pool,
+            user_id,
+            transaction_type,
+            status,
+            limit,
+            offset,
+        )
+        .await?;
+        let total =
+            ShopRepository::count_user_transactions(pool, user_id, transaction_type, status)
+                .await?;
+
+        Ok(TransactionListResponse {
+            transactions: transactions.into_iter().map(|t| t.into()).collect(),
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Clamp a requested history page to at most 50 rows and a non-negative offset
+    fn page_bounds(limit: i32, offset: i32) -> (i32, i32) {
+        (limit.clamp(1, 50), offset.max(0))
+    }
+
+    /// Get user's gold spending history
+    pub async fn get_gold_usage(
+        pool: &PgPool,
+        user_id: Uuid,
+        feature: Option<GoldFeature>,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<GoldUsageListResponse> {
+        let (limit, offset) = Self::page_bounds(limit, offset);
+        let usages =
+            ShopRepository::get_user_gold_usage(pool, user_id, feature, limit, offset).await?;
+        let total = ShopRepository::count_user_gold_usage(pool, user_id, feature).await?;
+
+        Ok(GoldUsageListResponse {
+            usages: usages.into_iter().map(|u| u.into()).collect(),
+            total,
+            limit,
+            offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> GoldPackage {
+        GoldPackage {
+            id: Uuid::new_v4(),
+            name: "Chest of Gold".to_string(),
+            gold_amount: 500,
+            price_cents: 999,
+            currency: "USD".to_string(),
+            stripe_price_id: Some("price_123".to_string()),
+            is_active: true,
+            bonus_percent: 10,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn stripe_price(unit_amount: i64, currency: Currency) -> Price {
+        Price {
+            active: Some(true),
+            currency: Some(currency),
+            unit_amount: Some(unit_amount),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn valid_package_can_be_checked_out() {
+        let package = package();
+        let currency = ShopService::check_package(&package).unwrap();
+
+        assert_eq!(currency, Currency::USD);
+        assert_eq!(currency.to_string().to_uppercase(), "USD");
+        assert!(ShopService::price_matches(&stripe_price(999, Currency::USD), &package, currency));
+    }
+
+    #[test]
+    fn inactive_or_misconfigured_packages_are_rejected() {
+        let inactive = GoldPackage { is_active: false, ..package() };
+        let free = GoldPackage { price_cents: 0, ..package() };
+        let unknown_currency = GoldPackage { currency: "GOLD".to_string(), ..package() };
+        for broken in [inactive, free, unknown_currency] {
+            assert!(matches!(
+                ShopService::check_package(&broken),
+                Err(AppError::BadRequest(_))
+            ));
+        }
+
+        // The linked Stripe price must be active and charge the same amount and currency
+        let package = package();
+        let usd = Currency::USD;
+        let archived = Price { active: Some(false), ..stripe_price(999, usd) };
+        assert!(!ShopService::price_matches(&archived, &package, usd));
+        assert!(!ShopService::price_matches(&stripe_price(1999, usd), &package, usd));
+        assert!(!ShopService::price_matches(&stripe_price(999, Currency::EUR), &package, usd));
+    }
+
+    #[test]
+    fn transaction_pages_are_clamped() {
+        assert_eq!(ShopService::page_bounds(20, 40), (20, 40));
+        assert_eq!(ShopService::page_bounds(500, 0), (50, 0));
+        assert_eq!(ShopService::page_bounds(0, -5), (1, 0));
+    }
+
+    const ROOMY: [i32; 4] = [100_000; 4];
+
+    #[test]
+    fn even_split_is_near_equal_and_sums_to_the_total() {
+        for total in [0, 1, 4, 1001, 4003, 99_999] {
+            let parts = ShopService::split_by_weights(total, [1, 1, 1, 1], ROOMY);
+
+            assert_eq!(parts.iter().sum::<i32>(), total);
+            let (min, max) = (parts.iter().min().unwrap(), parts.iter().max().unwrap());
+            assert!(max - min <= 1, "{:?} is not near-equal", parts);
+        }
+        assert_eq!(ShopService::split_by_weights(1002, [1, 1, 1, 1], ROOMY), [251, 251, 250, 250]);
+    }
+
+    #[test]
+    fn ratio_split_follows_the_weights() {
+        assert_eq!(ShopService::split_by_weights(1000, [2, 1, 1, 0], ROOMY), [500, 250, 250, 0]);
+    }
+
+    #[test]
+    fn split_spills_past_full_storage() {
+        // Crop has room for 100 only; the rest goes to the other weighted resources
+        let parts = ShopService::split_by_weights(1000, [1, 1, 1, 1], [1000, 1000, 1000, 100]);
+
+        assert_eq!(parts[3], 100);
+        assert_eq!(parts.iter().sum::<i32>(), 1000);
     }
 }
@@ -0,0 +1,684 @@
+use chrono::{Duration, Utc};
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::GameConfig;
+use crate::error::{AppError, AppResult};
+use crate::models::army::CarriedResources;
+use crate::models::building::BuildingType;
+use crate::models::transfer::{
+    BulkSendResourcesRequest, BulkTransferResponse, BulkTransferResult, BulkTransferStatus,
+    BulkTransferTarget, NewTransfer, ResourceTransfer, ResourceTransferResponse, SendResourcesRequest,
+    VillageTransfersResponse,
+};
+use crate::models::village::Village;
+use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::transfer_repo::TransferRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::repositories::village_repo::VillageRepository;
+use crate::services::army_service::ArmyService;
+use crate::services::building_service::BuildingService;
+use crate::services::cache_service::CacheService;
+use crate::services::resource_service::ResourceService;
+
+/// Merchants of one village at a point in time
+struct MerchantPool {
+    /// Resources a single merchant carries
+    capacity: i32,
+    total: i32,
+    free: i32,
+}
+
+pub struct TransferService;
+
+impl TransferService {
+    /// Send resources by merchant to another village (own or another player's)
+    pub async fn send_resources(
+        pool: &PgPool,
+        game: &GameConfig,
+        player_id: Uuid,
+        from_village_id: Uuid,
+        request: SendResourcesRequest,
+    ) -> AppResult<ResourceTransferResponse> {
+        if request.wood < 0 || request.clay < 0 || request.iron < 0 || request.crop < 0 {
+            return Err(AppError::BadRequest("Cannot send negative resources".into()));
+        }
+
+        let total =
+            request.wood as i64 + request.clay as i64 + request.iron as i64 + request.crop as i64;
+        if total <= 0 {
+            return Err(AppError::BadRequest("Must send at least one resource".into()));
+        }
+
+        let from_village = VillageRepository::find_by_id(pool, from_village_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Source village", from_village_id))?;
+
+        if from_village.user_id != player_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        // Merchants work out of the Market
+        let market_level = Self::market_level(pool, from_village_id).await?;
+        if market_level <= 0 {
+            return Err(AppError::BadRequest("A Market is required to send resources".into()));
+        }
+
+        let target = VillageRepository::find_by_coordinates(pool, request.to_x, request.to_y)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Target village not found".into()))?;
+
+        if target.id == from_village_id {
+            return Err(AppError::BadRequest("Cannot send resources to the same village".into()));
+        }
+
+        // Check resources after applying production so far
+        let village = ResourceService::update_village_resources(pool, game, from_village_id).await?;
+        if village.wood < request.wood
+            || village.clay < request.clay
+            || village.iron < request.iron
+            || village.crop < request.crop
+        {
+            return Err(AppError::BadRequest("Not enough resources".into()));
+        }
+
+        let merchants = Self::merchant_pool(pool, game, from_village_id, market_level).await?;
+        let needed = Self::merchants_needed(total, merchants.capacity);
+
+        let distance =
+            ArmyService::calculate_distance(from_village.x, from_village.y, target.x, target.y);
+        let departed_at = Utc::now();
+        let arrives_at = departed_at + Self::merchant_travel_time(distance, game.merchant_speed);
+        let tax_percent = game.transfer_tax_percent.clamp(0, 100);
+
+        // Count free merchants, deduct and record the transfer in one transaction. The
+        // village lock makes a concurrent send wait, so both cannot book the same merchants.
+        let mut tx = pool.begin().await?;
+        VillageRepository::lock(&mut *tx, from_village_id).await?;
+        let outgoing = TransferRepository::find_outgoing(&mut *tx, from_village_id).await?;
+        let free = Self::free_merchants(merchants.total, merchants.capacity, &outgoing);
+        if needed > free {
+            return Err(AppError::BadRequest(format!(
+                "Not enough merchants: {} needed, {} available",
+                needed, free
+            )));
+        }
+
+        VillageRepository::deduct_resources(
+            &mut *tx,
+            from_village_id,
+            request.wood,
+            request.clay,
+            request.iron,
+            request.crop,
+        )
+        .await?;
+
+        let transfer = TransferRepository::create(
+            &mut *tx,
+            NewTransfer {
+                sender_id: player_id,
+                from_village_id,
+                to_village_id: target.id,
+                wood: request.wood,
+                clay: request.clay,
+                iron: request.iron,
+                crop: request.crop,
+                tax_percent,
+                departed_at,
+                arrives_at,
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!(
+            "Merchants left village {} for village {} carrying {} resources, arrive at {}",
+            from_village_id, target.id, total, arrives_at
+        );
+
+        Ok(transfer.into())
+    }
+
+    /// Dispatch merchants from one village to several of the player's own villages.
+    /// Targets are served in order; once merchants run out the remaining targets
+    /// are partially filled or skipped rather than failing the whole call.
+    pub async fn send_resources_bulk(
+        pool: &PgPool,
+        game: &GameConfig,
+        player_id: Uuid,
+        from_village_id: Uuid,
+        request: BulkSendResourcesRequest,
+    ) -> AppResult<BulkTransferResponse> {
+        if request.targets.is_empty() {
+            return Err(AppError::BadRequest("At least one target is required".into()));
+        }
+
+        if request
+            .targets
+            .iter()
+            .any(|t| t.wood < 0 || t.clay < 0 || t.iron < 0 || t.crop < 0)
+        {
+            return Err(AppError::BadRequest("Cannot send negative resources".into()));
+        }
+
+        let from_village = VillageRepository::find_by_id(pool, from_village_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Source village", from_village_id))?;
+
+        if from_village.user_id != player_id {
+            return Err(AppError::Forbidden("Access denied".into()));
+        }
+
+        let market_level = Self::market_level(pool, from_village_id).await?;
+        if market_level <= 0 {
+            return Err(AppError::BadRequest("A Market is required to send resources".into()));
+        }
+
+        // Validate the whole request against stock up front so targets are not
+        // served by whichever happens to come first
+        let village = ResourceService::update_village_resources(pool, game, from_village_id).await?;
+        if !Self::bulk_within_stock(&request.targets, &village) {
+            return Err(AppError::BadRequest("Not enough resources".into()));
+        }
+
+        let merchants = Self::merchant_pool(pool, game, from_village_id, market_level).await?;
+        let capacity = merchants.capacity;
+
+        let owned = VillageRepository::find_by_user_id(pool, player_id).await?;
+        let tax_percent = game.transfer_tax_percent.clamp(0, 100);
+        let mut results = Vec::with_capacity(request.targets.len());
+
+        // All targets are booked in one transaction against a fresh count of free
+        // merchants, taken under the village lock as in a single send
+        let mut tx = pool.begin().await?;
+        VillageRepository::lock(&mut *tx, from_village_id).await?;
+        let outgoing = TransferRepository::find_outgoing(&mut *tx, from_village_id).await?;
+        let free = Self::free_merchants(merchants.total, capacity, &outgoing);
+        let mut merchants_left = free;
+
+        for target in request.targets {
+            let skip = |reason: &str| BulkTransferResult {
+                to_village_id: target.village_id,
+                status: BulkTransferStatus::Skipped,
+                transfer: None,
+                reason: Some(reason.to_string()),
+            };
+
+            let Some(to_village) = owned.iter().find(|v| v.id == target.village_id) else {
+                results.push(skip("Target is not one of your villages"));
+                continue;
+            };
+            if to_village.id == from_village_id {
+                results.push(skip("Cannot send resources to the same village"));
+                continue;
+            }
+            if target.total() <= 0 {
+                results.push(skip("Nothing to send"));
+                continue;
+            }
+            let Some(((wood, clay, iron, crop), merchants_used)) =
+                Self::bulk_load(&target, merchants_left, capacity)
+            else {
+                results.push(skip("No merchants available"));
+                continue;
+            };
+            let load = wood as i64 + clay as i64 + iron as i64 + crop as i64;
+
+            VillageRepository::deduct_resources(&mut *tx, from_village_id, wood, clay, iron, crop)
+                .await?;
+
+            let distance = ArmyService::calculate_distance(
+                from_village.x,
+                from_village.y,
+                to_village.x,
+                to_village.y,
+            );
+            let departed_at = Utc::now();
+            let arrives_at =
+                departed_at + Self::merchant_travel_time(distance, game.merchant_speed);
+
+            let transfer = TransferRepository::create(
+                &mut *tx,
+                NewTransfer {
+                    sender_id: player_id,
+                    from_village_id,
+                    to_village_id: to_village.id,
+                    wood,
+                    clay,
+                    iron,
+                    crop,
+                    tax_percent,
+                    departed_at,
+                    arrives_at,
+                },
+            )
+            .await?;
+
+            merchants_left -= merchants_used;
+
+            let status = if load < target.total() {
+                BulkTransferStatus::Partial
+            } else {
+                BulkTransferStatus::Sent
+            };
+
+            results.push(BulkTransferResult {
+                to_village_id: to_village.id,
+                status,
+                transfer: Some(transfer.into()),
+                reason: (status == BulkTransferStatus::Partial)
+                    .then(|| "Not enough merchants for the full amount".to_string()),
+            });
+        }
+
+        tx.commit().await?;
+
+        let merchants_used = free - merchants_left;
+
+        info!(
+            "Bulk dispatch from village {}: {} targets, {} merchants used",
+            from_village_id,
+            results.len(),
+            merchants_used
+        );
+
+        Ok(BulkTransferResponse {
+            from_village_id,
+            merchants_total: merchants.total,
+            merchants_used,
+            results,
+        })
+    }
+
+    /// Send loot that did not fit in `home` to the nearest other village of its owner
+    /// with free storage. Needs a Market at home and the owner's forward_loot_overflow
+    /// setting; otherwise the overflow is lost as before. Returns the transfer if one left.
+    pub async fn forward_overflow(
+        pool: &PgPool,
+        game: &GameConfig,
+        home: &Village,
+        overflow: &CarriedResources,
+    ) -> AppResult<Option<ResourceTransfer>> {
+        if overflow.total() <= 0 {
+            return Ok(None);
+        }
+
+        let enabled = UserRepository::get_settings(pool, home.user_id)
+            .await?
+            .is_some_and(|s| s.forward_loot_overflow);
+        if !enabled {
+            return Ok(None);
+        }
+
+        let market_level = Self::market_level(pool, home.id).await?;
+        if market_level <= 0 {
+            return Ok(None);
+        }
+
+        // Forwarding uses the same merchants as any other transfer
+        let merchants = Self::merchant_pool(pool, game, home.id, market_level).await?;
+        let max_load = merchants.free as i64 * merchants.capacity as i64;
+        if max_load <= 0 {
+            info!("No free merchants in village {} to forward overflowing loot", home.id);
+            return Ok(None);
+        }
+
+        let mut villages: Vec<Village> = VillageRepository::find_by_user_id(pool, home.user_id)
+            .await?
+            .into_iter()
+            .filter(|v| v.id != home.id)
+            .collect();
+        villages.sort_by(|a, b| {
+            let da = ArmyService::calculate_distance(home.x, home.y, a.x, a.y);
+            let db = ArmyService::calculate_distance(home.x, home.y, b.x, b.y);
+            da.total_cmp(&db)
+        });
+
+        for target in villages {
+            // Measure free storage against the target's stock as of now
+            let target = ResourceService::update_village_resources(pool, game, target.id).await?;
+            let (fits, _) = ArmyService::split_delivery(overflow, &target);
+            let (wood, clay, iron, crop) =
+                Self::fit_to_capacity((fits.wood, fits.clay, fits.iron, fits.crop), max_load);
+            if wood + clay + iron + crop <= 0 {
+                continue;
+            }
+
+            let distance = ArmyService::calculate_distance(home.x, home.y, target.x, target.y);
+            let departed_at = Utc::now();
+            let arrives_at = departed_at + Self::merchant_travel_time(distance, game.merchant_speed);
+
+            // Moving loot between own villages is not taxed
+            let transfer = TransferRepository::create(
+                pool,
+                NewTransfer {
+                    sender_id: home.user_id,
+                    from_village_id: home.id,
+                    to_village_id: target.id,
+                    wood,
+                    clay,
+                    iron,
+                    crop,
+                    tax_percent: 0,
+                    departed_at,
+                    arrives_at,
+                },
+            )
+            .await?;
+
+            info!(
+                "Forwarded {} overflowing loot from village {} to village {}, arrives at {}",
+                wood + clay + iron + crop,
+                home.id,
+                target.id,
+                arrives_at
+            );
+
+            return Ok(Some(transfer));
+        }
+
+        Ok(None)
+    }
+
+    /// One merchant per Market level
+    fn merchants_for_market(market_level: i32) -> i32 {
+        market_level.max(0)
+    }
+
+    fn merchants_needed(amount: i64, capacity: i32) -> i32 {
+        if amount <= 0 {
+            return 0;
+        }
+        let capacity = capacity.max(1) as i64;
+        ((amount + capacity - 1) / capacity).min(i32::MAX as i64) as i32
+    }
+
+    /// Highest Market level in a village (0 without one)
+    async fn market_level(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
+        let level = BuildingRepository::find_by_type(pool, village_id, BuildingType::Market)
+            .await?
+            .iter()
+            .map(|b| b.level)
+            .max()
+            .unwrap_or(0);
+        Ok(level)
+    }
+
+    /// How many merchants a village has, how many are not out on a transfer and
+    /// how much each one carries
+    async fn merchant_pool(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+        market_level: i32,
+    ) -> AppResult<MerchantPool> {
+        let carry_bonus = BuildingService::carry_bonus_percent(pool, game, village_id).await?;
+        let capacity = (game.merchant_capacity * (100 + carry_bonus) / 100).max(1);
+        let total = Self::merchants_for_market(market_level);
+        let outgoing = TransferRepository::find_outgoing(pool, village_id).await?;
+
+        Ok(MerchantPool {
+            capacity,
+            total,
+            free: Self::free_merchants(total, capacity, &outgoing),
+        })
+    }
+
+    /// Merchants left over once those out on the given transfers are counted
+    fn free_merchants(total: i32, capacity: i32, outgoing: &[ResourceTransfer]) -> i32 {
+        let busy: i32 = outgoing
+            .iter()
+            .map(|t| Self::merchants_needed(t.total(), capacity))
+            .sum();
+        (total - busy).max(0)
+    }
+
+    /// Scale wood, clay, iron and crop down proportionally so the load fits the
+    /// remaining merchant capacity
+    fn fit_to_capacity(amounts: (i32, i32, i32, i32), capacity: i64) -> (i32, i32, i32, i32) {
+        let (wood, clay, iron, crop) = amounts;
+        let total = wood as i64 + clay as i64 + iron as i64 + crop as i64;
+        if total <= capacity {
+            return amounts;
+        }
+
+        let scale = |amount: i32| (amount as i64 * capacity.max(0) / total) as i32;
+        (scale(wood), scale(clay), scale(iron), scale(crop))
+    }
+
+    /// Whether the source village holds everything asked for across all targets
+    fn bulk_within_stock(targets: &[BulkTransferTarget], village: &Village) -> bool {
+        let sum = |f: fn(&BulkTransferTarget) -> i32| -> i64 {
+            targets.iter().map(|t| f(t) as i64).sum()
+        };
+        sum(|t| t.wood) <= village.wood as i64
+            && sum(|t| t.clay) <= village.clay as i64
+            && sum(|t| t.iron) <= village.iron as i64
+            && sum(|t| t.crop) <= village.crop as i64
+    }
+
+    /// What the free merchants can carry of one bulk target and how many of them it
+    /// takes; `None` once nothing fits
+    fn bulk_load(
+        target: &BulkTransferTarget,
+        merchants_left: i32,
+        capacity: i32,
+    ) -> Option<((i32, i32, i32, i32), i32)> {
+        if merchants_left <= 0 {
+            return None;
+        }
+        let amounts = Self::fit_to_capacity(
+            (target.wood, target.clay, target.iron, target.crop),
+            merchants_left as i64 * capacity as i64,
+        );
+        let load = amounts.0 as i64 + amounts.1 as i64 + amounts.2 as i64 + amounts.3 as i64;
+        if load <= 0 {
+            return None;
+        }
+        Some((amounts, Self::merchants_needed(load, capacity)))
+    }
+
+    /// Merchant travel time; speed is fields per hour
+    fn merchant_travel_time(distance: f64, speed: f64) -> Duration {
+        let hours = distance / speed.max(1.0);
+        let seconds = (hours * 3600.0) as i64;
+
+        // Minimum 1 minute travel time
+        Duration::seconds(seconds.max(60))
+    }
+
+    /// Get merchants leaving and arriving at a village
+    pub async fn get_village_transfers(
+        pool: &PgPool,
+        village_id: Uuid,
+    ) -> AppResult<VillageTransfersResponse> {
+        let outgoing = TransferRepository::find_outgoing(pool, village_id).await?;
+        let incoming = TransferRepository::find_incoming(pool, village_id).await?;
+
+        Ok(VillageTransfersResponse {
+            outgoing: outgoing.into_iter().map(|t| t.into()).collect(),
+            incoming: incoming.into_iter().map(|t| t.into()).collect(),
+        })
+    }
+
+    /// Deliver all transfers that have arrived (called by background job)
+    pub async fn process_arrived_transfers(pool: &PgPool, redis: &ConnectionManager) -> AppResult<i32> {
+        let arrived = TransferRepository::find_arrived(pool).await?;
+        let mut delivered = 0;
+
+        for transfer in arrived {
+            match Self::deliver(pool, &transfer).await {
+                Ok(true) => {
+                    info!(
+                        "Merchants from village {} delivered to village {} ({}% lost in transit)",
+                        transfer.from_village_id, transfer.to_village_id, transfer.tax_percent
+                    );
+                    CacheService::invalidate_village(redis, transfer.to_village_id).await;
+                    delivered += 1;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    error!("Failed to deliver transfer {}: {:?}", transfer.id, e);
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// Claim a transfer and credit its target together, so a failed credit leaves the
+    /// transfer in place for the next run. Returns false if another run claimed it first.
+    async fn deliver(pool: &PgPool, transfer: &ResourceTransfer) -> AppResult<bool> {
+        let mut tx = pool.begin().await?;
+        if !TransferRepository::delete(&mut *tx, transfer.id).await? {
+            return Ok(false);
+        }
+
+        VillageRepository::add_resources(
+            &mut *tx,
+            transfer.to_village_id,
+            transfer.delivered(transfer.wood),
+            transfer.delivered(transfer.clay),
+            transfer.delivered(transfer.iron),
+            transfer.delivered(transfer.crop),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::village;
+
+    fn transfer(amount: i32, tax_percent: i32) -> ResourceTransfer {
+        let now = Utc::now();
+        ResourceTransfer {
+            id: Uuid::new_v4(),
+            sender_id: Uuid::new_v4(),
+            from_village_id: Uuid::new_v4(),
+            to_village_id: Uuid::new_v4(),
+            wood: amount,
+            clay: amount,
+            iron: amount,
+            crop: amount,
+            tax_percent,
+            departed_at: now,
+            arrives_at: now + Duration::hours(1),
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn merchants_needed_rounds_up_per_merchant() {
+        assert_eq!(TransferService::merchants_needed(0, 500), 0);
+        assert_eq!(TransferService::merchants_needed(1, 500), 1);
+        assert_eq!(TransferService::merchants_needed(500, 500), 1);
+        assert_eq!(TransferService::merchants_needed(501, 500), 2);
+    }
+
+    #[test]
+    fn merchants_needed_handles_loads_beyond_i32() {
+        let load = i32::MAX as i64 * 4;
+        assert_eq!(TransferService::merchants_needed(load, 1), i32::MAX);
+        assert_eq!(TransferService::merchants_needed(load, 1000), 8_589_935);
+    }
+
+    #[test]
+    fn merchants_out_on_transfers_are_not_free() {
+        // 500 per merchant: a 1000 load ties up two, a 1200 load three
+        let outgoing = [transfer(250, 0), transfer(300, 0)];
+        assert_eq!(TransferService::free_merchants(10, 500, &outgoing), 5);
+        assert_eq!(TransferService::free_merchants(4, 500, &outgoing), 0);
+        assert_eq!(TransferService::free_merchants(3, 500, &[]), 3);
+    }
+
+    #[test]
+    fn fit_to_capacity_scales_proportionally() {
+        let amounts = (600, 200, 200, 0);
+        assert_eq!(TransferService::fit_to_capacity(amounts, 2000), (600, 200, 200, 0));
+        assert_eq!(TransferService::fit_to_capacity(amounts, 500), (300, 100, 100, 0));
+    }
+
+    fn target(wood: i32, clay: i32, iron: i32, crop: i32) -> BulkTransferTarget {
+        BulkTransferTarget { village_id: Uuid::new_v4(), wood, clay, iron, crop }
+    }
+
+    #[test]
+    fn bulk_send_checks_the_combined_request_against_stock() {
+        let source = village(1000, 5000);
+        let fits = [target(400, 400, 400, 400), target(600, 600, 600, 600)];
+        assert!(TransferService::bulk_within_stock(&fits, &source));
+
+        // Each target alone fits, together they need more wood than the village has
+        let too_much = [target(600, 0, 0, 0), target(500, 0, 0, 0)];
+        assert!(!TransferService::bulk_within_stock(&too_much, &source));
+    }
+
+    #[test]
+    fn bulk_targets_share_merchants_until_they_run_out() {
+        // Five merchants carrying 500 each
+        let capacity = 500;
+        let mut merchants_left = 5;
+
+        let (load, used) =
+            TransferService::bulk_load(&target(300, 300, 300, 100), merchants_left, capacity)
+                .unwrap();
+        assert_eq!(load, (300, 300, 300, 100));
+        assert_eq!(used, 2);
+        merchants_left -= used;
+
+        // Three merchants left carry 1500 of the 3000 asked for, split evenly
+        let (load, used) =
+            TransferService::bulk_load(&target(1000, 1000, 1000, 0), merchants_left, capacity)
+                .unwrap();
+        assert_eq!(load, (500, 500, 500, 0));
+        assert_eq!(used, 3);
+        merchants_left -= used;
+
+        // Nothing is left for the last target
+        assert_eq!(merchants_left, 0);
+        assert!(TransferService::bulk_load(&target(100, 0, 0, 0), merchants_left, capacity)
+            .is_none());
+    }
+
+    #[test]
+    fn overflowing_crop_is_forwarded_to_a_village_with_room() {
+        let loot = CarriedResources { wood: 0, clay: 0, iron: 0, crop: 900 };
+        let (stored, overflow) = ArmyService::split_delivery(&loot, &village(700, 1000));
+        assert_eq!(stored.crop, 300);
+        assert_eq!(overflow.crop, 600);
+
+        let (fits, lost) = ArmyService::split_delivery(&overflow, &village(500, 1000));
+        assert_eq!(fits.crop, 500);
+        assert_eq!(lost.crop, 100);
+
+        // Two free merchants carrying 200 each limit what leaves
+        let load = TransferService::fit_to_capacity((fits.wood, fits.clay, fits.iron, fits.crop), 400);
+        assert_eq!(load, (0, 0, 0, 400));
+    }
+
+    #[test]
+    fn farther_transfer_takes_longer() {
+        let near = TransferService::merchant_travel_time(5.0, 16.0);
+        let far = TransferService::merchant_travel_time(20.0, 16.0);
+
+        assert!(far > near);
+        assert_eq!(far, near * 4);
+        // Even a neighbouring village takes the minimum minute
+        assert_eq!(TransferService::merchant_travel_time(0.1, 16.0), Duration::minutes(1));
+    }
+
+    #[test]
+    fn configured_tax_reduces_delivered_resources() {
+        assert_eq!(transfer(1000, 0).delivered(1000), 1000);
+        assert_eq!(transfer(1000, 10).delivered(1000), 900);
+        assert_eq!(transfer(999, 25).delivered(999), 749);
+    }
+}
@@ -1,19 +1,70 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::{AppError, AppResult};
-use crate::models::troop::{Troop, TroopCost, TroopDefinition, TroopQueue, TroopType, TrainTroopsResponse};
+use crate::models::activity::VillageActivityType;
+use crate::models::building::Building;
+use crate::models::troop::{
+    DisbandTroopsResponse, Troop, TroopAvailability, TroopCost, TroopDefinition,
+    TroopDefinitionResponse, TroopQueue, TroopQueueLine, TroopQueueSummaryResponse,
+    TroopReserveResponse, TroopType, TroopUpkeepEntry, TroopUpkeepResponse, TrainTroopsResponse,
+};
 use crate::repositories::building_repo::BuildingRepository;
 use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
+use crate::services::activity_service::ActivityService;
+use crate::services::resource_service::ResourceService;
+use crate::services::village_service::VillageService;
 
 pub struct TroopService;
 
 impl TroopService {
+    /// Get the troops a village keeps home (empty if never configured)
+    pub async fn get_reserve(pool: &PgPool, village_id: Uuid) -> AppResult<TroopReserveResponse> {
+        let reserves = TroopRepository::find_reserve(pool, village_id)
+            .await?
+            .map(|r| r.reserves.0)
+            .unwrap_or_default();
+
+        Ok(TroopReserveResponse { village_id, reserves })
+    }
+
+    /// Replace the troops a village keeps home; zero counts are dropped
+    pub async fn set_reserve(
+        pool: &PgPool,
+        village_id: Uuid,
+        reserves: HashMap<TroopType, i32>,
+    ) -> AppResult<TroopReserveResponse> {
+        if let Some((troop_type, count)) = reserves.iter().find(|(_, count)| **count < 0) {
+            return Err(AppError::BadRequest(format!(
+                "Reserve for {:?} cannot be negative (got {})",
+                troop_type, count
+            )));
+        }
+
+        let reserves: HashMap<TroopType, i32> =
+            reserves.into_iter().filter(|(_, count)| *count > 0).collect();
+
+        let reserve = TroopRepository::upsert_reserve(pool, village_id, &reserves).await?;
+
+        Ok(TroopReserveResponse {
+            village_id,
+            reserves: reserve.reserves.0,
+        })
+    }
+
     /// Get all available troop definitions
-    pub async fn get_definitions(pool: &PgPool) -> AppResult<Vec<TroopDefinition>> {
-        TroopRepository::get_all_definitions(pool).await
+    pub async fn get_definitions(pool: &PgPool) -> AppResult<Vec<TroopDefinitionResponse>> {
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        Ok(Self::definition_responses(definitions))
+    }
+
+    /// Public shape of the troop definitions, in the order they were loaded
+    fn definition_responses(definitions: Vec<TroopDefinition>) -> Vec<TroopDefinitionResponse> {
+        definitions.into_iter().map(|d| d.into()).collect()
     }
 
     /// Get troops in a village
@@ -26,6 +77,56 @@ impl TroopService {
         TroopRepository::get_queue_by_village(pool, village_id).await
     }
 
+    /// Per production building: units still in training and when the last of them finishes
+    pub async fn get_queue_summary(
+        pool: &PgPool,
+        village_id: Uuid,
+    ) -> AppResult<TroopQueueSummaryResponse> {
+        let queue = TroopRepository::get_queue_by_village(pool, village_id).await?;
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let lines = Self::queue_lines(&queue, &definitions, Utc::now());
+
+        let all_done_at = TroopRepository::get_last_queue_end_time(pool, village_id).await?;
+
+        Ok(TroopQueueSummaryResponse {
+            village_id,
+            all_done_at,
+            lines,
+        })
+    }
+
+    /// Units still pending and the last end time per training building, soonest free first
+    fn queue_lines(
+        queue: &[TroopQueue],
+        definitions: &[TroopDefinition],
+        now: DateTime<Utc>,
+    ) -> Vec<TroopQueueLine> {
+        let mut lines: Vec<TroopQueueLine> = Vec::new();
+        for entry in queue {
+            let Some(definition) = definitions.iter().find(|d| d.troop_type == entry.troop_type) else {
+                continue;
+            };
+            let pending = entry.count - entry.units_completed_at(now);
+
+            match lines
+                .iter_mut()
+                .find(|l| l.building_type == definition.required_building)
+            {
+                Some(line) => {
+                    line.units_pending += pending;
+                    line.ends_at = line.ends_at.max(entry.ends_at);
+                }
+                None => lines.push(TroopQueueLine {
+                    building_type: definition.required_building.clone(),
+                    units_pending: pending,
+                    ends_at: entry.ends_at,
+                }),
+            }
+        }
+        lines.sort_by_key(|l| l.ends_at);
+        lines
+    }
+
     /// Check if training requirements are met
     pub async fn check_training_requirements(
         pool: &PgPool,
@@ -39,19 +140,65 @@ impl TroopService {
 
         // Check if required building exists at required level
         let buildings = BuildingRepository::find_by_type(pool, village_id, definition.required_building.clone()).await?;
+        let max_level = buildings.iter().map(|b| b.level).max();
 
-        let max_level = buildings.iter().map(|b| b.level).max().unwrap_or(0);
-
-        if max_level < definition.required_building_level {
-            return Err(AppError::BadRequest(format!(
-                "{:?} level {} required (current: {})",
-                definition.required_building, definition.required_building_level, max_level
-            )));
+        if let Some(missing) = Self::missing_requirement(&definition, max_level) {
+            return Err(AppError::BadRequest(missing));
         }
 
         Ok(definition)
     }
 
+    /// Describe the unmet building requirement of a troop, given the highest level of
+    /// its required building in the village (`None` if not built)
+    fn missing_requirement(definition: &TroopDefinition, max_level: Option<i32>) -> Option<String> {
+        match max_level {
+            None => Some(format!(
+                "{:?} requires {:?} level {} (not built)",
+                definition.troop_type, definition.required_building, definition.required_building_level
+            )),
+            Some(level) if level < definition.required_building_level => Some(format!(
+                "{:?} requires {:?} level {} (current: {})",
+                definition.troop_type, definition.required_building, definition.required_building_level, level
+            )),
+            Some(_) => None,
+        }
+    }
+
+    /// Whether each troop type can currently be trained in a village, without changing anything
+    pub async fn get_availability(pool: &PgPool, village_id: Uuid) -> AppResult<Vec<TroopAvailability>> {
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
+        Ok(Self::availability(&definitions, &buildings))
+    }
+
+    /// Trainability of each troop type given a village's buildings
+    fn availability(
+        definitions: &[TroopDefinition],
+        buildings: &[Building],
+    ) -> Vec<TroopAvailability> {
+        definitions
+            .iter()
+            .map(|d| {
+                let current_level = buildings
+                    .iter()
+                    .filter(|b| b.building_type == d.required_building)
+                    .map(|b| b.level)
+                    .max();
+                let missing_requirement = Self::missing_requirement(d, current_level);
+
+                TroopAvailability {
+                    troop_type: d.troop_type,
+                    available: missing_requirement.is_none(),
+                    required_building: d.required_building.clone(),
+                    required_level: d.required_building_level,
+                    current_level,
+                    missing_requirement,
+                }
+            })
+            .collect()
+    }
+
     /// Train troops
     pub async fn train_troops(
         pool: &PgPool,
@@ -78,7 +225,7 @@ impl TroopService {
         // Check and deduct resources
         let village = VillageRepository::find_by_id(pool, village_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Village not found".into()))?;
+            .ok_or_else(|| AppError::not_found("Village", village_id))?;
 
         if village.wood < total_cost.wood
             || village.clay < total_cost.clay
@@ -142,20 +289,33 @@ impl TroopService {
         Ok(())
     }
 
-    /// Process all completed training (called by background job)
-    pub async fn process_completed_training(pool: &PgPool) -> AppResult<i32> {
-        let completed = TroopRepository::find_completed_training(pool).await?;
-        let count = completed.len() as i32;
-
-        for entry in completed {
-            // Add troops to village
-            TroopRepository::add_troops(pool, entry.village_id, entry.troop_type, entry.count).await?;
+    /// Deliver units of a batch that have finished training so far.
+    /// Returns the number of units added to the village.
+    pub async fn deliver_trained_units(pool: &PgPool, entry: &TroopQueue) -> AppResult<i32> {
+        let units = entry.units_completed_at(Utc::now());
+        if units <= 0 {
+            return Ok(0);
+        }
 
-            // Remove from queue
-            TroopRepository::remove_from_queue(pool, entry.id).await?;
+        // Claim the units so a concurrent run cannot deliver them twice; the claim and
+        // the units joining the village commit together so a failure loses neither
+        let mut tx = pool.begin().await?;
+        if !TroopRepository::take_trained_units(&mut tx, entry, units).await? {
+            return Ok(0);
         }
 
-        Ok(count)
+        TroopRepository::add_troops(&mut *tx, entry.village_id, entry.troop_type, units).await?;
+        tx.commit().await?;
+        VillageService::recompute_population(pool, entry.village_id).await?;
+
+        ActivityService::record(
+            pool,
+            entry.village_id,
+            VillageActivityType::TroopsTrained,
+            serde_json::json!({ "troop_type": entry.troop_type, "count": units }),
+        );
+
+        Ok(units)
     }
 
     /// Cancel training (if not yet started)
@@ -196,8 +356,377 @@ impl TroopService {
         Ok(())
     }
 
+    /// Permanently dismiss troops that are home, refunding the configured share
+    /// of their training cost. Troops away on missions cannot be disbanded.
+    pub async fn disband_troops(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+        troops: HashMap<TroopType, i32>,
+    ) -> AppResult<DisbandTroopsResponse> {
+        let disbanded: HashMap<TroopType, i32> =
+            troops.into_iter().filter(|(_, count)| *count != 0).collect();
+        if disbanded.is_empty() {
+            return Err(AppError::BadRequest("Must disband at least one troop".into()));
+        }
+
+        let village_troops = TroopRepository::find_by_village(pool, village_id).await?;
+        Self::check_disband(&disbanded, &village_troops)?;
+
+        let definitions = TroopRepository::get_all_definitions(pool).await?;
+        let (wood, clay, iron, crop) = Self::disband_refund(game, &disbanded, &definitions);
+        if wood + clay + iron + crop > 0 {
+            ResourceService::update_village_resources(pool, game, village_id).await?;
+        }
+
+        // The check above ran on a snapshot; each removal re-checks the troops at home,
+        // so troops sent away meanwhile fail the whole disband instead of being killed
+        let mut tx = pool.begin().await?;
+        for (troop_type, count) in &disbanded {
+            if !TroopRepository::disband_from_village(&mut *tx, village_id, *troop_type, *count)
+                .await?
+            {
+                return Err(AppError::BadRequest(format!(
+                    "Not enough {:?} in the village",
+                    troop_type
+                )));
+            }
+        }
+        if wood + clay + iron + crop > 0 {
+            VillageRepository::add_resources(&mut *tx, village_id, wood, clay, iron, crop).await?;
+        }
+        tx.commit().await?;
+
+        VillageService::recompute_population(pool, village_id).await?;
+
+        Ok(DisbandTroopsResponse {
+            village_id,
+            disbanded,
+            wood_refunded: wood,
+            clay_refunded: clay,
+            iron_refunded: iron,
+            crop_refunded: crop,
+            crop_consumption: Self::get_crop_consumption(pool, village_id).await?,
+        })
+    }
+
+    /// Only troops at home can be disbanded, and counts cannot be negative
+    fn check_disband(
+        disbanded: &HashMap<TroopType, i32>,
+        village_troops: &[Troop],
+    ) -> AppResult<()> {
+        for (troop_type, count) in disbanded {
+            if *count < 0 {
+                return Err(AppError::BadRequest(format!(
+                    "Troop count for {:?} cannot be negative (got {})",
+                    troop_type, count
+                )));
+            }
+            let at_home = village_troops
+                .iter()
+                .find(|t| t.troop_type == *troop_type)
+                .map(|t| t.in_village)
+                .unwrap_or(0);
+            if *count > at_home {
+                return Err(AppError::BadRequest(format!(
+                    "Only {} {:?} are in the village (requested {})",
+                    at_home, troop_type, count
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resources given back for disbanded troops: the configured share of their training cost
+    fn disband_refund(
+        game: &GameConfig,
+        disbanded: &HashMap<TroopType, i32>,
+        definitions: &[TroopDefinition],
+    ) -> (i32, i32, i32, i32) {
+        let refund_percent = game.disband_refund_percent.clamp(0, 100) as i64;
+        let (mut wood, mut clay, mut iron, mut crop) = (0i64, 0i64, 0i64, 0i64);
+        for (troop_type, count) in disbanded {
+            if let Some(def) = definitions.iter().find(|d| d.troop_type == *troop_type) {
+                let count = *count as i64;
+                wood += def.wood_cost as i64 * count * refund_percent / 100;
+                clay += def.clay_cost as i64 * count * refund_percent / 100;
+                iron += def.iron_cost as i64 * count * refund_percent / 100;
+                crop += def.crop_cost as i64 * count * refund_percent / 100;
+            }
+        }
+
+        (
+            wood.min(i32::MAX as i64) as i32,
+            clay.min(i32::MAX as i64) as i32,
+            iron.min(i32::MAX as i64) as i32,
+            crop.min(i32::MAX as i64) as i32,
+        )
+    }
+
     /// Get total crop consumption for a village from troops
     pub async fn get_crop_consumption(pool: &PgPool, village_id: Uuid) -> AppResult<i32> {
         TroopRepository::get_total_crop_consumption(pool, village_id).await
     }
+
+    /// Break down a village's crop upkeep per troop type next to building upkeep and production
+    pub async fn get_upkeep(
+        pool: &PgPool,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<TroopUpkeepResponse> {
+        let definitions: HashMap<TroopType, TroopDefinition> =
+            TroopRepository::get_all_definitions(pool)
+                .await?
+                .into_iter()
+                .map(|d| (d.troop_type, d))
+                .collect();
+
+        let troops = Self::upkeep_entries(
+            TroopRepository::get_fed_troops(pool, village_id).await?,
+            &definitions,
+        );
+
+        // Same figures the resource tick charges, so the totals match the breakdown
+        let troop_upkeep = Self::get_crop_consumption(pool, village_id).await?;
+        let production = ResourceService::calculate_production(pool, game, village_id).await?;
+        let building_upkeep = production.crop_consumption - troop_upkeep;
+        let gross_crop_per_hour = production.crop_per_hour;
+
+        Ok(TroopUpkeepResponse {
+            village_id,
+            troops,
+            troop_upkeep,
+            building_upkeep,
+            gross_crop_per_hour,
+            net_crop_per_hour: production.net_crop_per_hour,
+        })
+    }
+
+    /// Crop eaten per hour by each troop type a village feeds
+    fn upkeep_entries(
+        fed: Vec<(TroopType, i32)>,
+        definitions: &HashMap<TroopType, TroopDefinition>,
+    ) -> Vec<TroopUpkeepEntry> {
+        fed.into_iter()
+            .map(|(troop_type, count)| {
+                let crop_per_unit = definitions
+                    .get(&troop_type)
+                    .map(|d| d.crop_consumption)
+                    .unwrap_or(0);
+                TroopUpkeepEntry {
+                    troop_type,
+                    count,
+                    crop_per_unit,
+                    crop_per_hour: count * crop_per_unit,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::building::BuildingType;
+    use crate::test_support::troop_definition;
+
+    fn elite() -> TroopDefinition {
+        TroopDefinition {
+            required_building: BuildingType::Stable,
+            required_building_level: 5,
+            ..troop_definition(TroopType::WarElephant, 150, 80, 60, 4)
+        }
+    }
+
+    #[test]
+    fn definitions_keep_their_stats_costs_and_requirements() {
+        let definitions: Vec<TroopDefinition> = TroopType::ALL
+            .into_iter()
+            .map(|t| troop_definition(t, 10, 20, 30, 6))
+            .chain([elite()])
+            .collect();
+
+        let responses = TroopService::definition_responses(definitions.clone());
+
+        assert_eq!(responses.len(), definitions.len());
+        for (response, definition) in responses.iter().zip(&definitions) {
+            assert_eq!(response.troop_type, definition.troop_type);
+            assert_eq!(response.tribe, definition.tribe);
+        }
+        let elephant = responses.last().unwrap();
+        assert_eq!(
+            (elephant.attack, elephant.defense_infantry, elephant.defense_cavalry),
+            (150, 80, 60)
+        );
+        assert_eq!(elephant.speed, 4);
+        assert_eq!(
+            (elephant.wood_cost, elephant.clay_cost, elephant.iron_cost, elephant.crop_cost),
+            (100, 100, 100, 50)
+        );
+        assert_eq!(elephant.required_building, BuildingType::Stable);
+        assert_eq!(elephant.required_building_level, 5);
+    }
+
+    #[test]
+    fn training_is_blocked_without_the_building_or_below_its_level() {
+        let missing = TroopService::missing_requirement(&elite(), None).unwrap();
+        assert!(missing.contains("Stable level 5 (not built)"), "{}", missing);
+
+        let too_low = TroopService::missing_requirement(&elite(), Some(3)).unwrap();
+        assert!(too_low.contains("Stable level 5 (current: 3)"), "{}", too_low);
+    }
+
+    #[test]
+    fn training_is_allowed_once_the_building_is_upgraded() {
+        assert_eq!(TroopService::missing_requirement(&elite(), Some(5)), None);
+        assert_eq!(TroopService::missing_requirement(&elite(), Some(9)), None);
+    }
+
+    #[test]
+    fn upkeep_matches_the_troops_consumption() {
+        let definitions: HashMap<TroopType, TroopDefinition> = [
+            troop_definition(TroopType::Infantry, 40, 35, 50, 6),
+            TroopDefinition { crop_consumption: 5, ..elite() },
+        ]
+        .into_iter()
+        .map(|d| (d.troop_type, d))
+        .collect();
+        let fed = vec![(TroopType::Infantry, 100), (TroopType::WarElephant, 10)];
+
+        let entries = TroopService::upkeep_entries(fed, &definitions);
+
+        let elephants = entries.iter().find(|e| e.troop_type == TroopType::WarElephant).unwrap();
+        assert_eq!(elephants.crop_per_unit, 5);
+        assert_eq!(elephants.crop_per_hour, 50);
+        assert_eq!(entries.iter().map(|e| e.crop_per_hour).sum::<i32>(), 100 + 50);
+    }
+
+    fn queued(troop_type: TroopType, count: i32, started_at: DateTime<Utc>) -> TroopQueue {
+        TroopQueue {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            troop_type,
+            count,
+            each_duration_seconds: 60,
+            started_at,
+            ends_at: started_at + Duration::seconds(60 * count as i64),
+            created_at: started_at,
+        }
+    }
+
+    #[test]
+    fn queue_summary_reports_each_training_line_separately() {
+        let now = Utc::now();
+        let definitions = vec![
+            troop_definition(TroopType::Infantry, 40, 35, 50, 6),
+            TroopDefinition {
+                required_building: BuildingType::Stable,
+                ..troop_definition(TroopType::HighlandPony, 60, 40, 30, 14)
+            },
+        ];
+        let first = queued(TroopType::Infantry, 10, now);
+        let second = queued(TroopType::Infantry, 5, first.ends_at);
+        let cavalry = queued(TroopType::HighlandPony, 20, now);
+        let queue = vec![first, second.clone(), cavalry.clone()];
+
+        let lines = TroopService::queue_lines(&queue, &definitions, now);
+
+        assert_eq!(lines.len(), 2);
+        assert!(matches!(lines[0].building_type, BuildingType::Barracks));
+        assert_eq!((lines[0].units_pending, lines[0].ends_at), (15, second.ends_at));
+        assert!(matches!(lines[1].building_type, BuildingType::Stable));
+        assert_eq!((lines[1].units_pending, lines[1].ends_at), (20, cavalry.ends_at));
+    }
+
+    fn stable(level: i32) -> Building {
+        Building {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            building_type: BuildingType::Stable,
+            slot: 10,
+            level,
+            is_upgrading: false,
+            upgrade_ends_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn availability_reports_the_missing_building_until_it_is_built() {
+        let definitions = vec![troop_definition(TroopType::Infantry, 40, 35, 50, 6), elite()];
+        let barracks = Building { building_type: BuildingType::Barracks, slot: 9, ..stable(1) };
+        let elephant = |buildings: &[Building]| {
+            TroopService::availability(&definitions, buildings)
+                .into_iter()
+                .find(|a| a.troop_type == TroopType::WarElephant)
+                .unwrap()
+        };
+
+        let before = elephant(std::slice::from_ref(&barracks));
+        assert!(!before.available);
+        assert_eq!(before.current_level, None);
+        assert!(before.missing_requirement.unwrap().contains("Stable level 5 (not built)"));
+
+        let after = elephant(&[barracks, stable(5)]);
+        assert!(after.available);
+        assert_eq!(after.current_level, Some(5));
+        assert_eq!(after.missing_requirement, None);
+    }
+
+    fn garrison(troop_type: TroopType, count: i32, in_village: i32) -> Troop {
+        Troop {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            troop_type,
+            count,
+            in_village,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn disbanding_reduces_troops_and_upkeep() {
+        let definitions = vec![troop_definition(TroopType::Infantry, 40, 35, 50, 6)];
+        let by_type: HashMap<TroopType, TroopDefinition> =
+            definitions.iter().map(|d| (d.troop_type, d.clone())).collect();
+        let disbanded = HashMap::from([(TroopType::Infantry, 30)]);
+        let upkeep = |count: i32| {
+            TroopService::upkeep_entries(vec![(TroopType::Infantry, count)], &by_type)[0]
+                .crop_per_hour
+        };
+
+        assert!(TroopService::check_disband(
+            &disbanded,
+            &[garrison(TroopType::Infantry, 100, 100)]
+        )
+        .is_ok());
+        assert_eq!(upkeep(100) - upkeep(100 - 30), 30);
+
+        // No refund by default; a configured share of the training cost otherwise
+        let game = GameConfig::default();
+        assert_eq!(TroopService::disband_refund(&game, &disbanded, &definitions), (0, 0, 0, 0));
+        let refunding = GameConfig { disband_refund_percent: 20, ..GameConfig::default() };
+        assert_eq!(
+            TroopService::disband_refund(&refunding, &disbanded, &definitions),
+            (600, 600, 600, 300)
+        );
+    }
+
+    #[test]
+    fn cannot_disband_more_than_are_at_home() {
+        // 100 infantry, but 80 of them are away on a mission
+        let troops = [garrison(TroopType::Infantry, 100, 20)];
+
+        let too_many = HashMap::from([(TroopType::Infantry, 21)]);
+        assert!(matches!(
+            TroopService::check_disband(&too_many, &troops),
+            Err(AppError::BadRequest(_))
+        ));
+        let absent = HashMap::from([(TroopType::Spearman, 1)]);
+        assert!(TroopService::check_disband(&absent, &troops).is_err());
+        let negative = HashMap::from([(TroopType::Infantry, -5)]);
+        assert!(TroopService::check_disband(&negative, &troops).is_err());
+    }
 }
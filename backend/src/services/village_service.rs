@@ -1,25 +1,54 @@
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
+use crate::config::GameConfig;
 use crate::error::AppResult;
 use crate::models::building::{Building, BuildingType, CreateBuilding};
-use crate::models::village::{CreateVillage, Village};
+use crate::models::village::{CreateVillage, LoyaltyEvent, Village, VillageLoyaltyResponse};
+use crate::repositories::army_repo::ArmyRepository;
 use crate::repositories::building_repo::BuildingRepository;
+use crate::repositories::troop_repo::TroopRepository;
 use crate::repositories::village_repo::VillageRepository;
 
+/// Loyalty a village starts with and regenerates back up to
+pub const MAX_LOYALTY: i32 = 100;
+
+/// Loyalty regained per hour by villages below the maximum
+pub const LOYALTY_REGEN_PER_HOUR: i32 = 1;
+
+/// Number of recent loyalty-reducing battles returned with loyalty status
+const LOYALTY_EVENT_LIMIT: i64 = 10;
+
 pub struct VillageService;
 
 impl VillageService {
+    /// Whether a village owned by `owner_id` at `(x, y)` shows on a fogged map: the
+    /// viewer's own villages, villages on tiles they discovered and villages near one of theirs
+    pub fn is_visible_through_fog(
+        viewer_id: Uuid,
+        owner_id: Uuid,
+        (x, y): (i32, i32),
+        own_villages: &[Village],
+        discovered: &[(i32, i32)],
+        visibility_radius: i32,
+    ) -> bool {
+        owner_id == viewer_id
+            || discovered.contains(&(x, y))
+            || own_villages.iter().any(|o| {
+                (o.x - x).abs() <= visibility_radius && (o.y - y).abs() <= visibility_radius
+            })
+    }
+
     /// Create a new village with initial buildings
     pub async fn create_village_with_buildings(
-        pool: &PgPool,
+        conn: &mut PgConnection,
         input: CreateVillage,
     ) -> AppResult<(Village, Vec<Building>)> {
         // Create village
-        let village = VillageRepository::create(pool, input).await?;
+        let village = VillageRepository::create(&mut *conn, input).await?;
 
         // Create initial buildings
-        let buildings = Self::create_initial_buildings(pool, village.id).await?;
+        let buildings = Self::create_initial_buildings(conn, village.id).await?;
 
         Ok((village, buildings))
     }
@@ -27,7 +56,7 @@ impl VillageService {
     /// Create initial buildings for a new village
     /// Based on Travian's starting layout
     async fn create_initial_buildings(
-        pool: &PgPool,
+        conn: &mut PgConnection,
         village_id: Uuid,
     ) -> AppResult<Vec<Building>> {
         let mut buildings = Vec::new();
@@ -40,7 +69,8 @@ impl VillageService {
         ];
 
         for (slot, building_type, level) in village_buildings {
-            let building = create_building_with_level(pool, village_id, slot, building_type, level).await?;
+            let building =
+                create_building_with_level(conn, village_id, slot, building_type, level).await?;
             buildings.push(building);
         }
 
@@ -72,7 +102,8 @@ impl VillageService {
         ];
 
         for (slot, building_type) in resource_fields {
-            let building = create_building_with_level(pool, village_id, slot, building_type, 0).await?;
+            let building =
+                create_building_with_level(conn, village_id, slot, building_type, 0).await?;
             buildings.push(building);
         }
 
@@ -105,10 +136,92 @@ impl VillageService {
 
         Ok(None)
     }
+
+    /// Recompute village population from building levels and troop upkeep
+    pub async fn recompute_population(pool: &PgPool, village_id: Uuid) -> AppResult<Village> {
+        let buildings = BuildingRepository::find_by_village_id(pool, village_id).await?;
+
+        VillageRepository::update_population(pool, village_id, Self::building_population(&buildings))
+            .await
+    }
+
+    /// Population housed by a village's buildings at their current levels
+    pub fn building_population(buildings: &[Building]) -> i32 {
+        buildings
+            .iter()
+            .map(|b| b.building_type.population_at_level(b.level))
+            .sum()
+    }
+
+    /// Current loyalty of a village with the conquer battles that recently reduced it
+    pub async fn get_loyalty(pool: &PgPool, village: &Village) -> AppResult<VillageLoyaltyResponse> {
+        let recent_events =
+            ArmyRepository::find_loyalty_events(pool, village.id, LOYALTY_EVENT_LIMIT).await?;
+
+        Ok(Self::loyalty_status(village, recent_events))
+    }
+
+    fn loyalty_status(
+        village: &Village,
+        recent_events: Vec<LoyaltyEvent>,
+    ) -> VillageLoyaltyResponse {
+        VillageLoyaltyResponse {
+            village_id: village.id,
+            loyalty: village.loyalty,
+            max_loyalty: MAX_LOYALTY,
+            regen_per_hour: LOYALTY_REGEN_PER_HOUR,
+            recent_events,
+        }
+    }
+
+    /// Regenerate loyalty for all villages (called hourly by background job)
+    pub async fn regenerate_loyalty(pool: &PgPool) -> AppResult<u64> {
+        VillageRepository::regenerate_loyalty(pool, LOYALTY_REGEN_PER_HOUR, MAX_LOYALTY).await
+    }
+
+    /// Whether a player is still below the server's hard village cap
+    pub async fn can_own_another_village(
+        pool: &PgPool,
+        game: &GameConfig,
+        user_id: Uuid,
+    ) -> AppResult<bool> {
+        if game.max_villages_per_player <= 0 {
+            return Ok(true);
+        }
+
+        let owned = VillageRepository::count_by_user_id(pool, user_id).await?;
+        Ok(Self::is_below_village_cap(game, owned))
+    }
+
+    /// Whether `owned` villages leave room for one more (a cap of 0 means unlimited)
+    pub fn is_below_village_cap(game: &GameConfig, owned: i64) -> bool {
+        game.max_villages_per_player <= 0 || owned < game.max_villages_per_player
+    }
+
+    /// Station the configured starting garrison in a newly settled village
+    pub async fn grant_starting_garrison(
+        conn: &mut PgConnection,
+        game: &GameConfig,
+        village_id: Uuid,
+    ) -> AppResult<()> {
+        if game.starting_garrison.is_empty() {
+            return Ok(());
+        }
+
+        for &(troop_type, count) in &game.starting_garrison {
+            TroopRepository::add_troops(&mut *conn, village_id, troop_type, count).await?;
+        }
+
+        let buildings = BuildingRepository::find_by_village_id(&mut *conn, village_id).await?;
+        let population = Self::building_population(&buildings);
+        VillageRepository::update_population(conn, village_id, population).await?;
+
+        Ok(())
+    }
 }
 
 async fn create_building_with_level(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     village_id: Uuid,
     slot: i32,
     building_type: BuildingType,
@@ -121,7 +234,7 @@ async fn create_building_with_level(
     };
 
     // Create building (starts at level 1 by default)
-    let building = BuildingRepository::create(pool, create).await?;
+    let building = BuildingRepository::create(&mut *conn, create).await?;
 
     // If level is different, update it
     if level != 1 {
@@ -137,7 +250,7 @@ async fn create_building_with_level(
         )
         .bind(building.id)
         .bind(level)
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?;
 
         return Ok(updated);
@@ -145,3 +258,99 @@ async fn create_building_with_level(
 
     Ok(building)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{game_config, village};
+    use chrono::Utc;
+
+    fn building(building_type: BuildingType, slot: i32, level: i32) -> Building {
+        Building {
+            id: Uuid::new_v4(),
+            village_id: Uuid::new_v4(),
+            building_type,
+            slot,
+            level,
+            is_upgrading: false,
+            upgrade_ends_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn village_hit_by_a_conquer_shows_reduced_loyalty_and_the_hit() {
+        let mut target = village(0, 800);
+        target.loyalty = 72;
+        let hit = LoyaltyEvent {
+            report_id: Uuid::new_v4(),
+            attacker_player_id: Uuid::new_v4(),
+            attacker_village_id: Uuid::new_v4(),
+            loyalty_reduced: 28,
+            occurred_at: Utc::now(),
+        };
+
+        let status = VillageService::loyalty_status(&target, vec![hit.clone()]);
+
+        assert_eq!(status.loyalty, 72);
+        assert_eq!(status.max_loyalty, MAX_LOYALTY);
+        assert_eq!(status.recent_events.len(), 1);
+        assert_eq!(status.recent_events[0].report_id, hit.report_id);
+        assert_eq!(status.recent_events[0].loyalty_reduced, 28);
+    }
+
+    #[test]
+    fn village_cap_counts_owned_villages_and_zero_means_unlimited() {
+        let mut game = game_config();
+        game.max_villages_per_player = 3;
+        assert!(VillageService::is_below_village_cap(&game, 2));
+        assert!(!VillageService::is_below_village_cap(&game, 3));
+
+        game.max_villages_per_player = 0;
+        assert!(VillageService::is_below_village_cap(&game, 500));
+    }
+
+    #[test]
+    fn upgrading_a_building_raises_population_by_its_level_cost() {
+        let mut buildings = vec![
+            building(BuildingType::MainBuilding, 1, 3),
+            building(BuildingType::Woodcutter, 101, 2),
+            building(BuildingType::Barracks, 2, 0),
+        ];
+        assert_eq!(VillageService::building_population(&buildings), 2 + 2);
+
+        // Constructing the Barracks adds its base population
+        buildings[2].level = 1;
+        assert_eq!(VillageService::building_population(&buildings), 2 + 2 + 4);
+
+        // Every fifth level houses one more
+        buildings[2].level = 6;
+        assert_eq!(VillageService::building_population(&buildings), 2 + 2 + 5);
+    }
+
+    #[test]
+    fn distant_village_stays_hidden_until_scouted() {
+        let home = village(0, 1000);
+        let viewer = home.user_id;
+        let (owner, target) = (Uuid::new_v4(), (20, -15));
+        let visible = |discovered: &[(i32, i32)]| {
+            VillageService::is_visible_through_fog(
+                viewer,
+                owner,
+                target,
+                std::slice::from_ref(&home),
+                discovered,
+                7,
+            )
+        };
+
+        assert!(!visible(&[]));
+
+        // A scout arriving at the target reveals the tiles around it
+        let discovered: Vec<(i32, i32)> = (-2..=2)
+            .flat_map(|dx| (-2..=2).map(move |dy| (target.0 + dx, target.1 + dy)))
+            .collect();
+        assert!(visible(&discovered));
+    }
+}
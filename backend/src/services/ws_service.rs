@@ -17,6 +17,8 @@ pub enum WsEvent {
     AttackIncoming(AttackIncomingData),
     TroopTrainingComplete(TroopTrainingCompleteData),
     TroopsStarved(TroopsStarvedData),
+    SupportAutoReturned(SupportAutoReturnedData),
+    IncomingCountChanged(IncomingCountChangedData),
     Connected { user_id: Uuid },
 }
 
@@ -69,6 +71,22 @@ pub struct TroopsStarvedData {
     pub quantity: i32,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SupportAutoReturnedData {
+    pub army_id: Uuid,
+    pub from_village_id: Uuid,
+    pub to_village_id: Option<Uuid>,
+    pub returns_at: chrono::DateTime<chrono::Utc>,
+    /// Why the support was sent home: "expired" or "starvation"
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IncomingCountChangedData {
+    pub village_id: Uuid,
+    pub incoming_count: i64,
+}
+
 /// Connection info for a single WebSocket connection
 struct Connection {
     sender: mpsc::UnboundedSender<Message>,
@@ -0,0 +1,135 @@
+//! Fixtures shared by the unit tests
+
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::config::GameConfig;
+use crate::models::army::{Army, ArmyTroops, CarriedResources, MissionType};
+use crate::models::building::BuildingType;
+use crate::models::hero::{Hero, HeroStatus};
+use crate::models::troop::{TribeType, TroopDefinition, TroopType};
+use crate::models::village::Village;
+
+/// Game rules with every setting at its default, whatever the environment says
+pub fn game_config() -> GameConfig {
+    GameConfig::default()
+}
+
+/// A village at the origin holding `stock` of every resource, with
+/// warehouse and granary both sized `capacity`
+pub fn village(stock: i32, capacity: i32) -> Village {
+    Village {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        name: "Test".to_string(),
+        x: 0,
+        y: 0,
+        is_capital: false,
+        wood: stock,
+        clay: stock,
+        iron: stock,
+        crop: stock,
+        warehouse_capacity: capacity,
+        granary_capacity: capacity,
+        population: 0,
+        culture_points: 0,
+        loyalty: 100,
+        resources_updated_at: Utc::now(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// An outbound army that left a minute ago and arrives in an hour
+pub fn army(mission: MissionType, troops: ArmyTroops) -> Army {
+    let now = Utc::now();
+    Army {
+        id: Uuid::new_v4(),
+        player_id: Uuid::new_v4(),
+        from_village_id: Uuid::new_v4(),
+        to_x: 10,
+        to_y: 10,
+        to_village_id: None,
+        mission,
+        troops: Json(troops),
+        resources: Json(CarriedResources::default()),
+        departed_at: now - Duration::minutes(1),
+        arrives_at: now + Duration::hours(1),
+        returns_at: mission.returns().then(|| now + Duration::hours(2)),
+        is_returning: false,
+        is_stationed: false,
+        battle_report_id: None,
+        hero_id: None,
+        max_station_hours: None,
+        created_at: now,
+    }
+}
+
+/// A troop definition with the given combat stats and speed; costs and the
+/// rest are fixed placeholder values
+pub fn troop_definition(
+    troop_type: TroopType,
+    attack: i32,
+    defense_infantry: i32,
+    defense_cavalry: i32,
+    speed: i32,
+) -> TroopDefinition {
+    TroopDefinition {
+        id: Uuid::new_v4(),
+        troop_type,
+        tribe: troop_type.tribe(),
+        name: format!("{:?}", troop_type),
+        description: None,
+        attack,
+        defense_infantry,
+        defense_cavalry,
+        speed,
+        carry_capacity: 50,
+        crop_consumption: 1,
+        training_time_seconds: 60,
+        wood_cost: 100,
+        clay_cost: 100,
+        iron_cost: 100,
+        crop_cost: 50,
+        required_building: BuildingType::Barracks,
+        required_building_level: 1,
+        loyalty_reduction: 0,
+        is_scout: false,
+        created_at: Utc::now(),
+    }
+}
+
+/// A level-1 hero at full health in `status`, at home in its village
+pub fn hero(status: HeroStatus) -> Hero {
+    let home_village_id = Uuid::new_v4();
+    Hero {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        slot_number: 1,
+        name: "Test hero".to_string(),
+        tribe: TribeType::Phasuttha,
+        home_village_id,
+        current_village_id: Some(home_village_id),
+        status,
+        level: 1,
+        experience: 0,
+        experience_to_next: 100,
+        health: 100,
+        health_regen_rate: Decimal::ZERO,
+        unassigned_points: 0,
+        fighting_strength: 0,
+        off_bonus: 0,
+        def_bonus: 0,
+        resources_bonus: 0,
+        base_attack: 0,
+        base_defense: 0,
+        base_speed: Decimal::from(7),
+        last_health_update: Utc::now(),
+        died_at: None,
+        revive_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
@@ -30,12 +30,38 @@ export interface Army {
     returns_at: string | null;
     is_returning: boolean;
     is_stationed: boolean;
+    hero_id: string | null;
+}
+
+export interface StationedSupport extends Army {
+    sender_name: string | null;
+    sender_alliance_tag: string | null;
+    from_village_name: string | null;
+    from_x: number | null;
+    from_y: number | null;
+}
+
+export interface HeroBattleResult {
+    hero_id: string;
+    hero_name: string;
+    damage_dealt: number;
+    damage_taken: number;
+    died: boolean;
+    leveled_up: boolean;
+    experience_gained: number;
+}
+
+export interface BattleHeroResult {
+    attacker: HeroBattleResult | null;
+    defender: HeroBattleResult | null;
 }
 
 export interface BattleReport {
     id: string;
     attacker_player_id: string;
     defender_player_id: string | null;
+    attacker_alliance_tag: string | null;
+    defender_alliance_tag: string | null;
     attacker_village_id: string;
     defender_village_id: string | null;
     mission: MissionType;
@@ -44,7 +70,13 @@ export interface BattleReport {
     attacker_losses: TroopCounts;
     defender_losses: TroopCounts;
     resources_stolen: CarriedResources;
+    defender_resources_before: CarriedResources | null;
     winner: 'attacker' | 'defender' | 'draw';
+    loyalty_reduced: number;
+    conquest_outcome: 'failed' | 'loyalty_reduced' | 'no_chiefs_survived' | 'no_chiefs_sent' | null;
+    defender_details_hidden: boolean;
+    hero_result: BattleHeroResult | null;
+    wall_bonus_percent: number | null;
     occurred_at: string;
     is_read: boolean;
 }
@@ -62,6 +94,7 @@ export interface ScoutReport {
     success: boolean;
     scouted_resources: CarriedResources | null;
     scouted_troops: TroopCounts | null;
+    troops_exact: boolean;
     occurred_at: string;
     is_read: boolean;
 }
@@ -74,10 +107,26 @@ export interface SendArmyRequest {
     resources?: CarriedResources;
 }
 
+export interface TravelTimeRequest {
+    to_x: number;
+    to_y: number;
+    mission: MissionType;
+    troops: TroopCounts;
+    depart_at?: string;
+}
+
+export interface TravelTimePreview {
+    distance: number;
+    travel_seconds: number;
+    departs_at: string;
+    arrives_at: string;
+    returns_at: string | null;
+}
+
 interface ArmyState {
     outgoingArmies: Army[];
     incomingArmies: Army[];
-    stationedTroops: Army[];
+    stationedTroops: StationedSupport[];
     supportSent: Army[];
     reports: BattleReport[];
     scoutReports: ScoutReport[];
@@ -135,6 +184,14 @@ function createArmyStore() {
             }
         },
 
+        // Preview distance and ETA without sending troops
+        previewTravelTime: async (villageId: string, request: TravelTimeRequest) => {
+            return api.post<TravelTimePreview>(
+                `/api/villages/${villageId}/travel-time`,
+                request
+            );
+        },
+
         // Load outgoing armies from village
         loadOutgoing: async (villageId: string) => {
             update(state => ({ ...state, loading: true, error: null }));
@@ -304,7 +361,7 @@ function createArmyStore() {
         loadStationed: async (villageId: string) => {
             update(state => ({ ...state, loading: true, error: null }));
             try {
-                const armies = await api.get<Army[]>(
+                const armies = await api.get<StationedSupport[]>(
                     `/api/villages/${villageId}/stationed`
                 );
                 update(state => ({
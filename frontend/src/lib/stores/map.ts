@@ -6,6 +6,7 @@ export interface MapVillageInfo {
     id: string;
     name: string;
     player_name: string | null;
+    alliance_tag: string | null;
     population: number;
     is_own: boolean;
 }
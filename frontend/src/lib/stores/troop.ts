@@ -23,7 +23,9 @@ export type TroopType =
     // Chief units (can reduce loyalty)
     | 'royal_advisor'
     | 'harbor_master'
-    | 'elder_chief';
+    | 'elder_chief'
+    // Settlers (found new villages)
+    | 'settler';
 
 export type TribeType = 'phasuttha' | 'nava' | 'kiri' | 'special';
 
@@ -46,6 +48,7 @@ export interface TroopDefinition {
     required_building: string;
     required_building_level: number;
     loyalty_reduction: number;
+    is_scout: boolean;
 }
 
 export interface Troop {
@@ -262,6 +265,8 @@ export function getTroopIcon(type: TroopType): string {
         royal_advisor: '👑',
         harbor_master: '⚓',
         elder_chief: '🧙',
+        // Settlers
+        settler: '🏕️',
     };
     return icons[type] || '👤';
 }
@@ -298,3 +303,8 @@ export function getDefinitionByType(definitions: TroopDefinition[], type: TroopT
 export function isChiefTroop(type: TroopType): boolean {
     return type === 'royal_advisor' || type === 'harbor_master' || type === 'elder_chief';
 }
+
+// Settlers found a new village on Settle missions
+export function isSettlerTroop(type: TroopType): boolean {
+    return type === 'settler';
+}